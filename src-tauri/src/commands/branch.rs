@@ -1,12 +1,69 @@
+use crate::config::AppConfig;
 use crate::error::AppResult;
 use crate::git;
 use crate::state::AppState;
 use tauri::State;
 
+/// Remembers the branch HEAD is now on so reopening the repo can restore it
+fn remember_branch(state: &AppState, branch: &str) -> AppResult<()> {
+    let path = state.require_repo_path()?;
+    AppConfig::load().set_last_branch(&path.to_string_lossy(), branch);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_branches(
+    filter: Option<String>,
+    include_local: Option<bool>,
+    include_remote: Option<bool>,
+    with_details: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<git::BranchInfo>> {
+    let repo = state.open_repo()?;
+    git::list_branches(
+        &repo,
+        filter.as_deref(),
+        include_local.unwrap_or(true),
+        include_remote.unwrap_or(true),
+        with_details.unwrap_or(true),
+    )
+}
+
+#[tauri::command]
+pub async fn get_branch_details(
+    name: String,
+    state: State<'_, AppState>,
+) -> AppResult<git::BranchInfo> {
+    let repo = state.open_repo()?;
+    git::get_branch_details(&repo, &name)
+}
+
 #[tauri::command]
-pub async fn get_branches(state: State<'_, AppState>) -> AppResult<Vec<git::BranchInfo>> {
+pub async fn checkout_commit(
+    commit_hash: String,
+    force: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    state.with_repo_lock(|repo| git::checkout_commit(repo, &commit_hash, force.unwrap_or(false))).await
+}
+
+#[tauri::command]
+pub async fn get_branch_description(
+    name: String,
+    state: State<'_, AppState>,
+) -> AppResult<Option<String>> {
     let repo = state.open_repo()?;
-    git::list_branches(&repo)
+    git::get_branch_description(&repo, &name)
+}
+
+#[tauri::command]
+pub async fn set_branch_description(
+    name: String,
+    text: String,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let repo = state.open_repo()?;
+    git::set_branch_description(&repo, &name, &text)
 }
 
 #[tauri::command]
@@ -21,14 +78,27 @@ pub async fn create_branch(
     checkout: bool,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
-    let repo = state.open_repo()?;
-    git::create_branch(&repo, &name, checkout)
+    state.with_repo_lock(|repo| git::create_branch(repo, &name, checkout)).await?;
+    if checkout {
+        remember_branch(&state, &name)?;
+    }
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn checkout_branch(name: String, state: State<'_, AppState>) -> AppResult<()> {
-    let repo = state.open_repo()?;
-    git::checkout_branch(&repo, &name)
+pub async fn checkout_branch(
+    name: String,
+    force: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    // Checking out a remote branch lands on its local counterpart
+    let branch = state
+        .with_repo_lock(|repo| {
+            git::checkout_branch(repo, &name, force.unwrap_or(false))?;
+            git::get_current_branch(repo)
+        })
+        .await?;
+    remember_branch(&state, &branch)
 }
 
 #[tauri::command]
@@ -37,8 +107,7 @@ pub async fn delete_branch(
     force: bool,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
-    let repo = state.open_repo()?;
-    git::delete_branch(&repo, &name, force)
+    state.with_repo_lock(|repo| git::delete_branch(repo, &name, force)).await
 }
 
 #[tauri::command]
@@ -47,12 +116,36 @@ pub async fn rename_branch(
     new_name: String,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
-    let repo = state.open_repo()?;
-    git::rename_branch(&repo, &old_name, &new_name)
+    state.with_repo_lock(|repo| git::rename_branch(repo, &old_name, &new_name)).await
 }
 
 #[tauri::command]
-pub async fn merge_branch(name: String, state: State<'_, AppState>) -> AppResult<String> {
-    let repo = state.open_repo()?;
-    git::merge_branch(&repo, &name)
+pub async fn copy_branch(
+    source: String,
+    new_name: String,
+    checkout: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    state.with_repo_lock(|repo| git::copy_branch(repo, &source, &new_name, checkout.unwrap_or(false))).await
+}
+
+#[tauri::command]
+pub async fn merge_branch(
+    name: String,
+    no_ff: Option<bool>,
+    no_commit: Option<bool>,
+    message: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state
+        .with_repo_lock(|repo| {
+            git::merge_branch(
+                repo,
+                &name,
+                no_ff.unwrap_or(false),
+                no_commit.unwrap_or(false),
+                message.as_deref(),
+            )
+        })
+        .await
 }
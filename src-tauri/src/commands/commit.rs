@@ -1,3 +1,4 @@
+use crate::config::AppConfig;
 use crate::error::AppResult;
 use crate::git;
 use crate::state::AppState;
@@ -8,10 +9,55 @@ pub async fn get_commits(
     branch: Option<String>,
     limit: Option<usize>,
     skip: Option<usize>,
+    with_parents: Option<bool>,
+    with_stats: Option<bool>,
     state: State<'_, AppState>,
 ) -> AppResult<Vec<git::CommitInfo>> {
     let repo = state.open_repo()?;
-    git::list_commits(
+    let mut commits = git::list_commits(
+        &repo,
+        branch.as_deref(),
+        limit.unwrap_or(100),
+        skip.unwrap_or(0),
+    )?;
+    if with_parents.unwrap_or(false) {
+        git::fill_parent_summaries(&repo, &mut commits);
+    }
+    if with_stats.unwrap_or(false) {
+        git::fill_commit_stats(&repo, &mut commits)?;
+    }
+    Ok(commits)
+}
+
+#[tauri::command]
+pub async fn get_commit_count(
+    branch: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<usize> {
+    let repo = state.open_repo()?;
+    git::count_commits(&repo, branch.as_deref())
+}
+
+#[tauri::command]
+pub async fn search_commits(
+    query: git::CommitQuery,
+    limit: Option<usize>,
+    skip: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<git::CommitInfo>> {
+    let repo = state.open_repo()?;
+    git::search_commits(&repo, &query, limit.unwrap_or(100), skip.unwrap_or(0))
+}
+
+#[tauri::command]
+pub async fn get_commit_graph(
+    branch: Option<String>,
+    limit: Option<usize>,
+    skip: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<git::GraphCommit>> {
+    let repo = state.open_repo()?;
+    git::get_commit_graph(
         &repo,
         branch.as_deref(),
         limit.unwrap_or(100),
@@ -22,30 +68,97 @@ pub async fn get_commits(
 #[tauri::command]
 pub async fn get_commit(
     hash: String,
+    with_parents: Option<bool>,
     state: State<'_, AppState>,
 ) -> AppResult<git::CommitInfo> {
     let repo = state.open_repo()?;
-    git::get_commit(&repo, &hash)
+    let mut commit = git::get_commit(&repo, &hash)?;
+    if with_parents.unwrap_or(false) {
+        git::fill_parent_summaries(&repo, std::slice::from_mut(&mut commit));
+    }
+    Ok(commit)
 }
 
 #[tauri::command]
 pub async fn get_file_history(
     path: String,
     limit: Option<usize>,
+    skip: Option<usize>,
+    follow: Option<bool>,
     state: State<'_, AppState>,
-) -> AppResult<Vec<git::CommitInfo>> {
+) -> AppResult<Vec<git::FileHistoryEntry>> {
     let repo = state.open_repo()?;
-    git::list_file_history(&repo, &path, limit.unwrap_or(200))
+    git::list_file_history(
+        &repo,
+        &path,
+        limit.unwrap_or(200),
+        skip.unwrap_or(0),
+        follow.unwrap_or(false),
+    )
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_commit(
     message: String,
     amend: bool,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    author_date: Option<String>,
+    run_hooks: Option<bool>,
+    initial_branch: Option<String>,
     state: State<'_, AppState>,
-) -> AppResult<String> {
+) -> AppResult<git::CreateCommitResult> {
+    let author = git::AuthorOverride {
+        name: author_name,
+        email: author_email,
+        date: author_date,
+    };
+    let run_hooks = run_hooks.unwrap_or_else(|| AppConfig::load().run_hooks);
+    state.with_repo_lock(|repo| git::create_commit(repo, &message, amend, &author, run_hooks, initial_branch.as_deref())).await
+}
+
+/// Folds the staged changes into HEAD keeping its message and author
+#[tauri::command]
+pub async fn add_to_last_commit(
+    force: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<git::CreateCommitResult> {
+    state.with_repo_lock(|repo| git::add_to_last_commit(repo, force.unwrap_or(false))).await
+}
+
+#[tauri::command]
+pub async fn get_commit_template(
+    clean: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<Option<String>> {
+    let repo = state.open_repo()?;
+    git::get_commit_template(&repo, clean.unwrap_or(false))
+}
+
+/// Checks a message against the given rules, or the ones saved in the app config
+#[tauri::command]
+pub async fn validate_commit_message(
+    message: String,
+    rules: Option<git::CommitRules>,
+) -> AppResult<Vec<git::CommitMessageViolation>> {
+    let rules = rules.unwrap_or_else(|| AppConfig::load().commit_rules);
+    git::validate_commit_message(&message, &rules)
+}
+
+#[tauri::command]
+pub async fn can_commit(state: State<'_, AppState>) -> AppResult<git::CommitReadiness> {
     let repo = state.open_repo()?;
-    git::create_commit(&repo, &message, amend)
+    git::can_commit(&repo)
+}
+
+#[tauri::command]
+pub async fn squash_commits(
+    from_hash: String,
+    message: String,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.with_repo_lock(|repo| git::squash_commits(repo, &from_hash, &message)).await
 }
 
 #[tauri::command]
@@ -54,8 +167,13 @@ pub async fn stage_files(
     state: State<'_, AppState>,
 ) -> AppResult<()> {
     let path = state.require_repo_path()?;
-    let repo = state.open_repo()?;
-    git::stage_files(&repo, &files, &path)
+    state.with_repo_lock(|repo| git::stage_files(repo, &files, &path)).await
+}
+
+/// Stages a whole directory (or file) by pathspec, e.g. a collapsed `newdir/`
+#[tauri::command]
+pub async fn stage_path(path: String, state: State<'_, AppState>) -> AppResult<()> {
+    state.with_repo_lock(|repo| git::stage_path(repo, &path)).await
 }
 
 #[tauri::command]
@@ -65,8 +183,7 @@ pub async fn stage_partial_changes(
     state: State<'_, AppState>,
 ) -> AppResult<()> {
     let repo_path = state.require_repo_path()?;
-    let repo = state.open_repo()?;
-    git::stage_partial_changes(&repo, &path, &selections, &repo_path)
+    state.with_repo_lock(|repo| git::stage_partial_changes(repo, &path, &selections, &repo_path)).await
 }
 
 #[tauri::command]
@@ -74,8 +191,7 @@ pub async fn unstage_files(
     files: Vec<String>,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
-    let repo = state.open_repo()?;
-    git::unstage_files(&repo, &files)
+    state.with_repo_lock(|repo| git::unstage_files(repo, &files)).await
 }
 
 #[tauri::command]
@@ -85,20 +201,48 @@ pub async fn unstage_partial_changes(
     state: State<'_, AppState>,
 ) -> AppResult<()> {
     let repo_path = state.require_repo_path()?;
-    let repo = state.open_repo()?;
-    git::unstage_partial_changes(&repo, &path, &selections, &repo_path)
+    state.with_repo_lock(|repo| git::unstage_partial_changes(repo, &path, &selections, &repo_path)).await
 }
 
 #[tauri::command]
 pub async fn stage_all(state: State<'_, AppState>) -> AppResult<()> {
-    let repo = state.open_repo()?;
-    git::stage_all(&repo)
+    state.with_repo_lock(git::stage_all).await
 }
 
 #[tauri::command]
 pub async fn unstage_all(state: State<'_, AppState>) -> AppResult<()> {
+    state.with_repo_lock(git::unstage_all).await
+}
+
+/// Both flags are required so nothing is deleted by omission
+#[tauri::command]
+pub async fn reset_working_tree(
+    include_untracked: bool,
+    include_ignored: bool,
+    state: State<'_, AppState>,
+) -> AppResult<git::ResetWorkingTreeResult> {
+    state.with_repo_lock(|repo| git::reset_working_tree(repo, include_untracked, include_ignored)).await
+}
+
+/// Run with `dry_run` first to show what would be deleted
+#[tauri::command]
+pub async fn clean_working_tree(
+    directories: bool,
+    ignored: bool,
+    dry_run: bool,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<String>> {
+    state.with_repo_lock(|repo| git::clean_working_tree(repo, directories, ignored, dry_run)).await
+}
+
+/// What discarding `files` would lose, for an accurate confirmation
+#[tauri::command]
+pub async fn preview_discard(
+    files: Vec<String>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<git::DiscardPreview>> {
     let repo = state.open_repo()?;
-    git::unstage_all(&repo)
+    git::preview_discard(&repo, &files)
 }
 
 #[tauri::command]
@@ -106,8 +250,16 @@ pub async fn discard_changes(
     files: Vec<String>,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
-    let repo = state.open_repo()?;
-    git::discard_changes(&repo, &files)
+    state.with_repo_lock(|repo| git::discard_changes(repo, &files)).await
+}
+
+#[tauri::command]
+pub async fn restore_files(
+    files: Vec<String>,
+    staged: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<String>> {
+    state.with_repo_lock(|repo| git::restore_files(repo, &files, staged.unwrap_or(false))).await
 }
 
 #[tauri::command]
@@ -115,8 +267,7 @@ pub async fn cherry_pick(
     commit_hash: String,
     state: State<'_, AppState>,
 ) -> AppResult<String> {
-    let repo = state.open_repo()?;
-    git::cherry_pick(&repo, &commit_hash)
+    state.with_repo_lock(|repo| git::cherry_pick(repo, &commit_hash)).await
 }
 
 #[tauri::command]
@@ -124,8 +275,17 @@ pub async fn revert_commit(
     commit_hash: String,
     state: State<'_, AppState>,
 ) -> AppResult<String> {
-    let repo = state.open_repo()?;
-    git::revert_commit(&repo, &commit_hash)
+    state.with_repo_lock(|repo| git::revert_commit(repo, &commit_hash)).await
+}
+
+#[tauri::command]
+pub async fn continue_cherry_pick(state: State<'_, AppState>) -> AppResult<String> {
+    state.with_repo_lock(git::continue_cherry_pick).await
+}
+
+#[tauri::command]
+pub async fn continue_revert(state: State<'_, AppState>) -> AppResult<String> {
+    state.with_repo_lock(git::continue_revert).await
 }
 
 #[tauri::command]
@@ -134,8 +294,27 @@ pub async fn reset_to_commit(
     mode: String,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
+    state.with_repo_lock(|repo| git::reset_to_commit(repo, &commit_hash, &mode)).await
+}
+
+#[tauri::command]
+pub async fn reset_paths_to_commit(
+    commit_hash: String,
+    paths: Vec<String>,
+    update_worktree: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<git::ResetPathsResult> {
+    state.with_repo_lock(|repo| git::reset_paths_to_commit(repo, &commit_hash, &paths, update_worktree.unwrap_or(true))).await
+}
+
+#[tauri::command]
+pub async fn get_reflog(
+    reference: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<git::ReflogEntry>> {
     let repo = state.open_repo()?;
-    git::reset_to_commit(&repo, &commit_hash, &mode)
+    git::get_reflog(&repo, reference.as_deref(), limit.unwrap_or(100))
 }
 
 #[tauri::command]
@@ -153,6 +332,30 @@ pub async fn execute_interactive_rebase(
     entries: Vec<git::RebaseEntry>,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
+    state.with_repo_lock(|repo| git::perform_interactive_rebase(repo, &base_hash, &entries)).await
+}
+
+/// Rebase in progress on disk, including ones started in a terminal
+#[tauri::command]
+pub async fn get_rebase_status(state: State<'_, AppState>) -> AppResult<git::RebaseStatus> {
     let repo = state.open_repo()?;
-    git::perform_interactive_rebase(&repo, &base_hash, &entries)
+    git::get_rebase_status(&repo)
+}
+
+#[tauri::command]
+pub async fn rebase_continue(state: State<'_, AppState>) -> AppResult<git::RebaseStatus> {
+    state.with_repo_lock(git::rebase_continue).await
+}
+
+#[tauri::command]
+pub async fn rebase_abort(state: State<'_, AppState>) -> AppResult<()> {
+    state.with_repo_lock(git::rebase_abort).await
+}
+
+#[tauri::command]
+pub async fn drop_commit(
+    commit_hash: String,
+    state: State<'_, AppState>,
+) -> AppResult<git::DropCommitResult> {
+    state.with_repo_lock(|repo| git::drop_commit(repo, &commit_hash)).await
 }
@@ -12,3 +12,23 @@ pub async fn compare_refs(
     let repo = state.open_repo()?;
     git::compare_refs(&repo, &base_ref, &head_ref)
 }
+
+#[tauri::command]
+pub async fn get_merge_base(
+    a: String,
+    b: String,
+    state: State<'_, AppState>,
+) -> AppResult<Option<git::CommitInfo>> {
+    let repo = state.open_repo()?;
+    git::get_merge_base(&repo, &a, &b)
+}
+
+#[tauri::command]
+pub async fn get_ahead_behind_between(
+    a: String,
+    b: String,
+    state: State<'_, AppState>,
+) -> AppResult<git::AheadBehind> {
+    let repo = state.open_repo()?;
+    git::get_ahead_behind_between(&repo, &a, &b)
+}
@@ -12,6 +12,12 @@ pub async fn get_conflict_info(
     git::get_conflict_info(&repo, &path)
 }
 
+#[tauri::command]
+pub async fn get_all_conflicts(state: State<'_, AppState>) -> AppResult<Vec<git::ConflictEntry>> {
+    let repo = state.open_repo()?;
+    git::get_all_conflicts(&repo)
+}
+
 #[tauri::command]
 pub async fn get_conflicted_file(
     state: State<'_, AppState>,
@@ -28,21 +34,56 @@ pub async fn resolve_conflict(
     content: String,
     mark_resolved: bool,
 ) -> AppResult<()> {
-    let repo = state.open_repo()?;
+    state
+        .with_repo_lock(|repo| {
+            // Save the resolved content
+            git::save_resolved_file(repo, &path, &content)?;
 
-    // Save the resolved content
-    git::save_resolved_file(&repo, &path, &content)?;
+            // Optionally mark as resolved (stage the file)
+            if mark_resolved {
+                git::mark_resolved(repo, &path)?;
+            }
 
-    // Optionally mark as resolved (stage the file)
-    if mark_resolved {
-        git::mark_resolved(&repo, &path)?;
-    }
+            Ok(())
+        })
+        .await
+}
 
-    Ok(())
+#[tauri::command]
+pub async fn resolve_conflict_side(
+    state: State<'_, AppState>,
+    path: String,
+    choice: git::ConflictChoice,
+) -> AppResult<()> {
+    state.with_repo_lock(|repo| git::resolve_conflict_side(repo, &path, choice)).await
+}
+
+#[tauri::command]
+pub async fn resolve_conflict_section(
+    state: State<'_, AppState>,
+    path: String,
+    section_id: usize,
+    choice: git::ConflictChoice,
+) -> AppResult<bool> {
+    state.with_repo_lock(|repo| git::resolve_conflict_section(repo, &path, section_id, choice)).await
 }
 
 #[tauri::command]
 pub async fn abort_merge(state: State<'_, AppState>) -> AppResult<()> {
-    let repo = state.open_repo()?;
-    git::abort_merge(&repo)
+    state.with_repo_lock(git::abort_merge).await
+}
+
+#[tauri::command]
+pub async fn commit_merge(state: State<'_, AppState>, message: Option<String>) -> AppResult<String> {
+    state.with_repo_lock(|repo| git::commit_merge(repo, message.as_deref())).await
+}
+
+#[tauri::command]
+pub async fn abort_cherry_pick(state: State<'_, AppState>) -> AppResult<()> {
+    state.with_repo_lock(git::abort_cherry_pick).await
+}
+
+#[tauri::command]
+pub async fn abort_revert(state: State<'_, AppState>) -> AppResult<()> {
+    state.with_repo_lock(git::abort_revert).await
 }
@@ -4,52 +4,155 @@ use crate::state::AppState;
 use tauri::State;
 
 #[tauri::command]
-pub async fn get_working_diff(state: State<'_, AppState>) -> AppResult<Vec<git::DiffInfo>> {
+pub async fn get_working_diff(
+    word_diff: Option<bool>,
+    operation_id: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<git::DiffInfo>> {
     let repo = state.open_repo()?;
-    git::get_working_diff(&repo)
+    let mut diffs = state.with_operation(operation_id.as_deref(), |cancel| git::get_working_diff(&repo, cancel))?;
+    if word_diff.unwrap_or(false) {
+        git::apply_word_diff(&mut diffs);
+    }
+    Ok(diffs)
 }
 
 #[tauri::command]
-pub async fn get_staged_diff(state: State<'_, AppState>) -> AppResult<Vec<git::DiffInfo>> {
+pub async fn get_staged_diff(
+    word_diff: Option<bool>,
+    operation_id: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<git::DiffInfo>> {
     let repo = state.open_repo()?;
-    git::get_staged_diff(&repo)
+    let mut diffs = state.with_operation(operation_id.as_deref(), |cancel| git::get_staged_diff(&repo, cancel))?;
+    if word_diff.unwrap_or(false) {
+        git::apply_word_diff(&mut diffs);
+    }
+    Ok(diffs)
+}
+
+/// Quick "+N −M across K files" for uncommitted changes
+#[tauri::command]
+pub async fn get_diff_stats(staged: Option<bool>, state: State<'_, AppState>) -> AppResult<git::DiffStats> {
+    let repo = state.open_repo()?;
+    git::get_diff_stats(&repo, staged.unwrap_or(false))
 }
 
 #[tauri::command]
 pub async fn get_commit_diff(
     commit_hash: String,
+    parent_index: Option<usize>,
+    word_diff: Option<bool>,
+    operation_id: Option<String>,
     state: State<'_, AppState>,
 ) -> AppResult<Vec<git::DiffInfo>> {
     let repo = state.open_repo()?;
-    git::get_commit_diff(&repo, &commit_hash)
+    let mut diffs = state.with_operation(operation_id.as_deref(), |cancel| {
+        git::get_commit_diff(&repo, &commit_hash, parent_index.unwrap_or(0), cancel)
+    })?;
+    if word_diff.unwrap_or(false) {
+        git::apply_word_diff(&mut diffs);
+    }
+    Ok(diffs)
+}
+
+#[tauri::command]
+pub async fn get_commit_diff_summary(
+    commit_hash: String,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<git::DiffFileSummary>> {
+    let repo = state.open_repo()?;
+    git::get_commit_diff_summary(&repo, &commit_hash)
 }
 
 #[tauri::command]
 pub async fn get_file_diff(
     path: String,
     staged: bool,
+    word_diff: Option<bool>,
     state: State<'_, AppState>,
 ) -> AppResult<git::DiffInfo> {
     let repo_path = state.require_repo_path()?;
     let repo = state.open_repo()?;
-    git::get_file_diff(&repo, &path, staged, &repo_path)
+    let mut diff = git::get_file_diff(&repo, &path, staged, &repo_path)?;
+    if word_diff.unwrap_or(false) {
+        git::apply_word_diff(std::slice::from_mut(&mut diff));
+    }
+    Ok(diff)
+}
+
+#[tauri::command]
+pub async fn get_file_diff_paged(
+    path: String,
+    staged: bool,
+    hunk_offset: Option<usize>,
+    hunk_limit: Option<usize>,
+    max_lines: Option<usize>,
+    word_diff: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<git::DiffPage> {
+    let repo = state.open_repo()?;
+    let mut page = git::get_file_diff_paged(
+        &repo,
+        &path,
+        staged,
+        hunk_offset.unwrap_or(0),
+        hunk_limit.unwrap_or(50),
+        max_lines.unwrap_or(git::DEFAULT_PAGED_DIFF_MAX_LINES),
+    )?;
+    if word_diff.unwrap_or(false) {
+        git::apply_word_diff(std::slice::from_mut(&mut page.diff));
+    }
+    Ok(page)
 }
 
 #[tauri::command]
 pub async fn get_file_blame(
     path: String,
+    operation_id: Option<String>,
     state: State<'_, AppState>,
 ) -> AppResult<Vec<git::BlameInfo>> {
     let repo = state.open_repo()?;
-    git::get_file_blame(&repo, &path)
+    state.with_operation(operation_id.as_deref(), |cancel| git::get_file_blame(&repo, &path, cancel))
 }
 
 #[tauri::command]
 pub async fn get_file_diff_at_commit(
     commit_hash: String,
     path: String,
+    word_diff: Option<bool>,
     state: State<'_, AppState>,
 ) -> AppResult<git::DiffInfo> {
     let repo = state.open_repo()?;
-    git::get_file_diff_at_commit(&repo, &commit_hash, &path)
+    let mut diff = git::get_file_diff_at_commit(&repo, &commit_hash, &path)?;
+    if word_diff.unwrap_or(false) {
+        git::apply_word_diff(std::slice::from_mut(&mut diff));
+    }
+    Ok(diff)
+}
+
+#[tauri::command]
+pub async fn get_file_at_commit(
+    commit_hash: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> AppResult<git::FileAtCommit> {
+    let repo = state.open_repo()?;
+    git::get_file_at_commit(&repo, &commit_hash, &path)
+}
+
+#[tauri::command]
+pub async fn list_tree(
+    commit_ish: Option<String>,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<git::TreeEntryInfo>> {
+    let repo = state.open_repo()?;
+    git::list_tree(&repo, commit_ish.as_deref(), path.as_deref())
+}
+
+#[tauri::command]
+pub async fn lfs_status(state: State<'_, AppState>) -> AppResult<Vec<git::LfsFile>> {
+    let path = state.require_repo_path()?;
+    git::list_lfs_files(&path)
 }
@@ -1,7 +1,17 @@
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 use crate::git;
 use crate::state::AppState;
-use tauri::State;
+use tauri::{AppHandle, State};
+use tauri_plugin_shell::ShellExt;
+
+/// Open a URL in the default browser, through the shell plugin's `open`
+fn open_url(app: &AppHandle, url: &str) -> AppResult<()> {
+    // Deprecated in favor of tauri-plugin-opener, which isn't a dependency yet
+    #[allow(deprecated)]
+    app.shell()
+        .open(url, None)
+        .map_err(|e| AppError::with_details("OPEN_URL_FAILED", "Falha ao abrir o navegador", &e.to_string()))
+}
 
 #[tauri::command]
 pub async fn check_github_cli(state: State<'_, AppState>) -> AppResult<bool> {
@@ -19,6 +29,68 @@ pub async fn list_pull_requests(
     git::list_pull_requests(&repo_path, pr_state.as_deref(), limit.unwrap_or(30))
 }
 
+#[tauri::command]
+pub async fn get_repo_slug(state: State<'_, AppState>) -> AppResult<git::RepoSlug> {
+    let repo_path = state.require_repo_path()?;
+    git::get_repo_slug(&repo_path)
+}
+
+#[tauri::command]
+pub async fn open_on_github_commit(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    hash: String,
+) -> AppResult<()> {
+    let repo_path = state.require_repo_path()?;
+    open_url(&app, &git::github_commit_url(&repo_path, &hash)?)
+}
+
+#[tauri::command]
+pub async fn open_on_github_branch(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> AppResult<()> {
+    let repo_path = state.require_repo_path()?;
+    open_url(&app, &git::github_branch_url(&repo_path, &name)?)
+}
+
+/// Opens the file on the current branch, or on the HEAD commit when detached
+#[tauri::command]
+pub async fn open_on_github_file(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    line: Option<u32>,
+) -> AppResult<()> {
+    let repo_path = state.require_repo_path()?;
+    let repo = state.open_repo()?;
+    let head = repo.head()?;
+    let git_ref = match head.shorthand() {
+        Some(branch) if head.is_branch() => branch.to_string(),
+        _ => head.peel_to_commit()?.id().to_string(),
+    };
+    open_url(&app, &git::github_file_url(&repo_path, &git_ref, &path, line)?)
+}
+
+#[tauri::command]
+pub async fn get_default_branch(state: State<'_, AppState>) -> AppResult<String> {
+    let repo_path = state.require_repo_path()?;
+    git::get_default_branch(&repo_path)
+}
+
+/// Same as `list_pull_requests`, but drops this repo's cached gh responses first
+#[tauri::command]
+pub async fn refresh_pull_requests(
+    state: State<'_, AppState>,
+    pr_state: Option<String>,
+    limit: Option<u32>,
+) -> AppResult<Vec<git::PullRequest>> {
+    let repo_path = state.require_repo_path()?;
+    git::clear_gh_cache(&repo_path);
+    git::list_pull_requests(&repo_path, pr_state.as_deref(), limit.unwrap_or(30))
+}
+
 #[tauri::command]
 pub async fn get_pull_request(
     state: State<'_, AppState>,
@@ -48,6 +120,42 @@ pub async fn create_pull_request(
     )
 }
 
+/// Pushes the current branch with upstream tracking, then opens a PR from it.
+/// A failed push is returned as-is and no PR is attempted.
+#[tauri::command]
+pub async fn publish_branch_and_pr(
+    state: State<'_, AppState>,
+    title: String,
+    body: Option<String>,
+    base: String,
+    remote: Option<String>,
+    draft: bool,
+) -> AppResult<git::PullRequest> {
+    let repo_path = state.require_repo_path()?;
+    let remote = remote.unwrap_or_else(|| "origin".to_string());
+    let ssh_key = super::remote::configured_ssh_key(&state);
+
+    // Only the branch is read under the lock; the push is network-bound and,
+    // like push_remote, must not hold up the repo's other writes
+    let branch = state
+        .with_repo_lock(|repo| {
+            let head = repo.head()?;
+            match head.shorthand() {
+                Some(name) if head.is_branch() => Ok(name.to_string()),
+                _ => Err(AppError::with_details(
+                    "DETACHED_HEAD",
+                    "Nenhuma branch ativa para publicar",
+                    "Faça checkout de uma branch antes de criar o PR",
+                )),
+            }
+        })
+        .await?;
+    let repo = state.open_repo()?;
+    git::push(&repo, &remote, &branch, false, false, true, ssh_key.as_ref())?;
+
+    git::create_pull_request(&repo_path, &title, body.as_deref(), &base, Some(&branch), draft)
+}
+
 #[tauri::command]
 pub async fn get_pull_request_reviews(
     state: State<'_, AppState>,
@@ -66,6 +174,27 @@ pub async fn get_pull_request_comments(
     git::get_pull_request_comments(&repo_path, number)
 }
 
+#[tauri::command]
+pub async fn get_pr_review_threads(
+    state: State<'_, AppState>,
+    number: u64,
+) -> AppResult<Vec<git::ReviewThread>> {
+    let repo_path = state.require_repo_path()?;
+    git::get_pr_review_threads(&repo_path, number)
+}
+
+#[tauri::command]
+pub async fn resolve_review_thread(state: State<'_, AppState>, thread_id: String) -> AppResult<()> {
+    let repo_path = state.require_repo_path()?;
+    git::set_review_thread_resolved(&repo_path, &thread_id, true)
+}
+
+#[tauri::command]
+pub async fn unresolve_review_thread(state: State<'_, AppState>, thread_id: String) -> AppResult<()> {
+    let repo_path = state.require_repo_path()?;
+    git::set_review_thread_resolved(&repo_path, &thread_id, false)
+}
+
 #[tauri::command]
 pub async fn get_pull_request_files(
     state: State<'_, AppState>,
@@ -134,6 +263,15 @@ pub async fn get_pull_request_diff(
     git::get_pull_request_diff(&repo_path, number)
 }
 
+#[tauri::command]
+pub async fn get_pull_request_diff_structured(
+    state: State<'_, AppState>,
+    number: u64,
+) -> AppResult<Vec<git::DiffInfo>> {
+    let repo_path = state.require_repo_path()?;
+    git::get_pull_request_diff_structured(&repo_path, number)
+}
+
 #[tauri::command]
 pub async fn get_pr_checks(
     state: State<'_, AppState>,
@@ -143,6 +281,25 @@ pub async fn get_pr_checks(
     git::get_pr_checks(&repo_path, number)
 }
 
+#[tauri::command]
+pub async fn list_workflow_runs(
+    state: State<'_, AppState>,
+    branch: Option<String>,
+    limit: Option<u32>,
+) -> AppResult<Vec<git::WorkflowRun>> {
+    let repo_path = state.require_repo_path()?;
+    git::list_workflow_runs(&repo_path, branch.as_deref(), limit.unwrap_or(20))
+}
+
+#[tauri::command]
+pub async fn get_workflow_run(
+    state: State<'_, AppState>,
+    id: u64,
+) -> AppResult<git::WorkflowRunDetails> {
+    let repo_path = state.require_repo_path()?;
+    git::get_workflow_run(&repo_path, id)
+}
+
 #[tauri::command]
 pub async fn checkout_pull_request(state: State<'_, AppState>, number: u64) -> AppResult<()> {
     let repo_path = state.require_repo_path()?;
@@ -1,8 +1,15 @@
+use crate::config::{self, AppConfig};
 use crate::error::AppResult;
 use crate::git;
 use crate::state::AppState;
 use tauri::State;
 
+/// SSH key configured for the active repo (or globally), passphrase included
+pub(crate) fn configured_ssh_key(state: &AppState) -> Option<git::SshKey> {
+    let repo_path = state.get_repo_path().map(|p| p.to_string_lossy().to_string());
+    AppConfig::load().ssh_key_for(repo_path.as_deref())
+}
+
 #[tauri::command]
 pub async fn get_remotes(state: State<'_, AppState>) -> AppResult<Vec<git::RemoteInfo>> {
     let repo = state.open_repo()?;
@@ -41,10 +48,15 @@ pub async fn rename_remote(
 #[tauri::command]
 pub async fn fetch_remote(
     remote: Option<String>,
+    fetch_tags: Option<bool>,
+    operation_id: Option<String>,
     state: State<'_, AppState>,
-) -> AppResult<()> {
+) -> AppResult<Vec<git::FetchResult>> {
     let repo = state.open_repo()?;
-    git::fetch(&repo, remote.as_deref())
+    let ssh_key = configured_ssh_key(&state);
+    state.with_operation(operation_id.as_deref(), |cancel| {
+        git::fetch(&repo, remote.as_deref(), fetch_tags.unwrap_or(false), ssh_key.as_ref(), cancel)
+    })
 }
 
 #[tauri::command]
@@ -53,8 +65,8 @@ pub async fn pull_remote(
     branch: String,
     state: State<'_, AppState>,
 ) -> AppResult<String> {
-    let repo = state.open_repo()?;
-    git::pull(&repo, &remote, &branch)
+    let ssh_key = configured_ssh_key(&state);
+    state.with_repo_lock(|repo| git::pull(repo, &remote, &branch, ssh_key.as_ref())).await
 }
 
 #[tauri::command]
@@ -62,10 +74,21 @@ pub async fn push_remote(
     remote: String,
     branch: String,
     force: bool,
+    force_with_lease: Option<bool>,
+    set_upstream: Option<bool>,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
     let repo = state.open_repo()?;
-    git::push(&repo, &remote, &branch, force)
+    let ssh_key = configured_ssh_key(&state);
+    git::push(
+        &repo,
+        &remote,
+        &branch,
+        force,
+        force_with_lease.unwrap_or(false),
+        set_upstream.unwrap_or(false),
+        ssh_key.as_ref(),
+    )
 }
 
 #[tauri::command]
@@ -78,3 +101,48 @@ pub async fn set_upstream(
     let repo = state.open_repo()?;
     git::set_upstream(&repo, &branch, &remote, &remote_branch)
 }
+
+/// Fetches and reports what pushing `branch` would send, without pushing
+#[tauri::command]
+pub async fn push_dry_run(
+    remote: String,
+    branch: String,
+    operation_id: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<git::PushPreview> {
+    let repo = state.open_repo()?;
+    let ssh_key = configured_ssh_key(&state);
+    state.with_operation(operation_id.as_deref(), |cancel| {
+        git::push_dry_run(&repo, &remote, &branch, ssh_key.as_ref(), cancel)
+    })
+}
+
+/// Key path overriding the global one for the active repo
+#[tauri::command]
+pub async fn get_repo_ssh_key(state: State<'_, AppState>) -> AppResult<Option<String>> {
+    let path = state.require_repo_path()?;
+    Ok(AppConfig::load().repo_ssh_keys.get(path.to_string_lossy().as_ref()).cloned())
+}
+
+#[tauri::command]
+pub async fn set_repo_ssh_key(key_path: Option<String>, state: State<'_, AppState>) -> AppResult<()> {
+    let path = state.require_repo_path()?;
+    AppConfig::load().set_repo_ssh_key(&path.to_string_lossy(), key_path.as_deref())
+}
+
+/// Stores the passphrase in the OS keyring; `None` forgets it
+#[tauri::command]
+pub async fn set_ssh_passphrase(key_path: String, passphrase: Option<String>) -> AppResult<()> {
+    config::set_ssh_passphrase(&key_path, passphrase.as_deref())
+}
+
+#[tauri::command]
+pub async fn has_ssh_passphrase(key_path: String) -> AppResult<bool> {
+    Ok(config::ssh_passphrase(&key_path).is_some())
+}
+
+#[tauri::command]
+pub async fn unset_upstream(branch: String, state: State<'_, AppState>) -> AppResult<()> {
+    let repo = state.open_repo()?;
+    git::unset_upstream(&repo, &branch)
+}
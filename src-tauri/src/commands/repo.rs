@@ -1,9 +1,12 @@
-use crate::config::{AppConfig, RecentRepo};
-use crate::error::AppResult;
+use crate::config::{AppConfig, AppSettings, RecentRepo};
+use crate::error::{AppError, AppResult, ErrorKind};
 use crate::git;
 use crate::state::AppState;
 use std::path::{Component, Path, PathBuf};
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
+
+/// Event sent to every window whenever the open repos or the active one change
+pub const ACTIVE_REPO_CHANGED_EVENT: &str = "repo://active-changed";
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -49,36 +52,55 @@ fn resolve_repo_file_path(repo_path: &Path, relative_path: &str) -> AppResult<Pa
 #[tauri::command]
 pub async fn open_repo(
     path: String,
+    app: AppHandle,
     state: State<'_, AppState>,
 ) -> AppResult<git::RepoInfo> {
-    let repo_path = PathBuf::from(&path);
-    let info = git::get_repo_info(&repo_path)?;
+    let mut info = git::get_repo_info(&PathBuf::from(&path))?;
 
+    let mut config = AppConfig::load();
     if info.is_repo {
+        // A subfolder was discovered up to its repo; the root is what gets stored
+        let repo_path = PathBuf::from(&info.path);
+        let repo = git2::Repository::open(&repo_path).map_err(AppError::git_error)?;
+        info.last_branch = git::resolve_last_branch(&repo, config.last_branch(&info.path).as_deref());
+
         state.set_repo_path(repo_path);
 
         // Add to recent repos
-        let mut config = AppConfig::load();
-        config.add_recent_repo(&path);
+        config.add_recent_repo(&info.path);
+        emit_active_repo_changed(&app, &state);
+    } else if config.recent_repos.iter().any(|r| r.path == path) {
+        // A recent entry that stopped being a repo (deleted .git, moved) goes away
+        config.remove_recent_repo(&path);
     }
 
     Ok(info)
 }
 
 #[tauri::command]
-pub async fn close_repo(state: State<'_, AppState>) -> AppResult<()> {
+pub async fn close_repo(app: AppHandle, state: State<'_, AppState>) -> AppResult<()> {
     state.clear_repo();
+    emit_active_repo_changed(&app, &state);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn close_repo_by_id(id: String, state: State<'_, AppState>) -> AppResult<()> {
+pub async fn close_repo_by_id(
+    id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
     state.close_repo_by_id(&id);
+    emit_active_repo_changed(&app, &state);
     Ok(())
 }
 
 #[tauri::command]
 pub async fn get_open_repos(state: State<'_, AppState>) -> AppResult<Vec<OpenRepoInfo>> {
+    open_repo_list(&state)
+}
+
+fn open_repo_list(state: &AppState) -> AppResult<Vec<OpenRepoInfo>> {
     let repos = state.get_open_repos();
     let active_id = state.get_active_repo_id();
 
@@ -97,11 +119,45 @@ pub async fn get_open_repos(state: State<'_, AppState>) -> AppResult<Vec<OpenRep
 }
 
 #[tauri::command]
-pub async fn set_active_repo(id: String, state: State<'_, AppState>) -> AppResult<bool> {
-    Ok(state.set_active_repo(&id))
+pub async fn set_active_repo(
+    id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<bool> {
+    let changed = state.set_active_repo(&id);
+    if changed {
+        emit_active_repo_changed(&app, &state);
+    }
+    Ok(changed)
 }
 
-#[derive(serde::Serialize)]
+/// Payload of [`ACTIVE_REPO_CHANGED_EVENT`]; carries the whole tab list so
+/// listeners don't need another round-trip
+#[derive(Clone, serde::Serialize)]
+pub struct ActiveRepoChanged {
+    pub id: Option<String>,
+    pub path: Option<String>,
+    pub open_repos: Vec<OpenRepoInfo>,
+}
+
+/// Notifies all windows; the command already succeeded, so failures here
+/// only cost the other surfaces a refresh
+fn emit_active_repo_changed(app: &AppHandle, state: &AppState) {
+    let open_repos = match open_repo_list(state) {
+        Ok(repos) => repos,
+        Err(_) => return,
+    };
+    let payload = ActiveRepoChanged {
+        id: state.get_active_repo_id(),
+        path: state
+            .get_repo_path()
+            .map(|p| p.to_string_lossy().to_string()),
+        open_repos,
+    };
+    let _ = app.emit(ACTIVE_REPO_CHANGED_EVENT, payload);
+}
+
+#[derive(Clone, serde::Serialize)]
 pub struct OpenRepoInfo {
     pub id: String,
     pub path: String,
@@ -109,6 +165,43 @@ pub struct OpenRepoInfo {
     pub is_active: bool,
 }
 
+/// Tab badge data for one open repo; `error` is set instead of the counts
+/// when that repo can't be read, so one bad repo doesn't hide the others
+#[derive(serde::Serialize)]
+pub struct RepoStatusBadge {
+    pub id: String,
+    #[serde(flatten)]
+    pub summary: Option<git::RepoStatusSummary>,
+    pub error: Option<String>,
+}
+
+#[tauri::command]
+pub async fn get_all_repo_statuses(state: State<'_, AppState>) -> AppResult<Vec<RepoStatusBadge>> {
+    let badges = state
+        .get_open_repos()
+        .into_iter()
+        .map(|(id, path)| {
+            match git2::Repository::open(&path)
+                .map_err(AppError::git_error)
+                .and_then(|repo| git::get_status_summary(&repo))
+            {
+                Ok(summary) => RepoStatusBadge {
+                    id,
+                    summary: Some(summary),
+                    error: None,
+                },
+                Err(e) => RepoStatusBadge {
+                    id,
+                    summary: None,
+                    error: Some(e.to_string()),
+                },
+            }
+        })
+        .collect();
+
+    Ok(badges)
+}
+
 #[tauri::command]
 pub async fn get_repo_info(state: State<'_, AppState>) -> AppResult<git::RepoInfo> {
     let path = state.require_repo_path()?;
@@ -116,15 +209,44 @@ pub async fn get_repo_info(state: State<'_, AppState>) -> AppResult<git::RepoInf
 }
 
 #[tauri::command]
-pub async fn get_repo_status(state: State<'_, AppState>) -> AppResult<git::RepoStatus> {
+pub async fn get_repo_stats(
+    deep: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<git::RepoStats> {
+    let repo = state.open_repo()?;
+    git::get_repo_stats(&repo, deep.unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn run_gc(
+    aggressive: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<git::GcResult> {
+    let path = state.require_repo_path()?;
+    git::run_gc(&path, aggressive.unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn get_repo_status(
+    include_ignored: Option<bool>,
+    collapse_untracked: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<git::RepoStatus> {
     let repo = state.open_repo()?;
-    git::get_status(&repo)
+    git::get_status(&repo, include_ignored.unwrap_or(false), collapse_untracked.unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn is_working_tree_clean(state: State<'_, AppState>) -> AppResult<git::WorkingTreeState> {
+    let repo = state.open_repo()?;
+    git::is_working_tree_clean(&repo)
 }
 
 #[tauri::command]
 pub async fn init_repo(path: String, bare: bool) -> AppResult<git::RepoInfo> {
     let repo_path = PathBuf::from(&path);
-    git::init_repository(&repo_path, bare)?;
+    let config = AppConfig::load();
+    git::init_repository(&repo_path, bare, Some(&config.default_branch))?;
     git::get_repo_info(&repo_path)
 }
 
@@ -132,26 +254,37 @@ pub async fn init_repo(path: String, bare: bool) -> AppResult<git::RepoInfo> {
 pub async fn clone_repo(
     url: String,
     path: String,
+    operation_id: Option<String>,
     state: State<'_, AppState>,
-) -> AppResult<git::RepoInfo> {
+) -> AppResult<git::CloneResult> {
     let repo_path = PathBuf::from(&path);
-    git::clone_repository(&url, &repo_path)?;
-
-    let info = git::get_repo_info(&repo_path)?;
+    // Fails before touching state, so failed clones never reach recent repos
+    let ssh_key = AppConfig::load().ssh_key_for(None);
+    let result = state.with_operation(operation_id.as_deref(), |cancel| {
+        git::clone_repository(&url, &repo_path, ssh_key.as_ref(), cancel)
+    })?;
 
-    if info.is_repo {
+    if result.info.is_repo {
         state.set_repo_path(repo_path);
 
         let mut config = AppConfig::load();
         config.add_recent_repo(&path);
     }
 
-    Ok(info)
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn cancel_operation(
+    operation_id: String,
+    state: State<'_, AppState>,
+) -> AppResult<bool> {
+    Ok(state.cancel_operation(&operation_id))
 }
 
 #[tauri::command]
 pub async fn get_recent_repos() -> AppResult<Vec<RecentRepo>> {
-    let config = AppConfig::load();
+    let mut config = AppConfig::load();
     Ok(config.get_recent_repos())
 }
 
@@ -170,23 +303,52 @@ pub async fn clear_recent_repos() -> AppResult<()> {
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_settings() -> AppResult<AppSettings> {
+    Ok(AppConfig::load().settings())
+}
+
+#[tauri::command]
+pub async fn update_settings(settings: AppSettings) -> AppResult<AppSettings> {
+    let mut config = AppConfig::load();
+    config.update_settings(settings)?;
+    Ok(config.settings())
+}
+
 #[tauri::command]
 pub async fn get_git_config_value(
     key: String,
+    scope: Option<git::ConfigScope>,
     state: State<'_, AppState>,
-) -> AppResult<Option<String>> {
-    let repo = state.open_repo()?;
-    Ok(git::get_git_config(&repo, &key))
+) -> AppResult<Option<git::ConfigValue>> {
+    // Global and system values are readable without an open repository
+    let repo = state.open_repo().ok();
+    git::get_git_config(repo.as_ref(), &key, scope)
 }
 
 #[tauri::command]
 pub async fn set_git_config_value(
     key: String,
     value: String,
+    scope: Option<git::ConfigScope>,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
-    let repo = state.open_repo()?;
-    git::set_git_config(&repo, &key, &value)
+    let repo = state.open_repo().ok();
+    git::set_git_config(
+        repo.as_ref(),
+        &key,
+        &value,
+        scope.unwrap_or(git::ConfigScope::Local),
+    )
+}
+
+#[tauri::command]
+pub async fn list_git_config(
+    scope: Option<git::ConfigScope>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<git::ConfigEntry>> {
+    let repo = state.open_repo().ok();
+    git::list_git_config(repo.as_ref(), scope)
 }
 
 #[tauri::command]
@@ -212,64 +374,225 @@ pub async fn write_file(state: State<'_, AppState>, path: String, content: Strin
 #[tauri::command]
 pub async fn open_in_vscode(state: State<'_, AppState>) -> AppResult<()> {
     let path = state.require_repo_path()?;
-    let path_str = path.to_string_lossy().to_string();
+    launch_editor("code", &path, None, None, &path)
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        let mut cmd = std::process::Command::new("cmd");
-        cmd.args(["/C", "code", &path_str]);
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        cmd.spawn()
-            .map_err(|e| crate::error::AppError::with_details("VSCODE_ERROR", "Falha ao abrir VS Code", &e.to_string()))?;
+/// Opens `path` (default: the repo) in the editor from the settings,
+/// jumping to `line` when the editor has a syntax for it. Terminal editors
+/// run inside `emulator`
+#[tauri::command]
+pub async fn open_in_editor(
+    path: Option<String>,
+    line: Option<u32>,
+    emulator: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let repo_path = state.require_repo_path()?;
+    let target = resolve_open_target(repo_path.clone(), path.as_deref())?;
+    let editor = AppConfig::load().external_editor;
+    let editor = if editor.trim().is_empty() { "code" } else { editor.trim() };
+    launch_editor(editor, &target, line, emulator.as_deref(), &repo_path)
+}
+
+/// Absolute paths are used as given; relative ones must stay inside the repo
+fn resolve_open_target(repo_path: PathBuf, path: Option<&str>) -> AppResult<PathBuf> {
+    let target = match path.filter(|p| !p.trim().is_empty()) {
+        Some(p) if Path::new(p).is_absolute() => PathBuf::from(p),
+        Some(p) => resolve_repo_file_path(&repo_path, p)?,
+        None => repo_path,
+    };
+    if !target.exists() {
+        return Err(AppError::with_details(
+            "PATH_NOT_FOUND",
+            "Caminho nao encontrado",
+            &target.to_string_lossy(),
+        ));
     }
+    Ok(target)
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .args(["-a", "Visual Studio Code", &path_str])
-            .spawn()
-            .map_err(|e| crate::error::AppError::with_details("VSCODE_ERROR", "Falha ao abrir VS Code", &e.to_string()))?;
+fn editor_name(editor: &str) -> String {
+    Path::new(editor)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Editors that need a TTY, so they're run inside a terminal emulator
+fn is_terminal_editor(editor: &str) -> bool {
+    matches!(
+        editor_name(editor).as_str(),
+        "vi" | "vim" | "nvim" | "nano" | "emacs" | "micro" | "hx" | "kak"
+    )
+}
+
+/// Arguments that open `file` at `line` in a known editor; unknown editors
+/// just get the file
+fn editor_args(editor: &str, file: &str, line: Option<u32>) -> Vec<String> {
+    let name = editor_name(editor);
+    let Some(line) = line else {
+        return vec![file.to_string()];
+    };
+    match name.as_str() {
+        "code" | "code-insiders" | "codium" | "cursor" => vec!["-g".to_string(), format!("{}:{}", file, line)],
+        "subl" | "zed" => vec![format!("{}:{}", file, line)],
+        "idea" | "idea64" | "pycharm" | "webstorm" | "clion" | "goland" | "rider" | "phpstorm" | "rubymine" => {
+            vec!["--line".to_string(), line.to_string(), file.to_string()]
+        }
+        "gvim" | "gedit" | "vi" | "vim" | "nvim" | "nano" | "emacs" | "micro" | "kak" => {
+            vec![format!("+{}", line), file.to_string()]
+        }
+        "hx" => vec![format!("{}:{}", file, line)],
+        _ => vec![file.to_string()],
     }
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        std::process::Command::new("code")
-            .arg(&path_str)
-            .spawn()
-            .map_err(|e| crate::error::AppError::with_details("VSCODE_ERROR", "Falha ao abrir VS Code", &e.to_string()))?;
+/// Finds `program` like the shell would: as a path, or on PATH (with
+/// PATHEXT extensions on Windows)
+fn find_executable(program: &str) -> Option<PathBuf> {
+    let as_path = Path::new(program);
+    if as_path.components().count() > 1 {
+        return as_path.is_file().then(|| as_path.to_path_buf());
+    }
+
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .map(str::to_string)
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+
+    std::env::split_paths(&std::env::var_os("PATH")?).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}{}", program, ext)))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+fn launch_editor(
+    editor: &str,
+    target: &Path,
+    line: Option<u32>,
+    emulator: Option<&str>,
+    workdir: &Path,
+) -> AppResult<()> {
+    let editor_error = |e: std::io::Error| AppError::with_details("EDITOR_ERROR", "Falha ao abrir editor", &e.to_string());
+    let args = editor_args(editor, &target.to_string_lossy(), line);
+
+    let Some(program) = find_executable(editor) else {
+        // The VS Code app bundle works even without the `code` shell command
+        #[cfg(target_os = "macos")]
+        if editor == "code" {
+            std::process::Command::new("open")
+                .args(["-a", "Visual Studio Code"])
+                .arg(target)
+                .spawn()
+                .map_err(editor_error)?;
+            return Ok(());
+        }
+        return Err(AppError::with_details(
+            "EDITOR_NOT_FOUND",
+            "Editor nao encontrado. Configure o editor externo nas configuracoes",
+            editor,
+        ));
+    };
+
+    if is_terminal_editor(editor) {
+        let Some(emulator) = emulator.filter(|e| !e.trim().is_empty()) else {
+            return Err(AppError::with_details(
+                "TERMINAL_EDITOR",
+                "Editores de terminal precisam de um terminal externo. Configure-o nas configuracoes",
+                editor,
+            )
+            .with_kind(ErrorKind::Validation));
+        };
+        let run: Vec<String> = std::iter::once(program.to_string_lossy().to_string()).chain(args).collect();
+        return spawn_terminal(emulator, workdir, &run);
     }
 
+    // Spawned directly: std quotes the arguments itself, .cmd shims included
+    let mut cmd = std::process::Command::new(&program);
+    cmd.args(&args);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    cmd.spawn().map_err(editor_error)?;
+
     Ok(())
 }
 
 #[tauri::command]
 pub async fn open_in_explorer(state: State<'_, AppState>) -> AppResult<()> {
     let path = state.require_repo_path()?;
-    let path_str = path.to_string_lossy().to_string();
+    reveal_in_file_manager(&path)
+}
+
+/// Opens `path` (absolute, or relative to the active repo; defaults to the
+/// repo itself) in the system file manager, selecting it when it's a file
+#[tauri::command]
+pub async fn open_in_file_manager(path: Option<String>, state: State<'_, AppState>) -> AppResult<()> {
+    let repo_path = state.require_repo_path()?;
+    let target = resolve_open_target(repo_path, path.as_deref())?;
+    reveal_in_file_manager(&target)
+}
+
+fn file_manager_error(opener: &str, e: std::io::Error) -> AppError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        AppError::with_details(
+            "FILE_MANAGER_NOT_FOUND",
+            "Gerenciador de arquivos nao disponivel",
+            &format!("{} nao encontrado no PATH", opener),
+        )
+    } else {
+        AppError::with_details("EXPLORER_ERROR", "Falha ao abrir gerenciador de arquivos", &e.to_string())
+    }
+}
+
+/// Files are selected in their folder where the platform supports it
+fn reveal_in_file_manager(target: &Path) -> AppResult<()> {
+    let path_str = target.to_string_lossy().to_string();
+    let is_file = target.is_file();
 
     #[cfg(target_os = "windows")]
     {
         let mut cmd = std::process::Command::new("explorer");
-        cmd.arg(&path_str);
+        if is_file {
+            // explorer parses its own command line: std's quoting would wrap
+            // the whole `/select,...` switch, so only the path is quoted
+            cmd.raw_arg(format!("/select,\"{}\"", path_str));
+        } else {
+            cmd.arg(&path_str);
+        }
         cmd.creation_flags(CREATE_NO_WINDOW);
-        cmd.spawn()
-            .map_err(|e| crate::error::AppError::with_details("EXPLORER_ERROR", "Falha ao abrir Explorer", &e.to_string()))?;
+        cmd.spawn().map_err(|e| file_manager_error("explorer", e))?;
     }
 
     #[cfg(target_os = "macos")]
     {
-        std::process::Command::new("open")
-            .arg(&path_str)
+        let mut cmd = std::process::Command::new("open");
+        if is_file {
+            cmd.arg("-R");
+        }
+        cmd.arg(&path_str)
             .spawn()
-            .map_err(|e| crate::error::AppError::with_details("EXPLORER_ERROR", "Falha ao abrir Finder", &e.to_string()))?;
+            .map_err(|e| file_manager_error("open", e))?;
     }
 
     #[cfg(target_os = "linux")]
     {
+        // xdg-open can't select a file; open the folder that holds it
+        let folder = if is_file {
+            target.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or(path_str)
+        } else {
+            path_str
+        };
         std::process::Command::new("xdg-open")
-            .arg(&path_str)
+            .arg(&folder)
             .spawn()
-            .map_err(|e| crate::error::AppError::with_details("EXPLORER_ERROR", "Falha ao abrir gerenciador de arquivos", &e.to_string()))?;
+            .map_err(|e| file_manager_error("xdg-open", e))?;
     }
 
     Ok(())
@@ -278,119 +601,212 @@ pub async fn open_in_explorer(state: State<'_, AppState>) -> AppResult<()> {
 #[tauri::command]
 pub async fn open_in_terminal(emulator: String, state: State<'_, AppState>) -> AppResult<()> {
     let path = state.require_repo_path()?;
-    let path_str = path.to_string_lossy().to_string();
+    spawn_terminal(&emulator, &path, &[])
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        let mut cmd = match emulator.as_str() {
-            "gnome-terminal" => {
-                let mut c = std::process::Command::new("gnome-terminal");
-                c.arg(format!("--working-directory={}", path_str));
-                c
-            }
-            "konsole" => {
-                let mut c = std::process::Command::new("konsole");
-                c.args(["--workdir", &path_str]);
-                c
-            }
-            "xfce4-terminal" => {
-                let mut c = std::process::Command::new("xfce4-terminal");
-                c.args(["--working-directory", &path_str]);
-                c
+fn spawn_terminal(emulator: &str, dir: &Path, run: &[String]) -> AppResult<()> {
+    let mut cmd = terminal_command(emulator, &dir.to_string_lossy(), run);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    cmd.spawn()
+        .map_err(|e| AppError::with_details("TERMINAL_ERROR", "Falha ao abrir terminal", &e.to_string()))?;
+    Ok(())
+}
+
+/// Quotes `arg` for a POSIX shell
+#[cfg(unix)]
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Opens `emulator` in `dir`, running `run` instead of a shell when it's not empty
+#[cfg(target_os = "linux")]
+fn terminal_command(emulator: &str, dir: &str, run: &[String]) -> std::process::Command {
+    let mut c = std::process::Command::new(emulator);
+    c.current_dir(dir);
+    match emulator {
+        "gnome-terminal" => {
+            c.arg(format!("--working-directory={}", dir));
+            if !run.is_empty() {
+                c.arg("--").args(run);
             }
-            "tilix" => {
-                let mut c = std::process::Command::new("tilix");
-                c.args(["--working-directory", &path_str]);
-                c
+        }
+        "konsole" => {
+            c.args(["--workdir", dir]);
+            if !run.is_empty() {
+                c.arg("-e").args(run);
             }
-            "alacritty" => {
-                let mut c = std::process::Command::new("alacritty");
-                c.args(["--working-directory", &path_str]);
-                c
+        }
+        "xfce4-terminal" => {
+            c.args(["--working-directory", dir]);
+            if !run.is_empty() {
+                c.arg("-x").args(run);
             }
-            "kitty" => {
-                let mut c = std::process::Command::new("kitty");
-                c.args(["--directory", &path_str]);
-                c
+        }
+        "tilix" => {
+            c.args(["--working-directory", dir]);
+            if !run.is_empty() {
+                // tilix takes the whole command line as a single argument
+                let line: Vec<String> = run.iter().map(|a| shell_quote(a)).collect();
+                c.args(["-e", &line.join(" ")]);
             }
-            "xterm" => {
-                let mut c = std::process::Command::new("xterm");
-                c.args(["-e", "bash", "-c", &format!("cd '{}'; exec bash", path_str)]);
-                c
+        }
+        "kitty" => {
+            c.args(["--directory", dir]).args(run);
+        }
+        "xterm" => {
+            if run.is_empty() {
+                c.args(["-e", "bash", "-c", &format!("cd '{}'; exec bash", dir)]);
+            } else {
+                c.arg("-e").args(run);
             }
-            _ => {
-                let mut c = std::process::Command::new(&emulator);
-                c.args(["--working-directory", &path_str]);
-                c
+        }
+        // alacritty and anything unknown
+        _ => {
+            c.args(["--working-directory", dir]);
+            if !run.is_empty() {
+                c.arg("-e").args(run);
             }
-        };
-        cmd.spawn()
-            .map_err(|e| crate::error::AppError::with_details("TERMINAL_ERROR", "Falha ao abrir terminal", &e.to_string()))?;
+        }
     }
+    c
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        let mut cmd = match emulator.as_str() {
-            "terminal" => {
-                let mut c = std::process::Command::new("open");
-                c.args(["-a", "Terminal", &path_str]);
-                c
-            }
-            "iterm" => {
-                let mut c = std::process::Command::new("open");
-                c.args(["-a", "iTerm", &path_str]);
-                c
-            }
-            "alacritty" => {
-                let mut c = std::process::Command::new("alacritty");
-                c.args(["--working-directory", &path_str]);
-                c
-            }
-            "kitty" => {
-                let mut c = std::process::Command::new("kitty");
-                c.args(["--directory", &path_str]);
-                c
+/// Opens `emulator` in `dir`, running `run` instead of a shell when it's not empty
+#[cfg(target_os = "macos")]
+fn terminal_command(emulator: &str, dir: &str, run: &[String]) -> std::process::Command {
+    match emulator {
+        "alacritty" => {
+            let mut c = std::process::Command::new("alacritty");
+            c.args(["--working-directory", dir]);
+            if !run.is_empty() {
+                c.arg("-e").args(run);
             }
-            _ => {
-                let mut c = std::process::Command::new("open");
-                c.args(["-a", &emulator, &path_str]);
-                c
-            }
-        };
-        cmd.spawn()
-            .map_err(|e| crate::error::AppError::with_details("TERMINAL_ERROR", "Falha ao abrir terminal", &e.to_string()))?;
+            c
+        }
+        "kitty" => {
+            let mut c = std::process::Command::new("kitty");
+            c.args(["--directory", dir]).args(run);
+            c
+        }
+        _ if !run.is_empty() => {
+            // `open -a` can't pass a command; have the app run it through AppleScript
+            let quoted: Vec<String> = run.iter().map(|a| shell_quote(a)).collect();
+            let line = format!("cd {} && {}", shell_quote(dir), quoted.join(" "))
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            let script = if emulator == "iterm" {
+                format!(
+                    "tell application \"iTerm\"\nactivate\ncreate window with default profile\ntell current session of current window to write text \"{}\"\nend tell",
+                    line
+                )
+            } else {
+                format!("tell application \"Terminal\"\nactivate\ndo script \"{}\"\nend tell", line)
+            };
+            let mut c = std::process::Command::new("osascript");
+            c.args(["-e", &script]);
+            c
+        }
+        "terminal" => {
+            let mut c = std::process::Command::new("open");
+            c.args(["-a", "Terminal", dir]);
+            c
+        }
+        "iterm" => {
+            let mut c = std::process::Command::new("open");
+            c.args(["-a", "iTerm", dir]);
+            c
+        }
+        _ => {
+            let mut c = std::process::Command::new("open");
+            c.args(["-a", emulator, dir]);
+            c
+        }
     }
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        let mut cmd = match emulator.as_str() {
-            "wt" => {
-                let mut c = std::process::Command::new("wt");
-                c.args(["-d", &path_str]);
-                c
-            }
-            "powershell" => {
-                let mut c = std::process::Command::new("powershell");
-                c.args(["-NoExit", "-Command", &format!("cd '{}'", path_str)]);
-                c
+/// Opens `emulator` in `dir`, running `run` instead of a shell when it's not empty
+#[cfg(target_os = "windows")]
+fn terminal_command(emulator: &str, dir: &str, run: &[String]) -> std::process::Command {
+    match emulator {
+        "wt" => {
+            let mut c = std::process::Command::new("wt");
+            c.args(["-d", dir]).args(run);
+            c
+        }
+        "powershell" => {
+            let mut c = std::process::Command::new("powershell");
+            let mut script = format!("cd '{}'", dir.replace('\'', "''"));
+            if !run.is_empty() {
+                let line: Vec<String> = run.iter().map(|a| format!("'{}'", a.replace('\'', "''"))).collect();
+                script.push_str(&format!("; & {}", line.join(" ")));
             }
-            _ => {
-                let mut c = std::process::Command::new("cmd");
-                c.args(["/K", &format!("cd /d {}", path_str)]);
-                c
+            c.args(["-NoExit", "-Command", &script]);
+            c
+        }
+        _ => {
+            let mut c = std::process::Command::new("cmd");
+            if run.is_empty() {
+                c.args(["/K", &format!("cd /d {}", dir)]);
+            } else {
+                c.current_dir(dir);
+                c.arg("/C").args(run);
             }
-        };
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        cmd.spawn()
-            .map_err(|e| crate::error::AppError::with_details("TERMINAL_ERROR", "Falha ao abrir terminal", &e.to_string()))?;
+            c
+        }
     }
-
-    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::resolve_repo_file_path;
+    use super::{editor_args, is_terminal_editor, resolve_repo_file_path};
+
+    #[test]
+    fn editor_args_usa_sintaxe_de_linha_de_cada_editor() {
+        assert_eq!(editor_args("code", "a.rs", Some(12)), vec!["-g", "a.rs:12"]);
+        assert_eq!(editor_args("/usr/bin/subl", "a.rs", Some(3)), vec!["a.rs:3"]);
+        assert_eq!(editor_args("idea", "a.rs", Some(7)), vec!["--line", "7", "a.rs"]);
+        assert_eq!(editor_args("gvim", "a.rs", Some(1)), vec!["+1", "a.rs"]);
+        assert_eq!(editor_args("nvim", "a.rs", Some(4)), vec!["+4", "a.rs"]);
+        assert_eq!(editor_args("hx", "a.rs", Some(4)), vec!["a.rs:4"]);
+        assert_eq!(editor_args("desconhecido", "a.rs", Some(1)), vec!["a.rs"]);
+        assert_eq!(editor_args("code", "a.rs", None), vec!["a.rs"]);
+    }
+
+    #[test]
+    fn is_terminal_editor_reconhece_editores_sem_gui() {
+        assert!(is_terminal_editor("nvim"));
+        assert!(is_terminal_editor("/usr/bin/vim"));
+        assert!(is_terminal_editor("nano.exe"));
+        assert!(!is_terminal_editor("gvim"));
+        assert!(!is_terminal_editor("code"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn terminal_command_roda_o_editor_dentro_do_emulador() {
+        let run = vec!["/usr/bin/nvim".to_string(), "+3".to_string(), "/repo/a b.rs".to_string()];
+        let args = |emulator: &str, run: &[String]| -> Vec<String> {
+            super::terminal_command(emulator, "/repo", run)
+                .get_args()
+                .map(|a| a.to_string_lossy().to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            args("gnome-terminal", &run),
+            vec!["--working-directory=/repo", "--", "/usr/bin/nvim", "+3", "/repo/a b.rs"]
+        );
+        assert_eq!(
+            args("alacritty", &run),
+            vec!["--working-directory", "/repo", "-e", "/usr/bin/nvim", "+3", "/repo/a b.rs"]
+        );
+        assert_eq!(
+            args("tilix", &run),
+            vec!["--working-directory", "/repo", "-e", "'/usr/bin/nvim' '+3' '/repo/a b.rs'"]
+        );
+        assert_eq!(args("konsole", &[]), vec!["--workdir", "/repo"]);
+    }
 
     #[test]
     fn resolve_repo_file_path_aceita_caminho_relativo_valido() {
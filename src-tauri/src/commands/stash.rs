@@ -14,43 +14,55 @@ pub async fn create_stash(
     message: Option<String>,
     include_untracked: bool,
     keep_index: bool,
+    staged_only: Option<bool>,
     files: Option<Vec<String>>,
     state: State<'_, AppState>,
 ) -> AppResult<String> {
-    // If specific files are provided, use git command
-    if let Some(file_list) = files {
-        if !file_list.is_empty() {
-            let repo_path = state.require_repo_path()?;
-            return git::create_stash_with_files(
-                &repo_path,
-                message.as_deref(),
-                include_untracked,
-                &file_list,
-            );
-        }
+    let staged_only = staged_only.unwrap_or(false);
+    git::validate_stash_options(include_untracked, keep_index, staged_only)?;
+
+    // Specific files or staged-only stashes go through the git command
+    let file_list = files.unwrap_or_default();
+    if staged_only || !file_list.is_empty() {
+        let repo_path = state.require_repo_path()?;
+        return git::create_stash_with_files(
+            &repo_path,
+            message.as_deref(),
+            include_untracked,
+            staged_only,
+            &file_list,
+        );
     }
 
     // Otherwise use libgit2 for full stash
-    let mut repo = state.open_repo()?;
-    git::create_stash(&mut repo, message.as_deref(), include_untracked, keep_index)
+    state
+        .with_repo_lock_mut(|repo| git::create_stash(repo, message.as_deref(), include_untracked, keep_index))
+        .await
 }
 
 #[tauri::command]
 pub async fn apply_stash(
     index: usize,
     state: State<'_, AppState>,
-) -> AppResult<()> {
-    let mut repo = state.open_repo()?;
-    git::apply_stash(&mut repo, index, false)
+) -> AppResult<git::StashApplyResult> {
+    state.with_repo_lock_mut(|repo| git::apply_stash(repo, index, false)).await
 }
 
 #[tauri::command]
 pub async fn pop_stash(
     index: usize,
     state: State<'_, AppState>,
+) -> AppResult<git::StashApplyResult> {
+    state.with_repo_lock_mut(|repo| git::apply_stash(repo, index, true)).await
+}
+
+#[tauri::command]
+pub async fn rename_stash(
+    index: usize,
+    message: String,
+    state: State<'_, AppState>,
 ) -> AppResult<()> {
-    let mut repo = state.open_repo()?;
-    git::apply_stash(&mut repo, index, true)
+    state.with_repo_lock(|repo| git::rename_stash(repo, index, &message)).await
 }
 
 #[tauri::command]
@@ -58,12 +70,10 @@ pub async fn drop_stash(
     index: usize,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
-    let mut repo = state.open_repo()?;
-    git::drop_stash(&mut repo, index)
+    state.with_repo_lock_mut(|repo| git::drop_stash(repo, index)).await
 }
 
 #[tauri::command]
 pub async fn clear_stashes(state: State<'_, AppState>) -> AppResult<()> {
-    let mut repo = state.open_repo()?;
-    git::clear_stashes(&mut repo)
+    state.with_repo_lock_mut(git::clear_stashes).await
 }
@@ -1,15 +1,23 @@
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::git::{self, CommitRules, SshKey};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const MAX_RECENT_REPOS: usize = 10;
+const ALLOWED_THEMES: &[&str] = &["light", "dark", "system"];
+/// Service name for secrets kept in the OS keyring
+const KEYRING_SERVICE: &str = "com.gitarc.app";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RecentRepo {
     pub path: String,
     pub name: String,
     pub last_opened: i64,
+    /// Branch checked out the last time the app switched branches here
+    #[serde(default)]
+    pub last_branch: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -17,6 +25,102 @@ pub struct AppConfig {
     pub recent_repos: Vec<RecentRepo>,
     pub theme: String,
     pub default_branch: String,
+    #[serde(default)]
+    pub run_hooks: bool,
+    #[serde(default)]
+    pub commit_rules: CommitRules,
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+    #[serde(default)]
+    pub external_editor: String,
+    /// Per-repo overrides of `ssh_key_path`, keyed by repo path
+    #[serde(default)]
+    pub repo_ssh_keys: HashMap<String, String>,
+}
+
+/// User-editable part of `AppConfig`; recent repos are managed separately
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AppSettings {
+    pub theme: String,
+    pub default_branch: String,
+    /// Run pre-commit/commit-msg hooks when committing
+    #[serde(default)]
+    pub run_hooks: bool,
+    /// Conventional Commits checks shown before committing
+    #[serde(default)]
+    pub commit_rules: CommitRules,
+    /// Private key tried when the SSH agent has none that works; its
+    /// passphrase lives in the OS keyring, not here
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+    /// Editor command (code, subl, idea, nvim...); empty means VS Code
+    #[serde(default)]
+    pub external_editor: String,
+}
+
+/// Rejects key paths that don't point at a file; empty means "not set"
+fn validate_ssh_key_path(path: Option<&str>) -> AppResult<()> {
+    match path.filter(|p| !p.is_empty()) {
+        Some(p) if !std::path::Path::new(p).is_file() => Err(AppError::with_details(
+            "INVALID_SSH_KEY_PATH",
+            "Arquivo de chave SSH nao encontrado",
+            p,
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn passphrase_entry(key_path: &str) -> AppResult<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, &format!("ssh:{}", key_path)).map_err(keyring_error)
+}
+
+fn keyring_error(e: keyring::Error) -> AppError {
+    AppError::with_details("KEYRING_ERROR", "Falha ao acessar o cofre de senhas do sistema", &e.to_string())
+}
+
+/// Passphrase saved for a private key, if any
+pub fn ssh_passphrase(key_path: &str) -> Option<String> {
+    passphrase_entry(key_path).ok()?.get_password().ok()
+}
+
+/// Saves (or with `None`, forgets) a key's passphrase in the OS keyring
+pub fn set_ssh_passphrase(key_path: &str, passphrase: Option<&str>) -> AppResult<()> {
+    let entry = passphrase_entry(key_path)?;
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => entry.set_password(passphrase).map_err(keyring_error),
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(keyring_error(e)),
+        },
+    }
+}
+
+impl AppSettings {
+    pub fn validate(&self) -> AppResult<()> {
+        // Empty values mean "not set" and keep the app/git defaults
+        if !self.theme.is_empty() && !ALLOWED_THEMES.contains(&self.theme.as_str()) {
+            return Err(AppError::with_details(
+                "INVALID_THEME",
+                "Tema invalido",
+                &format!("{} (permitidos: {})", self.theme, ALLOWED_THEMES.join(", ")),
+            ));
+        }
+
+        if !self.default_branch.is_empty()
+            && !git2::Reference::is_valid_name(&format!("refs/heads/{}", self.default_branch))
+        {
+            return Err(AppError::with_details(
+                "INVALID_BRANCH_NAME",
+                "Nome de branch padrao invalido",
+                &self.default_branch,
+            ));
+        }
+
+        self.commit_rules.subject_regex()?;
+        validate_ssh_key_path(self.ssh_key_path.as_deref())?;
+
+        Ok(())
+    }
 }
 
 impl AppConfig {
@@ -47,34 +151,115 @@ impl AppConfig {
         Ok(())
     }
 
+    pub fn settings(&self) -> AppSettings {
+        AppSettings {
+            theme: self.theme.clone(),
+            default_branch: self.default_branch.clone(),
+            run_hooks: self.run_hooks,
+            commit_rules: self.commit_rules.clone(),
+            ssh_key_path: self.ssh_key_path.clone(),
+            external_editor: self.external_editor.clone(),
+        }
+    }
+
+    pub fn update_settings(&mut self, settings: AppSettings) -> AppResult<()> {
+        settings.validate()?;
+        self.theme = settings.theme;
+        self.default_branch = settings.default_branch;
+        self.run_hooks = settings.run_hooks;
+        self.commit_rules = settings.commit_rules;
+        self.ssh_key_path = settings.ssh_key_path.filter(|p| !p.is_empty());
+        self.external_editor = settings.external_editor.trim().to_string();
+        self.save()
+    }
+
+    /// SSH key for a repo: its own override, else the global one
+    pub fn ssh_key_for(&self, repo_path: Option<&str>) -> Option<SshKey> {
+        let path = self.ssh_key_path_for(repo_path)?;
+        Some(SshKey {
+            path: PathBuf::from(path),
+            passphrase: ssh_passphrase(path),
+        })
+    }
+
+    /// Just the path resolution of `ssh_key_for`, without a keyring lookup
+    fn ssh_key_path_for(&self, repo_path: Option<&str>) -> Option<&String> {
+        repo_path
+            .and_then(|repo| self.repo_ssh_keys.get(repo))
+            .or(self.ssh_key_path.as_ref())
+    }
+
+    pub fn set_repo_ssh_key(&mut self, repo_path: &str, key_path: Option<&str>) -> AppResult<()> {
+        validate_ssh_key_path(key_path)?;
+        match key_path.filter(|p| !p.is_empty()) {
+            Some(key_path) => self.repo_ssh_keys.insert(repo_path.to_string(), key_path.to_string()),
+            None => self.repo_ssh_keys.remove(repo_path),
+        };
+        self.save()
+    }
+
     pub fn add_recent_repo(&mut self, path: &str) {
-        // Remove if exists
-        self.recent_repos.retain(|r| r.path != path);
+        self.push_recent_repo(path);
+        let _ = self.save();
+    }
+
+    /// Moves `path` to the front of the recent list, in its canonical form
+    fn push_recent_repo(&mut self, path: &str) {
+        // Remove if exists, keeping what we remembered about it
+        let last_branch = self.last_branch(path);
+        let key = git::repo_path_key(Path::new(path));
+        self.recent_repos
+            .retain(|r| git::repo_path_key(Path::new(&r.path)) != key);
+        let canonical = git::canonical_repo_path(Path::new(path));
+        let path = canonical.to_string_lossy().to_string();
 
         // Get name from path
-        let name = PathBuf::from(path)
+        let name = canonical
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| path.to_string());
+            .unwrap_or_else(|| path.clone());
 
         // Add to front
         self.recent_repos.insert(
             0,
             RecentRepo {
-                path: path.to_string(),
+                path,
                 name,
                 last_opened: std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .map(|d| d.as_secs() as i64)
                     .unwrap_or(0),
+                last_branch,
             },
         );
 
         // Limit size
         self.recent_repos.truncate(MAX_RECENT_REPOS);
+    }
 
-        // Save
-        let _ = self.save();
+    pub fn last_branch(&self, path: &str) -> Option<String> {
+        self.recent_repos
+            .iter()
+            .find(|r| r.path == path)
+            .or_else(|| {
+                // Older entries may have been saved in another spelling
+                let key = git::repo_path_key(Path::new(path));
+                self.recent_repos
+                    .iter()
+                    .find(|r| git::repo_path_key(Path::new(&r.path)) == key)
+            })
+            .and_then(|r| r.last_branch.clone())
+    }
+
+    /// Remembers the branch for a recent repo; unknown paths are ignored
+    pub fn set_last_branch(&mut self, path: &str, branch: &str) {
+        let Some(recent) = self.recent_repos.iter_mut().find(|r| r.path == path) else {
+            return;
+        };
+        if recent.last_branch.as_deref() != Some(branch) {
+            recent.last_branch = Some(branch.to_string());
+            let _ = self.save();
+        }
     }
 
     pub fn remove_recent_repo(&mut self, path: &str) {
@@ -82,11 +267,125 @@ impl AppConfig {
         let _ = self.save();
     }
 
-    pub fn get_recent_repos(&self) -> Vec<RecentRepo> {
-        self.recent_repos
-            .iter()
-            .filter(|r| std::path::Path::new(&r.path).exists())
-            .cloned()
-            .collect()
+    /// Drops recent repos whose folder no longer exists; true if any were removed
+    fn drop_missing_recent_repos(&mut self) -> bool {
+        let before = self.recent_repos.len();
+        self.recent_repos.retain(|r| std::path::Path::new(&r.path).exists());
+        self.recent_repos.len() != before
+    }
+
+    /// Recent repos that still exist; moved or deleted ones are pruned from disk too
+    pub fn get_recent_repos(&mut self) -> Vec<RecentRepo> {
+        if self.drop_missing_recent_repos() {
+            let _ = self.save();
+        }
+        self.recent_repos.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(theme: &str, default_branch: &str) -> AppSettings {
+        AppSettings {
+            theme: theme.to_string(),
+            default_branch: default_branch.to_string(),
+            run_hooks: false,
+            commit_rules: CommitRules::default(),
+            ssh_key_path: None,
+            external_editor: String::new(),
+        }
+    }
+
+    #[test]
+    fn validate_aceita_temas_conhecidos_e_vazio() {
+        assert!(settings("dark", "main").validate().is_ok());
+        assert!(settings("system", "").validate().is_ok());
+        assert!(settings("", "").validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejeita_tema_desconhecido() {
+        let err = settings("neon", "main").validate().unwrap_err();
+        assert_eq!(err.code, "INVALID_THEME");
+    }
+
+    #[test]
+    fn validate_rejeita_branch_padrao_invalida() {
+        let err = settings("dark", "nome invalido..").validate().unwrap_err();
+        assert_eq!(err.code, "INVALID_BRANCH_NAME");
+    }
+
+    #[test]
+    fn push_recent_repo_nao_duplica_grafias_do_mesmo_caminho() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("Repo");
+        fs::create_dir(&repo).unwrap();
+        let plain = repo.to_string_lossy().to_string();
+
+        let mut config = AppConfig::default();
+        config.recent_repos.push(RecentRepo {
+            path: format!("{}{}", plain, std::path::MAIN_SEPARATOR),
+            name: "Repo".to_string(),
+            last_opened: 0,
+            last_branch: Some("develop".to_string()),
+        });
+        config.push_recent_repo(&plain);
+        config.push_recent_repo(&repo.join(".").to_string_lossy());
+
+        assert_eq!(config.recent_repos.len(), 1);
+        let recent = &config.recent_repos[0];
+        assert_eq!(recent.path, git::canonical_repo_path(&repo).to_string_lossy());
+        assert_eq!(recent.name, "Repo");
+        assert_eq!(recent.last_branch.as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn drop_missing_recent_repos_remove_caminhos_inexistentes() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().to_string_lossy().to_string();
+        let mut config = AppConfig::default();
+        for path in [existing.clone(), dir.path().join("sumiu").to_string_lossy().to_string()] {
+            config.recent_repos.push(RecentRepo {
+                path,
+                name: "repo".to_string(),
+                last_opened: 0,
+                last_branch: None,
+            });
+        }
+
+        assert!(config.drop_missing_recent_repos());
+        assert_eq!(config.recent_repos.len(), 1);
+        assert_eq!(config.recent_repos[0].path, existing);
+        assert!(!config.drop_missing_recent_repos());
+    }
+
+    #[test]
+    fn ssh_key_do_repo_tem_prioridade_sobre_a_global() {
+        let mut config = AppConfig {
+            ssh_key_path: Some("/chaves/global".to_string()),
+            ..AppConfig::default()
+        };
+        config
+            .repo_ssh_keys
+            .insert("/repos/app".to_string(), "/chaves/app".to_string());
+
+        let key = |repo| config.ssh_key_path_for(repo).map(String::as_str);
+        assert_eq!(key(Some("/repos/app")), Some("/chaves/app"));
+        assert_eq!(key(Some("/repos/outro")), Some("/chaves/global"));
+        assert_eq!(key(None), Some("/chaves/global"));
+
+        let mut invalid = settings("dark", "main");
+        invalid.ssh_key_path = Some("/nao/existe/id_ed25519".to_string());
+        assert_eq!(invalid.validate().unwrap_err().code, "INVALID_SSH_KEY_PATH");
+    }
+
+    #[test]
+    fn validate_rejeita_padrao_de_commit_invalido() {
+        let mut invalid = settings("dark", "main");
+        invalid.commit_rules.subject_pattern = Some("[".to_string());
+        let err = invalid.validate().unwrap_err();
+        assert_eq!(err.code, "INVALID_COMMIT_RULES");
     }
 }
@@ -1,9 +1,81 @@
 use serde::Serialize;
 use std::fmt;
 
+/// Broad error category, so the frontend can react without matching every code
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    NotFound,
+    Conflict,
+    Auth,
+    Network,
+    Validation,
+    Internal,
+}
+
+impl ErrorKind {
+    /// Best-effort category for codes built through `new`/`with_details`
+    pub fn from_code(code: &str) -> Self {
+        if code.ends_with("NOT_FOUND") || code.ends_with("NOT_IN_COMMIT") {
+            ErrorKind::NotFound
+        } else if code.contains("CONFLICT") || code == "UNRESOLVED_CONFLICTS" {
+            ErrorKind::Conflict
+        } else if code.contains("AUTH") {
+            ErrorKind::Auth
+        } else if matches!(code, "PUSH_FAILED" | "PULL_FAILED" | "FETCH_FAILED" | "CLONE_FAILED") {
+            ErrorKind::Network
+        } else if code.starts_with("INVALID_")
+            || code.starts_with("NOT_")
+            || code.starts_with("NO_")
+            || code.starts_with("NOTHING_")
+            || code.starts_with("CANNOT_")
+            || code.ends_with("_EXISTS")
+            || code.ends_with("_NOT_EMPTY")
+            || code.ends_with("_NOT_CONFIGURED")
+            || code == "HOOK_FAILED"
+        {
+            ErrorKind::Validation
+        } else {
+            ErrorKind::Internal
+        }
+    }
+}
+
+/// Specific code, message and kind for a libgit2 error, by error code then class
+fn classify_git2(e: &git2::Error) -> (&'static str, &'static str, ErrorKind) {
+    use git2::{ErrorClass, ErrorCode};
+    match e.code() {
+        ErrorCode::NotFound => ("NOT_FOUND", "Objeto não encontrado", ErrorKind::NotFound),
+        ErrorCode::UnbornBranch => ("UNBORN_BRANCH", "Branch ainda não tem commits", ErrorKind::NotFound),
+        ErrorCode::Exists => ("ALREADY_EXISTS", "Já existe", ErrorKind::Validation),
+        ErrorCode::Ambiguous => ("AMBIGUOUS_REF", "Referência ambígua", ErrorKind::Validation),
+        ErrorCode::Auth => ("AUTH_FAILED", "Falha de autenticação", ErrorKind::Auth),
+        ErrorCode::Certificate => ("CERTIFICATE_ERROR", "Certificado inválido", ErrorKind::Auth),
+        ErrorCode::Conflict => ("CONFLICT", "Alterações locais seriam sobrescritas", ErrorKind::Conflict),
+        ErrorCode::MergeConflict => ("MERGE_CONFLICT", "Conflitos de merge detectados", ErrorKind::Conflict),
+        ErrorCode::Unmerged => ("UNMERGED_ENTRIES", "Existem conflitos não resolvidos", ErrorKind::Conflict),
+        ErrorCode::Uncommitted | ErrorCode::IndexDirty => (
+            "UNCOMMITTED_CHANGES",
+            "Existem alterações não commitadas",
+            ErrorKind::Validation,
+        ),
+        ErrorCode::NotFastForward => ("NOT_FAST_FORWARD", "Não é fast-forward", ErrorKind::Validation),
+        ErrorCode::Locked => ("REPO_LOCKED", "Repositório bloqueado por outro processo", ErrorKind::Conflict),
+        ErrorCode::BareRepo => ("BARE_REPO", "Operação não suportada em repositório bare", ErrorKind::Validation),
+        ErrorCode::InvalidSpec | ErrorCode::Invalid => ("INVALID_SPEC", "Especificação inválida", ErrorKind::Validation),
+        _ => match e.class() {
+            ErrorClass::Net | ErrorClass::Ssl | ErrorClass::Ssh | ErrorClass::Http => {
+                ("NETWORK_ERROR", "Erro de rede", ErrorKind::Network)
+            }
+            _ => ("GIT_ERROR", "Erro do Git", ErrorKind::Internal),
+        },
+    }
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct AppError {
     pub code: String,
+    pub kind: ErrorKind,
     pub message: String,
     pub details: Option<String>,
 }
@@ -13,6 +85,7 @@ impl AppError {
     pub fn new(code: &str, message: &str) -> Self {
         Self {
             code: code.to_string(),
+            kind: ErrorKind::from_code(code),
             message: message.to_string(),
             details: None,
         }
@@ -21,22 +94,30 @@ impl AppError {
     pub fn with_details(code: &str, message: &str, details: &str) -> Self {
         Self {
             code: code.to_string(),
+            kind: ErrorKind::from_code(code),
             message: message.to_string(),
             details: Some(details.to_string()),
         }
     }
 
+    pub fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
     // Repository errors
     pub fn no_repo() -> Self {
-        Self::new("NO_REPO", "Nenhum repositório aberto")
+        Self::new("NO_REPO", "Nenhum repositório aberto").with_kind(ErrorKind::Validation)
     }
 
     pub fn invalid_repo(path: &str) -> Self {
         Self::with_details("INVALID_REPO", "Diretório não é um repositório Git", path)
+            .with_kind(ErrorKind::Validation)
     }
 
     pub fn repo_not_found(path: &str) -> Self {
         Self::with_details("REPO_NOT_FOUND", "Caminho não encontrado", path)
+            .with_kind(ErrorKind::NotFound)
     }
 
     // Git config errors
@@ -46,64 +127,84 @@ impl AppError {
             "Usuário Git não configurado",
             "Configure com: git config --global user.name \"Seu Nome\" && git config --global user.email \"seu@email.com\""
         )
+        .with_kind(ErrorKind::Validation)
     }
 
     // Branch errors
     pub fn branch_not_found(name: &str) -> Self {
         Self::with_details("BRANCH_NOT_FOUND", "Branch não encontrada", name)
+            .with_kind(ErrorKind::NotFound)
     }
 
     pub fn branch_already_exists(name: &str) -> Self {
         Self::with_details("BRANCH_EXISTS", "Branch já existe", name)
+            .with_kind(ErrorKind::Validation)
     }
 
     pub fn cannot_delete_current_branch() -> Self {
         Self::new("CANNOT_DELETE_CURRENT", "Não é possível deletar a branch atual")
+            .with_kind(ErrorKind::Validation)
     }
 
     // Commit errors
     pub fn nothing_to_commit() -> Self {
         Self::new("NOTHING_TO_COMMIT", "Nenhuma alteração para commit")
+            .with_kind(ErrorKind::Validation)
     }
 
     pub fn commit_not_found(hash: &str) -> Self {
         Self::with_details("COMMIT_NOT_FOUND", "Commit não encontrado", hash)
+            .with_kind(ErrorKind::NotFound)
     }
 
     // Merge errors
     pub fn merge_conflict() -> Self {
         Self::new("MERGE_CONFLICT", "Conflitos de merge detectados")
+            .with_kind(ErrorKind::Conflict)
     }
 
     // Remote errors
     pub fn remote_not_found(name: &str) -> Self {
         Self::with_details("REMOTE_NOT_FOUND", "Remote não encontrado", name)
+            .with_kind(ErrorKind::NotFound)
     }
 
     pub fn push_failed(details: &str) -> Self {
         Self::with_details("PUSH_FAILED", "Falha ao fazer push", details)
+            .with_kind(ErrorKind::Network)
     }
 
     pub fn pull_failed(details: &str) -> Self {
         Self::with_details("PULL_FAILED", "Falha ao fazer pull", details)
+            .with_kind(ErrorKind::Network)
     }
 
     // Stash errors
     pub fn stash_not_found(index: usize) -> Self {
         Self::with_details("STASH_NOT_FOUND", "Stash não encontrado", &index.to_string())
+            .with_kind(ErrorKind::NotFound)
     }
 
     // Generic errors
     pub fn git_error(e: git2::Error) -> Self {
-        Self::with_details("GIT_ERROR", "Erro do Git", &e.message().to_string())
+        let (code, message, kind) = classify_git2(&e);
+        Self::with_details(code, message, e.message()).with_kind(kind)
     }
 
     pub fn io_error(e: std::io::Error) -> Self {
-        Self::with_details("IO_ERROR", "Erro de I/O", &e.to_string())
+        let kind = match e.kind() {
+            std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+            _ => ErrorKind::Internal,
+        };
+        Self::with_details("IO_ERROR", "Erro de I/O", &e.to_string()).with_kind(kind)
+    }
+
+    pub fn cancelled() -> Self {
+        Self::new("CANCELLED", "Operação cancelada")
     }
 
     pub fn internal(message: &str) -> Self {
-        Self::new("INTERNAL_ERROR", message)
+        Self::new("INTERNAL_ERROR", message).with_kind(ErrorKind::Internal)
     }
 }
 
@@ -262,4 +363,76 @@ mod tests {
         assert_eq!(err.code, "INTERNAL_ERROR");
         assert_eq!(err.message, "algo quebrou internamente");
     }
+
+    #[test]
+    fn construtores_definem_kind() {
+        assert_eq!(AppError::no_repo().kind, ErrorKind::Validation);
+        assert_eq!(AppError::commit_not_found("abc").kind, ErrorKind::NotFound);
+        assert_eq!(AppError::merge_conflict().kind, ErrorKind::Conflict);
+        assert_eq!(AppError::push_failed("x").kind, ErrorKind::Network);
+        assert_eq!(AppError::git_user_not_configured().kind, ErrorKind::Validation);
+        assert_eq!(AppError::internal("x").kind, ErrorKind::Internal);
+    }
+
+    #[test]
+    fn kind_inferido_do_codigo() {
+        assert_eq!(AppError::new("PATH_NOT_FOUND", "x").kind, ErrorKind::NotFound);
+        assert_eq!(AppError::new("UNRESOLVED_CONFLICTS", "x").kind, ErrorKind::Conflict);
+        assert_eq!(AppError::new("NOT_MERGING", "x").kind, ErrorKind::Validation);
+        assert_eq!(AppError::new("GC_FAILED", "x").kind, ErrorKind::Internal);
+    }
+
+    #[test]
+    fn from_git2_error_usa_codigo_e_classe() {
+        let not_found = git2::Error::new(git2::ErrorCode::NotFound, git2::ErrorClass::Reference, "x");
+        assert_eq!(AppError::from(not_found).kind, ErrorKind::NotFound);
+
+        let net = git2::Error::new(git2::ErrorCode::GenericError, git2::ErrorClass::Net, "x");
+        assert_eq!(AppError::from(net).kind, ErrorKind::Network);
+
+        let auth = git2::Error::new(git2::ErrorCode::Auth, git2::ErrorClass::Http, "x");
+        assert_eq!(AppError::from(auth).kind, ErrorKind::Auth);
+    }
+
+    fn git2_error(code: git2::ErrorCode, class: git2::ErrorClass) -> AppError {
+        AppError::from(git2::Error::new(code, class, "mensagem do libgit2"))
+    }
+
+    #[test]
+    fn from_git2_error_mapeia_codigos_especificos() {
+        let cases = [
+            (git2::ErrorCode::NotFound, "NOT_FOUND"),
+            (git2::ErrorCode::Auth, "AUTH_FAILED"),
+            (git2::ErrorCode::Conflict, "CONFLICT"),
+            (git2::ErrorCode::Unmerged, "UNMERGED_ENTRIES"),
+            (git2::ErrorCode::Uncommitted, "UNCOMMITTED_CHANGES"),
+            (git2::ErrorCode::Locked, "REPO_LOCKED"),
+        ];
+        for (code, expected) in cases {
+            let err = git2_error(code, git2::ErrorClass::None);
+            assert_eq!(err.code, expected);
+            assert_eq!(err.details.as_deref(), Some("mensagem do libgit2"));
+        }
+    }
+
+    #[test]
+    fn from_git2_error_generico_mantem_git_error() {
+        let err = git2_error(git2::ErrorCode::GenericError, git2::ErrorClass::Odb);
+        assert_eq!(err.code, "GIT_ERROR");
+        assert_eq!(err.kind, ErrorKind::Internal);
+    }
+
+    #[test]
+    fn from_git2_error_de_rede_sem_codigo_usa_classe() {
+        let err = git2_error(git2::ErrorCode::GenericError, git2::ErrorClass::Ssh);
+        assert_eq!(err.code, "NETWORK_ERROR");
+        assert_eq!(err.kind, ErrorKind::Network);
+    }
+
+    #[test]
+    fn kind_serializado_junto_com_code() {
+        let json = serde_json::to_value(AppError::commit_not_found("abc")).unwrap();
+        assert_eq!(json["code"], "COMMIT_NOT_FOUND");
+        assert_eq!(json["kind"], "not_found");
+    }
 }
@@ -1,6 +1,7 @@
-use crate::error::{AppError, AppResult};
-use git2::{BranchType, Repository};
+use crate::error::{AppError, AppResult, ErrorKind};
+use git2::{BranchType, Oid, Repository};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BranchInfo {
@@ -13,92 +14,104 @@ pub struct BranchInfo {
     pub upstream: Option<String>,
     pub ahead: Option<usize>,
     pub behind: Option<usize>,
+    /// `None` only when ahead/behind weren't computed (listing without details)
+    pub upstream_status: Option<UpstreamStatus>,
     pub author_name: Option<String>,
     pub author_email: Option<String>,
     pub commit_date: Option<i64>,
+    pub description: Option<String>,
 }
 
-pub fn list_branches(repo: &Repository) -> AppResult<Vec<BranchInfo>> {
-    let current_branch = repo
-        .head()
-        .ok()
-        .and_then(|h| h.shorthand().map(String::from));
+/// Tracking state of a local branch. `Gone` means an upstream is configured
+/// but its ref no longer exists (deleted on the remote and pruned).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpstreamStatus {
+    Synced,
+    Ahead,
+    Behind,
+    Diverged,
+    Gone,
+    None,
+}
 
-    let mut branches = Vec::new();
+impl UpstreamStatus {
+    fn from_counts(ahead: usize, behind: usize) -> Self {
+        match (ahead, behind) {
+            (0, 0) => UpstreamStatus::Synced,
+            (_, 0) => UpstreamStatus::Ahead,
+            (0, _) => UpstreamStatus::Behind,
+            _ => UpstreamStatus::Diverged,
+        }
+    }
+}
+
+/// Data shared by every branch built in one listing
+struct BranchListContext {
+    current_branch: Option<String>,
+    with_details: bool,
+    descriptions: HashMap<String, String>,
+    // Branches that share the same tip and upstream tip reuse one graph walk
+    ahead_behind_cache: HashMap<(Oid, Oid), (usize, usize)>,
+}
 
-    // Local branches
-    for branch in repo.branches(Some(BranchType::Local))? {
-        let (branch, _) = branch?;
-        let name = branch.name()?.unwrap_or("").to_string();
-        let reference = branch.get();
-        let commit_hash = reference.target().map(|oid| oid.to_string()[..7].to_string());
-
-        let commit = reference.peel_to_commit().ok();
-        let commit_message = commit.as_ref().and_then(|c| c.summary().map(String::from));
-        let author_name = commit.as_ref().map(|c| c.author().name().unwrap_or("").to_string());
-        let author_email = commit.as_ref().map(|c| c.author().email().unwrap_or("").to_string());
-        let commit_date = commit.as_ref().map(|c| c.time().seconds());
-
-        let upstream = branch
-            .upstream()
+impl BranchListContext {
+    fn new(repo: &Repository, with_details: bool) -> Self {
+        let current_branch = repo
+            .head()
             .ok()
-            .and_then(|u| u.name().ok().flatten().map(String::from));
+            .and_then(|h| h.shorthand().map(String::from));
 
-        let (ahead, behind) = if let Some(ref _upstream_name) = upstream {
-            calculate_ahead_behind(repo, &name).unwrap_or((None, None))
-        } else {
-            (None, None)
-        };
+        Self {
+            current_branch,
+            with_details,
+            descriptions: load_branch_descriptions(repo),
+            ahead_behind_cache: HashMap::new(),
+        }
+    }
+}
 
-        branches.push(BranchInfo {
-            name: name.clone(),
-            is_current: current_branch.as_ref() == Some(&name),
-            is_remote: false,
-            is_head: reference.is_branch(),
-            commit_hash,
-            commit_message,
-            upstream,
-            ahead,
-            behind,
-            author_name,
-            author_email,
-            commit_date,
-        });
+/// Lists branches, optionally narrowed by a case-insensitive name substring and
+/// by kind. Filtering happens before any commit lookups. Without
+/// `with_details` the last-commit fields and ahead/behind are left empty; use
+/// `get_branch_details` to fill them per branch.
+pub fn list_branches(
+    repo: &Repository,
+    filter: Option<&str>,
+    include_local: bool,
+    include_remote: bool,
+    with_details: bool,
+) -> AppResult<Vec<BranchInfo>> {
+    let filter = filter.map(str::to_lowercase).filter(|f| !f.is_empty());
+    let matches_filter = |name: &str| {
+        filter
+            .as_ref()
+            .map_or(true, |f| name.to_lowercase().contains(f.as_str()))
+    };
+
+    let mut context = BranchListContext::new(repo, with_details);
+    let mut branches = Vec::new();
+
+    let mut branch_types = Vec::new();
+    if include_local {
+        branch_types.push(BranchType::Local);
+    }
+    if include_remote {
+        branch_types.push(BranchType::Remote);
     }
 
-    // Remote branches
-    for branch in repo.branches(Some(BranchType::Remote))? {
-        let (branch, _) = branch?;
-        let name = branch.name()?.unwrap_or("").to_string();
+    for branch_type in branch_types {
+        for branch in repo.branches(Some(branch_type))? {
+            let (branch, _) = branch?;
+            let name = branch.name()?.unwrap_or("").to_string();
 
-        // Skip HEAD references
-        if name.ends_with("/HEAD") {
-            continue;
-        }
+            // Skip remote HEAD references
+            if (branch_type == BranchType::Remote && name.ends_with("/HEAD")) || !matches_filter(&name) {
+                continue;
+            }
 
-        let reference = branch.get();
-        let commit_hash = reference.target().map(|oid| oid.to_string()[..7].to_string());
-
-        let commit = reference.peel_to_commit().ok();
-        let commit_message = commit.as_ref().and_then(|c| c.summary().map(String::from));
-        let author_name = commit.as_ref().map(|c| c.author().name().unwrap_or("").to_string());
-        let author_email = commit.as_ref().map(|c| c.author().email().unwrap_or("").to_string());
-        let commit_date = commit.as_ref().map(|c| c.time().seconds());
-
-        branches.push(BranchInfo {
-            name,
-            is_current: false,
-            is_remote: true,
-            is_head: false,
-            commit_hash,
-            commit_message,
-            upstream: None,
-            ahead: None,
-            behind: None,
-            author_name,
-            author_email,
-            commit_date,
-        });
+            branches.push(build_branch_info(repo, &branch, name, branch_type, &mut context));
+        }
     }
 
     // Sort: current first, then local, then remote
@@ -115,26 +128,156 @@ pub fn list_branches(repo: &Repository) -> AppResult<Vec<BranchInfo>> {
     Ok(branches)
 }
 
-fn calculate_ahead_behind(repo: &Repository, branch_name: &str) -> AppResult<(Option<usize>, Option<usize>)> {
-    let branch = repo.find_branch(branch_name, BranchType::Local)?;
-    let upstream = match branch.upstream() {
-        Ok(u) => u,
-        Err(_) => return Ok((None, None)),
+/// Returns the full info (last commit, ahead/behind) for a single local or
+/// remote branch.
+pub fn get_branch_details(repo: &Repository, name: &str) -> AppResult<BranchInfo> {
+    let (branch, branch_type) = repo
+        .find_branch(name, BranchType::Local)
+        .map(|b| (b, BranchType::Local))
+        .or_else(|_| repo.find_branch(name, BranchType::Remote).map(|b| (b, BranchType::Remote)))
+        .map_err(|_| AppError::branch_not_found(name))?;
+
+    let mut context = BranchListContext::new(repo, true);
+    Ok(build_branch_info(repo, &branch, name.to_string(), branch_type, &mut context))
+}
+
+fn build_branch_info(
+    repo: &Repository,
+    branch: &git2::Branch,
+    name: String,
+    branch_type: BranchType,
+    context: &mut BranchListContext,
+) -> BranchInfo {
+    let with_details = context.with_details;
+    let is_remote = branch_type == BranchType::Remote;
+    let reference = branch.get();
+    let commit_hash = reference.target().map(|oid| oid.to_string()[..7].to_string());
+
+    let commit = if with_details {
+        reference.peel_to_commit().ok()
+    } else {
+        None
+    };
+    let commit_message = commit.as_ref().and_then(|c| c.summary().map(String::from));
+    let author_name = commit.as_ref().map(|c| c.author().name().unwrap_or("").to_string());
+    let author_email = commit.as_ref().map(|c| c.author().email().unwrap_or("").to_string());
+    let commit_date = commit.as_ref().map(|c| c.time().seconds());
+
+    let upstream_branch = if is_remote { None } else { branch.upstream().ok() };
+    // `upstream()` fails both when nothing is configured and when the ref is gone
+    let gone_upstream = if is_remote || upstream_branch.is_some() {
+        None
+    } else {
+        reference
+            .name()
+            .and_then(|refname| repo.branch_upstream_name(refname).ok())
+            .and_then(|buf| buf.as_str().map(String::from))
+    };
+    let upstream_gone = gone_upstream.is_some();
+    let upstream = upstream_branch
+        .as_ref()
+        .and_then(|u| u.name().ok().flatten().map(String::from))
+        .or_else(|| {
+            gone_upstream.map(|refname| refname.strip_prefix("refs/remotes/").unwrap_or(&refname).to_string())
+        });
+
+    let upstream_oid = upstream_branch.as_ref().and_then(|u| u.get().target());
+    let (ahead, behind) = match (with_details, reference.target(), upstream_oid) {
+        (true, Some(local_oid), Some(upstream_oid)) => {
+            let counts = match context.ahead_behind_cache.get(&(local_oid, upstream_oid)) {
+                Some(counts) => Some(*counts),
+                None => {
+                    let counts = repo.graph_ahead_behind(local_oid, upstream_oid).ok();
+                    if let Some(counts) = counts {
+                        context.ahead_behind_cache.insert((local_oid, upstream_oid), counts);
+                    }
+                    counts
+                }
+            };
+            (counts.map(|c| c.0), counts.map(|c| c.1))
+        }
+        _ => (None, None),
     };
 
-    let local_oid = branch.get().target().ok_or_else(|| AppError::with_details(
-        "SYMBOLIC_REF",
-        "Branch local aponta para referência simbólica, não é possível calcular ahead/behind",
-        branch_name,
-    ))?;
-    let upstream_oid = upstream.get().target().ok_or_else(|| AppError::with_details(
-        "SYMBOLIC_REF",
-        "Branch upstream aponta para referência simbólica, não é possível calcular ahead/behind",
-        branch_name,
-    ))?;
-
-    let (ahead, behind) = repo.graph_ahead_behind(local_oid, upstream_oid)?;
-    Ok((Some(ahead), Some(behind)))
+    let upstream_status = if upstream_gone {
+        Some(UpstreamStatus::Gone)
+    } else if upstream.is_none() {
+        Some(UpstreamStatus::None)
+    } else {
+        ahead.zip(behind).map(|(a, b)| UpstreamStatus::from_counts(a, b))
+    };
+
+    let description = if is_remote {
+        None
+    } else {
+        context.descriptions.get(&name).cloned()
+    };
+
+    BranchInfo {
+        is_current: !is_remote && context.current_branch.as_deref() == Some(name.as_str()),
+        is_remote,
+        is_head: !is_remote && reference.is_branch(),
+        name,
+        commit_hash,
+        commit_message,
+        upstream,
+        ahead,
+        behind,
+        upstream_status,
+        author_name,
+        author_email,
+        commit_date,
+        description,
+    }
+}
+
+/// Reads every `branch.<name>.description` in one pass over the config
+fn load_branch_descriptions(repo: &Repository) -> HashMap<String, String> {
+    let mut descriptions = HashMap::new();
+
+    if let Ok(config) = repo.config() {
+        if let Ok(entries) = config.entries(Some(r"^branch\..*\.description$")) {
+            let _ = entries.for_each(|entry| {
+                let name = entry
+                    .name()
+                    .and_then(|n| n.strip_prefix("branch."))
+                    .and_then(|n| n.strip_suffix(".description"));
+                if let (Some(name), Some(value)) = (name, entry.value()) {
+                    descriptions.insert(name.to_string(), value.to_string());
+                }
+            });
+        }
+    }
+
+    descriptions
+}
+
+pub fn get_branch_description(repo: &Repository, name: &str) -> AppResult<Option<String>> {
+    repo.find_branch(name, BranchType::Local)
+        .map_err(|_| AppError::branch_not_found(name))?;
+
+    let config = repo.config()?;
+    Ok(config.get_string(&format!("branch.{}.description", name)).ok())
+}
+
+/// Sets the branch description; an empty text removes the config entry.
+pub fn set_branch_description(repo: &Repository, name: &str, text: &str) -> AppResult<()> {
+    repo.find_branch(name, BranchType::Local)
+        .map_err(|_| AppError::branch_not_found(name))?;
+
+    let mut config = repo.config()?;
+    let key = format!("branch.{}.description", name);
+
+    if text.trim().is_empty() {
+        match config.remove(&key) {
+            Err(e) if e.code() != git2::ErrorCode::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    } else {
+        config.set_str(&key, text)?;
+    }
+
+    Ok(())
 }
 
 pub fn get_current_branch(repo: &Repository) -> AppResult<String> {
@@ -142,6 +285,15 @@ pub fn get_current_branch(repo: &Repository) -> AppResult<String> {
     Ok(head.shorthand().unwrap_or("HEAD").to_string())
 }
 
+/// Branch to restore when reopening a repo: `stored` while it's still a local
+/// branch, otherwise the one HEAD is on
+pub fn resolve_last_branch(repo: &Repository, stored: Option<&str>) -> Option<String> {
+    stored
+        .filter(|name| repo.find_branch(name, BranchType::Local).is_ok())
+        .map(String::from)
+        .or_else(|| repo.head().ok().and_then(|h| h.shorthand().map(String::from)))
+}
+
 pub fn create_branch(repo: &Repository, name: &str, checkout: bool) -> AppResult<()> {
     // Check if branch exists
     if repo.find_branch(name, BranchType::Local).is_ok() {
@@ -154,13 +306,70 @@ pub fn create_branch(repo: &Repository, name: &str, checkout: bool) -> AppResult
     repo.branch(name, &commit, false)?;
 
     if checkout {
-        checkout_branch(repo, name)?;
+        checkout_branch(repo, name, false)?;
     }
 
     Ok(())
 }
 
-pub fn checkout_branch(repo: &Repository, name: &str) -> AppResult<()> {
+/// Checks out a commit into a detached HEAD, for inspecting old history.
+/// Without `force`, local changes that would be overwritten abort the
+/// checkout with `CHECKOUT_CONFLICT` instead of being discarded.
+pub fn checkout_commit(repo: &Repository, commit_hash: &str, force: bool) -> AppResult<()> {
+    let oid = Oid::from_str(commit_hash).map_err(|_| AppError::commit_not_found(commit_hash))?;
+    let commit = repo
+        .find_commit(oid)
+        .map_err(|_| AppError::commit_not_found(commit_hash))?;
+
+    checkout_tree_checked(repo, commit.as_object(), force)?;
+    repo.set_head_detached(oid).map_err(|e| {
+        AppError::with_details("CHECKOUT_ERROR", "Erro ao definir HEAD", e.message())
+    })?;
+
+    Ok(())
+}
+
+/// Checks out `target` into the working tree. A safe checkout reports the
+/// files that block it; `force` overwrites them.
+fn checkout_tree_checked(repo: &Repository, target: &git2::Object, force: bool) -> AppResult<()> {
+    let mut conflicts: Vec<String> = Vec::new();
+
+    let result = {
+        let mut checkout_opts = git2::build::CheckoutBuilder::new();
+        if force {
+            checkout_opts.force();
+        } else {
+            checkout_opts.safe();
+            checkout_opts
+                .notify_on(git2::CheckoutNotificationType::CONFLICT)
+                .notify(|_, path, _, _, _| {
+                    if let Some(path) = path {
+                        conflicts.push(path.to_string_lossy().to_string());
+                    }
+                    true
+                });
+        }
+        repo.checkout_tree(target, Some(&mut checkout_opts))
+    };
+
+    match result {
+        Ok(()) => Ok(()),
+        Err(_) if !conflicts.is_empty() => Err(AppError::with_details(
+            "CHECKOUT_CONFLICT",
+            "O checkout sobrescreveria alteracoes locais",
+            &conflicts.join("\n"),
+        )),
+        Err(e) => Err(AppError::with_details(
+            "CHECKOUT_ERROR",
+            "Erro ao fazer checkout",
+            e.message(),
+        )),
+    }
+}
+
+/// Switches to a branch. Uncommitted changes that would be overwritten abort
+/// with `CHECKOUT_CONFLICT` unless `force` is set.
+pub fn checkout_branch(repo: &Repository, name: &str, force: bool) -> AppResult<()> {
     let (reference, is_remote) = if let Ok(branch) = repo.find_branch(name, BranchType::Local) {
         (branch.into_reference(), false)
     } else if let Ok(branch) = repo.find_branch(name, BranchType::Remote) {
@@ -169,67 +378,58 @@ pub fn checkout_branch(repo: &Repository, name: &str) -> AppResult<()> {
         return Err(AppError::branch_not_found(name));
     };
 
-    // Use force checkout - let git handle conflicts
-    let mut checkout_opts = git2::build::CheckoutBuilder::new();
-    checkout_opts.force();
-
     if is_remote {
-        // Create local branch from remote
-        let short_name = name.rsplit('/').next().unwrap_or(name);
+        // Create local branch from remote: `origin/feature/x` becomes `feature/x`
+        let short_name = remote_branch_short_name(repo, &reference, name);
+        let short_name = short_name.as_str();
         let commit = reference.peel_to_commit().map_err(|e| {
             AppError::with_details("CHECKOUT_ERROR", "Erro ao obter commit da branch", &e.message().to_string())
         })?;
 
         // Check if local branch already exists
         let local_ref = format!("refs/heads/{}", short_name);
-        if repo.find_branch(short_name, BranchType::Local).is_ok() {
+        let mut local_branch = if let Ok(branch) = repo.find_branch(short_name, BranchType::Local) {
+            // A same-named branch tracking something else isn't this remote branch
+            let tracked = branch
+                .upstream()
+                .ok()
+                .and_then(|upstream| upstream.name().ok().flatten().map(str::to_string));
+            if let Some(tracked) = tracked.filter(|tracked| tracked != name) {
+                return Err(AppError::with_details(
+                    "AMBIGUOUS_BRANCH",
+                    "Já existe uma branch local com esse nome rastreando outra branch",
+                    &format!("{} rastreia {}, não {}", short_name, tracked, name),
+                )
+                .with_kind(ErrorKind::Conflict));
+            }
+
             // Local branch exists, just checkout to it
-            let reference = repo.find_reference(&local_ref).map_err(|e| {
-                AppError::with_details("CHECKOUT_ERROR", "Erro ao encontrar referência local", &e.message().to_string())
-            })?;
-            let commit = reference.peel_to_commit().map_err(|e| {
+            let commit = branch.get().peel_to_commit().map_err(|e| {
                 AppError::with_details("CHECKOUT_ERROR", "Erro ao obter commit", &e.message().to_string())
             })?;
-            repo.checkout_tree(commit.as_object(), Some(&mut checkout_opts)).map_err(|e| {
-                AppError::with_details("CHECKOUT_ERROR", "Erro ao fazer checkout", &e.message().to_string())
-            })?;
-            repo.set_head(&local_ref).map_err(|e| {
-                AppError::with_details("CHECKOUT_ERROR", "Erro ao definir HEAD", &e.message().to_string())
-            })?;
-            // Set upstream tracking if not already configured
-            let mut local_branch = repo.find_branch(short_name, BranchType::Local).map_err(|e| {
-                AppError::with_details("CHECKOUT_ERROR", "Erro ao encontrar branch local", &e.message().to_string())
-            })?;
-            if local_branch.upstream().is_err() {
-                let _ = local_branch.set_upstream(Some(name));
-            }
+            checkout_tree_checked(repo, commit.as_object(), force)?;
+            branch
         } else {
-            // Create new local branch from remote
-            let mut local_branch = repo.branch(short_name, &commit, false).map_err(|e| {
+            // Checkout first so a dirty tree doesn't leave a stray local branch behind
+            checkout_tree_checked(repo, commit.as_object(), force)?;
+            repo.branch(short_name, &commit, false).map_err(|e| {
                 AppError::with_details("CHECKOUT_ERROR", "Erro ao criar branch local", &e.message().to_string())
-            })?;
-            // Set upstream tracking so push/pull work automatically
-            let _ = local_branch.set_upstream(Some(name));
-            let reference = repo.find_reference(&local_ref).map_err(|e| {
-                AppError::with_details("CHECKOUT_ERROR", "Erro ao encontrar referência", &e.message().to_string())
-            })?;
-            let commit = reference.peel_to_commit().map_err(|e| {
-                AppError::with_details("CHECKOUT_ERROR", "Erro ao obter commit", &e.message().to_string())
-            })?;
-            repo.checkout_tree(commit.as_object(), Some(&mut checkout_opts)).map_err(|e| {
-                AppError::with_details("CHECKOUT_ERROR", "Erro ao fazer checkout", &e.message().to_string())
-            })?;
-            repo.set_head(&local_ref).map_err(|e| {
-                AppError::with_details("CHECKOUT_ERROR", "Erro ao definir HEAD", &e.message().to_string())
-            })?;
+            })?
+        };
+        repo.set_head(&local_ref).map_err(|e| {
+            AppError::with_details("CHECKOUT_ERROR", "Erro ao definir HEAD", &e.message().to_string())
+        })?;
+
+        // Track the remote branch so push/pull and ahead/behind work right away;
+        // an existing local branch keeps whatever upstream it already had
+        if local_branch.upstream().is_err() {
+            local_branch.set_upstream(Some(name))?;
         }
     } else {
         let commit = reference.peel_to_commit().map_err(|e| {
             AppError::with_details("CHECKOUT_ERROR", "Erro ao obter commit da branch", &e.message().to_string())
         })?;
-        repo.checkout_tree(commit.as_object(), Some(&mut checkout_opts)).map_err(|e| {
-            AppError::with_details("CHECKOUT_ERROR", "Erro ao fazer checkout", &e.message().to_string())
-        })?;
+        checkout_tree_checked(repo, commit.as_object(), force)?;
 
         let ref_name = format!("refs/heads/{}", name);
         repo.set_head(&ref_name).map_err(|e| {
@@ -240,6 +440,22 @@ pub fn checkout_branch(repo: &Repository, name: &str) -> AppResult<()> {
     Ok(())
 }
 
+/// Local name for a remote-tracking branch: only the remote prefix is dropped,
+/// since the branch name itself may contain '/'
+fn remote_branch_short_name(repo: &Repository, reference: &git2::Reference, name: &str) -> String {
+    let remote = reference
+        .name()
+        .and_then(|refname| repo.branch_remote_name(refname).ok())
+        .and_then(|buf| buf.as_str().map(str::to_string));
+    match remote {
+        Some(remote) => name
+            .strip_prefix(&format!("{}/", remote))
+            .unwrap_or(name)
+            .to_string(),
+        None => name.split_once('/').map(|(_, branch)| branch).unwrap_or(name).to_string(),
+    }
+}
+
 pub fn delete_branch(repo: &Repository, name: &str, force: bool) -> AppResult<()> {
     let current = get_current_branch(repo)?;
     if current == name {
@@ -280,7 +496,62 @@ pub fn rename_branch(repo: &Repository, old_name: &str, new_name: &str) -> AppRe
     Ok(())
 }
 
-pub fn merge_branch(repo: &Repository, branch_name: &str) -> AppResult<String> {
+/// Creates `new_name` at the tip of `source`, copying its `branch.<name>.*`
+/// config (upstream, description) like `git branch -c`.
+pub fn copy_branch(repo: &Repository, source: &str, new_name: &str, checkout: bool) -> AppResult<()> {
+    let source_branch = repo
+        .find_branch(source, BranchType::Local)
+        .map_err(|_| AppError::branch_not_found(source))?;
+
+    if repo.find_branch(new_name, BranchType::Local).is_ok() {
+        return Err(AppError::branch_already_exists(new_name));
+    }
+
+    let commit = source_branch.get().peel_to_commit()?;
+    repo.branch(new_name, &commit, false)?;
+
+    let mut config = repo.config()?;
+    let mut copied: Vec<(String, String)> = Vec::new();
+    // Only the repo's own config holds branch settings worth copying
+    let local = config.open_level(git2::ConfigLevel::Local)?;
+    local
+        .entries(Some(r"^branch\."))?
+        .for_each(|entry| {
+            if let (Some(name), Some(value)) = (entry.name(), entry.value()) {
+                // The subsection is everything between the first and last dot,
+                // so `branch.feat.x.remote` belongs to `feat.x`, not `feat`
+                if let Some((subsection, key)) = name.strip_prefix("branch.").and_then(|n| n.rsplit_once('.')) {
+                    if subsection == source {
+                        copied.push((key.to_string(), value.to_string()));
+                    }
+                }
+            }
+        })?;
+
+    // Added one by one so multivars (several `merge` lines) keep every value
+    for (key, value) in copied {
+        config.set_multivar(&format!("branch.{}.{}", new_name, key), "^$", &value)?;
+    }
+
+    if checkout {
+        checkout_branch(repo, new_name, false)?;
+    }
+
+    Ok(())
+}
+
+/// Merges `branch_name` into HEAD. Returns `"fast-forward"` when HEAD just
+/// moved, otherwise the short hash of the merge commit. `no_ff` always
+/// creates a merge commit; `message` replaces the default "Merge branch" one.
+/// With `no_commit` a clean merge stops before committing and returns
+/// `"pending"`, leaving the result for review (`commit_merge`/`abort_merge`).
+pub fn merge_branch(
+    repo: &Repository,
+    branch_name: &str,
+    no_ff: bool,
+    no_commit: bool,
+    message: Option<&str>,
+) -> AppResult<String> {
     let branch_ref = format!("refs/heads/{}", branch_name);
     let branch_oid = repo.refname_to_id(&branch_ref)?;
     let branch_commit = repo.find_commit(branch_oid)?;
@@ -291,7 +562,16 @@ pub fn merge_branch(repo: &Repository, branch_name: &str) -> AppResult<String> {
     // Check if it's a fast-forward merge
     let merge_base = repo.merge_base(head_commit.id(), branch_commit.id())?;
 
-    if merge_base == head_commit.id() {
+    if merge_base == branch_commit.id() {
+        return Err(AppError::with_details(
+            "ALREADY_UP_TO_DATE",
+            "A branch atual já contém essa branch",
+            branch_name,
+        )
+        .with_kind(ErrorKind::Validation));
+    }
+
+    if merge_base == head_commit.id() && !no_ff {
         // Fast-forward
         let reflog_msg = format!("merge {}: Fast-forward", branch_name);
         repo.reference(
@@ -304,44 +584,41 @@ pub fn merge_branch(repo: &Repository, branch_name: &str) -> AppResult<String> {
         return Ok("fast-forward".to_string());
     }
 
-    // Regular merge
-    let signature = repo.signature()?;
-    let mut index = repo.merge_commits(&head_commit, &branch_commit, None)?;
-
-    if index.has_conflicts() {
-        // Write conflicts to index
-        let conflicts: Vec<_> = index.conflicts()?.collect();
-        let mut repo_index = repo.index()?;
-
-        for conflict in conflicts.into_iter().flatten() {
-            if let Some(their) = conflict.their {
-                repo_index.add(&their)?;
-            }
+    // Regular merge through the merge state machine, so conflicts and reviews
+    // leave MERGE_HEAD behind for `abort_merge`/`commit_merge`. Annotating
+    // from the ref makes MERGE_MSG read "Merge branch '<name>'".
+    let annotated = repo.reference_to_annotated_commit(&repo.find_reference(&branch_ref)?)?;
+    let mut checkout_opts = git2::build::CheckoutBuilder::new();
+    checkout_opts.safe();
+    repo.merge(&[&annotated], None, Some(&mut checkout_opts)).map_err(|e| {
+        if e.code() == git2::ErrorCode::Conflict {
+            AppError::with_details("CHECKOUT_CONFLICT", "O merge sobrescreveria alteracoes locais", e.message())
+        } else {
+            AppError::from(e)
         }
-        repo_index.write()?;
+    })?;
 
+    if repo.index()?.has_conflicts() {
         return Err(AppError::merge_conflict());
     }
 
-    let tree_id = index.write_tree_to(repo)?;
-    let tree = repo.find_tree(tree_id)?;
-
-    let message = format!("Merge branch '{}'", branch_name);
-    let commit_id = repo.commit(
-        Some("HEAD"),
-        &signature,
-        &signature,
-        &message,
-        &tree,
-        &[&head_commit, &branch_commit],
-    )?;
+    let message = message.map(str::trim).filter(|m| !m.is_empty());
+    if no_commit {
+        if let Some(message) = message {
+            // Becomes the default message of the eventual commit_merge
+            std::fs::write(repo.path().join("MERGE_MSG"), format!("{}\n", message))
+                .map_err(|e| AppError::with_details("MERGE_ERROR", "Erro ao salvar mensagem do merge", &e.to_string()))?;
+        }
+        return Ok("pending".to_string());
+    }
 
-    Ok(commit_id.to_string()[..7].to_string())
+    super::commit_merge(repo, message)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::{abort_merge, commit_merge};
     use git2::Signature;
     use tempfile::TempDir;
 
@@ -372,10 +649,184 @@ mod tests {
         (dir, repo)
     }
 
+    #[test]
+    fn checkout_de_branch_remota_cria_local_com_upstream() {
+        let (_dir, repo) = setup_repo_with_commit();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/remotes/origin/feature", head, false, "teste")
+            .unwrap();
+
+        checkout_branch(&repo, "origin/feature", false).unwrap();
+
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/feature"));
+        let config = repo.config().unwrap().snapshot().unwrap();
+        assert_eq!(config.get_str("branch.feature.remote").unwrap(), "origin");
+        assert_eq!(config.get_str("branch.feature.merge").unwrap(), "refs/heads/feature");
+
+        let branches = list_branches(&repo, None, true, true, true).unwrap();
+        let local = branches
+            .iter()
+            .find(|b| b.name == "feature" && !b.is_remote)
+            .unwrap();
+        assert_eq!(local.upstream.as_deref(), Some("origin/feature"));
+        assert_eq!((local.ahead, local.behind), (Some(0), Some(0)));
+    }
+
+    #[test]
+    fn checkout_de_branch_remota_com_barras_cria_local_com_upstream() {
+        let (_dir, repo) = setup_repo_with_commit();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/remotes/origin/feature/login", head, false, "teste")
+            .unwrap();
+
+        checkout_branch(&repo, "origin/feature/login", false).unwrap();
+
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/feature/login"));
+        let config = repo.config().unwrap().snapshot().unwrap();
+        assert_eq!(config.get_str("branch.feature/login.remote").unwrap(), "origin");
+        assert_eq!(
+            config.get_str("branch.feature/login.merge").unwrap(),
+            "refs/heads/feature/login"
+        );
+
+        let branches = list_branches(&repo, None, true, true, true).unwrap();
+        let local = branches
+            .iter()
+            .find(|b| b.name == "feature/login" && !b.is_remote)
+            .unwrap();
+        assert_eq!(local.upstream.as_deref(), Some("origin/feature/login"));
+        assert_eq!((local.ahead, local.behind), (Some(0), Some(0)));
+    }
+
+    #[test]
+    fn checkout_de_branch_remota_preserva_caminho_com_barras() {
+        let (_dir, repo) = setup_repo_with_commit();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/remotes/origin/team/feature/x", head, false, "teste")
+            .unwrap();
+
+        checkout_branch(&repo, "origin/team/feature/x", false).unwrap();
+
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/team/feature/x"));
+        assert!(repo.find_branch("x", BranchType::Local).is_err());
+    }
+
+    #[test]
+    fn checkout_de_branch_remota_recusa_local_que_rastreia_outra() {
+        let (_dir, repo) = setup_repo_with_commit();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        repo.remote("upstream", "https://example.com/upstream.git").unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/remotes/origin/feature", head, false, "teste").unwrap();
+        repo.reference("refs/remotes/upstream/feature", head, false, "teste").unwrap();
+
+        checkout_branch(&repo, "origin/feature", false).unwrap();
+        let err = checkout_branch(&repo, "upstream/feature", false).unwrap_err();
+        assert_eq!(err.code, "AMBIGUOUS_BRANCH");
+        assert_eq!(err.kind, ErrorKind::Conflict);
+
+        // Checking out the branch it already tracks still works
+        checkout_branch(&repo, "origin/feature", false).unwrap();
+    }
+
+    /// Cria a branch `feature` com um commit a mais que HEAD, sem trocar de branch
+    fn criar_feature_a_frente(repo: &Repository, dir: &std::path::Path) -> Oid {
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        std::fs::write(dir.join("feature.txt"), "nova").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("feature.txt")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let oid = repo
+            .commit(None, &sig, &sig, "feature", &tree, &[&head])
+            .unwrap();
+        repo.branch("feature", &repo.find_commit(oid).unwrap(), false).unwrap();
+
+        // Restore index and working tree to HEAD
+        index.read_tree(&head.tree().unwrap()).unwrap();
+        index.write().unwrap();
+        std::fs::remove_file(dir.join("feature.txt")).unwrap();
+        oid
+    }
+
+    #[test]
+    fn merge_branch_faz_fast_forward_por_padrao() {
+        let (dir, repo) = setup_repo_with_commit();
+        let feature = criar_feature_a_frente(&repo, dir.path());
+
+        assert_eq!(merge_branch(&repo, "feature", false, false, None).unwrap(), "fast-forward");
+        assert_eq!(repo.head().unwrap().target(), Some(feature));
+    }
+
+    #[test]
+    fn merge_branch_no_ff_cria_commit_de_merge_com_mensagem() {
+        let (dir, repo) = setup_repo_with_commit();
+        let base = repo.head().unwrap().target().unwrap();
+        let feature = criar_feature_a_frente(&repo, dir.path());
+
+        let result = merge_branch(&repo, "feature", true, false, Some("Merge da feature")).unwrap();
+        assert_ne!(result, "fast-forward");
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_ids().collect::<Vec<_>>(), vec![base, feature]);
+        assert_eq!(head.message(), Some("Merge da feature"));
+        assert_eq!(head.tree_id(), repo.find_commit(feature).unwrap().tree_id());
+        assert!(dir.path().join("feature.txt").exists());
+
+        let err = merge_branch(&repo, "feature", true, false, None).unwrap_err();
+        assert_eq!(err.code, "ALREADY_UP_TO_DATE");
+    }
+
+    #[test]
+    fn merge_branch_no_commit_deixa_merge_para_revisar() {
+        let (dir, repo) = setup_repo_with_commit();
+        let base = repo.head().unwrap().target().unwrap();
+        let feature = criar_feature_a_frente(&repo, dir.path());
+
+        let result = merge_branch(&repo, "feature", true, true, Some("Merge revisado")).unwrap();
+        assert_eq!(result, "pending");
+        assert_eq!(repo.state(), git2::RepositoryState::Merge);
+        assert_eq!(repo.head().unwrap().target(), Some(base));
+        assert!(dir.path().join("feature.txt").exists());
+
+        commit_merge(&repo, None).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_ids().collect::<Vec<_>>(), vec![base, feature]);
+        assert_eq!(head.message(), Some("Merge revisado\n"));
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+    }
+
+    #[test]
+    fn merge_branch_sem_mensagem_usa_nome_da_branch() {
+        let (dir, repo) = setup_repo_with_commit();
+        criar_feature_a_frente(&repo, dir.path());
+
+        merge_branch(&repo, "feature", true, false, None).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("Merge branch 'feature'"));
+    }
+
+    #[test]
+    fn merge_branch_no_commit_pode_ser_abortado() {
+        let (dir, repo) = setup_repo_with_commit();
+        let base = repo.head().unwrap().target().unwrap();
+        criar_feature_a_frente(&repo, dir.path());
+
+        merge_branch(&repo, "feature", true, true, None).unwrap();
+        abort_merge(&repo).unwrap();
+
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert_eq!(repo.head().unwrap().target(), Some(base));
+        assert!(!dir.path().join("feature.txt").exists());
+    }
+
     #[test]
     fn list_branches_retorna_branch_main() {
         let (_dir, repo) = setup_repo_with_commit();
-        let branches = list_branches(&repo).unwrap();
+        let branches = list_branches(&repo, None, true, true, true).unwrap();
         assert!(!branches.is_empty());
         let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
         assert!(names.contains(&"master") || names.contains(&"main"));
@@ -384,7 +835,7 @@ mod tests {
     #[test]
     fn list_branches_marca_branch_atual_como_is_current() {
         let (_dir, repo) = setup_repo_with_commit();
-        let branches = list_branches(&repo).unwrap();
+        let branches = list_branches(&repo, None, true, true, true).unwrap();
         let current: Vec<_> = branches.iter().filter(|b| b.is_current).collect();
         assert_eq!(current.len(), 1, "deve haver exatamente uma branch atual");
     }
@@ -392,7 +843,7 @@ mod tests {
     #[test]
     fn list_branches_nenhuma_branch_remota_em_repo_local() {
         let (_dir, repo) = setup_repo_with_commit();
-        let branches = list_branches(&repo).unwrap();
+        let branches = list_branches(&repo, None, true, true, true).unwrap();
         let remotes: Vec<_> = branches.iter().filter(|b| b.is_remote).collect();
         assert!(remotes.is_empty());
     }
@@ -455,7 +906,7 @@ mod tests {
         let parent = repo.head().unwrap().peel_to_commit().unwrap();
         repo.commit(Some("HEAD"), &sig, &sig, "feature commit", &tree, &[&parent]).unwrap();
 
-        checkout_branch(&repo, "master").or_else(|_| checkout_branch(&repo, "main")).unwrap();
+        checkout_branch(&repo, "master", false).or_else(|_| checkout_branch(&repo, "main", false)).unwrap();
 
         let result = delete_branch(&repo, "feature-nao-mergeada", false);
         assert!(result.is_err());
@@ -475,14 +926,14 @@ mod tests {
     fn checkout_branch_muda_para_branch_local() {
         let (_dir, repo) = setup_repo_with_commit();
         create_branch(&repo, "outra-branch", false).unwrap();
-        checkout_branch(&repo, "outra-branch").unwrap();
+        checkout_branch(&repo, "outra-branch", false).unwrap();
         assert_eq!(get_current_branch(&repo).unwrap(), "outra-branch");
     }
 
     #[test]
     fn checkout_branch_inexistente_retorna_erro() {
         let (_dir, repo) = setup_repo_with_commit();
-        let result = checkout_branch(&repo, "nao-existe-xyz");
+        let result = checkout_branch(&repo, "nao-existe-xyz", false);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert_eq!(err.code, "BRANCH_NOT_FOUND");
@@ -495,12 +946,313 @@ mod tests {
         assert!(!branch.is_empty());
     }
 
+    #[test]
+    fn resolve_last_branch_cai_para_head_se_branch_sumiu() {
+        let (_dir, repo) = setup_repo_with_commit();
+        let head = get_current_branch(&repo).unwrap();
+        create_branch(&repo, "feature", false).unwrap();
+
+        assert_eq!(resolve_last_branch(&repo, Some("feature")).as_deref(), Some("feature"));
+        assert_eq!(resolve_last_branch(&repo, Some("apagada")), Some(head.clone()));
+        assert_eq!(resolve_last_branch(&repo, None), Some(head));
+    }
+
     #[test]
     fn create_e_list_branches_inclui_nova_branch() {
         let (_dir, repo) = setup_repo_with_commit();
         create_branch(&repo, "listada", false).unwrap();
-        let branches = list_branches(&repo).unwrap();
+        let branches = list_branches(&repo, None, true, true, true).unwrap();
         let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
         assert!(names.contains(&"listada"));
     }
+
+    #[test]
+    fn list_branches_calcula_ahead_behind_com_upstream() {
+        let (dir, repo) = setup_repo_with_commit();
+        let base = repo.head().unwrap().target().unwrap();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        repo.reference("refs/remotes/origin/master", base, true, "teste").unwrap();
+
+        let current = get_current_branch(&repo).unwrap();
+        create_branch(&repo, "outra", false).unwrap();
+        for name in [current.as_str(), "outra"] {
+            repo.find_branch(name, BranchType::Local)
+                .unwrap()
+                .set_upstream(Some("origin/master"))
+                .unwrap();
+        }
+
+        std::fs::write(dir.path().join("novo.txt"), "novo").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("novo.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let parent = repo.find_commit(base).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "segundo", &tree, &[&parent]).unwrap();
+
+        let branches = list_branches(&repo, None, true, true, true).unwrap();
+        let atual = branches.iter().find(|b| b.name == current).unwrap();
+        assert_eq!((atual.ahead, atual.behind), (Some(1), Some(0)));
+        let outra = branches.iter().find(|b| b.name == "outra").unwrap();
+        assert_eq!((outra.ahead, outra.behind), (Some(0), Some(0)));
+        let remota = branches.iter().find(|b| b.name == "origin/master").unwrap();
+        assert_eq!(remota.ahead, None);
+        assert_eq!(atual.upstream_status, Some(UpstreamStatus::Ahead));
+        assert_eq!(outra.upstream_status, Some(UpstreamStatus::Synced));
+        assert_eq!(remota.upstream_status, Some(UpstreamStatus::None));
+    }
+
+    #[test]
+    fn list_branches_detecta_upstream_removido() {
+        let (_dir, repo) = setup_repo_with_commit();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        let mut upstream_ref = repo.reference("refs/remotes/origin/feature", head, true, "teste").unwrap();
+        create_branch(&repo, "feature", false).unwrap();
+        repo.find_branch("feature", BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("origin/feature"))
+            .unwrap();
+        create_branch(&repo, "sem-upstream", false).unwrap();
+
+        upstream_ref.delete().unwrap();
+
+        let branches = list_branches(&repo, None, true, false, false).unwrap();
+        let feature = branches.iter().find(|b| b.name == "feature").unwrap();
+        assert_eq!(feature.upstream_status, Some(UpstreamStatus::Gone));
+        assert_eq!(feature.upstream.as_deref(), Some("origin/feature"));
+        let solta = branches.iter().find(|b| b.name == "sem-upstream").unwrap();
+        assert_eq!(solta.upstream_status, Some(UpstreamStatus::None));
+    }
+
+    #[test]
+    fn list_branches_filtra_por_nome_e_tipo() {
+        let (_dir, repo) = setup_repo_with_commit();
+        let head = repo.head().unwrap().target().unwrap();
+        create_branch(&repo, "feature/Login", false).unwrap();
+        create_branch(&repo, "fix/bug", false).unwrap();
+        repo.reference("refs/remotes/origin/feature/login-api", head, true, "teste").unwrap();
+
+        let branches = list_branches(&repo, Some("login"), true, true, true).unwrap();
+        let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["feature/Login", "origin/feature/login-api"]);
+
+        let remotes = list_branches(&repo, None, false, true, true).unwrap();
+        assert!(remotes.iter().all(|b| b.is_remote));
+        assert_eq!(remotes.len(), 1);
+
+        let locals = list_branches(&repo, Some("fix"), true, false, true).unwrap();
+        assert_eq!(locals.len(), 1);
+        assert_eq!(locals[0].name, "fix/bug");
+    }
+
+    #[test]
+    fn list_branches_sem_detalhes_nao_preenche_commit() {
+        let (_dir, repo) = setup_repo_with_commit();
+        let branches = list_branches(&repo, None, true, true, false).unwrap();
+        let atual = branches.iter().find(|b| b.is_current).unwrap();
+        assert!(atual.commit_hash.is_some());
+        assert!(atual.commit_message.is_none());
+        assert!(atual.commit_date.is_none());
+
+        let detalhes = get_branch_details(&repo, &atual.name).unwrap();
+        assert!(detalhes.is_current);
+        assert_eq!(detalhes.commit_message.as_deref(), Some("commit inicial"));
+        assert_eq!(detalhes.author_name.as_deref(), Some("Teste"));
+    }
+
+    #[test]
+    fn get_branch_details_branch_inexistente_falha() {
+        let (_dir, repo) = setup_repo_with_commit();
+        let err = get_branch_details(&repo, "nao-existe").unwrap_err();
+        assert_eq!(err.code, "BRANCH_NOT_FOUND");
+    }
+
+    #[test]
+    fn checkout_commit_deixa_head_destacado() {
+        let (dir, repo) = setup_repo_with_commit();
+        let first = repo.head().unwrap().target().unwrap();
+
+        std::fs::write(dir.path().join("novo.txt"), "novo").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("novo.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let parent = repo.find_commit(first).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "segundo", &tree, &[&parent]).unwrap();
+
+        checkout_commit(&repo, &first.to_string(), false).unwrap();
+
+        assert!(repo.head_detached().unwrap());
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+        assert!(!dir.path().join("novo.txt").exists());
+    }
+
+    #[test]
+    fn checkout_commit_hash_invalido_falha() {
+        let (_dir, repo) = setup_repo_with_commit();
+        let err = checkout_commit(&repo, "zzz", false).unwrap_err();
+        assert_eq!(err.code, "COMMIT_NOT_FOUND");
+    }
+
+    #[test]
+    fn checkout_commit_com_alteracoes_locais_retorna_conflito() {
+        let (dir, repo) = setup_repo_with_commit();
+        let first = repo.head().unwrap().target().unwrap();
+
+        std::fs::write(dir.path().join("README.md"), "# Segundo").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let parent = repo.find_commit(first).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "segundo", &tree, &[&parent]).unwrap();
+
+        std::fs::write(dir.path().join("README.md"), "# Local nao commitado").unwrap();
+
+        let err = checkout_commit(&repo, &first.to_string(), false).unwrap_err();
+        assert_eq!(err.code, "CHECKOUT_CONFLICT");
+        assert!(err.details.unwrap().contains("README.md"));
+        assert!(!repo.head_detached().unwrap());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("README.md")).unwrap(),
+            "# Local nao commitado"
+        );
+
+        checkout_commit(&repo, &first.to_string(), true).unwrap();
+        assert!(repo.head_detached().unwrap());
+        assert_eq!(std::fs::read_to_string(dir.path().join("README.md")).unwrap(), "# Teste");
+    }
+
+    #[test]
+    fn checkout_branch_preserva_alteracoes_locais_sem_force() {
+        let (dir, repo) = setup_repo_with_commit();
+        let current = get_current_branch(&repo).unwrap();
+        create_branch(&repo, "outra", false).unwrap();
+
+        std::fs::write(dir.path().join("README.md"), "# Na outra").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("refs/heads/outra"), &sig, &sig, "na outra", &tree, &[&parent]).unwrap();
+        // Volta o index/worktree para o HEAD atual e cria uma alteracao local
+        repo.reset(parent.as_object(), git2::ResetType::Hard, None).unwrap();
+        std::fs::write(dir.path().join("README.md"), "# Local").unwrap();
+
+        let err = checkout_branch(&repo, "outra", false).unwrap_err();
+        assert_eq!(err.code, "CHECKOUT_CONFLICT");
+        assert!(err.details.unwrap().contains("README.md"));
+        assert_eq!(get_current_branch(&repo).unwrap(), current);
+        assert_eq!(std::fs::read_to_string(dir.path().join("README.md")).unwrap(), "# Local");
+
+        checkout_branch(&repo, "outra", true).unwrap();
+        assert_eq!(get_current_branch(&repo).unwrap(), "outra");
+        assert_eq!(std::fs::read_to_string(dir.path().join("README.md")).unwrap(), "# Na outra");
+    }
+
+    #[test]
+    fn checkout_branch_com_arquivo_nao_relacionado_troca_normalmente() {
+        let (dir, repo) = setup_repo_with_commit();
+        create_branch(&repo, "outra", false).unwrap();
+        std::fs::write(dir.path().join("rascunho.txt"), "rascunho").unwrap();
+
+        checkout_branch(&repo, "outra", false).unwrap();
+        assert_eq!(get_current_branch(&repo).unwrap(), "outra");
+        assert!(dir.path().join("rascunho.txt").exists());
+    }
+
+    #[test]
+    fn branch_description_grava_le_e_remove() {
+        let (_dir, repo) = setup_repo_with_commit();
+        create_branch(&repo, "feature/nota", false).unwrap();
+
+        assert_eq!(get_branch_description(&repo, "feature/nota").unwrap(), None);
+
+        set_branch_description(&repo, "feature/nota", "Experimento de cache").unwrap();
+        assert_eq!(
+            get_branch_description(&repo, "feature/nota").unwrap().as_deref(),
+            Some("Experimento de cache")
+        );
+        let branches = list_branches(&repo, Some("nota"), true, false, false).unwrap();
+        assert_eq!(branches[0].description.as_deref(), Some("Experimento de cache"));
+
+        set_branch_description(&repo, "feature/nota", "").unwrap();
+        assert_eq!(get_branch_description(&repo, "feature/nota").unwrap(), None);
+        assert!(repo
+            .config()
+            .unwrap()
+            .get_entry("branch.feature/nota.description")
+            .is_err());
+    }
+
+    #[test]
+    fn set_branch_description_branch_inexistente_falha() {
+        let (_dir, repo) = setup_repo_with_commit();
+        let err = set_branch_description(&repo, "nao-existe", "x").unwrap_err();
+        assert_eq!(err.code, "BRANCH_NOT_FOUND");
+    }
+
+    #[test]
+    fn copy_branch_copia_ponta_e_configuracao() {
+        let (_dir, repo) = setup_repo_with_commit();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        repo.reference("refs/remotes/origin/base", head, true, "teste").unwrap();
+        create_branch(&repo, "base", false).unwrap();
+        repo.find_branch("base", BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("origin/base"))
+            .unwrap();
+        set_branch_description(&repo, "base", "Branch original").unwrap();
+
+        copy_branch(&repo, "base", "base-copia", true).unwrap();
+
+        let copia = repo.find_branch("base-copia", BranchType::Local).unwrap();
+        assert_eq!(copia.get().target(), Some(head));
+        assert_eq!(copia.upstream().unwrap().name().unwrap(), Some("origin/base"));
+        assert_eq!(
+            get_branch_description(&repo, "base-copia").unwrap().as_deref(),
+            Some("Branch original")
+        );
+        assert!(repo.find_branch("base", BranchType::Local).is_ok());
+        assert_eq!(get_current_branch(&repo).unwrap(), "base-copia");
+    }
+
+    #[test]
+    fn copy_branch_ignora_branch_com_prefixo_e_mantem_multivar() {
+        let (_dir, repo) = setup_repo_with_commit();
+        create_branch(&repo, "feat", false).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_multivar("branch.feat.merge", "^$", "refs/heads/a").unwrap();
+        config.set_multivar("branch.feat.merge", "^$", "refs/heads/b").unwrap();
+        config.set_str("branch.feat.x.remote", "outro").unwrap();
+
+        copy_branch(&repo, "feat", "feat-copia", false).unwrap();
+
+        let config = repo.config().unwrap().snapshot().unwrap();
+        let mut merges = Vec::new();
+        config
+            .multivar("branch.feat-copia.merge", None)
+            .unwrap()
+            .for_each(|entry| merges.push(entry.value().unwrap().to_string()))
+            .unwrap();
+        assert_eq!(merges, vec!["refs/heads/a", "refs/heads/b"]);
+        assert!(config.get_string("branch.feat-copia.remote").is_err());
+        assert!(config.get_string("branch.feat-copia.x.remote").is_err());
+    }
+
+    #[test]
+    fn copy_branch_nome_existente_falha() {
+        let (_dir, repo) = setup_repo_with_commit();
+        create_branch(&repo, "a", false).unwrap();
+        create_branch(&repo, "b", false).unwrap();
+        let err = copy_branch(&repo, "a", "b", false).unwrap_err();
+        assert_eq!(err.code, "BRANCH_EXISTS");
+    }
 }
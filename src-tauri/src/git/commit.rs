@@ -1,16 +1,40 @@
-use crate::error::{AppError, AppResult};
-use crate::git::{get_file_diff, DiffInfo, LineType};
+use crate::error::{AppError, AppResult, ErrorKind};
+use crate::git::{conflicted_paths, get_file_diff, DiffInfo, LineType};
 use git2::{IndexEntry, IndexTime, Oid, Repository};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const MAX_COUNT_CACHE_ENTRIES: usize = 256;
+
+/// Commit counts keyed by tip OID; a tip's history never changes, so entries stay valid
+static COMMIT_COUNT_CACHE: Lazy<Mutex<HashMap<Oid, usize>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+const MAX_STATS_CACHE_ENTRIES: usize = 4096;
+
+/// Diff stats keyed by commit OID; a commit's diff to its first parent never changes
+static COMMIT_STATS_CACHE: Lazy<Mutex<HashMap<Oid, CommitStats>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Size of a commit's change against its first parent (the empty tree for root commits)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CommitStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CommitInfo {
     pub hash: String,
     pub short_hash: String,
     pub message: String,
+    /// First line only, with a wrapped subject joined by libgit2
     pub summary: String,
+    /// Everything up to the first blank line, as written
+    pub subject: String,
+    /// Everything after the first blank line, internal blank lines kept
     pub body: Option<String>,
     pub author_name: String,
     pub author_email: String,
@@ -19,9 +43,28 @@ pub struct CommitInfo {
     pub committer_email: String,
     pub committer_date: i64,
     pub parents: Vec<String>,
+    /// Summary of each parent, in parent order; only filled by `fill_parent_summaries`
+    #[serde(default)]
+    pub parent_summaries: Vec<String>,
+    /// Only filled by `fill_commit_stats`
+    #[serde(default)]
+    pub stats: Option<CommitStats>,
     pub is_merge: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CommitQuery {
+    pub branch: Option<String>,
+    /// Case-insensitive substring of the full commit message
+    pub message: Option<String>,
+    /// Case-insensitive substring of the author name or email
+    pub author: Option<String>,
+    /// Only commits touching this path (slower: diffs each commit against its parent)
+    pub path: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PartialHunkSelection {
     pub hunk_index: usize,
@@ -75,6 +118,118 @@ pub fn list_commits(
     Ok(commits)
 }
 
+/// Counts the commits reachable from `branch` (or HEAD). The result is cached
+/// by tip OID so repeated calls are free until the branch moves.
+pub fn count_commits(repo: &Repository, branch: Option<&str>) -> AppResult<usize> {
+    let tip = match branch {
+        Some(branch_name) => repo
+            .refname_to_id(&format!("refs/heads/{}", branch_name))
+            .or_else(|_| repo.refname_to_id(&format!("refs/remotes/origin/{}", branch_name)))
+            .ok(),
+        None => repo.head().ok().and_then(|h| h.target()),
+    };
+
+    let tip = match tip {
+        Some(oid) => oid,
+        None => return Ok(0),
+    };
+
+    if let Some(count) = COMMIT_COUNT_CACHE.lock().unwrap().get(&tip) {
+        return Ok(*count);
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(tip)?;
+    let count = revwalk.count();
+
+    let mut cache = COMMIT_COUNT_CACHE.lock().unwrap();
+    if cache.len() >= MAX_COUNT_CACHE_ENTRIES {
+        cache.clear();
+    }
+    cache.insert(tip, count);
+
+    Ok(count)
+}
+
+pub fn search_commits(
+    repo: &Repository,
+    query: &CommitQuery,
+    limit: usize,
+    skip: usize,
+) -> AppResult<Vec<CommitInfo>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TIME | git2::Sort::TOPOLOGICAL)?;
+
+    if let Some(branch_name) = query.branch.as_deref() {
+        let oid = repo
+            .refname_to_id(&format!("refs/heads/{}", branch_name))
+            .or_else(|_| repo.refname_to_id(&format!("refs/remotes/origin/{}", branch_name)))
+            .map_err(|_| AppError::branch_not_found(branch_name))?;
+        revwalk.push(oid)?;
+    } else {
+        revwalk.push_head()?;
+    }
+
+    let message = query
+        .message
+        .as_deref()
+        .filter(|m| !m.is_empty())
+        .map(str::to_lowercase);
+    let author = query
+        .author
+        .as_deref()
+        .filter(|a| !a.is_empty())
+        .map(str::to_lowercase);
+    let path = query.path.as_deref().filter(|p| !p.is_empty());
+
+    let mut commits = Vec::new();
+    let mut matched = 0;
+
+    for oid in revwalk {
+        if commits.len() >= limit {
+            break;
+        }
+
+        let commit = repo.find_commit(oid?)?;
+        let time = commit.author().when().seconds();
+
+        if query.since.map(|since| time < since).unwrap_or(false)
+            || query.until.map(|until| time > until).unwrap_or(false)
+        {
+            continue;
+        }
+
+        if let Some(ref needle) = message {
+            if !commit.message().unwrap_or("").to_lowercase().contains(needle) {
+                continue;
+            }
+        }
+
+        if let Some(ref needle) = author {
+            let sig = commit.author();
+            let name = sig.name().unwrap_or("").to_lowercase();
+            let email = sig.email().unwrap_or("").to_lowercase();
+            if !name.contains(needle) && !email.contains(needle) {
+                continue;
+            }
+        }
+
+        // Most expensive filter last
+        if let Some(path) = path {
+            if !commit_touches_file(repo, &commit, path)? {
+                continue;
+            }
+        }
+
+        matched += 1;
+        if matched > skip {
+            commits.push(commit_to_info(&commit));
+        }
+    }
+
+    Ok(commits)
+}
+
 pub fn get_commit(repo: &Repository, hash: &str) -> AppResult<CommitInfo> {
     let oid = Oid::from_str(hash).map_err(|_| AppError::commit_not_found(hash))?;
     let commit = repo
@@ -83,24 +238,74 @@ pub fn get_commit(repo: &Repository, hash: &str) -> AppResult<CommitInfo> {
     Ok(commit_to_info(&commit))
 }
 
+/// Looks up each parent's summary so merges can be labeled by what they
+/// joined. Opt-in, since it costs one lookup per parent.
+pub fn fill_parent_summaries(repo: &Repository, commits: &mut [CommitInfo]) {
+    for info in commits.iter_mut() {
+        info.parent_summaries = info
+            .parents
+            .iter()
+            .map(|hash| {
+                Oid::from_str(hash)
+                    .and_then(|oid| repo.find_commit(oid))
+                    .ok()
+                    .and_then(|parent| parent.summary().map(String::from))
+                    .unwrap_or_default()
+            })
+            .collect();
+    }
+}
+
+/// Attaches diff stats to each commit of a page. Opt-in, since every commit
+/// not yet cached costs a tree diff.
+pub fn fill_commit_stats(repo: &Repository, commits: &mut [CommitInfo]) -> AppResult<()> {
+    for info in commits.iter_mut() {
+        let oid = Oid::from_str(&info.hash).map_err(|_| AppError::commit_not_found(&info.hash))?;
+        info.stats = Some(commit_stats(repo, oid)?);
+    }
+    Ok(())
+}
+
+fn commit_stats(repo: &Repository, oid: Oid) -> AppResult<CommitStats> {
+    if let Some(stats) = COMMIT_STATS_CACHE.lock().unwrap().get(&oid) {
+        return Ok(*stats);
+    }
+
+    let commit = repo.find_commit(oid)?;
+    let parent_tree = match commit.parent_count() {
+        0 => None,
+        _ => Some(commit.parent(0)?.tree()?),
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let diff_stats = diff.stats()?;
+    let stats = CommitStats {
+        files_changed: diff_stats.files_changed(),
+        insertions: diff_stats.insertions(),
+        deletions: diff_stats.deletions(),
+    };
+
+    let mut cache = COMMIT_STATS_CACHE.lock().unwrap();
+    if cache.len() >= MAX_STATS_CACHE_ENTRIES {
+        cache.clear();
+    }
+    cache.insert(oid, stats);
+
+    Ok(stats)
+}
+
 pub(crate) fn commit_to_info(commit: &git2::Commit) -> CommitInfo {
     let hash = commit.id().to_string();
     let message = commit.message().unwrap_or("").to_string();
     let summary = commit.summary().unwrap_or("").to_string();
-    let body = message
-        .lines()
-        .skip(1)
-        .collect::<Vec<_>>()
-        .join("\n")
-        .trim()
-        .to_string();
+    let (subject, body) = split_commit_message(&message);
 
     CommitInfo {
         hash: hash.clone(),
         short_hash: hash[..7.min(hash.len())].to_string(),
         message,
         summary,
-        body: if body.is_empty() { None } else { Some(body) },
+        subject,
+        body,
         author_name: commit.author().name().unwrap_or("").to_string(),
         author_email: commit.author().email().unwrap_or("").to_string(),
         author_date: commit.author().when().seconds(),
@@ -108,38 +313,133 @@ pub(crate) fn commit_to_info(commit: &git2::Commit) -> CommitInfo {
         committer_email: commit.committer().email().unwrap_or("").to_string(),
         committer_date: commit.committer().when().seconds(),
         parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+        parent_summaries: Vec::new(),
+        stats: None,
         is_merge: commit.parent_count() > 1,
     }
 }
 
-pub fn create_commit(repo: &Repository, message: &str, amend: bool) -> AppResult<String> {
+/// Splits a message the way git does: the subject runs up to the first blank
+/// line (it may wrap), the body is the rest with its blank lines preserved
+fn split_commit_message(message: &str) -> (String, Option<String>) {
+    let mut lines = message.lines().skip_while(|l| l.trim().is_empty());
+    let subject: Vec<&str> = lines.by_ref().take_while(|l| !l.trim().is_empty()).collect();
+    let body: Vec<&str> = lines.skip_while(|l| l.trim().is_empty()).collect();
+
+    let body = body.join("\n").trim_end().to_string();
+    (subject.join("\n"), if body.is_empty() { None } else { Some(body) })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommitReadiness {
+    pub has_staged_changes: bool,
+    pub has_conflicts: bool,
+    pub head_exists: bool,
+}
+
+fn head_tree_id(repo: &Repository) -> Option<Oid> {
+    repo.head()
+        .ok()
+        .and_then(|head| head.peel_to_tree().ok())
+        .map(|tree| tree.id())
+}
+
+/// Whether the staged tree differs from HEAD's, i.e. a commit would not be a no-op
+fn index_differs_from_head(index: &git2::Index, tree_id: Oid, head_tree: Option<Oid>) -> bool {
+    match head_tree {
+        Some(head_tree) => tree_id != head_tree,
+        None => !index.is_empty(),
+    }
+}
+
+/// Cheap commit precondition check: compares the index tree to HEAD's tree
+pub fn can_commit(repo: &Repository) -> AppResult<CommitReadiness> {
+    let mut index = repo.index()?;
+    index.read(false)?;
+
+    let head_tree = head_tree_id(repo);
+    let has_conflicts = index.has_conflicts();
+
+    // A conflicted index can't be written as a tree; fall back to a diff
+    let has_staged_changes = if has_conflicts {
+        let tree = match head_tree {
+            Some(id) => Some(repo.find_tree(id)?),
+            None => None,
+        };
+        repo.diff_tree_to_index(tree.as_ref(), Some(&index), None)?
+            .deltas()
+            .len()
+            > 0
+    } else {
+        let tree_id = index.write_tree()?;
+        index_differs_from_head(&index, tree_id, head_tree)
+    };
+
+    Ok(CommitReadiness {
+        has_staged_changes,
+        has_conflicts,
+        head_exists: head_tree.is_some(),
+    })
+}
+
+/// Author fields that replace the configured identity; unset fields fall
+/// back to the committer's.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AuthorOverride {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    /// Unix seconds or RFC3339 (e.g. "2024-05-01T10:00:00-03:00")
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateCommitResult {
+    pub short_hash: String,
+    /// Branch that now points at the commit; `None` when HEAD is detached
+    pub branch: Option<String>,
+    pub detached: bool,
+}
+
+/// `initial_branch` names the branch the very first commit goes on (an unborn
+/// HEAD otherwise uses `init.defaultBranch`); it's ignored once HEAD exists.
+pub fn create_commit(
+    repo: &Repository,
+    message: &str,
+    amend: bool,
+    author: &AuthorOverride,
+    run_hooks: bool,
+    initial_branch: Option<&str>,
+) -> AppResult<CreateCommitResult> {
     let signature = repo
         .signature()
         .map_err(|_| AppError::git_user_not_configured())?;
+    let author_signature = build_author_signature(&signature, author)?;
+
+    let hooked_message;
+    let message = if run_hooks {
+        hooked_message = super::run_commit_hooks(repo, message)?;
+        hooked_message.as_str()
+    } else {
+        message
+    };
 
     let mut index = repo.index()?;
+    if run_hooks {
+        // pre-commit may have staged changes on disk
+        index.read(false)?;
+    }
 
-    // Check if there are staged changes
     let tree_id = index.write_tree()?;
+    // Amend may only reword, so only plain commits need staged changes
+    if !amend && !index_differs_from_head(&index, tree_id, head_tree_id(repo)) {
+        return Err(AppError::nothing_to_commit());
+    }
     let tree = repo.find_tree(tree_id)?;
 
     if amend {
-        let head = repo.head()?;
-        let head_commit = head.peel_to_commit()?;
-        let parents: Vec<git2::Commit> = head_commit.parents().collect();
-        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
-
-        let commit_id = repo.commit(
-            Some("HEAD"),
-            &signature,
-            &signature,
-            message,
-            &tree,
-            &parent_refs,
-        )?;
-
-        let id_str = commit_id.to_string();
-        Ok(id_str[..7.min(id_str.len())].to_string())
+        let head_commit = repo.head()?.peel_to_commit()?;
+        let commit_id = amend_head(&head_commit, Some(&author_signature), &signature, Some(message), &tree)?;
+        commit_result(repo, commit_id)
     } else {
         let head = repo.head();
 
@@ -147,20 +447,270 @@ pub fn create_commit(repo: &Repository, message: &str, amend: bool) -> AppResult
             let head_commit = head_ref.peel_to_commit()?;
             repo.commit(
                 Some("HEAD"),
-                &signature,
+                &author_signature,
                 &signature,
                 message,
                 &tree,
                 &[&head_commit],
             )?
         } else {
-            // Initial commit
-            repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[])?
+            // Initial commit. HEAD only moves to the named branch once the
+            // commit exists, so a failed commit leaves it where it was.
+            let refname = match initial_branch.map(str::trim).filter(|b| !b.is_empty()) {
+                Some(branch) => {
+                    let refname = format!("refs/heads/{}", branch);
+                    if !git2::Reference::is_valid_name(&refname) {
+                        return Err(AppError::with_details("INVALID_BRANCH_NAME", "Nome de branch invalido", branch));
+                    }
+                    Some(refname)
+                }
+                None => None,
+            };
+            let commit_id = repo.commit(
+                Some(refname.as_deref().unwrap_or("HEAD")),
+                &author_signature,
+                &signature,
+                message,
+                &tree,
+                &[],
+            )?;
+            if let Some(refname) = &refname {
+                repo.set_head(refname)?;
+            }
+            commit_id
         };
 
-        let id_str = commit_id.to_string();
-        Ok(id_str[..7.min(id_str.len())].to_string())
+        commit_result(repo, commit_id)
+    }
+}
+
+/// Rewrites HEAD with `tree`; `None` author/message keep the original ones
+fn amend_head(
+    head_commit: &git2::Commit,
+    author: Option<&git2::Signature>,
+    committer: &git2::Signature,
+    message: Option<&str>,
+    tree: &git2::Tree,
+) -> AppResult<Oid> {
+    // `repo.commit` refuses to move HEAD when the tip isn't the first parent
+    Ok(head_commit.amend(Some("HEAD"), author, Some(committer), None, message, Some(tree))?)
+}
+
+/// Whether the current branch's upstream already contains `commit`
+fn is_commit_pushed(repo: &Repository, commit: Oid) -> AppResult<Option<String>> {
+    if repo.head_detached()? {
+        return Ok(None);
+    }
+    let head = repo.head()?;
+    let Some(name) = head.shorthand() else {
+        return Ok(None);
+    };
+    let branch = repo.find_branch(name, git2::BranchType::Local)?;
+    let Ok(upstream) = branch.upstream() else {
+        return Ok(None);
+    };
+    let Some(upstream_oid) = upstream.get().target() else {
+        return Ok(None);
+    };
+    if upstream_oid == commit || repo.graph_descendant_of(upstream_oid, commit)? {
+        return Ok(upstream.name()?.map(String::from));
+    }
+    Ok(None)
+}
+
+/// "Forgot a file": folds the staged changes into HEAD, keeping its message
+/// and author. Refuses when HEAD is already on the upstream unless `force`.
+pub fn add_to_last_commit(repo: &Repository, force: bool) -> AppResult<CreateCommitResult> {
+    let signature = repo
+        .signature()
+        .map_err(|_| AppError::git_user_not_configured())?;
+    let head_commit = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|_| AppError::new("NO_HEAD_COMMIT", "Nao ha commit anterior para emendar"))?;
+
+    let mut index = repo.index()?;
+    let tree_id = index.write_tree()?;
+    if tree_id == head_commit.tree_id() {
+        return Err(AppError::nothing_to_commit());
+    }
+
+    if !force {
+        if let Some(upstream) = is_commit_pushed(repo, head_commit.id())? {
+            return Err(AppError::with_details(
+                "COMMIT_ALREADY_PUSHED",
+                "O ultimo commit ja foi enviado; emendar exigira force push",
+                &upstream,
+            )
+            .with_kind(ErrorKind::Validation));
+        }
+    }
+
+    let tree = repo.find_tree(tree_id)?;
+    let commit_id = amend_head(&head_commit, None, &signature, None, &tree)?;
+    commit_result(repo, commit_id)
+}
+
+fn commit_result(repo: &Repository, commit_id: Oid) -> AppResult<CreateCommitResult> {
+    let id_str = commit_id.to_string();
+    let detached = repo.head_detached()?;
+    let branch = if detached {
+        None
+    } else {
+        repo.head()?.shorthand().map(String::from)
+    };
+
+    Ok(CreateCommitResult {
+        short_hash: id_str[..7.min(id_str.len())].to_string(),
+        branch,
+        detached,
+    })
+}
+
+fn build_author_signature(
+    committer: &git2::Signature<'static>,
+    author: &AuthorOverride,
+) -> AppResult<git2::Signature<'static>> {
+    let name = match author.name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => committer.name().unwrap_or("").to_string(),
+    };
+    let email = match author.email.as_deref().map(str::trim) {
+        Some(email) if !email.is_empty() => email.to_string(),
+        _ => committer.email().unwrap_or("").to_string(),
+    };
+    let when = match author.date.as_deref().map(str::trim) {
+        Some(date) if !date.is_empty() => parse_author_date(date)?,
+        _ => committer.when(),
+    };
+
+    git2::Signature::new(&name, &email, &when).map_err(AppError::from)
+}
+
+/// Parses unix seconds or an RFC3339 timestamp into a git time.
+fn parse_author_date(date: &str) -> AppResult<git2::Time> {
+    let invalid = || {
+        AppError::with_details(
+            "INVALID_AUTHOR_DATE",
+            "Data do autor invalida (use segundos unix ou RFC3339)",
+            date,
+        )
+    };
+
+    if let Ok(seconds) = date.parse::<i64>() {
+        return Ok(git2::Time::new(seconds, 0));
+    }
+
+    // YYYY-MM-DDTHH:MM:SS[.fraction](Z|+HH:MM|-HH:MM)
+    let bytes = date.as_bytes();
+    if bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return Err(invalid());
+    }
+
+    let number =
+        |range: std::ops::Range<usize>| date.get(range).and_then(|s| s.parse::<i64>().ok());
+    let (year, month, day) = (number(0..4), number(5..7), number(8..10));
+    let (hour, minute, second) = (number(11..13), number(14..16), number(17..19));
+    let (Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)) =
+        (year, month, day, hour, minute, second)
+    else {
+        return Err(invalid());
+    };
+
+    let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if !(1..=12).contains(&month) || !(1..=days_in_month).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return Err(invalid());
     }
+
+    let mut rest = &date[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(|b| b.is_ascii_digit()).count();
+        if digits == 0 {
+            return Err(invalid());
+        }
+        rest = &fraction[digits..];
+    }
+
+    let offset_minutes = match rest {
+        "Z" | "z" => 0,
+        _ if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return Err(invalid()),
+            };
+            let hours = rest[1..3].parse::<i64>().map_err(|_| invalid())?;
+            let minutes = rest[4..6].parse::<i64>().map_err(|_| invalid())?;
+            sign * (hours * 60 + minutes)
+        }
+        _ => return Err(invalid()),
+    };
+
+    // Days since 1970-01-01 (civil calendar, proleptic Gregorian)
+    let (y, m) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    let local_seconds = days * 86400 + hour * 3600 + minute * 60 + second;
+    Ok(git2::Time::new(local_seconds - offset_minutes * 60, offset_minutes as i32))
+}
+
+/// Text of the commit message template (`commit.template`, else a repo-root
+/// `.gitmessage`). With `clean`, lines starting with `core.commentChar` are dropped.
+pub fn get_commit_template(repo: &Repository, clean: bool) -> AppResult<Option<String>> {
+    let config = repo.config()?;
+    let workdir = repo.workdir();
+
+    let path = match config.get_path("commit.template") {
+        // Relative paths are resolved against the worktree, like git does from its root
+        Ok(path) if path.is_relative() => workdir.map(|w| w.join(&path)).unwrap_or(path),
+        Ok(path) => path,
+        Err(_) => match workdir.map(|w| w.join(".gitmessage")) {
+            Some(path) if path.is_file() => path,
+            _ => return Ok(None),
+        },
+    };
+
+    let template = std::fs::read_to_string(&path).map_err(|e| {
+        AppError::with_details(
+            "COMMIT_TEMPLATE_UNREADABLE",
+            "Não foi possível ler o template de commit",
+            &format!("{}: {}", path.display(), e),
+        )
+    })?;
+
+    if !clean {
+        return Ok(Some(template));
+    }
+
+    // "auto" makes git pick a char absent from the message; a template is
+    // written for the default one
+    let comment_char = config
+        .get_string("core.commentChar")
+        .ok()
+        .filter(|c| c != "auto")
+        .and_then(|c| c.chars().next())
+        .unwrap_or('#');
+    let cleaned: Vec<&str> = template
+        .lines()
+        .filter(|line| !line.starts_with(comment_char))
+        .collect();
+    Ok(Some(cleaned.join("\n").trim_end().to_string()))
 }
 
 pub fn stage_files(repo: &Repository, files: &[String], repo_path: &PathBuf) -> AppResult<()> {
@@ -168,7 +718,10 @@ pub fn stage_files(repo: &Repository, files: &[String], repo_path: &PathBuf) ->
 
     for file in files {
         let full_path = repo_path.join(file);
-        if full_path.exists() {
+        if full_path.is_dir() {
+            // Collapsed untracked directories ("newdir/") arrive here as-is
+            add_pathspec(&mut index, file)?;
+        } else if full_path.exists() {
             index.add_path(std::path::Path::new(file))?;
         } else {
             // File was deleted
@@ -180,6 +733,21 @@ pub fn stage_files(repo: &Repository, files: &[String], repo_path: &PathBuf) ->
     Ok(())
 }
 
+/// Stages everything under `path` (a file or directory): new, modified and deleted files
+pub fn stage_path(repo: &Repository, path: &str) -> AppResult<()> {
+    let mut index = repo.index()?;
+    add_pathspec(&mut index, path)?;
+    index.write()?;
+    Ok(())
+}
+
+fn add_pathspec(index: &mut git2::Index, path: &str) -> AppResult<()> {
+    let pathspec = path.trim_end_matches('/');
+    index.add_all([pathspec].iter(), git2::IndexAddOption::DEFAULT, None)?;
+    index.update_all([pathspec].iter(), None)?;
+    Ok(())
+}
+
 pub fn stage_partial_changes(
     repo: &Repository,
     path: &str,
@@ -190,50 +758,24 @@ pub fn stage_partial_changes(
 }
 
 pub fn unstage_files(repo: &Repository, files: &[String]) -> AppResult<()> {
-    let head_tree = repo
-        .head()
-        .ok()
-        .and_then(|h| h.peel_to_tree().ok());
-
-    let mut index = repo.index()?;
+    if files.is_empty() {
+        return Ok(());
+    }
 
-    for file in files {
-        let path = std::path::Path::new(file);
-
-        if let Some(ref tree) = head_tree {
-            if let Ok(entry) = tree.get_path(path) {
-                // Restore to HEAD version
-                let obj = entry.to_object(repo)?;
-                let blob = obj.as_blob().ok_or_else(|| AppError::internal("Not a blob"))?;
-
-                index.add_frombuffer(
-                    &git2::IndexEntry {
-                        ctime: git2::IndexTime::new(0, 0),
-                        mtime: git2::IndexTime::new(0, 0),
-                        dev: 0,
-                        ino: 0,
-                        mode: entry.filemode() as u32,
-                        uid: 0,
-                        gid: 0,
-                        file_size: blob.content().len() as u32,
-                        id: entry.id(),
-                        flags: 0,
-                        flags_extended: 0,
-                        path: file.as_bytes().to_vec(),
-                    },
-                    blob.content(),
-                )?;
-            } else {
-                // File is new, remove from index
-                index.remove_path(path)?;
+    match repo.head().ok().and_then(|h| h.peel_to_commit().ok()) {
+        Some(head_commit) => {
+            repo.reset_default(Some(head_commit.as_object()), files)?;
+        }
+        None => {
+            // No HEAD yet (initial commit), remove from index
+            let mut index = repo.index()?;
+            for file in files {
+                index.remove_path(Path::new(file))?;
             }
-        } else {
-            // No HEAD, remove from index
-            index.remove_path(path)?;
+            index.write()?;
         }
     }
 
-    index.write()?;
     Ok(())
 }
 
@@ -262,11 +804,144 @@ pub fn unstage_all(repo: &Repository) -> AppResult<()> {
     Ok(())
 }
 
+/// What `discard_changes` would do to a path
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscardAction {
+    /// Modified: goes back to its HEAD content
+    Revert,
+    /// Not in HEAD (untracked or newly added): removed from disk
+    Delete,
+    /// Deleted from the working tree: brought back from HEAD
+    Restore,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiscardPreview {
+    /// As passed in; an untracked directory ("dir/") sums every file inside
+    pub path: String,
+    pub action: DiscardAction,
+    /// Lines that would be lost
+    pub additions: usize,
+    /// Lines that would come back
+    pub deletions: usize,
+    pub is_binary: bool,
+}
+
+/// Describes what discarding `files` would lose, without touching anything.
+/// Paths with nothing to discard are left out.
+pub fn preview_discard(repo: &Repository, files: &[String]) -> AppResult<Vec<DiscardPreview>> {
+    // An empty pathspec would cover the whole working tree
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+
+    let mut diff_opts = git2::DiffOptions::new();
+    diff_opts
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    for file in files {
+        diff_opts.pathspec(file.trim_end_matches('/'));
+    }
+    let diff = repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut diff_opts))?;
+
+    let mut previews: Vec<DiscardPreview> = Vec::new();
+    for delta_idx in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(delta_idx) else {
+            continue;
+        };
+        let delta_path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        let Some(file) = files.iter().find(|f| {
+            let dir = format!("{}/", f.trim_end_matches('/'));
+            delta_path == f.trim_end_matches('/') || delta_path.starts_with(&dir)
+        }) else {
+            continue;
+        };
+
+        let action = match delta.status() {
+            git2::Delta::Untracked | git2::Delta::Added => DiscardAction::Delete,
+            git2::Delta::Deleted => DiscardAction::Restore,
+            _ => DiscardAction::Revert,
+        };
+        let (additions, deletions) = match git2::Patch::from_diff(&diff, delta_idx)? {
+            Some(patch) => {
+                let (_, additions, deletions) = patch.line_stats()?;
+                (additions, deletions)
+            }
+            None => (0, 0),
+        };
+        // The binary flag is only reliable once the patch has been loaded
+        let is_binary = diff
+            .get_delta(delta_idx)
+            .map(|d| d.flags().is_binary())
+            .unwrap_or(false);
+
+        match previews.iter_mut().find(|p| &p.path == file) {
+            Some(preview) => {
+                preview.additions += additions;
+                preview.deletions += deletions;
+                preview.is_binary |= is_binary;
+            }
+            None => previews.push(DiscardPreview {
+                path: file.clone(),
+                action,
+                additions,
+                deletions,
+                is_binary,
+            }),
+        }
+    }
+
+    Ok(previews)
+}
+
 pub fn discard_changes(repo: &Repository, files: &[String]) -> AppResult<()> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| AppError::internal("Repositorio bare nao suportado"))?
+        .to_path_buf();
+
+    let mut status_opts = git2::StatusOptions::new();
+    status_opts.include_untracked(true).recurse_untracked_dirs(false);
+    let statuses = repo.statuses(Some(&mut status_opts))?;
+
+    // Untracked directories are reported once, with a trailing slash
+    let untracked: Vec<String> = statuses
+        .iter()
+        .filter(|e| e.status().is_wt_new() && !e.status().is_index_new())
+        .filter_map(|e| e.path().map(|p| p.to_string()))
+        .collect();
+
+    let mut tracked = Vec::new();
+    for file in files {
+        let is_untracked = untracked.iter().any(|u| {
+            u.trim_end_matches('/') == file.trim_end_matches('/')
+                || (u.ends_with('/') && file.starts_with(u.as_str()))
+        });
+
+        if is_untracked {
+            remove_worktree_path(&workdir, file)?;
+        } else {
+            tracked.push(file);
+        }
+    }
+
+    // An empty pathspec would check out the whole tree
+    if tracked.is_empty() {
+        return Ok(());
+    }
+
     let mut checkout_builder = git2::build::CheckoutBuilder::new();
     checkout_builder.force();
 
-    for file in files {
+    for file in tracked {
         checkout_builder.path(file);
     }
 
@@ -274,12 +949,220 @@ pub fn discard_changes(repo: &Repository, files: &[String]) -> AppResult<()> {
     Ok(())
 }
 
-fn apply_partial_changes(
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ResetWorkingTreeResult {
+    /// Tracked paths with staged or unstaged changes that went back to HEAD
+    pub reset: usize,
+    /// Untracked/ignored entries deleted (a directory counts once)
+    pub removed: usize,
+}
+
+/// Unstages and discards everything (`git reset --hard`), then optionally
+/// deletes untracked and/or ignored entries like `git clean -fd` / `-fdx`.
+/// Nested repositories are left alone, as `git clean` does without `-ff`.
+pub fn reset_working_tree(
     repo: &Repository,
-    path: &str,
-    staged: bool,
-    selections: &[PartialHunkSelection],
-    repo_path: &PathBuf,
+    include_untracked: bool,
+    include_ignored: bool,
+) -> AppResult<ResetWorkingTreeResult> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| AppError::internal("Repositorio bare nao suportado"))?
+        .to_path_buf();
+    let head = repo
+        .head()
+        .and_then(|h| h.peel_to_commit())
+        .map_err(|_| AppError::new("NO_HEAD_COMMIT", "Nao ha commit no HEAD para restaurar"))?;
+
+    let mut status_opts = git2::StatusOptions::new();
+    status_opts.include_untracked(false).include_ignored(false);
+    let mut result = ResetWorkingTreeResult {
+        reset: repo.statuses(Some(&mut status_opts))?.len(),
+        removed: 0,
+    };
+
+    // Collected before the reset, which turns staged new files into untracked ones
+    let to_remove = clean_candidates(repo, include_untracked, include_ignored, true)?;
+
+    repo.reset(head.as_object(), git2::ResetType::Hard, None)?;
+
+    for path in &to_remove {
+        remove_worktree_path(&workdir, path)?;
+    }
+    result.removed = to_remove.len();
+
+    Ok(result)
+}
+
+/// Untracked and/or ignored entries that `git clean` would delete. Without
+/// `directories` only loose files count; nested repositories and `.git`
+/// are never included.
+fn clean_candidates(
+    repo: &Repository,
+    untracked: bool,
+    ignored: bool,
+    directories: bool,
+) -> AppResult<Vec<String>> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| AppError::internal("Repositorio bare nao suportado"))?;
+
+    let mut status_opts = git2::StatusOptions::new();
+    status_opts
+        .include_untracked(untracked)
+        .recurse_untracked_dirs(false)
+        .include_ignored(ignored)
+        .recurse_ignored_dirs(false);
+
+    let mut paths = Vec::new();
+    for entry in repo.statuses(Some(&mut status_opts))?.iter() {
+        let status = entry.status();
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        let is_untracked = status.is_wt_new() && !status.is_index_new();
+        if !((is_untracked && untracked) || (status.is_ignored() && ignored)) {
+            continue;
+        }
+
+        let trimmed = path.trim_end_matches('/');
+        let is_dir = path.ends_with('/');
+        if (is_dir && !directories)
+            || Path::new(trimmed).components().next() == Some(std::path::Component::Normal(".git".as_ref()))
+            || (is_dir && workdir.join(trimmed).join(".git").exists())
+        {
+            continue;
+        }
+        paths.push(path.to_string());
+    }
+    Ok(paths)
+}
+
+/// `git clean`: deletes untracked files (and whole untracked directories with
+/// `directories`, ignored ones too with `ignored`). With `dry_run` nothing is
+/// deleted. Returns the affected paths either way.
+pub fn clean_working_tree(
+    repo: &Repository,
+    directories: bool,
+    ignored: bool,
+    dry_run: bool,
+) -> AppResult<Vec<String>> {
+    let paths = clean_candidates(repo, true, ignored, directories)?;
+    if dry_run {
+        return Ok(paths);
+    }
+
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| AppError::internal("Repositorio bare nao suportado"))?
+        .to_path_buf();
+    for path in &paths {
+        remove_worktree_path(&workdir, path)?;
+    }
+    Ok(paths)
+}
+
+/// Restores the given paths from HEAD, or from the index when `staged` is set,
+/// returning the files that actually had changes to restore.
+pub fn restore_files(repo: &Repository, files: &[String], staged: bool) -> AppResult<Vec<String>> {
+    let mut status_opts = git2::StatusOptions::new();
+    status_opts.include_untracked(false);
+    for file in files {
+        status_opts.pathspec(file);
+    }
+    status_opts.disable_pathspec_match(true);
+    let statuses = repo.statuses(Some(&mut status_opts))?;
+
+    let worktree_changes = git2::Status::WT_MODIFIED | git2::Status::WT_DELETED | git2::Status::WT_TYPECHANGE;
+    let index_changes = git2::Status::INDEX_MODIFIED | git2::Status::INDEX_DELETED | git2::Status::INDEX_TYPECHANGE;
+    let restorable = if staged {
+        worktree_changes
+    } else {
+        worktree_changes | index_changes
+    };
+
+    let restored: Vec<String> = statuses
+        .iter()
+        .filter(|e| e.status().intersects(restorable))
+        .filter_map(|e| e.path().map(|p| p.to_string()))
+        .filter(|p| files.contains(p))
+        .collect();
+
+    // An empty pathspec would check out the whole tree
+    if restored.is_empty() {
+        return Ok(restored);
+    }
+
+    let mut checkout_builder = git2::build::CheckoutBuilder::new();
+    checkout_builder.force();
+    for file in &restored {
+        checkout_builder.path(file);
+    }
+
+    if staged {
+        repo.checkout_index(None, Some(&mut checkout_builder))?;
+    } else {
+        repo.checkout_head(Some(&mut checkout_builder))?;
+    }
+
+    Ok(restored)
+}
+
+fn remove_worktree_path(workdir: &Path, file: &str) -> AppResult<()> {
+    let relative = Path::new(file.trim_end_matches('/'));
+    let escapes = relative.components().any(|c| {
+        !matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir)
+    });
+    if escapes || relative.as_os_str().is_empty() {
+        return Err(AppError::with_details(
+            "INVALID_PATH",
+            "Caminho fora do repositorio",
+            file,
+        ));
+    }
+
+    let full_path = workdir.join(relative);
+
+    // A symlinked parent could point outside the working directory. The
+    // parent itself may be gone (a deleted folder), so check the nearest
+    // ancestor that still exists
+    let mut parent = full_path.parent().unwrap_or(workdir);
+    while std::fs::symlink_metadata(parent).is_err() {
+        match parent.parent() {
+            Some(up) if up.starts_with(workdir) => parent = up,
+            _ => break,
+        }
+    }
+    if !parent.canonicalize()?.starts_with(workdir.canonicalize()?) {
+        return Err(AppError::with_details(
+            "INVALID_PATH",
+            "Caminho fora do repositorio",
+            file,
+        ));
+    }
+
+    let metadata = match std::fs::symlink_metadata(&full_path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    // symlink_metadata does not follow links, so a link is removed, not its target
+    if metadata.is_dir() {
+        std::fs::remove_dir_all(&full_path)?;
+    } else {
+        std::fs::remove_file(&full_path)?;
+    }
+
+    Ok(())
+}
+
+fn apply_partial_changes(
+    repo: &Repository,
+    path: &str,
+    staged: bool,
+    selections: &[PartialHunkSelection],
+    repo_path: &PathBuf,
 ) -> AppResult<()> {
     if selections.is_empty() {
         return Ok(());
@@ -674,30 +1557,120 @@ fn current_file_mode(repo: &Repository, path: &str) -> Option<u32> {
         .map(|entry| entry.filemode() as u32)
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileHistoryEntry {
+    #[serde(flatten)]
+    pub commit: CommitInfo,
+    /// How the file changed in this commit ("added", "modified", "deleted", "renamed")
+    pub file_status: String,
+    /// Path of the file in this commit; differs from the requested one past a rename
+    pub path: String,
+    pub old_path: Option<String>,
+}
+
+/// Commits that touched `file_path`, newest first. With `follow`, history
+/// continues under the old name when the file was renamed.
 pub fn list_file_history(
     repo: &Repository,
     file_path: &str,
     limit: usize,
-) -> AppResult<Vec<CommitInfo>> {
+    skip: usize,
+    follow: bool,
+) -> AppResult<Vec<FileHistoryEntry>> {
     let mut revwalk = repo.revwalk()?;
     revwalk.set_sorting(git2::Sort::TIME | git2::Sort::TOPOLOGICAL)?;
     revwalk.push_head()?;
 
-    let mut commits = Vec::new();
+    let mut tracked = file_path.to_string();
+    let mut matched = 0;
+    let mut entries = Vec::new();
 
     for oid_result in revwalk {
-        if commits.len() >= limit {
+        if entries.len() >= limit {
             break;
         }
         let oid = oid_result?;
         let commit = repo.find_commit(oid)?;
 
-        if commit_touches_file(repo, &commit, file_path)? {
-            commits.push(commit_to_info(&commit));
+        let Some((file_status, old_path)) = file_change_in_commit(repo, &commit, &tracked, follow)? else {
+            continue;
+        };
+
+        let path = tracked.clone();
+        if let Some(ref old) = old_path {
+            tracked = old.clone();
         }
+
+        matched += 1;
+        if matched <= skip {
+            continue;
+        }
+
+        entries.push(FileHistoryEntry {
+            commit: commit_to_info(&commit),
+            file_status: file_status.to_string(),
+            path,
+            old_path,
+        });
     }
 
-    Ok(commits)
+    Ok(entries)
+}
+
+/// How `file_path` changed relative to the first parent, if at all. The
+/// rename source is only looked up (with a full diff) when following.
+fn file_change_in_commit(
+    repo: &Repository,
+    commit: &git2::Commit,
+    file_path: &str,
+    follow: bool,
+) -> AppResult<Option<(&'static str, Option<String>)>> {
+    let path = Path::new(file_path);
+    let tree = commit.tree()?;
+    let current = tree.get_path(path).ok().map(|e| e.id());
+
+    let parent_tree = if commit.parent_count() > 0 {
+        Some(commit.parent(0)?.tree()?)
+    } else {
+        None
+    };
+    let previous = parent_tree
+        .as_ref()
+        .and_then(|t| t.get_path(path).ok())
+        .map(|e| e.id());
+
+    match (previous, current) {
+        (None, None) => Ok(None),
+        (Some(before), Some(after)) if before == after => Ok(None),
+        (Some(_), Some(_)) => Ok(Some(("modified", None))),
+        (Some(_), None) => Ok(Some(("deleted", None))),
+        (None, Some(_)) => {
+            if follow && parent_tree.is_some() {
+                if let Some(source) = rename_source(repo, parent_tree.as_ref(), &tree, file_path)? {
+                    return Ok(Some(("renamed", Some(source))));
+                }
+            }
+            Ok(Some(("added", None)))
+        }
+    }
+}
+
+fn rename_source(
+    repo: &Repository,
+    parent_tree: Option<&git2::Tree>,
+    tree: &git2::Tree,
+    file_path: &str,
+) -> AppResult<Option<String>> {
+    let mut diff = repo.diff_tree_to_tree(parent_tree, Some(tree), None)?;
+    let mut find_opts = git2::DiffFindOptions::new();
+    find_opts.renames(true);
+    diff.find_similar(Some(&mut find_opts))?;
+
+    Ok(diff
+        .deltas()
+        .filter(|d| d.status() == git2::Delta::Renamed)
+        .find(|d| d.new_file().path() == Some(Path::new(file_path)))
+        .and_then(|d| d.old_file().path().map(|p| p.to_string_lossy().to_string())))
 }
 
 fn commit_touches_file(
@@ -778,10 +1751,7 @@ pub fn revert_commit(repo: &Repository, commit_hash: &str) -> AppResult<String>
     let tree = repo.find_tree(tree_id)?;
     let head = repo.head()?.peel_to_commit()?;
 
-    let message = format!("Revert \"{}\"\n\nThis reverts commit {}.",
-        commit.summary().unwrap_or(""),
-        commit_hash
-    );
+    let message = revert_message(&commit);
 
     let new_commit_id = repo.commit(
         Some("HEAD"),
@@ -799,6 +1769,96 @@ pub fn revert_commit(repo: &Repository, commit_hash: &str) -> AppResult<String>
     Ok(id_str[..7.min(id_str.len())].to_string())
 }
 
+fn revert_message(commit: &git2::Commit) -> String {
+    format!(
+        "Revert \"{}\"\n\nThis reverts commit {}.",
+        commit.summary().unwrap_or(""),
+        commit.id()
+    )
+}
+
+/// Finishes a conflicted cherry-pick once the conflicts are resolved and staged
+pub fn continue_cherry_pick(repo: &Repository) -> AppResult<String> {
+    continue_operation(
+        repo,
+        &[
+            git2::RepositoryState::CherryPick,
+            git2::RepositoryState::CherryPickSequence,
+        ],
+        AppError::new("NOT_CHERRY_PICKING", "Não há cherry-pick em andamento"),
+        "CHERRY_PICK_HEAD",
+        |commit| commit.message().unwrap_or("").to_string(),
+        |commit| Some(commit.author().to_owned()),
+    )
+}
+
+/// Finishes a conflicted revert once the conflicts are resolved and staged
+pub fn continue_revert(repo: &Repository) -> AppResult<String> {
+    continue_operation(
+        repo,
+        &[
+            git2::RepositoryState::Revert,
+            git2::RepositoryState::RevertSequence,
+        ],
+        AppError::new("NOT_REVERTING", "Não há revert em andamento"),
+        "REVERT_HEAD",
+        revert_message,
+        |_| None,
+    )
+}
+
+/// Commits the staged tree on top of HEAD for the operation recorded in `head_file`.
+/// `author_for` returning `None` makes the local signature the author too.
+fn continue_operation(
+    repo: &Repository,
+    states: &[git2::RepositoryState],
+    not_in_state: AppError,
+    head_file: &str,
+    message_for: impl Fn(&git2::Commit) -> String,
+    author_for: impl Fn(&git2::Commit) -> Option<git2::Signature<'static>>,
+) -> AppResult<String> {
+    if !states.contains(&repo.state()) {
+        return Err(not_in_state);
+    }
+
+    let recorded = std::fs::read_to_string(repo.path().join(head_file))?;
+    let oid = Oid::from_str(recorded.trim())
+        .map_err(|_| AppError::commit_not_found(recorded.trim()))?;
+    let commit = repo.find_commit(oid)?;
+
+    let mut index = repo.index()?;
+    index.read(false)?;
+    if index.has_conflicts() {
+        return Err(AppError::with_details(
+            "UNRESOLVED_CONFLICTS",
+            "Ainda há conflitos não resolvidos",
+            &conflicted_paths(&index)?.join("\n"),
+        ));
+    }
+
+    let signature = repo
+        .signature()
+        .map_err(|_| AppError::git_user_not_configured())?;
+    let tree_id = index.write_tree()?;
+    let tree = repo.find_tree(tree_id)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let author = author_for(&commit).unwrap_or_else(|| signature.clone());
+
+    let new_commit_id = repo.commit(
+        Some("HEAD"),
+        &author,
+        &signature,
+        &message_for(&commit),
+        &tree,
+        &[&head],
+    )?;
+
+    repo.cleanup_state()?;
+
+    let id_str = new_commit_id.to_string();
+    Ok(id_str[..7.min(id_str.len())].to_string())
+}
+
 pub fn reset_to_commit(
     repo: &Repository,
     commit_hash: &str,
@@ -818,6 +1878,129 @@ pub fn reset_to_commit(
     Ok(())
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ResetPathsResult {
+    /// Paths now matching the target commit
+    pub restored: Vec<String>,
+    /// Paths absent from the target commit that were deleted
+    pub removed: Vec<String>,
+    /// Paths absent from the target commit that were left untouched
+    pub missing: Vec<String>,
+}
+
+/// Resets only `paths` to their state in `commit_hash`, like
+/// `git checkout <commit> -- <paths>` when `update_worktree` is set and
+/// `git reset <commit> -- <paths>` otherwise.
+pub fn reset_paths_to_commit(
+    repo: &Repository,
+    commit_hash: &str,
+    paths: &[String],
+    update_worktree: bool,
+) -> AppResult<ResetPathsResult> {
+    let oid = Oid::from_str(commit_hash).map_err(|_| AppError::commit_not_found(commit_hash))?;
+    let commit = repo.find_commit(oid)?;
+    let tree = commit.tree()?;
+
+    let mut result = ResetPathsResult::default();
+    for path in paths {
+        if tree.get_path(Path::new(path)).is_ok() {
+            result.restored.push(path.clone());
+        } else if update_worktree {
+            result.removed.push(path.clone());
+        } else {
+            result.missing.push(path.clone());
+        }
+    }
+
+    // An empty pathspec would act on the whole tree
+    if !result.restored.is_empty() {
+        if update_worktree {
+            let mut checkout_builder = git2::build::CheckoutBuilder::new();
+            checkout_builder.force();
+            for path in &result.restored {
+                checkout_builder.path(path);
+            }
+            repo.checkout_tree(commit.as_object(), Some(&mut checkout_builder))?;
+        } else {
+            repo.reset_default(Some(commit.as_object()), &result.restored)?;
+        }
+    }
+
+    if !result.removed.is_empty() {
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| AppError::internal("Repositorio bare nao suportado"))?
+            .to_path_buf();
+
+        let mut index = repo.index()?;
+        index.remove_all(&result.removed, None)?;
+        index.write()?;
+
+        for path in &result.removed {
+            remove_worktree_path(&workdir, path)?;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Squashes every commit from `from_hash` up to HEAD (inclusive) into a single
+/// commit with `message`. The earliest commit's author is preserved and the
+/// index/working tree are left untouched, like `git reset --soft` + `commit`.
+pub fn squash_commits(repo: &Repository, from_hash: &str, message: &str) -> AppResult<String> {
+    if repo.state() != git2::RepositoryState::Clean {
+        return Err(AppError::internal(
+            "O repositório tem operações pendentes (merge, rebase, cherry-pick, etc.)",
+        ));
+    }
+
+    let oid = Oid::from_str(from_hash).map_err(|_| AppError::commit_not_found(from_hash))?;
+    let from = repo
+        .find_commit(oid)
+        .map_err(|_| AppError::commit_not_found(from_hash))?;
+    let head = repo.head()?.peel_to_commit()?;
+
+    if head.id() != from.id() && !repo.graph_descendant_of(head.id(), from.id())? {
+        return Err(AppError::with_details(
+            "COMMIT_NOT_ANCESTOR",
+            "Commit nao e ancestral do HEAD",
+            from_hash,
+        ));
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head.id())?;
+    for parent_id in from.parent_ids() {
+        revwalk.hide(parent_id)?;
+    }
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            return Err(AppError::with_details(
+                "SQUASH_MERGE_COMMIT",
+                "Nao e possivel fazer squash atraves de um merge commit",
+                &commit.id().to_string(),
+            ));
+        }
+    }
+
+    let signature = repo
+        .signature()
+        .map_err(|_| AppError::git_user_not_configured())?;
+    let tree = head.tree()?;
+    let parents: Vec<git2::Commit> = from.parents().collect();
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+
+    let new_oid = repo.commit(None, &from.author(), &signature, message, &tree, &parent_refs)?;
+
+    // New commit has HEAD's tree, so a soft reset only moves the branch
+    let new_commit = repo.find_commit(new_oid)?;
+    repo.reset(new_commit.as_object(), git2::ResetType::Soft, None)?;
+
+    let id_str = new_oid.to_string();
+    Ok(id_str[..7.min(id_str.len())].to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -854,6 +2037,32 @@ mod tests {
         oid.to_string()
     }
 
+    #[test]
+    fn get_commit_template_resolve_config_e_remove_comentarios() {
+        let (dir, repo) = setup_repo();
+        assert_eq!(get_commit_template(&repo, false).unwrap(), None);
+
+        std::fs::write(dir.path().join(".gitmessage"), "feat: \n\n# explique o motivo\n").unwrap();
+        assert_eq!(
+            get_commit_template(&repo, false).unwrap().as_deref(),
+            Some("feat: \n\n# explique o motivo\n")
+        );
+
+        std::fs::write(dir.path().join("modelo.txt"), "titulo\n; comentario\n\ncorpo\n").unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("commit.template", "modelo.txt").unwrap();
+        config.set_str("core.commentChar", ";").unwrap();
+        assert_eq!(get_commit_template(&repo, true).unwrap().as_deref(), Some("titulo\n\ncorpo"));
+
+        std::fs::write(dir.path().join("modelo.txt"), "assunto\n# comentario\n").unwrap();
+        config.set_str("core.commentChar", "auto").unwrap();
+        assert_eq!(get_commit_template(&repo, true).unwrap().as_deref(), Some("assunto"));
+
+        config.set_str("commit.template", "sumiu.txt").unwrap();
+        let err = get_commit_template(&repo, false).unwrap_err();
+        assert_eq!(err.code, "COMMIT_TEMPLATE_UNREADABLE");
+    }
+
     fn read_index_file(repo: &Repository, path: &str) -> Option<String> {
         let index = repo.index().unwrap();
         let entry = index.get_path(Path::new(path), 0)?;
@@ -958,8 +2167,9 @@ mod tests {
         let repo_path = dir.path().to_path_buf();
         stage_files(&repo, &["novo.txt".to_string()], &repo_path).unwrap();
 
-        let short_hash = create_commit(&repo, "feat: novo arquivo", false).unwrap();
-        assert_eq!(short_hash.len(), 7);
+        let result = create_commit(&repo, "feat: novo arquivo", false, &AuthorOverride::default(), false, None).unwrap();
+        assert_eq!(result.short_hash.len(), 7);
+        assert!(!result.detached);
 
         let commits = list_commits(&repo, None, 10, 0).unwrap();
         assert_eq!(commits[0].summary, "feat: novo arquivo");
@@ -1049,4 +2259,751 @@ mod tests {
             assert!(c.is_empty());
         }
     }
+
+    #[test]
+    fn squash_commits_combina_commits_ate_head() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "base.txt", "base", "base");
+        let hash_b = make_commit(&repo, dir.path(), "b.txt", "b", "commit B");
+        make_commit(&repo, dir.path(), "c.txt", "c", "commit C");
+
+        squash_commits(&repo, &hash_b, "B e C juntos").unwrap();
+
+        let commits = list_commits(&repo, None, 10, 0).unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].summary, "B e C juntos");
+        assert_eq!(commits[1].summary, "base");
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        assert!(tree.get_path(Path::new("b.txt")).is_ok());
+        assert!(tree.get_path(Path::new("c.txt")).is_ok());
+    }
+
+    #[test]
+    fn squash_commits_rejeita_commit_fora_do_historico() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "base.txt", "base", "base");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let orphan = repo
+            .commit(None, &sig, &sig, "solto", &head.tree().unwrap(), &[&head])
+            .unwrap();
+
+        let err = squash_commits(&repo, &orphan.to_string(), "msg").unwrap_err();
+        assert_eq!(err.code, "COMMIT_NOT_ANCESTOR");
+    }
+
+    #[test]
+    fn count_commits_conta_historico_e_atualiza_quando_head_muda() {
+        let (dir, repo) = setup_repo();
+        assert_eq!(count_commits(&repo, None).unwrap(), 0);
+
+        make_commit(&repo, dir.path(), "a.txt", "a", "primeiro");
+        make_commit(&repo, dir.path(), "b.txt", "b", "segundo");
+        assert_eq!(count_commits(&repo, None).unwrap(), 2);
+
+        make_commit(&repo, dir.path(), "c.txt", "c", "terceiro");
+        assert_eq!(count_commits(&repo, None).unwrap(), 3);
+    }
+
+    #[test]
+    fn count_commits_branch_inexistente_retorna_zero() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "a", "primeiro");
+        assert_eq!(count_commits(&repo, Some("nao-existe")).unwrap(), 0);
+    }
+
+    #[test]
+    fn search_commits_filtra_por_mensagem_e_path() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "a", "feat: login");
+        make_commit(&repo, dir.path(), "b.txt", "b", "fix: logout");
+        make_commit(&repo, dir.path(), "a.txt", "a2", "feat: LOGIN com senha");
+
+        let by_message = CommitQuery {
+            message: Some("login".to_string()),
+            ..Default::default()
+        };
+        let commits = search_commits(&repo, &by_message, 10, 0).unwrap();
+        assert_eq!(commits.len(), 2);
+
+        let by_path = CommitQuery {
+            path: Some("b.txt".to_string()),
+            ..Default::default()
+        };
+        let commits = search_commits(&repo, &by_path, 10, 0).unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].summary, "fix: logout");
+    }
+
+    #[test]
+    fn search_commits_respeita_autor_datas_e_paginacao() {
+        let (dir, repo) = setup_repo();
+        for i in 0..4 {
+            make_commit(&repo, dir.path(), &format!("f{}.txt", i), "x", &format!("commit {}", i));
+        }
+
+        let by_author = CommitQuery {
+            author: Some("TESTE@test".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(search_commits(&repo, &by_author, 2, 0).unwrap().len(), 2);
+        assert_eq!(search_commits(&repo, &by_author, 10, 3).unwrap().len(), 1);
+
+        let future = CommitQuery {
+            since: Some(i64::MAX),
+            ..Default::default()
+        };
+        assert!(search_commits(&repo, &future, 10, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn discard_changes_remove_arquivo_e_diretorio_untracked() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "original", "inicial");
+
+        std::fs::write(dir.path().join("a.txt"), "alterado").unwrap();
+        std::fs::write(dir.path().join("novo.txt"), "novo").unwrap();
+        std::fs::create_dir_all(dir.path().join("pasta/sub")).unwrap();
+        std::fs::write(dir.path().join("pasta/sub/x.txt"), "x").unwrap();
+
+        discard_changes(
+            &repo,
+            &["a.txt".to_string(), "novo.txt".to_string(), "pasta/".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "original");
+        assert!(!dir.path().join("novo.txt").exists());
+        assert!(!dir.path().join("pasta").exists());
+    }
+
+    #[test]
+    fn preview_discard_descreve_o_que_sera_perdido() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "um\ndois\n", "inicial");
+        make_commit(&repo, dir.path(), "b.txt", "b\n", "segundo");
+
+        std::fs::write(dir.path().join("a.txt"), "um\ntres\nquatro\n").unwrap();
+        std::fs::remove_file(dir.path().join("b.txt")).unwrap();
+        std::fs::create_dir(dir.path().join("pasta")).unwrap();
+        std::fs::write(dir.path().join("pasta/x.txt"), "x\n").unwrap();
+        std::fs::write(dir.path().join("pasta/y.txt"), "y\ny\n").unwrap();
+        std::fs::write(dir.path().join("fora.txt"), "fora\n").unwrap();
+
+        let files = ["a.txt", "b.txt", "pasta/", "limpo.txt"].map(String::from);
+        let previews = preview_discard(&repo, &files).unwrap();
+        let summary: Vec<_> = previews
+            .iter()
+            .map(|p| (p.path.as_str(), p.action, p.additions, p.deletions))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a.txt", DiscardAction::Revert, 2, 1),
+                ("b.txt", DiscardAction::Restore, 0, 1),
+                ("pasta/", DiscardAction::Delete, 3, 0),
+            ]
+        );
+        // Nothing was touched
+        assert!(dir.path().join("pasta/x.txt").exists());
+        assert!(!dir.path().join("b.txt").exists());
+    }
+
+    #[test]
+    fn reset_working_tree_limpa_conforme_flags() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "original", "inicial");
+        make_commit(&repo, dir.path(), ".gitignore", "*.log\n", "ignore");
+
+        let sujar = || {
+            std::fs::write(dir.path().join("a.txt"), "alterado").unwrap();
+            std::fs::write(dir.path().join("novo.txt"), "novo").unwrap();
+            std::fs::create_dir_all(dir.path().join("pasta")).unwrap();
+            std::fs::write(dir.path().join("pasta/x.txt"), "x").unwrap();
+            std::fs::write(dir.path().join("debug.log"), "log").unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("novo.txt")).unwrap();
+            index.write().unwrap();
+        };
+
+        sujar();
+        let result = reset_working_tree(&repo, false, false).unwrap();
+        // a.txt and the staged novo.txt; the untracked folder survives
+        assert_eq!(result, ResetWorkingTreeResult { reset: 2, removed: 0 });
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "original");
+        assert!(dir.path().join("pasta/x.txt").exists());
+        assert!(dir.path().join("debug.log").exists());
+
+        sujar();
+        let result = reset_working_tree(&repo, true, false).unwrap();
+        assert_eq!(result.removed, 1);
+        assert!(!dir.path().join("pasta").exists());
+        assert!(dir.path().join("debug.log").exists());
+
+        let result = reset_working_tree(&repo, true, true).unwrap();
+        assert_eq!(result, ResetWorkingTreeResult { reset: 0, removed: 1 });
+        assert!(!dir.path().join("debug.log").exists());
+        assert!(repo.statuses(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn clean_working_tree_respeita_flags_e_dry_run() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), ".gitignore", "*.log\nbuild/\n", "ignore");
+        std::fs::write(dir.path().join("solto.txt"), "s").unwrap();
+        std::fs::create_dir_all(dir.path().join("pasta/sub")).unwrap();
+        std::fs::write(dir.path().join("pasta/sub/x.txt"), "x").unwrap();
+        std::fs::write(dir.path().join("debug.log"), "log").unwrap();
+        std::fs::create_dir(dir.path().join("build")).unwrap();
+        std::fs::write(dir.path().join("build/out"), "o").unwrap();
+        std::fs::create_dir(dir.path().join("aninhado")).unwrap();
+        Repository::init(dir.path().join("aninhado")).unwrap();
+
+        let preview = clean_working_tree(&repo, false, false, true).unwrap();
+        assert_eq!(preview, vec!["solto.txt"]);
+        let preview = clean_working_tree(&repo, true, true, true).unwrap();
+        assert_eq!(preview, vec!["build/", "debug.log", "pasta/", "solto.txt"]);
+        assert!(dir.path().join("solto.txt").exists());
+
+        let removed = clean_working_tree(&repo, true, false, false).unwrap();
+        assert_eq!(removed, vec!["pasta/", "solto.txt"]);
+        assert!(!dir.path().join("pasta").exists());
+        assert!(dir.path().join("debug.log").exists());
+        assert!(dir.path().join("aninhado/.git").exists());
+    }
+
+    #[test]
+    fn discard_changes_untracked_nao_restaura_outros_arquivos() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "original", "inicial");
+
+        std::fs::write(dir.path().join("a.txt"), "alterado").unwrap();
+        std::fs::write(dir.path().join("novo.txt"), "novo").unwrap();
+
+        discard_changes(&repo, &["novo.txt".to_string()]).unwrap();
+
+        assert!(!dir.path().join("novo.txt").exists());
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "alterado");
+    }
+
+    #[test]
+    fn remove_worktree_path_rejeita_caminho_fora_do_repo() {
+        let (dir, _repo) = setup_repo();
+        let err = remove_worktree_path(dir.path(), "../fora.txt").unwrap_err();
+        assert_eq!(err.code, "INVALID_PATH");
+    }
+
+    #[test]
+    fn remove_worktree_path_aceita_pasta_pai_apagada() {
+        let (dir, _repo) = setup_repo();
+        remove_worktree_path(dir.path(), "apagada/sub/a.txt").unwrap();
+        assert!(dir.path().exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn remove_worktree_path_rejeita_link_para_fora_com_pai_apagado() {
+        let (dir, _repo) = setup_repo();
+        let fora = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(fora.path(), dir.path().join("link")).unwrap();
+        let err = remove_worktree_path(dir.path(), "link/apagada/a.txt").unwrap_err();
+        assert_eq!(err.code, "INVALID_PATH");
+    }
+
+    #[test]
+    fn restore_files_recupera_arquivo_deletado_do_head() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "original", "inicial");
+        make_commit(&repo, dir.path(), "b.txt", "outro", "segundo");
+
+        std::fs::remove_file(dir.path().join("a.txt")).unwrap();
+        std::fs::write(dir.path().join("b.txt"), "alterado").unwrap();
+
+        let restored = restore_files(&repo, &["a.txt".to_string()], false).unwrap();
+
+        assert_eq!(restored, vec!["a.txt".to_string()]);
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "original");
+        assert_eq!(std::fs::read_to_string(dir.path().join("b.txt")).unwrap(), "alterado");
+    }
+
+    #[test]
+    fn restore_files_staged_usa_conteudo_do_index() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "original", "inicial");
+
+        std::fs::write(dir.path().join("a.txt"), "no index").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "no worktree").unwrap();
+
+        let restored = restore_files(&repo, &["a.txt".to_string(), "inexistente.txt".to_string()], true).unwrap();
+
+        assert_eq!(restored, vec!["a.txt".to_string()]);
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "no index");
+    }
+
+    #[test]
+    fn unstage_files_sem_head_remove_do_index() {
+        let (dir, repo) = setup_repo();
+        std::fs::write(dir.path().join("a.txt"), "novo").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+
+        unstage_files(&repo, &["a.txt".to_string()]).unwrap();
+
+        let index = repo.index().unwrap();
+        assert!(index.get_path(Path::new("a.txt"), 0).is_none());
+        assert!(dir.path().join("a.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unstage_files_restaura_modo_do_arquivo() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (dir, repo) = setup_repo();
+        repo.config().unwrap().set_bool("core.filemode", true).unwrap();
+        make_commit(&repo, dir.path(), "script.sh", "echo oi", "inicial");
+
+        let file = dir.path().join("script.sh");
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("script.sh")).unwrap();
+        index.write().unwrap();
+        assert_eq!(index.get_path(Path::new("script.sh"), 0).unwrap().mode, 0o100755);
+
+        unstage_files(&repo, &["script.sh".to_string()]).unwrap();
+
+        let index = repo.index().unwrap();
+        assert_eq!(index.get_path(Path::new("script.sh"), 0).unwrap().mode, 0o100644);
+        let status = repo.status_file(Path::new("script.sh")).unwrap();
+        assert!(!status.is_index_modified());
+        assert!(status.is_wt_modified());
+    }
+
+    #[test]
+    fn unstage_files_desfaz_rename_staged() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "antigo.txt", "conteudo", "inicial");
+
+        std::fs::rename(dir.path().join("antigo.txt"), dir.path().join("novo.txt")).unwrap();
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("antigo.txt")).unwrap();
+        index.add_path(Path::new("novo.txt")).unwrap();
+        index.write().unwrap();
+
+        unstage_files(&repo, &["antigo.txt".to_string(), "novo.txt".to_string()]).unwrap();
+
+        let index = repo.index().unwrap();
+        assert!(index.get_path(Path::new("antigo.txt"), 0).is_some());
+        assert!(index.get_path(Path::new("novo.txt"), 0).is_none());
+        let status = repo.status_file(Path::new("novo.txt")).unwrap();
+        assert!(status.is_wt_new());
+    }
+
+    #[test]
+    fn reset_paths_to_commit_restaura_apenas_caminhos_pedidos() {
+        let (dir, repo) = setup_repo();
+        let first = make_commit(&repo, dir.path(), "a.txt", "v1", "inicial");
+        make_commit(&repo, dir.path(), "a.txt", "v2", "segundo");
+        make_commit(&repo, dir.path(), "b.txt", "b", "terceiro");
+
+        let result = reset_paths_to_commit(&repo, &first, &["a.txt".to_string()], true).unwrap();
+
+        assert_eq!(result.restored, vec!["a.txt".to_string()]);
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "v1");
+        assert!(dir.path().join("b.txt").exists());
+        let status = repo.status_file(Path::new("a.txt")).unwrap();
+        assert!(status.is_index_modified());
+        assert!(!status.is_wt_modified());
+    }
+
+    #[test]
+    fn reset_paths_to_commit_sem_worktree_so_altera_index() {
+        let (dir, repo) = setup_repo();
+        let first = make_commit(&repo, dir.path(), "a.txt", "v1", "inicial");
+        make_commit(&repo, dir.path(), "a.txt", "v2", "segundo");
+
+        reset_paths_to_commit(&repo, &first, &["a.txt".to_string()], false).unwrap();
+
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "v2");
+        let status = repo.status_file(Path::new("a.txt")).unwrap();
+        assert!(status.is_index_modified());
+        assert!(status.is_wt_modified());
+    }
+
+    #[test]
+    fn reset_paths_to_commit_caminho_inexistente_no_alvo() {
+        let (dir, repo) = setup_repo();
+        let first = make_commit(&repo, dir.path(), "a.txt", "v1", "inicial");
+        make_commit(&repo, dir.path(), "b.txt", "b", "segundo");
+
+        let result = reset_paths_to_commit(&repo, &first, &["b.txt".to_string()], false).unwrap();
+        assert_eq!(result.missing, vec!["b.txt".to_string()]);
+        assert!(dir.path().join("b.txt").exists());
+
+        let result = reset_paths_to_commit(&repo, &first, &["b.txt".to_string()], true).unwrap();
+        assert_eq!(result.removed, vec!["b.txt".to_string()]);
+        assert!(!dir.path().join("b.txt").exists());
+        assert!(repo.index().unwrap().get_path(Path::new("b.txt"), 0).is_none());
+    }
+
+    #[test]
+    fn create_commit_com_autor_sobrescrito_mantem_committer() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "base.txt", "base", "base");
+        std::fs::write(dir.path().join("par.txt"), "pareamento").unwrap();
+        stage_files(&repo, &["par.txt".to_string()], &dir.path().to_path_buf()).unwrap();
+
+        let author = AuthorOverride {
+            name: Some("Maria".to_string()),
+            email: Some("maria@test.com".to_string()),
+            date: Some("2024-05-01T10:00:00-03:00".to_string()),
+        };
+        create_commit(&repo, "feat: pareamento", false, &author, false, None).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.author().name(), Some("Maria"));
+        assert_eq!(head.author().email(), Some("maria@test.com"));
+        assert_eq!(head.author().when().seconds(), 1714568400);
+        assert_eq!(head.author().when().offset_minutes(), -180);
+        assert_eq!(head.committer().name(), Some("Teste"));
+    }
+
+    #[test]
+    fn add_to_last_commit_mantem_mensagem_e_autor() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "a", "feat: primeiro");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let other = Signature::now("Outra Pessoa", "outra@test.com").unwrap();
+        head.amend(Some("HEAD"), Some(&other), None, None, Some("feat: original\n\ncorpo"), None)
+            .unwrap();
+
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("b.txt")).unwrap();
+        index.write().unwrap();
+
+        add_to_last_commit(&repo, false).unwrap();
+
+        let amended = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(amended.message(), Some("feat: original\n\ncorpo"));
+        assert_eq!(amended.author().name(), Some("Outra Pessoa"));
+        assert_eq!(amended.parent_count(), 0);
+        let tree = amended.tree().unwrap();
+        assert!(tree.get_name("a.txt").is_some());
+        assert!(tree.get_name("b.txt").is_some());
+    }
+
+    #[test]
+    fn add_to_last_commit_recusa_commit_ja_enviado_sem_force() {
+        let (dir, repo) = setup_repo();
+        let hash = make_commit(&repo, dir.path(), "a.txt", "a", "feat: primeiro");
+        let oid = Oid::from_str(&hash).unwrap();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        let branch_name = repo.head().unwrap().shorthand().unwrap().to_string();
+        let mut branch = repo.find_branch(&branch_name, git2::BranchType::Local).unwrap();
+        repo.reference(&format!("refs/remotes/origin/{}", branch_name), oid, true, "teste").unwrap();
+        branch.set_upstream(Some(&format!("origin/{}", branch_name))).unwrap();
+
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("b.txt")).unwrap();
+        index.write().unwrap();
+
+        let err = add_to_last_commit(&repo, false).unwrap_err();
+        assert_eq!(err.code, "COMMIT_ALREADY_PUSHED");
+
+        add_to_last_commit(&repo, true).unwrap();
+        assert_ne!(repo.head().unwrap().target().unwrap(), oid);
+    }
+
+    #[test]
+    fn add_to_last_commit_sem_head_falha() {
+        let (_dir, repo) = setup_repo();
+        let err = add_to_last_commit(&repo, false).unwrap_err();
+        assert_eq!(err.code, "NO_HEAD_COMMIT");
+    }
+
+    #[test]
+    fn fill_parent_summaries_segue_ordem_dos_pais_do_merge() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "a", "base");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        make_commit(&repo, dir.path(), "a.txt", "main", "feat: lado principal");
+        let ours = repo.head().unwrap().peel_to_commit().unwrap();
+
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let theirs_id = repo
+            .commit(None, &sig, &sig, "feat: lado da feature\n\ncorpo", &base.tree().unwrap(), &[&base])
+            .unwrap();
+        let theirs = repo.find_commit(theirs_id).unwrap();
+        let merge_id = repo
+            .commit(Some("HEAD"), &sig, &sig, "Merge feature", &ours.tree().unwrap(), &[&ours, &theirs])
+            .unwrap();
+
+        let mut commits = vec![get_commit(&repo, &merge_id.to_string()).unwrap()];
+        assert!(commits[0].parent_summaries.is_empty());
+
+        fill_parent_summaries(&repo, &mut commits);
+        assert_eq!(commits[0].parent_summaries, vec!["feat: lado principal", "feat: lado da feature"]);
+    }
+
+    #[test]
+    fn stage_path_estagia_diretorio_inteiro_incluindo_remocoes() {
+        let (dir, repo) = setup_repo();
+        std::fs::create_dir(dir.path().join("pasta")).unwrap();
+        make_commit(&repo, dir.path(), "pasta/velho.txt", "v", "base");
+        std::fs::remove_file(dir.path().join("pasta/velho.txt")).unwrap();
+        std::fs::create_dir_all(dir.path().join("pasta/sub")).unwrap();
+        std::fs::write(dir.path().join("pasta/novo.txt"), "n").unwrap();
+        std::fs::write(dir.path().join("pasta/sub/outro.txt"), "o").unwrap();
+        std::fs::write(dir.path().join("fora.txt"), "f").unwrap();
+
+        stage_path(&repo, "pasta/").unwrap();
+
+        let index = repo.index().unwrap();
+        let paths: Vec<String> = index
+            .iter()
+            .map(|e| String::from_utf8_lossy(&e.path).to_string())
+            .collect();
+        assert_eq!(paths, vec!["pasta/novo.txt", "pasta/sub/outro.txt"]);
+    }
+
+    #[test]
+    fn fill_commit_stats_conta_contra_primeiro_pai_e_arvore_vazia() {
+        let (dir, repo) = setup_repo();
+        let root = make_commit(&repo, dir.path(), "a.txt", "um\ndois\n", "inicial");
+        let second = make_commit(&repo, dir.path(), "a.txt", "um\ntres\nquatro\n", "altera");
+
+        let mut commits = vec![get_commit(&repo, &second).unwrap(), get_commit(&repo, &root).unwrap()];
+        assert!(commits[0].stats.is_none());
+
+        fill_commit_stats(&repo, &mut commits).unwrap();
+        assert_eq!(
+            commits[0].stats,
+            Some(CommitStats { files_changed: 1, insertions: 2, deletions: 1 })
+        );
+        assert_eq!(
+            commits[1].stats,
+            Some(CommitStats { files_changed: 1, insertions: 2, deletions: 0 })
+        );
+        assert!(COMMIT_STATS_CACHE.lock().unwrap().contains_key(&Oid::from_str(&root).unwrap()));
+    }
+
+    #[test]
+    fn split_commit_message_junta_titulo_quebrado_ate_linha_em_branco() {
+        let (subject, body) = split_commit_message("feat: um titulo longo\nque continua aqui\n\ncorpo\n");
+        assert_eq!(subject, "feat: um titulo longo\nque continua aqui");
+        assert_eq!(body.as_deref(), Some("corpo"));
+
+        let (subject, body) = split_commit_message("fix: so titulo\n");
+        assert_eq!(subject, "fix: so titulo");
+        assert_eq!(body, None);
+    }
+
+    #[test]
+    fn commit_to_info_preserva_paragrafos_do_corpo() {
+        let (dir, repo) = setup_repo();
+        let message = "feat: titulo\n\nprimeiro paragrafo\ncontinua\n\n\nsegundo paragrafo\n  indentado\n";
+        let hash = make_commit(&repo, dir.path(), "a.txt", "a", message);
+
+        let info = get_commit(&repo, &hash).unwrap();
+        assert_eq!(info.summary, "feat: titulo");
+        assert_eq!(info.subject, "feat: titulo");
+        assert_eq!(
+            info.body.as_deref(),
+            Some("primeiro paragrafo\ncontinua\n\n\nsegundo paragrafo\n  indentado")
+        );
+    }
+
+    #[test]
+    fn create_commit_inicial_em_branch_nomeada() {
+        let (dir, repo) = setup_repo();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        stage_files(&repo, &["a.txt".to_string()], &dir.path().to_path_buf()).unwrap();
+
+        let err = create_commit(&repo, "inicial", false, &AuthorOverride::default(), false, Some("nome invalido.."))
+            .unwrap_err();
+        assert_eq!(err.code, "INVALID_BRANCH_NAME");
+
+        let result = create_commit(&repo, "inicial", false, &AuthorOverride::default(), false, Some("trunk")).unwrap();
+        assert_eq!(result.branch.as_deref(), Some("trunk"));
+        assert!(!result.detached);
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/trunk"));
+    }
+
+    #[test]
+    fn create_commit_inicial_com_falha_nao_move_head() {
+        let (dir, repo) = setup_repo();
+        let unborn = repo.find_reference("HEAD").unwrap().symbolic_target().unwrap().to_string();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        stage_files(&repo, &["a.txt".to_string()], &dir.path().to_path_buf()).unwrap();
+
+        // A branch that already exists can't receive a root commit
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let sig = repo.signature().unwrap();
+        let other = repo.commit(None, &sig, &sig, "outro", &tree, &[]).unwrap();
+        repo.reference("refs/heads/trunk", other, false, "teste").unwrap();
+
+        create_commit(&repo, "inicial", false, &AuthorOverride::default(), false, Some("trunk")).unwrap_err();
+        let head = repo.find_reference("HEAD").unwrap();
+        assert_eq!(head.symbolic_target(), Some(unborn.as_str()));
+        assert_eq!(repo.refname_to_id("refs/heads/trunk").unwrap(), other);
+    }
+
+    #[test]
+    fn create_commit_com_head_destacado_informa_detached() {
+        let (dir, repo) = setup_repo();
+        let base = make_commit(&repo, dir.path(), "base.txt", "base", "base");
+        let branch_ref = repo.head().unwrap().name().unwrap().to_string();
+        repo.set_head_detached(Oid::from_str(&base).unwrap()).unwrap();
+
+        std::fs::write(dir.path().join("solto.txt"), "solto").unwrap();
+        stage_files(&repo, &["solto.txt".to_string()], &dir.path().to_path_buf()).unwrap();
+        let result = create_commit(&repo, "solto", false, &AuthorOverride::default(), false, Some("ignorada")).unwrap();
+
+        assert!(result.detached);
+        assert_eq!(result.branch, None);
+        // The branch stays where it was; only the detached HEAD moved
+        let branch_tip = repo.find_reference(&branch_ref).unwrap().target().unwrap();
+        assert_eq!(branch_tip.to_string(), base);
+        assert!(repo.head().unwrap().target().unwrap().to_string().starts_with(&result.short_hash));
+    }
+
+    #[test]
+    fn parse_author_date_aceita_unix_e_rfc3339() {
+        assert_eq!(parse_author_date("1700000000").unwrap().seconds(), 1700000000);
+        assert_eq!(parse_author_date("1970-01-01T00:00:00Z").unwrap().seconds(), 0);
+        assert_eq!(parse_author_date("2000-02-29T12:30:15.250+01:00").unwrap().seconds(), 951823815);
+        assert_eq!(parse_author_date("ontem").unwrap_err().code, "INVALID_AUTHOR_DATE");
+        assert_eq!(parse_author_date("2024-13-01T00:00:00Z").unwrap_err().code, "INVALID_AUTHOR_DATE");
+        assert_eq!(parse_author_date("2024-04-31T00:00:00Z").unwrap_err().code, "INVALID_AUTHOR_DATE");
+        assert_eq!(parse_author_date("2023-02-29T00:00:00Z").unwrap_err().code, "INVALID_AUTHOR_DATE");
+        assert_eq!(parse_author_date("1900-02-29T00:00:00Z").unwrap_err().code, "INVALID_AUTHOR_DATE");
+        assert!(parse_author_date("2024-02-29T00:00:00Z").is_ok());
+    }
+
+    #[test]
+    fn can_commit_detecta_alteracoes_staged() {
+        let (dir, repo) = setup_repo();
+        let readiness = can_commit(&repo).unwrap();
+        assert!(!readiness.head_exists);
+        assert!(!readiness.has_staged_changes);
+
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        stage_files(&repo, &["a.txt".to_string()], &dir.path().to_path_buf()).unwrap();
+        assert!(can_commit(&repo).unwrap().has_staged_changes);
+
+        create_commit(&repo, "inicial", false, &AuthorOverride::default(), false, None).unwrap();
+        let readiness = can_commit(&repo).unwrap();
+        assert!(readiness.head_exists);
+        assert!(!readiness.has_staged_changes);
+        assert!(!readiness.has_conflicts);
+    }
+
+    #[test]
+    fn create_commit_sem_alteracoes_retorna_nothing_to_commit() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "base.txt", "base", "base");
+
+        let err = create_commit(&repo, "vazio", false, &AuthorOverride::default(), false, None).unwrap_err();
+        assert_eq!(err.code, "NOTHING_TO_COMMIT");
+
+        // Amend can still reword the last commit
+        create_commit(&repo, "base reescrito", true, &AuthorOverride::default(), false, None).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("base reescrito"));
+    }
+
+    #[test]
+    fn continue_cherry_pick_exige_conflitos_resolvidos() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "base\n", "base");
+        let main_ref = repo.head().unwrap().name().unwrap().to_string();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("outra", &base, false).unwrap();
+        make_commit(&repo, dir.path(), "a.txt", "main\n", "main");
+
+        repo.set_head("refs/heads/outra").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        let picked = make_commit(&repo, dir.path(), "a.txt", "outra\n", "feat: outra");
+        repo.set_head(&main_ref).unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+
+        assert_eq!(cherry_pick(&repo, &picked).unwrap_err().code, "MERGE_CONFLICT");
+        let err = continue_cherry_pick(&repo).unwrap_err();
+        assert_eq!(err.code, "UNRESOLVED_CONFLICTS");
+        assert_eq!(err.details.as_deref(), Some("a.txt"));
+
+        std::fs::write(dir.path().join("a.txt"), "resolvido\n").unwrap();
+        stage_files(&repo, &["a.txt".to_string()], &dir.path().to_path_buf()).unwrap();
+        continue_cherry_pick(&repo).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("feat: outra"));
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert_eq!(continue_revert(&repo).unwrap_err().code, "NOT_REVERTING");
+    }
+
+    #[test]
+    fn continue_cherry_pick_preserva_autor_original() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "base\n", "base");
+        let main_ref = repo.head().unwrap().name().unwrap().to_string();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("outra", &base, false).unwrap();
+        make_commit(&repo, dir.path(), "a.txt", "main\n", "main");
+
+        repo.set_head("refs/heads/outra").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        let picked = make_commit(&repo, dir.path(), "a.txt", "outra\n", "feat: outra");
+        repo.set_head(&main_ref).unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Outra Pessoa").unwrap();
+        config.set_str("user.email", "outra@test.com").unwrap();
+
+        assert_eq!(cherry_pick(&repo, &picked).unwrap_err().code, "MERGE_CONFLICT");
+        std::fs::write(dir.path().join("a.txt"), "resolvido\n").unwrap();
+        stage_files(&repo, &["a.txt".to_string()], &dir.path().to_path_buf()).unwrap();
+        continue_cherry_pick(&repo).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.author().name(), Some("Teste"));
+        assert_eq!(head.author().email(), Some("teste@test.com"));
+        assert_eq!(head.committer().name(), Some("Outra Pessoa"));
+    }
+
+    #[test]
+    fn list_file_history_pagina_e_segue_renomeacao() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "velho.txt", "conteudo\nlinha 2\nlinha 3\n", "cria");
+        make_commit(&repo, dir.path(), "velho.txt", "conteudo\nlinha 2\nlinha 3\nlinha 4\n", "edita");
+        make_commit(&repo, dir.path(), "outro.txt", "x", "outro");
+
+        std::fs::rename(dir.path().join("velho.txt"), dir.path().join("novo.txt")).unwrap();
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("velho.txt")).unwrap();
+        index.write().unwrap();
+        make_commit(&repo, dir.path(), "novo.txt", "conteudo\nlinha 2\nlinha 3\nlinha 4\n", "renomeia");
+
+        let history = list_file_history(&repo, "novo.txt", 10, 0, false).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].file_status, "added");
+
+        let history = list_file_history(&repo, "novo.txt", 10, 0, true).unwrap();
+        let summaries: Vec<&str> = history.iter().map(|h| h.commit.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["renomeia", "edita", "cria"]);
+        assert_eq!(history[0].file_status, "renamed");
+        assert_eq!(history[0].old_path.as_deref(), Some("velho.txt"));
+        assert_eq!(history[1].path, "velho.txt");
+
+        let page = list_file_history(&repo, "novo.txt", 1, 1, true).unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].commit.summary, "edita");
+    }
 }
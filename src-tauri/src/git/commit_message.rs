@@ -0,0 +1,161 @@
+use crate::error::{AppError, AppResult};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Conventional Commits rules; stored in `AppConfig` so teams can tune them
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CommitRules {
+    /// Allowed commit types, used to build the default subject pattern
+    pub types: Vec<String>,
+    /// Custom subject regex; replaces the one built from `types`
+    pub subject_pattern: Option<String>,
+    pub max_subject_length: usize,
+    pub require_blank_line: bool,
+}
+
+impl Default for CommitRules {
+    fn default() -> Self {
+        Self {
+            types: ["feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert"]
+                .iter()
+                .map(|t| t.to_string())
+                .collect(),
+            subject_pattern: None,
+            max_subject_length: 72,
+            require_blank_line: true,
+        }
+    }
+}
+
+impl CommitRules {
+    pub fn subject_regex(&self) -> AppResult<Regex> {
+        let pattern = match self.subject_pattern.as_deref().filter(|p| !p.is_empty()) {
+            Some(pattern) => pattern.to_string(),
+            None => {
+                let types: Vec<String> = self.types.iter().map(|t| regex::escape(t)).collect();
+                format!(r"^({})(\(.+\))?!?: .+", types.join("|"))
+            }
+        };
+        Regex::new(&pattern).map_err(|e| {
+            AppError::with_details("INVALID_COMMIT_RULES", "Padrao de mensagem de commit invalido", &e.to_string())
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommitMessageViolation {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+fn violation(rule: &str, severity: Severity, message: String) -> CommitMessageViolation {
+    CommitMessageViolation {
+        rule: rule.to_string(),
+        severity,
+        message,
+    }
+}
+
+/// Checks a commit message against `rules`. Violations are advisory: the
+/// caller decides whether to commit anyway.
+pub fn validate_commit_message(message: &str, rules: &CommitRules) -> AppResult<Vec<CommitMessageViolation>> {
+    let subject_regex = rules.subject_regex()?;
+    let mut lines = message.lines();
+    let subject = lines.next().unwrap_or("").trim_end();
+
+    if subject.trim().is_empty() {
+        return Ok(vec![violation("subject-empty", Severity::Error, "A mensagem nao tem titulo".to_string())]);
+    }
+
+    let mut violations = Vec::new();
+
+    if !subject_regex.is_match(subject) {
+        violations.push(violation(
+            "subject-format",
+            Severity::Error,
+            format!("Titulo fora do padrao: esperado \"tipo(escopo): descricao\" ({})", rules.types.join(", ")),
+        ));
+    }
+
+    let length = subject.chars().count();
+    if rules.max_subject_length > 0 && length > rules.max_subject_length {
+        violations.push(violation(
+            "subject-length",
+            Severity::Warning,
+            format!("Titulo com {} caracteres (maximo {})", length, rules.max_subject_length),
+        ));
+    }
+
+    if rules.require_blank_line {
+        if let Some(second) = lines.next() {
+            if !second.trim().is_empty() {
+                violations.push(violation(
+                    "body-blank-line",
+                    Severity::Error,
+                    "Deixe uma linha em branco entre o titulo e o corpo".to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules_violated(message: &str, rules: &CommitRules) -> Vec<String> {
+        validate_commit_message(message, rules)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.rule)
+            .collect()
+    }
+
+    #[test]
+    fn mensagem_convencional_passa_sem_violacoes() {
+        let rules = CommitRules::default();
+        assert!(rules_violated("feat(ui): adiciona botao", &rules).is_empty());
+        assert!(rules_violated("fix!: corrige crash\n\ndetalhes", &rules).is_empty());
+    }
+
+    #[test]
+    fn detecta_formato_tamanho_e_linha_em_branco() {
+        let rules = CommitRules::default();
+        assert_eq!(rules_violated("adiciona botao", &rules), vec!["subject-format"]);
+        assert_eq!(rules_violated("feat: x\ncorpo colado", &rules), vec!["body-blank-line"]);
+        assert_eq!(rules_violated("   ", &rules), vec!["subject-empty"]);
+
+        let long = format!("feat: {}", "a".repeat(80));
+        let violations = validate_commit_message(&long, &rules).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn regras_customizadas_de_tipos_e_padrao() {
+        let rules = CommitRules {
+            types: vec!["wip".to_string()],
+            ..CommitRules::default()
+        };
+        assert!(rules_violated("wip: rascunho", &rules).is_empty());
+        assert_eq!(rules_violated("feat: x", &rules), vec!["subject-format"]);
+
+        let rules = CommitRules {
+            subject_pattern: Some("(".to_string()),
+            ..CommitRules::default()
+        };
+        let err = validate_commit_message("feat: x", &rules).unwrap_err();
+        assert_eq!(err.code, "INVALID_COMMIT_RULES");
+    }
+}
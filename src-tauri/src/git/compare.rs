@@ -2,7 +2,7 @@ use crate::error::{AppError, AppResult};
 use git2::{Oid, Repository};
 use serde::{Deserialize, Serialize};
 
-use super::{commit_to_info, parse_diff, CommitInfo, DiffInfo};
+use super::{commit_to_info, parse_diff, CancelToken, CommitInfo, DiffInfo};
 
 const MAX_COMPARE_COMMITS: usize = 200;
 
@@ -50,6 +50,33 @@ pub fn compare_refs(repo: &Repository, base_ref: &str, head_ref: &str) -> AppRes
     })
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct AheadBehind {
+    /// Commits reachable from `a` but not from `b`
+    pub ahead: usize,
+    /// Commits reachable from `b` but not from `a`
+    pub behind: usize,
+}
+
+/// Merge base of two refs; `None` when the histories are unrelated
+pub fn get_merge_base(repo: &Repository, a: &str, b: &str) -> AppResult<Option<CommitInfo>> {
+    let a_oid = resolve_ref_to_commit(repo, a)?.id();
+    let b_oid = resolve_ref_to_commit(repo, b)?.id();
+
+    match repo.merge_base(a_oid, b_oid) {
+        Ok(oid) => Ok(Some(commit_to_info(&repo.find_commit(oid)?))),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn get_ahead_behind_between(repo: &Repository, a: &str, b: &str) -> AppResult<AheadBehind> {
+    let a_oid = resolve_ref_to_commit(repo, a)?.id();
+    let b_oid = resolve_ref_to_commit(repo, b)?.id();
+    let (ahead, behind) = repo.graph_ahead_behind(a_oid, b_oid)?;
+    Ok(AheadBehind { ahead, behind })
+}
+
 fn resolve_ref_to_commit<'repo>(
     repo: &'repo Repository,
     reference: &str,
@@ -96,7 +123,7 @@ fn diff_between_commits(repo: &Repository, base_oid: Oid, head_oid: Oid) -> AppR
     let base_tree = base_commit.tree()?;
     let head_tree = head_commit.tree()?;
     let diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), None)?;
-    parse_diff(&diff, repo)
+    parse_diff(&diff, &CancelToken::default())
 }
 
 #[cfg(test)]
@@ -174,4 +201,36 @@ mod tests {
         assert!(result.head_only_commits.is_empty());
         assert!(result.diff.is_empty());
     }
+
+    #[test]
+    fn get_merge_base_e_ahead_behind_entre_refs() {
+        let (dir, repo) = setup_repo();
+        let base = commit_file(&repo, &dir, "README.md", "base\n", "base");
+        repo.branch("feature", &repo.head().unwrap().peel_to_commit().unwrap(), false)
+            .unwrap();
+        commit_file(&repo, &dir, "main.txt", "um\n", "um");
+        commit_file(&repo, &dir, "main.txt", "dois\n", "dois");
+
+        let merge_base = get_merge_base(&repo, "HEAD", "feature").unwrap().unwrap();
+        assert_eq!(merge_base.hash, base);
+
+        let counts = get_ahead_behind_between(&repo, "HEAD", "feature").unwrap();
+        assert_eq!(counts, AheadBehind { ahead: 2, behind: 0 });
+    }
+
+    #[test]
+    fn get_merge_base_de_historicos_sem_relacao_retorna_none() {
+        let (dir, repo) = setup_repo();
+        commit_file(&repo, &dir, "README.md", "base\n", "base");
+
+        // Orphan commit with no parents
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let orphan = repo.commit(None, &sig, &sig, "orfao", &tree, &[]).unwrap();
+        repo.reference("refs/heads/orfa", orphan, false, "teste").unwrap();
+
+        assert!(get_merge_base(&repo, "HEAD", "orfa").unwrap().is_none());
+        let counts = get_ahead_behind_between(&repo, "HEAD", "orfa").unwrap();
+        assert_eq!(counts, AheadBehind { ahead: 1, behind: 1 });
+    }
 }
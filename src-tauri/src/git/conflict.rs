@@ -16,11 +16,99 @@ pub struct ConflictInfo {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ConflictSection {
     pub id: usize,
+    /// Exact text of each side, line endings included
     pub ours: String,
     pub theirs: String,
     pub base: Option<String>,
     pub start_line: usize,
     pub end_line: usize,
+    /// Byte range of the whole region, markers included (end exclusive),
+    /// so a resolution can be spliced back into the file verbatim
+    pub start_offset: usize,
+    pub end_offset: usize,
+}
+
+#[derive(Debug)]
+struct ParsedConflicts {
+    ours_content: String,
+    theirs_content: String,
+    conflicts: Vec<ConflictSection>,
+}
+
+/// Three-way view of one conflicted index entry
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConflictEntry {
+    pub path: String,
+    pub ours_oid: Option<String>,
+    pub theirs_oid: Option<String>,
+    pub base_oid: Option<String>,
+    /// Contents are omitted when any side is binary
+    pub is_binary: bool,
+    pub ours_content: Option<String>,
+    pub theirs_content: Option<String>,
+    pub base_content: Option<String>,
+}
+
+/// Paths with conflict entries in `index`, each listed once
+pub(crate) fn conflicted_paths(index: &git2::Index) -> AppResult<Vec<String>> {
+    Ok(index
+        .conflicts()?
+        .filter_map(|c| c.ok())
+        .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        .collect())
+}
+
+/// Read every conflicted path from the index in one pass
+pub fn get_all_conflicts(repo: &Repository) -> AppResult<Vec<ConflictEntry>> {
+    let index = repo.index()?;
+    let mut result = Vec::new();
+
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let sides = [&conflict.our, &conflict.their, &conflict.ancestor];
+        let Some(path) = sides
+            .iter()
+            .find_map(|entry| entry.as_ref())
+            .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        else {
+            continue;
+        };
+
+        let blobs = sides
+            .iter()
+            .map(|entry| match entry {
+                Some(entry) => repo.find_blob(entry.id).map(Some),
+                None => Ok(None),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let is_binary = blobs.iter().flatten().any(|blob| {
+            blob.is_binary() || std::str::from_utf8(blob.content()).is_err()
+        });
+        let oid = |i: usize| blobs[i].as_ref().map(|blob| blob.id().to_string());
+        let text = |i: usize| {
+            if is_binary {
+                return None;
+            }
+            blobs[i]
+                .as_ref()
+                .map(|blob| String::from_utf8_lossy(blob.content()).to_string())
+        };
+
+        result.push(ConflictEntry {
+            path,
+            ours_oid: oid(0),
+            theirs_oid: oid(1),
+            base_oid: oid(2),
+            is_binary,
+            ours_content: text(0),
+            theirs_content: text(1),
+            base_content: text(2),
+        });
+    }
+
+    Ok(result)
 }
 
 /// Get conflict information for a file
@@ -41,92 +129,126 @@ pub fn get_conflict_info(repo: &Repository, file_path: &str) -> AppResult<Confli
     })?;
 
     // Parse conflict markers
-    let (ours_content, theirs_content, base_content, conflicts) = parse_conflict_markers(&content)?;
+    let parsed = parse_conflict_markers(&content)?;
+
+    // The merge base lives in index stage 1; markers only carry it with diff3
+    let base_content = read_conflict_stage(repo, file_path, 1)?
+        .and_then(|bytes| String::from_utf8(bytes).ok());
 
     Ok(ConflictInfo {
         path: file_path.to_string(),
-        ours_content,
-        theirs_content,
+        ours_content: parsed.ours_content,
+        theirs_content: parsed.theirs_content,
         base_content,
-        conflicts,
+        conflicts: parsed.conflicts,
     })
 }
 
-/// Parse git conflict markers from file content
-fn parse_conflict_markers(content: &str) -> AppResult<(String, String, Option<String>, Vec<ConflictSection>)> {
-    let lines: Vec<&str> = content.lines().collect();
+/// Blob content of a conflicted path at an index stage (1 = base, 2 = ours, 3 = theirs)
+fn read_conflict_stage(repo: &Repository, file_path: &str, stage: i32) -> AppResult<Option<Vec<u8>>> {
+    let index = repo.index()?;
+    match index.get_path(Path::new(file_path), stage) {
+        Some(entry) => Ok(Some(repo.find_blob(entry.id)?.content().to_vec())),
+        None => Ok(None),
+    }
+}
+
+/// Returns true if `line` is a conflict marker made of exactly seven `ch`,
+/// followed by end of line or a space (longer runs come from nested merges)
+fn is_marker(line: &str, ch: char) -> bool {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let run = line.chars().take_while(|c| *c == ch).count();
+    run == 7 && (line.len() == 7 || line[7..].starts_with(' '))
+}
+
+#[derive(PartialEq)]
+enum Side {
+    Ours,
+    Base,
+    Theirs,
+}
+
+/// Parse git conflict markers from file content, keeping text byte-exact
+fn parse_conflict_markers(content: &str) -> AppResult<ParsedConflicts> {
     let mut conflicts = Vec::new();
-    let mut ours_lines = Vec::new();
-    let mut theirs_lines = Vec::new();
+    let mut ours_content = String::new();
+    let mut theirs_content = String::new();
 
-    let mut in_conflict = false;
-    let mut in_ours = false;
-    let mut in_base = false;
-    let mut in_theirs = false;
+    let mut side: Option<Side> = None;
+    // Depth of markers from an inner (nested) conflict, kept as plain content
+    let mut nested = 0usize;
 
-    let mut current_ours = Vec::new();
-    let mut current_theirs = Vec::new();
-    let mut current_base: Vec<String> = Vec::new();
+    let mut current_ours = String::new();
+    let mut current_theirs = String::new();
+    let mut current_base: Option<String> = None;
     let mut conflict_start = 0;
-    let mut conflict_id = 0;
-    let mut has_base = false;
-
-    for (i, line) in lines.iter().enumerate() {
-        if line.starts_with("<<<<<<<") {
-            in_conflict = true;
-            in_ours = true;
-            in_base = false;
-            in_theirs = false;
-            conflict_start = i;
-            current_ours.clear();
-            current_theirs.clear();
-            current_base.clear();
-            has_base = false;
-        } else if line.starts_with("|||||||") && in_conflict {
+    let mut start_offset = 0;
+    let mut offset = 0;
+
+    for (i, line) in content.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len();
+
+        let Some(current) = side.as_ref() else {
+            if is_marker(line, '<') {
+                side = Some(Side::Ours);
+                conflict_start = i;
+                start_offset = line_start;
+                current_ours.clear();
+                current_theirs.clear();
+                current_base = None;
+            } else {
+                // Normal line (outside conflict)
+                ours_content.push_str(line);
+                theirs_content.push_str(line);
+            }
+            continue;
+        };
+
+        if nested == 0 && is_marker(line, '|') && *current == Side::Ours {
             // Base marker (diff3 style)
-            in_ours = false;
-            in_base = true;
-            in_theirs = false;
-            has_base = true;
-        } else if line.starts_with("=======") && in_conflict {
-            in_ours = false;
-            in_base = false;
-            in_theirs = true;
-        } else if line.starts_with(">>>>>>>") && in_conflict {
+            side = Some(Side::Base);
+            current_base = Some(String::new());
+        } else if nested == 0 && is_marker(line, '=') && *current != Side::Theirs {
+            side = Some(Side::Theirs);
+        } else if nested == 0 && is_marker(line, '>') && *current == Side::Theirs {
             // End of conflict
             conflicts.push(ConflictSection {
-                id: conflict_id,
-                ours: current_ours.join("\n"),
-                theirs: current_theirs.join("\n"),
-                base: if has_base { Some(current_base.join("\n")) } else { None },
+                id: conflicts.len(),
+                ours: current_ours.clone(),
+                theirs: current_theirs.clone(),
+                base: current_base.take(),
                 start_line: conflict_start,
                 end_line: i,
+                start_offset,
+                end_offset: offset,
             });
-            conflict_id += 1;
-
-            // For ours/theirs content, use ours as default
-            ours_lines.extend(current_ours.iter().cloned());
-            theirs_lines.extend(current_theirs.iter().cloned());
-
-            in_conflict = false;
-            in_ours = false;
-            in_base = false;
-            in_theirs = false;
-        } else if in_conflict {
-            if in_ours {
-                current_ours.push(line.to_string());
-            } else if in_base {
-                current_base.push(line.to_string());
-            } else if in_theirs {
-                current_theirs.push(line.to_string());
-            }
+            ours_content.push_str(&current_ours);
+            theirs_content.push_str(&current_theirs);
+            side = None;
         } else {
-            // Normal line (outside conflict)
-            ours_lines.push(line.to_string());
-            theirs_lines.push(line.to_string());
+            if is_marker(line, '<') {
+                nested += 1;
+            } else if nested > 0 && is_marker(line, '>') {
+                nested -= 1;
+            }
+
+            match current {
+                Side::Ours => current_ours.push_str(line),
+                Side::Base => current_base.get_or_insert_with(String::new).push_str(line),
+                Side::Theirs => current_theirs.push_str(line),
+            }
         }
     }
 
+    if side.is_some() {
+        return Err(AppError::with_details(
+            "MALFORMED_CONFLICT",
+            "Marcador de conflito sem fechamento",
+            &format!("linha {}", conflict_start + 1),
+        ));
+    }
+
     if conflicts.is_empty() {
         return Err(AppError::new(
             "NO_CONFLICTS",
@@ -134,12 +256,11 @@ fn parse_conflict_markers(content: &str) -> AppResult<(String, String, Option<St
         ));
     }
 
-    Ok((
-        ours_lines.join("\n"),
-        theirs_lines.join("\n"),
-        None, // Full base content not available in simple marker parsing
+    Ok(ParsedConflicts {
+        ours_content,
+        theirs_content,
         conflicts,
-    ))
+    })
 }
 
 /// Save resolved file content
@@ -162,18 +283,212 @@ pub fn mark_resolved(repo: &Repository, file_path: &str) -> AppResult<()> {
     Ok(())
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictChoice {
+    Ours,
+    Theirs,
+    Base,
+}
+
+impl ConflictChoice {
+    fn stage(self) -> i32 {
+        match self {
+            ConflictChoice::Base => 1,
+            ConflictChoice::Ours => 2,
+            ConflictChoice::Theirs => 3,
+        }
+    }
+}
+
+/// Resolve a whole file by taking one side from the merge index stages
+pub fn resolve_conflict_side(repo: &Repository, file_path: &str, choice: ConflictChoice) -> AppResult<()> {
+    let repo_path = repo.workdir().ok_or_else(|| AppError::internal("Bare repository"))?;
+    let full_path = repo_path.join(file_path);
+
+    let mut index = repo.index()?;
+    let conflicted = (1..=3).any(|stage| index.get_path(Path::new(file_path), stage).is_some());
+    if !conflicted {
+        return Err(AppError::with_details("NOT_CONFLICTED", "Arquivo não está em conflito", file_path));
+    }
+
+    match read_conflict_stage(repo, file_path, choice.stage())? {
+        Some(content) => {
+            fs::write(&full_path, content).map_err(|e| {
+                AppError::with_details("WRITE_ERROR", "Erro ao salvar arquivo", &e.to_string())
+            })?;
+            index.add_path(Path::new(file_path))?;
+        }
+        // A missing ours/theirs stage means that side deleted the file
+        None if choice != ConflictChoice::Base => {
+            if full_path.exists() {
+                fs::remove_file(&full_path)?;
+            }
+            index.remove_path(Path::new(file_path))?;
+        }
+        None => {
+            return Err(AppError::with_details(
+                "NO_CONFLICT_BASE",
+                "Arquivo não possui versão base",
+                file_path,
+            ));
+        }
+    }
+
+    index.write()?;
+    Ok(())
+}
+
+/// Resolve a single conflict region in place; the file is marked resolved
+/// once no regions remain. Returns whether the file is fully resolved.
+pub fn resolve_conflict_section(
+    repo: &Repository,
+    file_path: &str,
+    section_id: usize,
+    choice: ConflictChoice,
+) -> AppResult<bool> {
+    let content = get_conflicted_file_content(repo, file_path)?;
+    let parsed = parse_conflict_markers(&content)?;
+
+    let section = parsed
+        .conflicts
+        .iter()
+        .find(|c| c.id == section_id)
+        .ok_or_else(|| {
+            AppError::with_details(
+                "CONFLICT_SECTION_NOT_FOUND",
+                "Seção de conflito não encontrada",
+                &section_id.to_string(),
+            )
+        })?;
+
+    let replacement = match choice {
+        ConflictChoice::Ours => section.ours.as_str(),
+        ConflictChoice::Theirs => section.theirs.as_str(),
+        ConflictChoice::Base => section.base.as_deref().ok_or_else(|| {
+            AppError::with_details(
+                "NO_CONFLICT_BASE",
+                "Seção não possui base (use conflictStyle diff3)",
+                &section_id.to_string(),
+            )
+        })?,
+    };
+
+    let resolved = format!(
+        "{}{}{}",
+        &content[..section.start_offset],
+        replacement,
+        &content[section.end_offset..]
+    );
+    save_resolved_file(repo, file_path, &resolved)?;
+
+    let fully_resolved = parsed.conflicts.len() == 1;
+    if fully_resolved {
+        mark_resolved(repo, file_path)?;
+    }
+
+    Ok(fully_resolved)
+}
+
 /// Abort the current merge
 pub fn abort_merge(repo: &Repository) -> AppResult<()> {
-    // Check if we're in a merge state
+    abort_operation(
+        repo,
+        &[git2::RepositoryState::Merge],
+        AppError::new("NOT_MERGING", "Não há merge em andamento"),
+    )
+}
+
+/// Commits the merge in progress once every conflict is resolved, with
+/// HEAD and each MERGE_HEAD as parents. Without `message` the one prepared
+/// in MERGE_MSG is used, minus its `#` comment lines (the conflict list).
+/// Returns the short hash of the merge commit.
+pub fn commit_merge(repo: &Repository, message: Option<&str>) -> AppResult<String> {
     if repo.state() != git2::RepositoryState::Merge {
         return Err(AppError::new("NOT_MERGING", "Não há merge em andamento"));
     }
 
+    let mut index = repo.index()?;
+    index.read(false)?;
+    let conflicted = conflicted_paths(&index)?;
+    if !conflicted.is_empty() {
+        return Err(AppError::with_details(
+            "UNRESOLVED_CONFLICTS",
+            "Resolva os conflitos antes de concluir o merge",
+            &conflicted.join("\n"),
+        ));
+    }
+
+    // `mergehead_foreach` needs `&mut Repository`; the file is one id per line
+    let merge_heads = fs::read_to_string(repo.path().join("MERGE_HEAD")).map_err(|e| {
+        AppError::with_details("MERGE_ERROR", "Erro ao ler MERGE_HEAD", &e.to_string())
+    })?;
+    let mut parents = vec![repo.head()?.peel_to_commit()?];
+    for line in merge_heads.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let oid = git2::Oid::from_str(line)?;
+        parents.push(repo.find_commit(oid)?);
+    }
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+
+    let message = match message.map(str::trim).filter(|m| !m.is_empty()) {
+        Some(message) => message.to_string(),
+        None => repo
+            .message()
+            .ok()
+            .and_then(|m| git2::message_prettify(m, Some(b'#')).ok())
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or_else(|| "Merge".to_string()),
+    };
+
+    let signature = repo
+        .signature()
+        .map_err(|_| AppError::git_user_not_configured())?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let commit_id = repo.commit(Some("HEAD"), &signature, &signature, &message, &tree, &parent_refs)?;
+
+    repo.cleanup_state()?;
+
+    let id_str = commit_id.to_string();
+    Ok(id_str[..7].to_string())
+}
+
+pub fn abort_cherry_pick(repo: &Repository) -> AppResult<()> {
+    abort_operation(
+        repo,
+        &[
+            git2::RepositoryState::CherryPick,
+            git2::RepositoryState::CherryPickSequence,
+        ],
+        AppError::new("NOT_CHERRY_PICKING", "Não há cherry-pick em andamento"),
+    )
+}
+
+pub fn abort_revert(repo: &Repository) -> AppResult<()> {
+    abort_operation(
+        repo,
+        &[
+            git2::RepositoryState::Revert,
+            git2::RepositoryState::RevertSequence,
+        ],
+        AppError::new("NOT_REVERTING", "Não há revert em andamento"),
+    )
+}
+
+/// Hard-resets to HEAD and clears the operation state, if the repo is in one of `states`
+fn abort_operation(
+    repo: &Repository,
+    states: &[git2::RepositoryState],
+    not_in_state: AppError,
+) -> AppResult<()> {
+    if !states.contains(&repo.state()) {
+        return Err(not_in_state);
+    }
+
     // Reset to HEAD
     let head = repo.head()?.peel_to_commit()?;
     repo.reset(head.as_object(), git2::ResetType::Hard, None)?;
 
-    // Clean up merge state
+    // Clean up operation state
     repo.cleanup_state()?;
 
     Ok(())
@@ -188,3 +503,220 @@ pub fn get_conflicted_file_content(repo: &Repository, file_path: &str) -> AppRes
         AppError::with_details("READ_ERROR", "Erro ao ler arquivo", &e.to_string())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup_repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Teste").unwrap();
+            config.set_str("user.email", "teste@test.com").unwrap();
+        }
+        (dir, repo)
+    }
+
+    fn make_commit(repo: &Repository, dir: &Path, content: &str, msg: &str) -> git2::Oid {
+        fs::write(dir.join("a.txt"), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = repo.signature().unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents).unwrap()
+    }
+
+    /// Leaves the repo mid cherry-pick with a conflict on a.txt
+    fn conflicted_cherry_pick(repo: &Repository, dir: &Path) -> git2::Oid {
+        make_commit(repo, dir, "base\n", "base");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("outra", &base, false).unwrap();
+        make_commit(repo, dir, "main\n", "main");
+        let main_ref = repo.head().unwrap().name().unwrap().to_string();
+
+        repo.set_head("refs/heads/outra").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        let picked = make_commit(repo, dir, "outra\n", "feat: outra");
+
+        let main = repo.revparse_single(&main_ref).unwrap();
+        repo.checkout_tree(&main, Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        repo.set_head(&main_ref).unwrap();
+
+        repo.cherrypick(&repo.find_commit(picked).unwrap(), None).unwrap();
+        assert!(repo.index().unwrap().has_conflicts());
+        picked
+    }
+
+    #[test]
+    fn abort_cherry_pick_restaura_head_e_limpa_estado() {
+        let (dir, repo) = setup_repo();
+        conflicted_cherry_pick(&repo, dir.path());
+        assert_eq!(repo.state(), git2::RepositoryState::CherryPick);
+
+        abort_cherry_pick(&repo).unwrap();
+
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert!(!repo.index().unwrap().has_conflicts());
+        assert_eq!(fs::read_to_string(dir.path().join("a.txt")).unwrap(), "main\n");
+    }
+
+    #[test]
+    fn abort_fora_do_estado_retorna_erro() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "base\n", "base");
+
+        assert_eq!(abort_cherry_pick(&repo).unwrap_err().code, "NOT_CHERRY_PICKING");
+        assert_eq!(abort_revert(&repo).unwrap_err().code, "NOT_REVERTING");
+        assert_eq!(abort_merge(&repo).unwrap_err().code, "NOT_MERGING");
+    }
+
+    #[test]
+    fn parse_conflict_markers_preserva_crlf_e_offsets() {
+        let content = "a\r\n<<<<<<< HEAD\r\nnosso\r\n=======\r\ndeles\r\n>>>>>>> outra\r\nz\r\n";
+        let parsed = parse_conflict_markers(content).unwrap();
+
+        assert_eq!(parsed.ours_content, "a\r\nnosso\r\nz\r\n");
+        assert_eq!(parsed.theirs_content, "a\r\ndeles\r\nz\r\n");
+
+        let section = &parsed.conflicts[0];
+        assert_eq!(section.ours, "nosso\r\n");
+        assert_eq!(section.theirs, "deles\r\n");
+        assert_eq!((section.start_line, section.end_line), (1, 5));
+
+        let spliced = format!(
+            "{}{}{}",
+            &content[..section.start_offset],
+            section.theirs,
+            &content[section.end_offset..]
+        );
+        assert_eq!(spliced, parsed.theirs_content);
+    }
+
+    #[test]
+    fn parse_conflict_markers_com_duas_regioes_e_base_diff3() {
+        let content = "\
+<<<<<<< HEAD
+um
+||||||| base
+zero
+=======
+dois
+>>>>>>> outra
+meio
+<<<<<<< HEAD
+x
+=======
+<<<<<<<< interno
+y
+>>>>>>>> interno
+>>>>>>> outra
+fim";
+        let parsed = parse_conflict_markers(content).unwrap();
+
+        assert_eq!(parsed.conflicts.len(), 2);
+        assert_eq!(parsed.conflicts[0].base.as_deref(), Some("zero\n"));
+        assert_eq!(parsed.conflicts[0].theirs, "dois\n");
+        assert_eq!(parsed.conflicts[1].id, 1);
+        assert_eq!(parsed.conflicts[1].base, None);
+        assert_eq!(parsed.conflicts[1].theirs, "<<<<<<<< interno\ny\n>>>>>>>> interno\n");
+        assert_eq!(parsed.ours_content, "um\nmeio\nx\nfim");
+        assert_eq!(&content[parsed.conflicts[1].end_offset..], "fim");
+    }
+
+    #[test]
+    fn parse_conflict_markers_sem_fechamento_retorna_erro() {
+        let err = parse_conflict_markers("<<<<<<< HEAD\nx\n=======\n").unwrap_err();
+        assert_eq!(err.code, "MALFORMED_CONFLICT");
+    }
+
+    #[test]
+    fn commit_merge_sem_mensagem_remove_lista_de_conflitos() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "base\n", "base");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("outra", &base, false).unwrap();
+        make_commit(&repo, dir.path(), "main\n", "main");
+        let main_ref = repo.head().unwrap().name().unwrap().to_string();
+
+        repo.set_head("refs/heads/outra").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        let theirs = make_commit(&repo, dir.path(), "outra\n", "feat: outra");
+        repo.set_head(&main_ref).unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+
+        let annotated = repo.find_annotated_commit(theirs).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+        assert!(repo.message().unwrap().contains("Conflicts:"));
+
+        resolve_conflict_side(&repo, "a.txt", ConflictChoice::Theirs).unwrap();
+        commit_merge(&repo, None).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let message = head.message().unwrap();
+        assert!(message.starts_with("Merge"), "{}", message);
+        assert!(!message.contains("Conflicts"), "{}", message);
+        assert!(!message.contains('#'), "{}", message);
+        assert_eq!(head.parent_count(), 2);
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+    }
+
+    #[test]
+    fn get_conflict_info_le_base_do_index() {
+        let (dir, repo) = setup_repo();
+        conflicted_cherry_pick(&repo, dir.path());
+
+        let info = get_conflict_info(&repo, "a.txt").unwrap();
+        assert_eq!(info.base_content.as_deref(), Some("base\n"));
+        assert_eq!(info.conflicts.len(), 1);
+    }
+
+    #[test]
+    fn resolve_conflict_side_usa_blob_do_index() {
+        let (dir, repo) = setup_repo();
+        conflicted_cherry_pick(&repo, dir.path());
+
+        resolve_conflict_side(&repo, "a.txt", ConflictChoice::Theirs).unwrap();
+
+        assert_eq!(fs::read_to_string(dir.path().join("a.txt")).unwrap(), "outra\n");
+        assert!(!repo.index().unwrap().has_conflicts());
+        let err = resolve_conflict_side(&repo, "a.txt", ConflictChoice::Ours).unwrap_err();
+        assert_eq!(err.code, "NOT_CONFLICTED");
+    }
+
+    #[test]
+    fn resolve_conflict_section_marca_resolvido_na_ultima_secao() {
+        let (dir, repo) = setup_repo();
+        conflicted_cherry_pick(&repo, dir.path());
+        let content = "<<<<<<< HEAD\num\n=======\ndois\n>>>>>>> outra\nmeio\n<<<<<<< HEAD\nx\n=======\ny\n>>>>>>> outra\n";
+        fs::write(dir.path().join("a.txt"), content).unwrap();
+
+        assert!(!resolve_conflict_section(&repo, "a.txt", 1, ConflictChoice::Theirs).unwrap());
+        assert!(repo.index().unwrap().has_conflicts());
+
+        assert!(resolve_conflict_section(&repo, "a.txt", 0, ConflictChoice::Ours).unwrap());
+        assert_eq!(fs::read_to_string(dir.path().join("a.txt")).unwrap(), "um\nmeio\ny\n");
+        assert!(!repo.index().unwrap().has_conflicts());
+    }
+
+    #[test]
+    fn get_all_conflicts_retorna_os_tres_lados() {
+        let (dir, repo) = setup_repo();
+        conflicted_cherry_pick(&repo, dir.path());
+
+        let conflicts = get_all_conflicts(&repo).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        let entry = &conflicts[0];
+        assert_eq!(entry.path, "a.txt");
+        assert!(!entry.is_binary);
+        assert_eq!(entry.ours_content.as_deref(), Some("main\n"));
+        assert_eq!(entry.theirs_content.as_deref(), Some("outra\n"));
+        assert_eq!(entry.base_content.as_deref(), Some("base\n"));
+        assert!(entry.base_oid.is_some());
+    }
+}
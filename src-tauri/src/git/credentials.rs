@@ -0,0 +1,331 @@
+use crate::error::{AppError, AppResult};
+use std::cell::{Cell, RefCell};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Private key tried after the SSH agent, for users who don't run one or
+/// whose key isn't loaded in it
+#[derive(Debug, Clone)]
+pub struct SshKey {
+    pub path: PathBuf,
+    pub passphrase: Option<String>,
+}
+
+/// The askpass script reads the passphrase from here, so it never touches disk
+const PASSPHRASE_ENV: &str = "GITARC_SSH_PASSPHRASE";
+
+pub fn ssh_auth_failed(details: &str) -> AppError {
+    AppError::with_details(
+        "SSH_AUTH_FAILED",
+        "Falha na autenticação SSH. Configure uma chave SSH nas configurações",
+        details,
+    )
+}
+
+/// ssh's "no identity was accepted" failure, as reported by the git CLI
+pub(crate) fn is_ssh_auth_failure(stderr: &str) -> bool {
+    stderr.contains("Permission denied (publickey")
+}
+
+/// Temporary askpass script, deleted with its directory when dropped
+pub(crate) struct AskpassScript {
+    path: PathBuf,
+    _dir: tempfile::TempDir,
+}
+
+/// Makes the git CLI offer `key` after the agent's identities (ssh tries
+/// the agent first on its own). A `GIT_SSH_COMMAND` already set wins.
+/// The returned script must outlive the command.
+pub(crate) fn apply_ssh_key(cmd: &mut Command, key: Option<&SshKey>) -> AppResult<Option<AskpassScript>> {
+    let Some(key) = key else {
+        return Ok(None);
+    };
+    if std::env::var_os("GIT_SSH_COMMAND").is_some() {
+        return Ok(None);
+    }
+
+    let path = key.path.to_string_lossy().replace('\'', r"'\''");
+    cmd.env("GIT_SSH_COMMAND", format!("ssh -i '{}'", path));
+
+    let Some(passphrase) = &key.passphrase else {
+        return Ok(None);
+    };
+    let script = askpass_script()?;
+    cmd.env("SSH_ASKPASS", &script.path)
+        .env("SSH_ASKPASS_REQUIRE", "force")
+        .env(PASSPHRASE_ENV, passphrase);
+    Ok(Some(script))
+}
+
+/// Writes the script into a fresh directory only the current user can
+/// enter (one per command, so concurrent transfers don't delete each
+/// other's). `create_new` refuses to follow a planted file or symlink.
+fn write_askpass_script(file_name: &str, contents: &str) -> AppResult<AskpassScript> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix("gitarc-askpass-");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        builder.permissions(std::fs::Permissions::from_mode(0o700));
+        options.mode(0o700);
+    }
+
+    let dir = builder.tempdir()?;
+    let path = dir.path().join(file_name);
+    options.open(&path)?.write_all(contents.as_bytes())?;
+
+    Ok(AskpassScript { path, _dir: dir })
+}
+
+#[cfg(unix)]
+fn askpass_script() -> AppResult<AskpassScript> {
+    write_askpass_script("askpass.sh", &format!("#!/bin/sh\nprintf '%s\\n' \"${}\"\n", PASSPHRASE_ENV))
+}
+
+#[cfg(not(unix))]
+fn askpass_script() -> AppResult<AskpassScript> {
+    // Delayed expansion substitutes after parsing, so `&`, `|` or `>` in the
+    // passphrase are printed instead of run; `echo(` also copes with an empty one
+    write_askpass_script(
+        "askpass.cmd",
+        &format!("@echo off\r\nsetlocal EnableDelayedExpansion\r\necho(!{}!\r\n", PASSPHRASE_ENV),
+    )
+}
+
+/// Username and password from `git credential fill`
+#[derive(Debug, Clone, PartialEq)]
+struct HelperCredential {
+    username: String,
+    password: String,
+}
+
+/// Runs `git credential <action>` with the given attribute lines on stdin
+fn run_credential_helper(action: &str, input: &str) -> Option<String> {
+    let mut cmd = Command::new("git");
+    cmd.args(["credential", action])
+        // Never fall back to a terminal prompt nobody can answer
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let mut child = cmd.spawn().ok()?;
+    child.stdin.take()?.write_all(input.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn credential_request(url: &str, username: Option<&str>) -> String {
+    let mut input = format!("url={}\n", url);
+    if let Some(username) = username {
+        input.push_str(&format!("username={}\n", username));
+    }
+    input.push('\n');
+    input
+}
+
+fn parse_credential_output(output: &str) -> Option<HelperCredential> {
+    let mut username = None;
+    let mut password = None;
+    for line in output.lines() {
+        match line.split_once('=') {
+            Some(("username", value)) => username = Some(value.to_string()),
+            Some(("password", value)) => password = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Some(HelperCredential {
+        username: username?,
+        password: password?,
+    })
+}
+
+/// Asks the user's configured `credential.helper` (e.g. Git Credential Manager)
+fn credential_fill(url: &str, username: Option<&str>) -> Option<HelperCredential> {
+    parse_credential_output(&run_credential_helper("fill", &credential_request(url, username))?)
+}
+
+fn credential_input(url: &str, credential: &HelperCredential) -> String {
+    format!(
+        "url={}\nusername={}\npassword={}\n\n",
+        url, credential.username, credential.password
+    )
+}
+
+/// Tells the helper a credential was refused so it isn't offered again
+fn credential_reject(url: &str, credential: &HelperCredential) {
+    let _ = run_credential_helper("reject", &credential_input(url, credential));
+}
+
+/// What the credential callbacks of one transfer learned along the way
+#[derive(Default)]
+pub(crate) struct TransferCredentials {
+    ssh_exhausted: Cell<bool>,
+    /// Last helper credential handed to libgit2, with the URL it was for
+    offered: RefCell<Option<(String, HelperCredential)>>,
+}
+
+impl TransferCredentials {
+    /// Both the agent and the configured key were rejected
+    pub(crate) fn ssh_exhausted(&self) -> bool {
+        self.ssh_exhausted.get()
+    }
+
+    /// Call once the transfer succeeded: the helper stores the credential
+    /// that worked, like git does after a successful fetch or push
+    pub(crate) fn approve(&self) {
+        if let Some((url, credential)) = self.offered.borrow_mut().take() {
+            let _ = run_credential_helper("approve", &credential_input(&url, &credential));
+        }
+    }
+}
+
+/// Credential callbacks for libgit2 transfers. SSH: agent first, then the
+/// configured key file; once both are rejected `ssh_exhausted` is set and
+/// the transfer fails instead of libgit2 asking again forever. HTTPS: the
+/// git credential helper, consulted once per operation.
+pub(crate) fn credential_callbacks<'a>(
+    key: Option<&'a SshKey>,
+    state: &'a TransferCredentials,
+) -> git2::RemoteCallbacks<'a> {
+    let mut ssh_attempts = 0;
+    let mut callbacks = git2::RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+            // libgit2 only asks again when the server refused what we gave it
+            if let Some((refused_url, refused)) = state.offered.borrow_mut().take() {
+                credential_reject(&refused_url, &refused);
+                return Err(git2::Error::from_str("credenciais recusadas pelo servidor"));
+            }
+            let credential = credential_fill(url, username)
+                .ok_or_else(|| git2::Error::from_str("nenhuma credencial disponivel no credential helper"))?;
+            let cred = git2::Cred::userpass_plaintext(&credential.username, &credential.password);
+            *state.offered.borrow_mut() = Some((url.to_string(), credential));
+            return cred;
+        }
+
+        let user = username.unwrap_or("git");
+
+        if allowed.contains(git2::CredentialType::USERNAME) {
+            return git2::Cred::username(user);
+        }
+
+        if allowed.contains(git2::CredentialType::SSH_KEY) {
+            ssh_attempts += 1;
+            if ssh_attempts == 1 {
+                if let Ok(cred) = git2::Cred::ssh_key_from_agent(user) {
+                    return Ok(cred);
+                }
+                ssh_attempts += 1;
+            }
+            if ssh_attempts == 2 {
+                if let Some(key) = key {
+                    return git2::Cred::ssh_key(user, None, &key.path, key.passphrase.as_deref());
+                }
+            }
+            state.ssh_exhausted.set(true);
+            return Err(git2::Error::from_str("nenhuma chave SSH foi aceita"));
+        }
+
+        if allowed.contains(git2::CredentialType::DEFAULT) {
+            return git2::Cred::default();
+        }
+        Err(git2::Error::from_str("tipo de credencial não suportado"))
+    });
+    callbacks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_credential_output_exige_usuario_e_senha() {
+        let parsed = parse_credential_output("protocol=https\nhost=github.com\nusername=dev\npassword=a=b\n");
+        assert_eq!(
+            parsed,
+            Some(HelperCredential {
+                username: "dev".to_string(),
+                password: "a=b".to_string(),
+            })
+        );
+        assert_eq!(parse_credential_output("username=dev\n"), None);
+        assert_eq!(
+            credential_request("https://github.com/org/repo.git", Some("dev")),
+            "url=https://github.com/org/repo.git\nusername=dev\n\n"
+        );
+        let credential = HelperCredential {
+            username: "dev".to_string(),
+            password: "segredo".to_string(),
+        };
+        assert_eq!(
+            credential_input("https://github.com/org/repo.git", &credential),
+            "url=https://github.com/org/repo.git\nusername=dev\npassword=segredo\n\n"
+        );
+    }
+
+    #[test]
+    fn apply_ssh_key_define_comando_ssh_com_chave() {
+        if std::env::var_os("GIT_SSH_COMMAND").is_some() {
+            return;
+        }
+        let key = SshKey {
+            path: PathBuf::from("/home/dev/.ssh/id's"),
+            passphrase: None,
+        };
+        let mut cmd = Command::new("git");
+        assert!(apply_ssh_key(&mut cmd, Some(&key)).unwrap().is_none());
+
+        let envs: Vec<_> = cmd.get_envs().collect();
+        let ssh_command = envs
+            .iter()
+            .find(|(name, _)| *name == "GIT_SSH_COMMAND")
+            .and_then(|(_, value)| value.map(|v| v.to_string_lossy().to_string()));
+        assert_eq!(ssh_command.as_deref(), Some(r"ssh -i '/home/dev/.ssh/id'\''s'"));
+        assert!(!envs.iter().any(|(name, _)| *name == "SSH_ASKPASS"));
+    }
+
+    #[test]
+    fn apply_ssh_key_remove_script_askpass_ao_descartar() {
+        if std::env::var_os("GIT_SSH_COMMAND").is_some() {
+            return;
+        }
+        let key = SshKey {
+            path: PathBuf::from("/home/dev/.ssh/id_ed25519"),
+            passphrase: Some("a & b".to_string()),
+        };
+        let mut cmd = Command::new("git");
+        let script = apply_ssh_key(&mut cmd, Some(&key)).unwrap().unwrap();
+        let path = script.path.clone();
+        assert!(path.is_file());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |p: &std::path::Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o700);
+            assert_eq!(mode(path.parent().unwrap()), 0o700);
+        }
+        assert!(cmd
+            .get_envs()
+            .any(|(name, value)| name == "SSH_ASKPASS" && value == Some(path.as_os_str())));
+
+        drop(script);
+        assert!(!path.exists());
+        assert!(!path.parent().unwrap().exists());
+    }
+}
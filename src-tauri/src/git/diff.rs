@@ -1,8 +1,17 @@
-use crate::error::{AppError, AppResult};
+use crate::error::{AppError, AppResult, ErrorKind};
+use crate::git::{parse_lfs_pointer, CancelToken, LfsPointer};
 use git2::{DiffOptions, Oid, Repository};
 use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read};
 use std::path::PathBuf;
 
+// Bytes inspected for NUL when deciding if an untracked file is binary
+const BINARY_CHECK_BYTES: usize = 8000;
+// Lines rendered for an untracked file before the diff is truncated
+const MAX_UNTRACKED_DIFF_LINES: usize = 5000;
+// Blobs above this size are returned without content
+const MAX_FILE_CONTENT_BYTES: usize = 10 * 1024 * 1024;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DiffInfo {
     pub path: String,
@@ -12,6 +21,36 @@ pub struct DiffInfo {
     pub deletions: usize,
     pub is_binary: bool,
     pub hunks: Vec<HunkInfo>,
+    /// Set when only the first lines of a large file were included
+    pub truncated: bool,
+    /// Content is a Git LFS pointer rather than the real file
+    pub is_lfs: bool,
+    pub lfs_pointer: Option<LfsPointer>,
+    /// Every changed line differs only in CRLF vs LF
+    pub line_ending_only: bool,
+    /// Set when the content isn't valid UTF-8 and was decoded as this encoding
+    pub encoding: Option<String>,
+    /// Syntax highlighting language (Prism id) guessed from the file name
+    pub language: Option<String>,
+}
+
+/// Per-file change counts without hunk contents, for listing a commit's files
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiffFileSummary {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: String,
+    pub additions: usize,
+    pub deletions: usize,
+    pub is_binary: bool,
+}
+
+/// Totals for the status bar, without parsing hunks
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -31,6 +70,14 @@ pub struct LineInfo {
     pub content: String,
     pub origin: char,
     pub line_type: LineType,
+    /// Intra-line spans, only filled when word diff is requested
+    pub segments: Vec<LineSegment>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LineSegment {
+    pub text: String,
+    pub changed: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -43,7 +90,7 @@ pub enum LineType {
     Binary,
 }
 
-pub fn get_working_diff(repo: &Repository) -> AppResult<Vec<DiffInfo>> {
+pub fn get_working_diff(repo: &Repository, cancel: &CancelToken) -> AppResult<Vec<DiffInfo>> {
     let mut diff_opts = DiffOptions::new();
     diff_opts.include_untracked(true);
 
@@ -51,18 +98,72 @@ pub fn get_working_diff(repo: &Repository) -> AppResult<Vec<DiffInfo>> {
 
     let diff = repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut diff_opts))?;
 
-    parse_diff(&diff, repo)
+    parse_diff(&diff, cancel)
 }
 
-pub fn get_staged_diff(repo: &Repository) -> AppResult<Vec<DiffInfo>> {
+/// Same scope as `get_staged_diff` / `get_working_diff`; untracked files
+/// count all their lines as insertions, as the working diff shows them
+pub fn get_diff_stats(repo: &Repository, staged: bool) -> AppResult<DiffStats> {
+    let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+
+    let diff = if staged {
+        repo.diff_tree_to_index(head_tree.as_ref(), None, None)?
+    } else {
+        let mut diff_opts = DiffOptions::new();
+        diff_opts
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut diff_opts))?
+    };
+
+    let stats = diff.stats()?;
+    Ok(DiffStats {
+        files_changed: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
+}
+
+pub fn get_staged_diff(repo: &Repository, cancel: &CancelToken) -> AppResult<Vec<DiffInfo>> {
     let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
 
     let diff = repo.diff_tree_to_index(head_tree.as_ref(), None, None)?;
 
-    parse_diff(&diff, repo)
+    parse_diff(&diff, cancel)
+}
+
+/// Diff of a commit against one of its parents (`parent_index`, 0 = first);
+/// the root commit is diffed against an empty tree
+pub fn get_commit_diff(
+    repo: &Repository,
+    commit_hash: &str,
+    parent_index: usize,
+    cancel: &CancelToken,
+) -> AppResult<Vec<DiffInfo>> {
+    let oid = Oid::from_str(commit_hash).map_err(|_| AppError::commit_not_found(commit_hash))?;
+    let commit = repo.find_commit(oid)?;
+    let tree = commit.tree()?;
+
+    let parent_tree = if commit.parent_count() == 0 && parent_index == 0 {
+        None
+    } else if parent_index < commit.parent_count() {
+        Some(commit.parent(parent_index)?.tree()?)
+    } else {
+        return Err(AppError::with_details(
+            "INVALID_PARENT_INDEX",
+            "O commit nao tem esse pai",
+            &format!("{} tem {} pai(s)", commit_hash, commit.parent_count()),
+        )
+        .with_kind(ErrorKind::Validation));
+    };
+
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+    parse_diff(&diff, cancel)
 }
 
-pub fn get_commit_diff(repo: &Repository, commit_hash: &str) -> AppResult<Vec<DiffInfo>> {
+pub fn get_commit_diff_summary(repo: &Repository, commit_hash: &str) -> AppResult<Vec<DiffFileSummary>> {
     let oid = Oid::from_str(commit_hash).map_err(|_| AppError::commit_not_found(commit_hash))?;
     let commit = repo.find_commit(oid)?;
     let tree = commit.tree()?;
@@ -71,7 +172,57 @@ pub fn get_commit_diff(repo: &Repository, commit_hash: &str) -> AppResult<Vec<Di
 
     let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
 
-    parse_diff(&diff, repo)
+    let mut summaries = Vec::new();
+
+    for delta_idx in 0..diff.deltas().len() {
+        let delta = diff.get_delta(delta_idx).ok_or_else(|| AppError::internal("Índice de delta inválido"))?;
+        let (path, old_path) = delta_paths(&delta);
+
+        // Loading the patch only counts lines; hunks are left to get_file_diff_at_commit
+        let (additions, deletions) = match git2::Patch::from_diff(&diff, delta_idx)? {
+            Some(patch) => {
+                let (_, additions, deletions) = patch.line_stats()?;
+                (additions, deletions)
+            }
+            None => (0, 0),
+        };
+
+        // The binary flag is only reliable once the patch has been loaded
+        let is_binary = diff
+            .get_delta(delta_idx)
+            .map(|d| d.flags().is_binary())
+            .unwrap_or(false);
+
+        summaries.push(DiffFileSummary {
+            path,
+            old_path,
+            status: delta_status(delta.status()).to_string(),
+            additions,
+            deletions,
+            is_binary,
+        });
+    }
+
+    Ok(summaries)
+}
+
+fn delta_paths(delta: &git2::DiffDelta) -> (String, Option<String>) {
+    let old_path = delta.old_file().path().map(|p| p.to_string_lossy().to_string());
+    let new_path = delta.new_file().path().map(|p| p.to_string_lossy().to_string());
+    let path = new_path.unwrap_or_else(|| old_path.clone().unwrap_or_default());
+    (path, old_path)
+}
+
+fn delta_status(status: git2::Delta) -> &'static str {
+    match status {
+        git2::Delta::Added => "added",
+        git2::Delta::Deleted => "deleted",
+        git2::Delta::Modified => "modified",
+        git2::Delta::Renamed => "renamed",
+        git2::Delta::Copied => "copied",
+        git2::Delta::Untracked => "untracked",
+        _ => "unknown",
+    }
 }
 
 pub fn get_file_diff(
@@ -105,73 +256,171 @@ pub fn get_file_diff(
         repo.diff_index_to_workdir(Some(&mut index), Some(&mut diff_opts))?
     };
 
-    let diffs = parse_diff(&diff, repo)?;
+    let diffs = parse_diff(&diff, &CancelToken::default())?;
     diffs
         .into_iter()
         .find(|d| d.path == file_path)
         .ok_or_else(|| AppError::with_details("FILE_NOT_IN_DIFF", "Arquivo não encontrado no diff", file_path))
 }
 
+/// Diff lines above which `get_file_diff_paged` skips the hunks by default
+pub const DEFAULT_PAGED_DIFF_MAX_LINES: usize = 20_000;
+
+/// One window of a file's hunks; the counts in `diff` cover the whole file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiffPage {
+    #[serde(flatten)]
+    pub diff: DiffInfo,
+    pub total_hunks: usize,
+    pub hunk_offset: usize,
+}
+
+/// Like `get_file_diff`, but only the hunks in `hunk_offset..hunk_offset + hunk_limit`
+/// are built, so huge files can be loaded a piece at a time. Files with more
+/// than `max_lines` diff lines come back `truncated` with no hunks at all.
+pub fn get_file_diff_paged(
+    repo: &Repository,
+    file_path: &str,
+    staged: bool,
+    hunk_offset: usize,
+    hunk_limit: usize,
+    max_lines: usize,
+) -> AppResult<DiffPage> {
+    let mut diff_opts = DiffOptions::new();
+    diff_opts.pathspec(file_path).disable_pathspec_match(true);
+
+    let diff = if staged {
+        let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+        repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut diff_opts))?
+    } else {
+        // Untracked files go through the same patch path as tracked ones
+        diff_opts
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        let index = repo.index()?;
+        repo.diff_index_to_workdir(Some(&index), Some(&mut diff_opts))?
+    };
+
+    let not_found = || AppError::with_details("FILE_NOT_IN_DIFF", "Arquivo não encontrado no diff", file_path);
+    let delta_idx = diff
+        .deltas()
+        .position(|delta| delta_paths(&delta).0 == file_path)
+        .ok_or_else(not_found)?;
+
+    let window = hunk_offset..hunk_offset.saturating_add(hunk_limit);
+    parse_delta(&diff, delta_idx, window, Some(max_lines))?.ok_or_else(not_found)
+}
+
 fn get_untracked_file_diff(file_path: &str, repo_path: &PathBuf) -> AppResult<DiffInfo> {
     let full_path = repo_path.join(file_path);
-    let content = std::fs::read_to_string(&full_path)?;
-
-    let lines: Vec<LineInfo> = content
-        .lines()
-        .enumerate()
-        .map(|(i, line)| LineInfo {
-            old_line: None,
-            new_line: Some((i + 1) as u32),
-            content: line.to_string(),
-            origin: '+',
-            line_type: LineType::Addition,
-        })
-        .collect();
-
-    let additions = lines.len();
-
-    Ok(DiffInfo {
+
+    let mut diff = DiffInfo {
         path: file_path.to_string(),
         old_path: None,
         status: "added".to_string(),
-        additions,
+        additions: 0,
         deletions: 0,
         is_binary: false,
-        hunks: vec![HunkInfo {
-            header: format!("@@ -0,0 +1,{} @@", additions),
-            old_start: 0,
-            old_lines: 0,
-            new_start: 1,
-            new_lines: additions as u32,
-            lines,
-        }],
-    })
+        hunks: vec![],
+        truncated: false,
+        is_lfs: false,
+        lfs_pointer: None,
+        line_ending_only: false,
+        encoding: None,
+        language: language_for_path(file_path),
+    };
+
+    // Nested repositories and submodules show up as untracked directories
+    if full_path.is_dir() {
+        return Ok(diff);
+    }
+
+    let mut file = std::fs::File::open(&full_path)?;
+    let mut prefix = Vec::with_capacity(BINARY_CHECK_BYTES);
+    (&mut file)
+        .take(BINARY_CHECK_BYTES as u64)
+        .read_to_end(&mut prefix)?;
+
+    if prefix.contains(&0) {
+        diff.is_binary = true;
+        return Ok(diff);
+    }
+
+    let mut reader = BufReader::new(prefix.as_slice().chain(file));
+    let mut lines = Vec::new();
+    let mut buf = Vec::new();
+    let mut total = 0usize;
+    let mut non_utf8 = false;
+
+    // Keep counting past the cap so additions reflect the whole file
+    while reader.read_until(b'\n', &mut buf)? > 0 {
+        total += 1;
+        if lines.len() < MAX_UNTRACKED_DIFF_LINES {
+            let content = decode_line(&buf, &mut non_utf8);
+            lines.push(LineInfo {
+                old_line: None,
+                new_line: Some(total as u32),
+                content: content.trim_end_matches(['\n', '\r']).to_string(),
+                origin: '+',
+                line_type: LineType::Addition,
+                segments: Vec::new(),
+            });
+        }
+        buf.clear();
+    }
+
+    diff.additions = total;
+    diff.truncated = total > lines.len();
+    diff.hunks.push(HunkInfo {
+        header: format!("@@ -0,0 +1,{} @@", total),
+        old_start: 0,
+        old_lines: 0,
+        new_start: 1,
+        new_lines: total as u32,
+        lines,
+    });
+    detect_lfs_pointer(&mut diff);
+    diff.encoding = non_utf8.then(|| LATIN1.to_string());
+
+    Ok(diff)
 }
 
-pub(crate) fn parse_diff(diff: &git2::Diff, _repo: &Repository) -> AppResult<Vec<DiffInfo>> {
+/// Polls `cancel` between files so huge diffs can be abandoned with CANCELLED
+pub(crate) fn parse_diff(diff: &git2::Diff, cancel: &CancelToken) -> AppResult<Vec<DiffInfo>> {
     let mut diffs = Vec::new();
 
     for delta_idx in 0..diff.deltas().len() {
-        let delta = diff.get_delta(delta_idx).ok_or_else(|| AppError::internal("Índice de delta inválido"))?;
+        cancel.check()?;
+        if let Some(page) = parse_delta(diff, delta_idx, 0..usize::MAX, None)? {
+            diffs.push(page.diff);
+        }
+    }
 
-        let old_path = delta.old_file().path().map(|p| p.to_string_lossy().to_string());
-        let new_path = delta.new_file().path().map(|p| p.to_string_lossy().to_string());
-        let path = new_path.clone().unwrap_or_else(|| old_path.clone().unwrap_or_default());
-
-        let status = match delta.status() {
-            git2::Delta::Added => "added",
-            git2::Delta::Deleted => "deleted",
-            git2::Delta::Modified => "modified",
-            git2::Delta::Renamed => "renamed",
-            git2::Delta::Copied => "copied",
-            git2::Delta::Untracked => "untracked",
-            _ => "unknown",
-        };
+    Ok(diffs)
+}
+
+/// Builds the `DiffInfo` of one delta keeping only the hunks in `window`.
+/// Counts always cover the whole file. Past `max_lines` diff lines no hunk is
+/// materialized and the result is flagged `truncated`.
+fn parse_delta(
+    diff: &git2::Diff,
+    delta_idx: usize,
+    window: std::ops::Range<usize>,
+    max_lines: Option<usize>,
+) -> AppResult<Option<DiffPage>> {
+    let delta = diff.get_delta(delta_idx).ok_or_else(|| AppError::internal("Índice de delta inválido"))?;
+
+    let (path, old_path) = delta_paths(&delta);
+    let status = delta_status(delta.status());
 
-        let is_binary = delta.flags().is_binary();
+    let is_binary = delta.flags().is_binary();
 
-        if is_binary {
-            diffs.push(DiffInfo {
+    let language = language_for_path(&path);
+
+    if is_binary {
+        return Ok(Some(DiffPage {
+            diff: DiffInfo {
                 path,
                 old_path,
                 status: status.to_string(),
@@ -179,63 +428,85 @@ pub(crate) fn parse_diff(diff: &git2::Diff, _repo: &Repository) -> AppResult<Vec
                 deletions: 0,
                 is_binary: true,
                 hunks: vec![],
-            });
-            continue;
-        }
+                truncated: false,
+                is_lfs: false,
+                lfs_pointer: None,
+                line_ending_only: false,
+                encoding: None,
+                language,
+            },
+            total_hunks: 0,
+            hunk_offset: 0,
+        }));
+    }
 
-        // Get patch for this delta
-        let patch = match git2::Patch::from_diff(diff, delta_idx)? {
-            Some(p) => p,
-            None => continue,
-        };
+    // Get patch for this delta
+    let patch = match git2::Patch::from_diff(diff, delta_idx)? {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+
+    let total_hunks = patch.num_hunks();
+    let total_lines: usize = (0..total_hunks)
+        .map(|hunk_idx| patch.num_lines_in_hunk(hunk_idx).unwrap_or(0))
+        .sum();
+    let too_large = max_lines.is_some_and(|max| total_lines > max);
+
+    let mut hunks = Vec::new();
+    let mut total_additions = 0;
+    let mut total_deletions = 0;
+    let mut non_utf8 = false;
+
+    for hunk_idx in 0..total_hunks {
+        let (hunk, _) = patch.hunk(hunk_idx)?;
+        // Hunks outside the window are only counted
+        let keep = !too_large && window.contains(&hunk_idx);
+
+        let header = format!(
+            "@@ -{},{} +{},{} @@",
+            hunk.old_start(),
+            hunk.old_lines(),
+            hunk.new_start(),
+            hunk.new_lines()
+        );
 
-        let mut hunks = Vec::new();
-        let mut total_additions = 0;
-        let mut total_deletions = 0;
-
-        for hunk_idx in 0..patch.num_hunks() {
-            let (hunk, _) = patch.hunk(hunk_idx)?;
-
-            let header = format!(
-                "@@ -{},{} +{},{} @@",
-                hunk.old_start(),
-                hunk.old_lines(),
-                hunk.new_start(),
-                hunk.new_lines()
-            );
-
-            let mut lines = Vec::new();
-
-            for line_idx in 0..patch.num_lines_in_hunk(hunk_idx).unwrap_or(0) {
-                let line = patch.line_in_hunk(hunk_idx, line_idx)?;
-
-                let origin = line.origin();
-                let line_type = match origin {
-                    '+' | '>' => {
-                        total_additions += 1;
-                        LineType::Addition
-                    }
-                    '-' | '<' => {
-                        total_deletions += 1;
-                        LineType::Deletion
-                    }
-                    '@' => LineType::Header,
-                    _ => LineType::Context,
-                };
-
-                let content = String::from_utf8_lossy(line.content()).to_string();
-                // Remove trailing newline for cleaner display
-                let content = content.trim_end_matches('\n').to_string();
-
-                lines.push(LineInfo {
-                    old_line: line.old_lineno(),
-                    new_line: line.new_lineno(),
-                    content,
-                    origin,
-                    line_type,
-                });
+        let mut lines = Vec::new();
+
+        for line_idx in 0..patch.num_lines_in_hunk(hunk_idx).unwrap_or(0) {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+
+            let origin = line.origin();
+            let line_type = match origin {
+                '+' | '>' => {
+                    total_additions += 1;
+                    LineType::Addition
+                }
+                '-' | '<' => {
+                    total_deletions += 1;
+                    LineType::Deletion
+                }
+                '@' => LineType::Header,
+                _ => LineType::Context,
+            };
+            if !keep {
+                continue;
             }
 
+            let content = decode_line(line.content(), &mut non_utf8);
+            // Remove trailing newline for cleaner display
+            let content = content.trim_end_matches('\n').to_string();
+
+            lines.push(LineInfo {
+                old_line: line.old_lineno(),
+                new_line: line.new_lineno(),
+                content,
+                origin,
+                line_type,
+                segments: Vec::new(),
+            });
+        }
+
+        if keep {
             hunks.push(HunkInfo {
                 header,
                 old_start: hunk.old_start(),
@@ -245,19 +516,381 @@ pub(crate) fn parse_diff(diff: &git2::Diff, _repo: &Repository) -> AppResult<Vec
                 lines,
             });
         }
+    }
 
-        diffs.push(DiffInfo {
-            path,
-            old_path,
-            status: status.to_string(),
-            additions: total_additions,
-            deletions: total_deletions,
-            is_binary: false,
-            hunks,
+    let mut info = DiffInfo {
+        path,
+        old_path,
+        status: status.to_string(),
+        additions: total_additions,
+        deletions: total_deletions,
+        is_binary: false,
+        hunks,
+        truncated: too_large,
+        is_lfs: false,
+        lfs_pointer: None,
+        line_ending_only: false,
+        encoding: non_utf8.then(|| LATIN1.to_string()),
+        language,
+    };
+    detect_lfs_pointer(&mut info);
+    // Needs every line of the file to decide
+    if info.hunks.len() == total_hunks {
+        detect_line_ending_only(&mut info);
+    }
+
+    Ok(Some(DiffPage {
+        diff: info,
+        total_hunks,
+        hunk_offset: window.start.min(total_hunks),
+    }))
+}
+
+/// `-start[,count]` / `+start[,count]` from a hunk header; count defaults to 1
+fn parse_hunk_range(range: &str) -> Option<(u32, u32)> {
+    let (start, count) = match range.split_once(',') {
+        Some((start, count)) => (start, count.parse().ok()?),
+        None => (range, 1),
+    };
+    Some((start.parse().ok()?, count))
+}
+
+/// Builds a `DiffInfo` from one file's textual hunks (as in `git diff` output),
+/// so diffs that don't come from libgit2 render like local ones
+pub(crate) fn diff_info_from_patch(
+    path: String,
+    old_path: Option<String>,
+    status: &str,
+    is_binary: bool,
+    patch: &str,
+) -> DiffInfo {
+    let mut hunks: Vec<HunkInfo> = Vec::new();
+    let mut additions = 0;
+    let mut deletions = 0;
+    let (mut old_line, mut new_line) = (0u32, 0u32);
+
+    for raw in patch.lines() {
+        if raw.starts_with("@@") {
+            let mut ranges = raw.split_whitespace().skip(1);
+            let old = ranges.next().and_then(|r| r.strip_prefix('-')).and_then(parse_hunk_range);
+            let new = ranges.next().and_then(|r| r.strip_prefix('+')).and_then(parse_hunk_range);
+            let (Some((old_start, old_lines)), Some((new_start, new_lines))) = (old, new) else {
+                continue;
+            };
+            old_line = old_start;
+            new_line = new_start;
+            hunks.push(HunkInfo {
+                header: format!("@@ -{},{} +{},{} @@", old_start, old_lines, new_start, new_lines),
+                old_start,
+                old_lines,
+                new_start,
+                new_lines,
+                lines: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(hunk) = hunks.last_mut() else { continue };
+        let mut chars = raw.chars();
+        let origin = chars.next().unwrap_or(' ');
+        let content = chars.as_str().to_string();
+
+        let (line_type, old, new) = match origin {
+            '+' => {
+                additions += 1;
+                new_line += 1;
+                (LineType::Addition, None, Some(new_line - 1))
+            }
+            '-' => {
+                deletions += 1;
+                old_line += 1;
+                (LineType::Deletion, Some(old_line - 1), None)
+            }
+            // "\ No newline at end of file"
+            '\\' => continue,
+            _ => {
+                old_line += 1;
+                new_line += 1;
+                (LineType::Context, Some(old_line - 1), Some(new_line - 1))
+            }
+        };
+
+        hunk.lines.push(LineInfo {
+            old_line: old,
+            new_line: new,
+            content,
+            origin,
+            line_type,
+            segments: Vec::new(),
         });
     }
 
-    Ok(diffs)
+    let language = language_for_path(&path);
+    let mut info = DiffInfo {
+        path,
+        old_path,
+        status: status.to_string(),
+        additions,
+        deletions,
+        is_binary,
+        hunks,
+        truncated: false,
+        is_lfs: false,
+        lfs_pointer: None,
+        line_ending_only: false,
+        encoding: None,
+        language,
+    };
+    detect_lfs_pointer(&mut info);
+    detect_line_ending_only(&mut info);
+    info
+}
+
+/// Prism language id for a path, from special file names first and then the
+/// extension; `None` when there's nothing sensible to highlight
+pub fn language_for_path(path: &str) -> Option<String> {
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path).to_lowercase();
+
+    let by_name = match file_name.as_str() {
+        "dockerfile" | "containerfile" => Some("docker"),
+        "makefile" | "gnumakefile" => Some("makefile"),
+        "cmakelists.txt" => Some("cmake"),
+        ".gitignore" | ".dockerignore" | ".npmignore" | ".hgignore" => Some("ignore"),
+        ".bashrc" | ".bash_profile" | ".zshrc" | ".profile" => Some("bash"),
+        "gemfile" | "rakefile" => Some("ruby"),
+        _ => None,
+    };
+    if let Some(language) = by_name {
+        return Some(language.to_string());
+    }
+    if file_name.starts_with("dockerfile.") {
+        return Some("docker".to_string());
+    }
+
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    if stem.is_empty() {
+        // Dotfiles like `.env` have no extension
+        return None;
+    }
+    let language = match extension {
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "jsx",
+        "ts" | "mts" | "cts" => "typescript",
+        "tsx" => "tsx",
+        "py" | "pyi" => "python",
+        "rb" => "ruby",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "go" => "go",
+        "rs" => "rust",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" => "cpp",
+        "cs" => "csharp",
+        "php" => "php",
+        "swift" => "swift",
+        "m" | "mm" => "objectivec",
+        "scala" => "scala",
+        "r" => "r",
+        "sql" => "sql",
+        "html" | "htm" => "html",
+        "xml" | "svg" => "xml",
+        "css" => "css",
+        "scss" => "scss",
+        "sass" => "sass",
+        "less" => "less",
+        "json" => "json",
+        "yaml" | "yml" => "yaml",
+        "toml" => "toml",
+        "md" | "markdown" => "markdown",
+        "sh" | "bash" | "zsh" | "fish" => "bash",
+        "ps1" => "powershell",
+        "dockerfile" => "docker",
+        "cmake" => "cmake",
+        "gradle" | "groovy" => "groovy",
+        "lua" => "lua",
+        "pl" | "pm" => "perl",
+        "ex" | "exs" => "elixir",
+        "erl" => "erlang",
+        "hs" => "haskell",
+        "clj" => "clojure",
+        "vim" => "vim",
+        "vue" => "vue",
+        "svelte" => "svelte",
+        _ => return None,
+    };
+    Some(language.to_string())
+}
+
+/// Flags diffs whose content is an LFS pointer. Pointers are a few lines, so
+/// the hunks hold the whole file; the new side wins unless it was deleted.
+fn detect_lfs_pointer(diff: &mut DiffInfo) {
+    let side = |skip: LineType| {
+        diff.hunks
+            .iter()
+            .flat_map(|h| h.lines.iter())
+            .filter(|l| l.line_type != skip && l.line_type != LineType::Header)
+            .map(|l| format!("{}\n", l.content))
+            .collect::<String>()
+    };
+
+    let pointer = parse_lfs_pointer(&side(LineType::Deletion))
+        .or_else(|| parse_lfs_pointer(&side(LineType::Addition)));
+    diff.is_lfs = pointer.is_some();
+    diff.lfs_pointer = pointer;
+}
+
+/// Flags diffs whose lines changed only between CRLF and LF. Such a change
+/// rewrites lines one-for-one, so the comparison only runs when additions and
+/// deletions match.
+fn detect_line_ending_only(diff: &mut DiffInfo) {
+    if diff.additions == 0 || diff.additions != diff.deletions || diff.truncated {
+        return;
+    }
+
+    let side = |kind: LineType| {
+        diff.hunks
+            .iter()
+            .flat_map(|h| h.lines.iter())
+            .filter(move |l| l.line_type == kind)
+            .map(|l| l.content.trim_end_matches('\r'))
+    };
+    diff.line_ending_only = side(LineType::Deletion).eq(side(LineType::Addition));
+}
+
+const LATIN1: &str = "latin-1";
+
+/// UTF-8 when valid; otherwise decodes as Latin-1 (every byte maps to a char)
+/// and sets `non_utf8`, so legacy files show accents instead of U+FFFD
+fn decode_line(bytes: &[u8], non_utf8: &mut bool) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => {
+            *non_utf8 = true;
+            bytes.iter().map(|&b| b as char).collect()
+        }
+    }
+}
+
+// Lines longer than this (in tokens) are left without segments
+const MAX_WORD_DIFF_TOKENS: usize = 500;
+
+/// Fills `segments` for modified lines by pairing each run of deletions with
+/// the additions that follow it. Unpaired lines keep empty segments.
+pub fn apply_word_diff(diffs: &mut [DiffInfo]) {
+    for hunk in diffs.iter_mut().flat_map(|d| d.hunks.iter_mut()) {
+        let lines = &mut hunk.lines;
+        let mut i = 0;
+
+        while i < lines.len() {
+            if lines[i].line_type != LineType::Deletion {
+                i += 1;
+                continue;
+            }
+
+            let del_start = i;
+            while i < lines.len() && lines[i].line_type == LineType::Deletion {
+                i += 1;
+            }
+            let add_start = i;
+            while i < lines.len() && lines[i].line_type == LineType::Addition {
+                i += 1;
+            }
+
+            let pairs = (add_start - del_start).min(i - add_start);
+            for offset in 0..pairs {
+                let (old_segments, new_segments) =
+                    word_segments(&lines[del_start + offset].content, &lines[add_start + offset].content);
+                lines[del_start + offset].segments = old_segments;
+                lines[add_start + offset].segments = new_segments;
+            }
+        }
+    }
+}
+
+fn word_segments(old: &str, new: &str) -> (Vec<LineSegment>, Vec<LineSegment>) {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    if old_tokens.len() > MAX_WORD_DIFF_TOKENS || new_tokens.len() > MAX_WORD_DIFF_TOKENS {
+        return (Vec::new(), Vec::new());
+    }
+
+    // LCS table over tokens
+    let (n, m) = (old_tokens.len(), new_tokens.len());
+    let mut table = vec![vec![0usize; m + 1]; n + 1];
+    for a in (0..n).rev() {
+        for b in (0..m).rev() {
+            table[a][b] = if old_tokens[a] == new_tokens[b] {
+                table[a + 1][b + 1] + 1
+            } else {
+                table[a + 1][b].max(table[a][b + 1])
+            };
+        }
+    }
+
+    let mut old_common = vec![false; n];
+    let mut new_common = vec![false; m];
+    let (mut a, mut b) = (0, 0);
+    while a < n && b < m {
+        if old_tokens[a] == new_tokens[b] {
+            old_common[a] = true;
+            new_common[b] = true;
+            a += 1;
+            b += 1;
+        } else if table[a + 1][b] >= table[a][b + 1] {
+            a += 1;
+        } else {
+            b += 1;
+        }
+    }
+
+    (
+        merge_segments(&old_tokens, &old_common),
+        merge_segments(&new_tokens, &new_common),
+    )
+}
+
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        let is_word = |ch: char| ch.is_alphanumeric() || ch == '_';
+        let end = if is_word(c) || c.is_whitespace() {
+            let same_class = |ch: char| if is_word(c) { is_word(ch) } else { ch.is_whitespace() };
+            let mut end = idx + c.len_utf8();
+            while let Some(&(next_idx, next)) = chars.peek() {
+                if !same_class(next) {
+                    break;
+                }
+                end = next_idx + next.len_utf8();
+                chars.next();
+            }
+            end
+        } else {
+            idx + c.len_utf8()
+        };
+        tokens.push(&line[idx..end]);
+    }
+
+    tokens
+}
+
+fn merge_segments(tokens: &[&str], common: &[bool]) -> Vec<LineSegment> {
+    let mut segments: Vec<LineSegment> = Vec::new();
+
+    for (token, is_common) in tokens.iter().zip(common) {
+        let changed = !is_common;
+        match segments.last_mut() {
+            Some(last) if last.changed == changed => last.text.push_str(token),
+            _ => segments.push(LineSegment {
+                text: token.to_string(),
+                changed,
+            }),
+        }
+    }
+
+    segments
 }
 
 pub fn get_file_diff_at_commit(repo: &Repository, commit_hash: &str, file_path: &str) -> AppResult<DiffInfo> {
@@ -271,14 +904,58 @@ pub fn get_file_diff_at_commit(repo: &Repository, commit_hash: &str, file_path:
 
     let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_opts))?;
 
-    let diffs = parse_diff(&diff, repo)?;
+    let diffs = parse_diff(&diff, &CancelToken::default())?;
     diffs
         .into_iter()
         .find(|d| d.path == file_path || d.old_path.as_deref() == Some(file_path))
         .ok_or_else(|| AppError::with_details("FILE_NOT_IN_DIFF", "Arquivo não encontrado no commit", file_path))
 }
 
-pub fn get_file_blame(repo: &Repository, file_path: &str) -> AppResult<Vec<BlameInfo>> {
+/// A file's blob as stored in some commit
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileAtCommit {
+    pub path: String,
+    /// `None` for binary files or files above the size limit
+    pub content: Option<String>,
+    pub is_binary: bool,
+    pub size: usize,
+}
+
+pub fn get_file_at_commit(repo: &Repository, commit_hash: &str, file_path: &str) -> AppResult<FileAtCommit> {
+    let oid = Oid::from_str(commit_hash).map_err(|_| AppError::commit_not_found(commit_hash))?;
+    let commit = repo.find_commit(oid)?;
+    let tree = commit.tree()?;
+
+    let not_found = || {
+        AppError::with_details(
+            "FILE_NOT_IN_COMMIT",
+            "Arquivo não existia neste commit",
+            file_path,
+        )
+    };
+    let entry = tree.get_path(std::path::Path::new(file_path)).map_err(|_| not_found())?;
+    if entry.kind() != Some(git2::ObjectType::Blob) {
+        return Err(not_found());
+    }
+
+    let blob = repo.find_blob(entry.id())?;
+    let size = blob.size();
+    let is_binary = blob.is_binary();
+    let content = if is_binary || size > MAX_FILE_CONTENT_BYTES {
+        None
+    } else {
+        Some(String::from_utf8_lossy(blob.content()).to_string())
+    };
+
+    Ok(FileAtCommit {
+        path: file_path.to_string(),
+        content,
+        is_binary,
+        size,
+    })
+}
+
+pub fn get_file_blame(repo: &Repository, file_path: &str, cancel: &CancelToken) -> AppResult<Vec<BlameInfo>> {
     let blame = repo.blame_file(std::path::Path::new(file_path), None)?;
 
     let workdir = repo.workdir().ok_or_else(|| AppError::internal("Repositorio bare nao suportado"))?;
@@ -289,6 +966,7 @@ pub fn get_file_blame(repo: &Repository, file_path: &str) -> AppResult<Vec<Blame
     let mut current_line = 1u32;
 
     for hunk in blame.iter() {
+        cancel.check()?;
         let sig = hunk.final_signature();
         let commit_id = hunk.final_commit_id();
 
@@ -316,3 +994,354 @@ pub struct BlameInfo {
     pub date: i64,
     pub content: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn setup_repo() -> (TempDir, Repository) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Teste").unwrap();
+            config.set_str("user.email", "teste@test.com").unwrap();
+        }
+        (dir, repo)
+    }
+
+    fn make_commit(repo: &Repository, dir: &Path, files: &[(&str, &[u8])], msg: &str) -> String {
+        for (name, content) in files {
+            std::fs::write(dir.join(name), content).unwrap();
+        }
+        let mut index = repo.index().unwrap();
+        for (name, _) in files {
+            index.add_path(Path::new(name)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn get_file_diff_paged_retorna_janela_de_hunks_e_total() {
+        let (dir, repo) = setup_repo();
+        let original: String = (1..=100).map(|i| format!("linha {}\n", i)).collect();
+        make_commit(&repo, dir.path(), &[("grande.txt", original.as_bytes())], "grande");
+
+        // Three edits far apart make three hunks
+        let edited = original
+            .replace("linha 5\n", "cinco\n")
+            .replace("linha 50\n", "cinquenta\n")
+            .replace("linha 95\n", "noventa e cinco\n");
+        std::fs::write(dir.path().join("grande.txt"), edited).unwrap();
+
+        let page = get_file_diff_paged(&repo, "grande.txt", false, 1, 1, DEFAULT_PAGED_DIFF_MAX_LINES).unwrap();
+        assert_eq!(page.total_hunks, 3);
+        assert_eq!(page.hunk_offset, 1);
+        assert_eq!(page.diff.hunks.len(), 1);
+        assert!(page.diff.hunks[0].lines.iter().any(|l| l.content == "cinquenta"));
+        assert_eq!((page.diff.additions, page.diff.deletions), (3, 3));
+        assert!(!page.diff.truncated);
+
+        let past_end = get_file_diff_paged(&repo, "grande.txt", false, 10, 5, DEFAULT_PAGED_DIFF_MAX_LINES).unwrap();
+        assert!(past_end.diff.hunks.is_empty());
+        assert_eq!(past_end.hunk_offset, 3);
+    }
+
+    #[test]
+    fn get_file_diff_paged_trunca_arquivo_acima_do_limite() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), &[("base.txt", b"base\n")], "base");
+        let generated: String = (0..50).map(|i| format!("gerado {}\n", i)).collect();
+        std::fs::write(dir.path().join("bundle.min.js"), generated).unwrap();
+
+        let page = get_file_diff_paged(&repo, "bundle.min.js", false, 0, 10, 20).unwrap();
+        assert!(page.diff.truncated);
+        assert!(page.diff.hunks.is_empty());
+        assert_eq!(page.diff.additions, 50);
+        assert_eq!(page.diff.status, "untracked");
+        assert_eq!(page.total_hunks, 1);
+    }
+
+    #[test]
+    fn language_for_path_usa_nome_especial_e_extensao() {
+        assert_eq!(language_for_path("src/App.tsx").as_deref(), Some("tsx"));
+        assert_eq!(language_for_path("src-tauri/src/main.RS").as_deref(), Some("rust"));
+        assert_eq!(language_for_path("scripts/build.py").as_deref(), Some("python"));
+        assert_eq!(language_for_path("docker/Dockerfile").as_deref(), Some("docker"));
+        assert_eq!(language_for_path("Dockerfile.dev").as_deref(), Some("docker"));
+        assert_eq!(language_for_path("Makefile").as_deref(), Some("makefile"));
+        assert_eq!(language_for_path(".gitignore").as_deref(), Some("ignore"));
+        assert_eq!(language_for_path("sub\\CMakeLists.txt").as_deref(), Some("cmake"));
+        assert_eq!(language_for_path(".env"), None);
+        assert_eq!(language_for_path("LICENSE"), None);
+        assert_eq!(language_for_path("image.png"), None);
+    }
+
+    #[test]
+    fn get_diff_stats_inclui_linhas_de_arquivos_nao_rastreados() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), &[("a.txt", b"um\ndois\n")], "inicial");
+
+        std::fs::write(dir.path().join("a.txt"), "um\nnovo\nmais\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub/novo.txt"), "x\ny\nz\n").unwrap();
+
+        let staged = get_diff_stats(&repo, true).unwrap();
+        assert_eq!(staged, DiffStats { files_changed: 1, insertions: 2, deletions: 1 });
+
+        let working = get_diff_stats(&repo, false).unwrap();
+        assert_eq!(working, DiffStats { files_changed: 2, insertions: 5, deletions: 1 });
+    }
+
+    #[test]
+    fn get_commit_diff_do_commit_inicial_lista_arquivos_como_adicionados() {
+        let (dir, repo) = setup_repo();
+        let hash = make_commit(&repo, dir.path(), &[("a.txt", b"um\ndois\n"), ("b.txt", b"tres\n")], "inicial");
+
+        let diffs = get_commit_diff(&repo, &hash, 0, &CancelToken::default()).unwrap();
+        let files: Vec<_> = diffs.iter().map(|d| (d.path.as_str(), d.status.as_str(), d.additions)).collect();
+        assert_eq!(files, vec![("a.txt", "added", 2), ("b.txt", "added", 1)]);
+
+        let summary = get_commit_diff_summary(&repo, &hash).unwrap();
+        assert_eq!(summary.len(), 2);
+        assert!(summary.iter().all(|f| f.status == "added" && f.deletions == 0));
+    }
+
+    #[test]
+    fn get_commit_diff_de_merge_compara_com_cada_pai() {
+        let (dir, repo) = setup_repo();
+        let base = make_commit(&repo, dir.path(), &[("base.txt", b"base\n")], "base");
+        let base_commit = repo.find_commit(Oid::from_str(&base).unwrap()).unwrap();
+        repo.branch("lado", &base_commit, false).unwrap();
+        let main = make_commit(&repo, dir.path(), &[("main.txt", b"main\n")], "main");
+
+        repo.set_head("refs/heads/lado").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        let lado = make_commit(&repo, dir.path(), &[("lado.txt", b"lado\n")], "lado");
+
+        // Merge tree has both files, with main as first parent and lado as second
+        let main_commit = repo.find_commit(Oid::from_str(&main).unwrap()).unwrap();
+        let lado_commit = repo.find_commit(Oid::from_str(&lado).unwrap()).unwrap();
+        let mut index = repo.merge_commits(&main_commit, &lado_commit, None).unwrap();
+        let tree = repo.find_tree(index.write_tree_to(&repo).unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let merge = repo
+            .commit(None, &sig, &sig, "merge", &tree, &[&main_commit, &lado_commit])
+            .unwrap()
+            .to_string();
+
+        let cancel = CancelToken::default();
+        let vs_first: Vec<_> = get_commit_diff(&repo, &merge, 0, &cancel).unwrap().into_iter().map(|d| d.path).collect();
+        let vs_second: Vec<_> = get_commit_diff(&repo, &merge, 1, &cancel).unwrap().into_iter().map(|d| d.path).collect();
+        assert_eq!(vs_first, vec!["lado.txt"]);
+        assert_eq!(vs_second, vec!["main.txt"]);
+
+        let err = get_commit_diff(&repo, &merge, 2, &cancel).unwrap_err();
+        assert_eq!(err.code, "INVALID_PARENT_INDEX");
+    }
+
+    #[test]
+    fn get_commit_diff_summary_conta_linhas_sem_hunks() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), &[("a.txt", b"um\ndois\n")], "inicial");
+        let hash = make_commit(
+            &repo,
+            dir.path(),
+            &[("a.txt", b"um\ntres\nquatro\n"), ("img.bin", b"\x00\x01\x02")],
+            "altera",
+        );
+
+        let summary = get_commit_diff_summary(&repo, &hash).unwrap();
+        assert_eq!(summary.len(), 2);
+
+        let text = summary.iter().find(|s| s.path == "a.txt").unwrap();
+        assert_eq!(text.status, "modified");
+        assert_eq!(text.additions, 2);
+        assert_eq!(text.deletions, 1);
+        assert!(!text.is_binary);
+
+        let binary = summary.iter().find(|s| s.path == "img.bin").unwrap();
+        assert_eq!(binary.status, "added");
+        assert!(binary.is_binary);
+    }
+
+    #[test]
+    fn diff_marca_mudanca_apenas_de_fim_de_linha() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), &[("crlf.txt", b"um\ndois\n"), ("real.txt", b"um\n")], "inicial");
+        std::fs::write(dir.path().join("crlf.txt"), b"um\r\ndois\r\n").unwrap();
+        std::fs::write(dir.path().join("real.txt"), b"um\r\n").unwrap();
+        let hash = make_commit(&repo, dir.path(), &[("real.txt", b"outro\r\n")], "altera");
+
+        let working = get_working_diff(&repo, &CancelToken::default()).unwrap();
+        let crlf = working.iter().find(|d| d.path == "crlf.txt").unwrap();
+        assert!(crlf.line_ending_only);
+
+        let commit = get_commit_diff(&repo, &hash, 0, &CancelToken::default()).unwrap();
+        assert!(!commit[0].line_ending_only);
+    }
+
+    #[test]
+    fn diff_decodifica_conteudo_latin1() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), &[("a.txt", b"um\n")], "inicial");
+        let hash = make_commit(&repo, dir.path(), &[("a.txt", b"caf\xe9\n")], "acento");
+
+        let diff = get_commit_diff(&repo, &hash, 0, &CancelToken::default()).unwrap();
+        assert_eq!(diff[0].encoding.as_deref(), Some("latin-1"));
+        let added = diff[0].hunks[0].lines.iter().find(|l| l.line_type == LineType::Addition).unwrap();
+        assert_eq!(added.content, "café");
+
+        std::fs::write(dir.path().join("novo.txt"), b"ol\xe1\n").unwrap();
+        let untracked = get_file_diff(&repo, "novo.txt", false, &dir.path().to_path_buf()).unwrap();
+        assert_eq!(untracked.encoding.as_deref(), Some("latin-1"));
+
+        let hash = make_commit(&repo, dir.path(), &[("b.txt", "café\n".as_bytes())], "utf8");
+        let utf8 = get_commit_diff(&repo, &hash, 0, &CancelToken::default()).unwrap();
+        assert_eq!(utf8[0].encoding, None);
+    }
+
+    #[test]
+    fn get_file_diff_untracked_binario_nao_falha() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), &[("a.txt", b"um\n")], "inicial");
+        std::fs::write(dir.path().join("imagem.png"), b"\x89PNG\r\n\x1a\n\x00\x00\xff").unwrap();
+
+        let diff = get_file_diff(&repo, "imagem.png", false, &dir.path().to_path_buf()).unwrap();
+        assert!(diff.is_binary);
+        assert!(diff.hunks.is_empty());
+        assert_eq!(diff.status, "added");
+    }
+
+    #[test]
+    fn get_file_diff_untracked_grande_e_truncado() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), &[("a.txt", b"um\n")], "inicial");
+        let content: String = (0..MAX_UNTRACKED_DIFF_LINES + 10)
+            .map(|i| format!("linha {}\n", i))
+            .collect();
+        std::fs::write(dir.path().join("log.txt"), content).unwrap();
+
+        let diff = get_file_diff(&repo, "log.txt", false, &dir.path().to_path_buf()).unwrap();
+        assert!(diff.truncated);
+        assert_eq!(diff.additions, MAX_UNTRACKED_DIFF_LINES + 10);
+        assert_eq!(diff.hunks[0].lines.len(), MAX_UNTRACKED_DIFF_LINES);
+    }
+
+    #[test]
+    fn apply_word_diff_marca_apenas_palavras_alteradas() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), &[("a.txt", b"let total = soma(a, b);\n")], "inicial");
+        let hash = make_commit(
+            &repo,
+            dir.path(),
+            &[("a.txt", b"let total = media(a, b);\nnova linha\n")],
+            "altera",
+        );
+
+        let mut diffs = get_commit_diff(&repo, &hash, 0, &CancelToken::default()).unwrap();
+        apply_word_diff(&mut diffs);
+
+        let lines = &diffs[0].hunks[0].lines;
+        let deletion = lines.iter().find(|l| l.line_type == LineType::Deletion).unwrap();
+        let changed: Vec<&str> = deletion
+            .segments
+            .iter()
+            .filter(|s| s.changed)
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(changed, vec!["soma"]);
+
+        let additions: Vec<&LineInfo> = lines
+            .iter()
+            .filter(|l| l.line_type == LineType::Addition)
+            .collect();
+        let rebuilt: String = additions[0].segments.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(rebuilt, "let total = media(a, b);");
+        // Linha sem par fica sem segmentos
+        assert!(additions[1].segments.is_empty());
+    }
+
+    #[test]
+    fn diff_de_ponteiro_lfs_e_sinalizado() {
+        let (dir, repo) = setup_repo();
+        let pointer = b"version https://git-lfs.github.com/spec/v1\noid sha256:4d7a\nsize 5242880\n";
+        let hash = make_commit(&repo, dir.path(), &[("video.mp4", pointer)], "lfs");
+
+        let diffs = get_commit_diff(&repo, &hash, 0, &CancelToken::default()).unwrap();
+        assert!(diffs[0].is_lfs);
+        assert_eq!(
+            diffs[0].lfs_pointer,
+            Some(LfsPointer {
+                oid: "sha256:4d7a".to_string(),
+                size: 5242880,
+            })
+        );
+
+        let hash = make_commit(&repo, dir.path(), &[("b.txt", b"texto\n")], "texto");
+        assert!(!get_commit_diff(&repo, &hash, 0, &CancelToken::default()).unwrap()[0].is_lfs);
+    }
+
+    #[test]
+    fn get_commit_diff_cancelado_retorna_cancelled() {
+        let (dir, repo) = setup_repo();
+        let hash = make_commit(&repo, dir.path(), &[("a.txt", b"a\n"), ("b.txt", b"b\n")], "dois");
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let err = get_commit_diff(&repo, &hash, 0, &cancel).unwrap_err();
+        assert_eq!(err.code, "CANCELLED");
+    }
+
+    #[test]
+    fn diff_info_from_patch_numera_linhas_como_libgit2() {
+        let patch = "@@ -2,3 +2,3 @@\n a\n-b\n+B\n c\n@@ -10 +10,2 @@\n-fim\n\\ No newline at end of file\n+fim\n+extra";
+        let info = diff_info_from_patch("x.txt".to_string(), None, "modified", false, patch);
+
+        assert_eq!((info.additions, info.deletions), (3, 2));
+        assert_eq!(info.hunks.len(), 2);
+        assert_eq!(info.hunks[1].header, "@@ -10,1 +10,2 @@");
+
+        let lines = &info.hunks[0].lines;
+        assert_eq!((lines[1].old_line, lines[1].new_line), (Some(3), None));
+        assert_eq!((lines[2].old_line, lines[2].new_line), (None, Some(3)));
+        assert_eq!((lines[3].old_line, lines[3].new_line), (Some(4), Some(4)));
+        assert_eq!(lines[2].content, "B");
+
+        let last = &info.hunks[1].lines;
+        assert_eq!(last.len(), 3);
+        assert_eq!(last[2].new_line, Some(11));
+    }
+
+    #[test]
+    fn get_file_at_commit_retorna_versao_antiga() {
+        let (dir, repo) = setup_repo();
+        let first = make_commit(&repo, dir.path(), &[("a.txt", b"v1\n")], "v1");
+        make_commit(&repo, dir.path(), &[("a.txt", b"v2\n"), ("img.bin", b"\0\x01\x02")], "v2");
+
+        let file = get_file_at_commit(&repo, &first, "a.txt").unwrap();
+        assert_eq!(file.content.as_deref(), Some("v1\n"));
+        assert_eq!(file.size, 3);
+
+        let head = repo.head().unwrap().target().unwrap().to_string();
+        let binary = get_file_at_commit(&repo, &head, "img.bin").unwrap();
+        assert!(binary.is_binary);
+        assert!(binary.content.is_none());
+
+        let err = get_file_at_commit(&repo, &first, "img.bin").unwrap_err();
+        assert_eq!(err.code, "FILE_NOT_IN_COMMIT");
+    }
+}
@@ -1,7 +1,12 @@
 use crate::error::{AppError, AppResult};
+use crate::git::{diff_info_from_patch, DiffInfo};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -90,6 +95,31 @@ pub struct PullRequestComment {
     pub created_at: String,
 }
 
+/// A review conversation anchored to a diff line, with its resolution state
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewThread {
+    /// GraphQL node id, used to resolve/unresolve
+    pub id: String,
+    pub is_resolved: bool,
+    /// The commented lines changed since the thread was opened
+    pub is_outdated: bool,
+    pub path: String,
+    /// `None` when outdated; `original_line` still points at the old diff
+    pub line: Option<u64>,
+    pub original_line: Option<u64>,
+    pub resolved_by: Option<String>,
+    pub comments: Vec<ReviewThreadComment>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewThreadComment {
+    pub id: String,
+    pub author: String,
+    pub body: String,
+    pub created_at: String,
+    pub url: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PullRequestFile {
     pub filename: String,
@@ -120,8 +150,52 @@ pub fn check_gh_cli(repo_path: &Path) -> AppResult<bool> {
     }
 }
 
-/// Run gh command and return output
+type GhCacheKey = (PathBuf, Vec<String>);
+
+/// Read-only gh output per repo and arguments, reused for `GH_CACHE_TTL`
+static GH_CACHE: Lazy<Mutex<HashMap<GhCacheKey, (Instant, String)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+const GH_CACHE_TTL: Duration = Duration::from_secs(30);
+
+fn gh_cache_key(repo_path: &Path, args: &[&str]) -> GhCacheKey {
+    (repo_path.to_path_buf(), args.iter().map(|a| a.to_string()).collect())
+}
+
+fn cached_gh_output(key: &GhCacheKey, now: Instant) -> Option<String> {
+    let cache = GH_CACHE.lock().unwrap();
+    cache
+        .get(key)
+        .filter(|(stored_at, _)| now.duration_since(*stored_at) < GH_CACHE_TTL)
+        .map(|(_, output)| output.clone())
+}
+
+/// Drops every cached gh response for a repo
+pub fn clear_gh_cache(repo_path: &Path) {
+    GH_CACHE.lock().unwrap().retain(|(path, _), _| path != repo_path);
+}
+
+/// Run a read-only gh command (list/view/api GET), served from the cache while fresh
+fn run_gh_cached(repo_path: &Path, args: &[&str]) -> AppResult<String> {
+    let key = gh_cache_key(repo_path, args);
+    if let Some(output) = cached_gh_output(&key, Instant::now()) {
+        return Ok(output);
+    }
+
+    let output = exec_gh_command(repo_path, args)?;
+    GH_CACHE.lock().unwrap().insert(key, (Instant::now(), output.clone()));
+    Ok(output)
+}
+
+/// Run a gh command that may change GitHub state; a success invalidates the repo's cache
 fn run_gh_command(repo_path: &Path, args: &[&str]) -> AppResult<String> {
+    let output = exec_gh_command(repo_path, args)?;
+    clear_gh_cache(repo_path);
+    Ok(output)
+}
+
+/// Run gh command and return output
+fn exec_gh_command(repo_path: &Path, args: &[&str]) -> AppResult<String> {
     let gh_path = find_gh_cli().ok_or_else(|| {
         AppError::with_details(
             "GH_NOT_FOUND",
@@ -165,12 +239,175 @@ fn run_gh_command(repo_path: &Path, args: &[&str]) -> AppResult<String> {
     }
 }
 
+/// `owner/repo` on a GitHub (or GitHub Enterprise) host
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RepoSlug {
+    pub owner: String,
+    pub repo: String,
+    pub host: String,
+}
+
+/// Parses scp-like (`git@host:owner/repo.git`) and URL-style (https, ssh, git)
+/// remotes into a slug; `None` when the path isn't `owner/repo`
+fn parse_remote_url(url: &str) -> Option<RepoSlug> {
+    let url = url.trim();
+    let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        (host.split(':').next()?, path)
+    } else {
+        let (authority, path) = url.split_once(':')?;
+        (authority.rsplit('@').next()?, path)
+    };
+
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, repo) = path.split_once('/')?;
+    if host.is_empty() || owner.is_empty() || repo.is_empty() || repo.contains('/') {
+        return None;
+    }
+
+    Some(RepoSlug {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        host: host.to_string(),
+    })
+}
+
+/// Slug of the repo's `origin` remote, falling back to `gh repo view` when
+/// there's no origin or its URL isn't `owner/repo` shaped
+pub fn get_repo_slug(repo_path: &Path) -> AppResult<RepoSlug> {
+    let from_origin = git2::Repository::open(repo_path)
+        .ok()
+        .and_then(|repo| {
+            repo.find_remote("origin")
+                .ok()
+                .and_then(|remote| remote.url().map(String::from))
+        })
+        .and_then(|url| parse_remote_url(&url));
+    if let Some(slug) = from_origin {
+        return Ok(slug);
+    }
+
+    let output = run_gh_cached(repo_path, &["repo", "view", "--json", "url"])?;
+    let data: serde_json::Value = serde_json::from_str(&output)?;
+    data["url"]
+        .as_str()
+        .and_then(parse_remote_url)
+        .ok_or_else(|| AppError::new("REPO_SLUG_NOT_FOUND", "Não foi possível identificar o repositório no GitHub"))
+}
+
+/// Like `get_repo_slug`, but fails with NOT_GITHUB_REMOTE for hosts gh can't
+/// reach (GitLab, Bitbucket...); Enterprise hosts pass if gh is logged in there
+fn require_github_slug(repo_path: &Path) -> AppResult<RepoSlug> {
+    let slug = get_repo_slug(repo_path)?;
+    let is_github = slug.host.contains("github")
+        || run_gh_cached(repo_path, &["auth", "status", "--hostname", &slug.host]).is_ok();
+    if !is_github {
+        return Err(AppError::with_details(
+            "NOT_GITHUB_REMOTE",
+            "O remote origin não aponta para o GitHub",
+            &slug.host,
+        ));
+    }
+    Ok(slug)
+}
+
+/// Default branch from local refs: `origin/HEAD`'s target, else whichever of
+/// main/master exists locally or on origin
+fn default_branch_from_refs(repo: &git2::Repository) -> Option<String> {
+    let from_origin_head = repo
+        .find_reference("refs/remotes/origin/HEAD")
+        .ok()
+        .and_then(|r| r.symbolic_target().map(String::from))
+        .and_then(|target| target.strip_prefix("refs/remotes/origin/").map(String::from));
+    if from_origin_head.is_some() {
+        return from_origin_head;
+    }
+
+    ["main", "master"]
+        .iter()
+        .find(|name| {
+            repo.find_branch(name, git2::BranchType::Local).is_ok()
+                || repo.find_branch(&format!("origin/{}", name), git2::BranchType::Remote).is_ok()
+        })
+        .map(|name| name.to_string())
+}
+
+/// The repo's default branch as GitHub reports it, falling back to local refs
+/// when gh is unavailable or the repo isn't on GitHub
+pub fn get_default_branch(repo_path: &Path) -> AppResult<String> {
+    let from_gh = run_gh_cached(repo_path, &["repo", "view", "--json", "defaultBranchRef"])
+        .ok()
+        .and_then(|output| serde_json::from_str::<serde_json::Value>(&output).ok())
+        .and_then(|data| {
+            data["defaultBranchRef"]["name"]
+                .as_str()
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+        });
+    if let Some(branch) = from_gh {
+        return Ok(branch);
+    }
+
+    let repo = git2::Repository::open(repo_path)?;
+    default_branch_from_refs(&repo).ok_or_else(|| {
+        AppError::new("DEFAULT_BRANCH_NOT_FOUND", "Não foi possível determinar a branch padrão do repositório")
+    })
+}
+
+/// Percent-encodes a ref or path for a URL, keeping `/` as separator
+fn encode_url_path(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn repo_web_url(slug: &RepoSlug) -> String {
+    format!("https://{}/{}/{}", slug.host, slug.owner, slug.repo)
+}
+
+fn file_web_url(slug: &RepoSlug, git_ref: &str, path: &str, line: Option<u32>) -> String {
+    let mut url = format!(
+        "{}/blob/{}/{}",
+        repo_web_url(slug),
+        encode_url_path(git_ref),
+        encode_url_path(path.trim_start_matches('/'))
+    );
+    if let Some(line) = line {
+        url.push_str(&format!("#L{}", line));
+    }
+    url
+}
+
+pub fn github_commit_url(repo_path: &Path, hash: &str) -> AppResult<String> {
+    let slug = require_github_slug(repo_path)?;
+    Ok(format!("{}/commit/{}", repo_web_url(&slug), hash))
+}
+
+pub fn github_branch_url(repo_path: &Path, branch: &str) -> AppResult<String> {
+    let slug = require_github_slug(repo_path)?;
+    Ok(format!("{}/tree/{}", repo_web_url(&slug), encode_url_path(branch)))
+}
+
+/// Blob URL of `path` at `git_ref` (branch or commit), with an optional `#L<line>` anchor
+pub fn github_file_url(repo_path: &Path, git_ref: &str, path: &str, line: Option<u32>) -> AppResult<String> {
+    let slug = require_github_slug(repo_path)?;
+    Ok(file_web_url(&slug, git_ref, path, line))
+}
+
 /// List pull requests
 pub fn list_pull_requests(repo_path: &Path, state: Option<&str>, limit: u32) -> AppResult<Vec<PullRequest>> {
     let state_arg = state.unwrap_or("all");
     let limit_str = limit.to_string();
 
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &[
             "pr", "list",
@@ -234,7 +471,7 @@ pub fn list_pull_requests(repo_path: &Path, state: Option<&str>, limit: u32) ->
 pub fn get_pull_request(repo_path: &Path, number: u64) -> AppResult<PullRequest> {
     let number_str = number.to_string();
 
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &[
             "pr", "view", &number_str,
@@ -353,7 +590,7 @@ pub fn create_pull_request(
 pub fn get_pull_request_reviews(repo_path: &Path, number: u64) -> AppResult<Vec<PullRequestReview>> {
     let number_str = number.to_string();
 
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &[
             "pr", "view", &number_str,
@@ -388,7 +625,7 @@ pub fn get_pull_request_reviews(repo_path: &Path, number: u64) -> AppResult<Vec<
 pub fn get_pull_request_comments(repo_path: &Path, number: u64) -> AppResult<Vec<PullRequestComment>> {
     let number_str = number.to_string();
 
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &[
             "pr", "view", &number_str,
@@ -418,11 +655,104 @@ pub fn get_pull_request_comments(repo_path: &Path, number: u64) -> AppResult<Vec
     Ok(comments)
 }
 
+const REVIEW_THREADS_QUERY: &str = "query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          id isResolved isOutdated path line originalLine
+          resolvedBy { login }
+          comments(first: 100) {
+            nodes { id author { login } body createdAt url }
+          }
+        }
+      }
+    }
+  }
+}";
+
+/// Review threads of a PR grouped with their comments (first 100 of each).
+/// A PR without review comments yields an empty list.
+pub fn get_pr_review_threads(repo_path: &Path, number: u64) -> AppResult<Vec<ReviewThread>> {
+    let slug = require_github_slug(repo_path)?;
+    let query = format!("query={}", REVIEW_THREADS_QUERY);
+    let owner = format!("owner={}", slug.owner);
+    let name = format!("name={}", slug.repo);
+    let number_field = format!("number={}", number);
+
+    let output = run_gh_cached(
+        repo_path,
+        &[
+            "api", "graphql", "--hostname", &slug.host,
+            "-f", &query, "-f", &owner, "-f", &name, "-F", &number_field,
+        ],
+    )?;
+
+    let data: serde_json::Value = serde_json::from_str(&output).map_err(|e| {
+        AppError::with_details("PARSE_ERROR", "Erro ao parsear threads de review", &e.to_string())
+    })?;
+    Ok(parse_review_threads(&data))
+}
+
+fn parse_review_threads(data: &serde_json::Value) -> Vec<ReviewThread> {
+    let nodes = &data["data"]["repository"]["pullRequest"]["reviewThreads"]["nodes"];
+    nodes
+        .as_array()
+        .map(|threads| {
+            threads
+                .iter()
+                .map(|t| ReviewThread {
+                    id: t["id"].as_str().unwrap_or("").to_string(),
+                    is_resolved: t["isResolved"].as_bool().unwrap_or(false),
+                    is_outdated: t["isOutdated"].as_bool().unwrap_or(false),
+                    path: t["path"].as_str().unwrap_or("").to_string(),
+                    line: t["line"].as_u64(),
+                    original_line: t["originalLine"].as_u64(),
+                    resolved_by: t["resolvedBy"]["login"].as_str().map(String::from),
+                    comments: t["comments"]["nodes"]
+                        .as_array()
+                        .map(|comments| {
+                            comments
+                                .iter()
+                                .map(|c| ReviewThreadComment {
+                                    id: c["id"].as_str().unwrap_or("").to_string(),
+                                    // Deleted accounts come back as a null author
+                                    author: c["author"]["login"].as_str().unwrap_or("ghost").to_string(),
+                                    body: c["body"].as_str().unwrap_or("").to_string(),
+                                    created_at: c["createdAt"].as_str().unwrap_or("").to_string(),
+                                    url: c["url"].as_str().unwrap_or("").to_string(),
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Marks a review thread resolved (or reopens it) by its GraphQL node id
+pub fn set_review_thread_resolved(repo_path: &Path, thread_id: &str, resolved: bool) -> AppResult<()> {
+    let slug = require_github_slug(repo_path)?;
+    let mutation = if resolved { "resolveReviewThread" } else { "unresolveReviewThread" };
+    let query = format!(
+        "query=mutation($threadId: ID!) {{ {}(input: {{threadId: $threadId}}) {{ thread {{ id isResolved }} }} }}",
+        mutation
+    );
+    let thread_field = format!("threadId={}", thread_id);
+
+    run_gh_command(
+        repo_path,
+        &["api", "graphql", "--hostname", &slug.host, "-f", &query, "-f", &thread_field],
+    )?;
+    Ok(())
+}
+
 /// Get PR files
 pub fn get_pull_request_files(repo_path: &Path, number: u64) -> AppResult<Vec<PullRequestFile>> {
     let number_str = number.to_string();
 
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &[
             "pr", "view", &number_str,
@@ -433,16 +763,25 @@ pub fn get_pull_request_files(repo_path: &Path, number: u64) -> AppResult<Vec<Pu
     let data: serde_json::Value = serde_json::from_str(&output)
         .map_err(|e| AppError::with_details("PARSE_ERROR", "Erro ao parsear files", &e.to_string()))?;
 
+    // GitHub refuses diffs over its size limit; files are still listed, just without patches
+    let sections = get_pull_request_diff(repo_path, number)
+        .map(|diff| split_unified_diff(&diff))
+        .unwrap_or_default();
+
     let files = data["files"]
         .as_array()
         .map(|arr| {
             arr.iter()
-                .map(|f| PullRequestFile {
-                    filename: f["path"].as_str().unwrap_or("").to_string(),
-                    status: f["additions"].as_u64().map(|_| "modified").unwrap_or("unknown").to_string(),
-                    additions: f["additions"].as_u64().unwrap_or(0),
-                    deletions: f["deletions"].as_u64().unwrap_or(0),
-                    patch: None,
+                .map(|f| {
+                    let filename = f["path"].as_str().unwrap_or("").to_string();
+                    let section = sections.iter().find(|s| s.path == filename);
+                    PullRequestFile {
+                        status: section.map(|s| s.status).unwrap_or("modified").to_string(),
+                        patch: section.filter(|s| !s.patch.is_empty()).map(|s| s.patch.clone()),
+                        filename,
+                        additions: f["additions"].as_u64().unwrap_or(0),
+                        deletions: f["deletions"].as_u64().unwrap_or(0),
+                    }
                 })
                 .collect()
         })
@@ -451,6 +790,99 @@ pub fn get_pull_request_files(repo_path: &Path, number: u64) -> AppResult<Vec<Pu
     Ok(files)
 }
 
+/// One file's section of a unified diff (`git diff` / `gh pr diff` output)
+#[derive(Debug, Clone, PartialEq)]
+struct UnifiedDiffFile {
+    path: String,
+    old_path: Option<String>,
+    /// GitHub's file status: added, removed, modified or renamed
+    status: &'static str,
+    is_binary: bool,
+    /// Hunks only, from the first `@@` line, like the REST API's `patch`
+    patch: String,
+}
+
+/// Strips the `a/` / `b/` prefix from a `---`/`+++` path; `None` for /dev/null
+fn diff_header_path(raw: &str) -> Option<String> {
+    let raw = raw.trim_end();
+    if raw == "/dev/null" {
+        return None;
+    }
+    Some(
+        raw.strip_prefix("a/")
+            .or_else(|| raw.strip_prefix("b/"))
+            .unwrap_or(raw)
+            .to_string(),
+    )
+}
+
+/// Splits a multi-file unified diff into per-file sections, reading status
+/// and paths from the extended headers (new/deleted file mode, rename from/to)
+fn split_unified_diff(diff: &str) -> Vec<UnifiedDiffFile> {
+    let mut files = Vec::new();
+    let mut current: Option<UnifiedDiffFile> = None;
+    let mut in_hunks = false;
+
+    for line in diff.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            files.extend(current.take());
+            in_hunks = false;
+            // Fallback path for sections without ---/+++ lines (binary, pure rename, mode change)
+            let path = header
+                .trim_end()
+                .rsplit_once(" b/")
+                .map(|(_, b)| b.to_string())
+                .unwrap_or_default();
+            current = Some(UnifiedDiffFile {
+                path,
+                old_path: None,
+                status: "modified",
+                is_binary: false,
+                patch: String::new(),
+            });
+            continue;
+        }
+
+        let Some(file) = current.as_mut() else { continue };
+
+        if in_hunks || line.starts_with("@@") {
+            in_hunks = true;
+            file.patch.push_str(line);
+        } else if line.starts_with("new file mode") {
+            file.status = "added";
+        } else if line.starts_with("deleted file mode") {
+            file.status = "removed";
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            file.old_path = Some(from.trim_end().to_string());
+            file.status = "renamed";
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            file.path = to.trim_end().to_string();
+        } else if line.starts_with("Binary files ") || line.starts_with("GIT binary patch") {
+            file.is_binary = true;
+        } else if let Some(new) = line.strip_prefix("+++ ") {
+            if let Some(path) = diff_header_path(new) {
+                file.path = path;
+            }
+        } else if let Some(old) = line.strip_prefix("--- ") {
+            if file.status == "removed" {
+                if let Some(path) = diff_header_path(old) {
+                    file.path = path;
+                }
+            }
+        }
+    }
+    files.extend(current);
+
+    // The last hunk line carries the section's trailing newline; the API patch doesn't
+    for file in &mut files {
+        if file.patch.ends_with('\n') {
+            file.patch.pop();
+        }
+    }
+
+    files
+}
+
 /// Add a review to PR (approve, request changes, or comment)
 pub fn review_pull_request(
     repo_path: &Path,
@@ -532,7 +964,22 @@ pub fn ready_pull_request(repo_path: &Path, number: u64) -> AppResult<()> {
 /// Get PR diff
 pub fn get_pull_request_diff(repo_path: &Path, number: u64) -> AppResult<String> {
     let number_str = number.to_string();
-    run_gh_command(repo_path, &["pr", "diff", &number_str])
+    run_gh_cached(repo_path, &["pr", "diff", &number_str])
+}
+
+/// PR diff parsed into the same per-file shape as local diffs
+pub fn get_pull_request_diff_structured(repo_path: &Path, number: u64) -> AppResult<Vec<DiffInfo>> {
+    let diff = get_pull_request_diff(repo_path, number)?;
+
+    Ok(split_unified_diff(&diff)
+        .into_iter()
+        .map(|file| {
+            // Match libgit2's delta status names used by local diffs
+            let status = if file.status == "removed" { "deleted" } else { file.status };
+            let old_path = file.old_path.or_else(|| Some(file.path.clone()));
+            diff_info_from_patch(file.path, old_path, status, file.is_binary, &file.patch)
+        })
+        .collect())
 }
 
 /// Checkout PR branch locally
@@ -557,7 +1004,7 @@ pub struct CheckRun {
 /// Get CI check runs for a PR
 pub fn get_pr_checks(repo_path: &Path, number: u64) -> AppResult<Vec<CheckRun>> {
     let number_str = number.to_string();
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &[
             "pr", "checks", &number_str,
@@ -587,6 +1034,120 @@ pub fn get_pr_checks(repo_path: &Path, number: u64) -> AppResult<Vec<CheckRun>>
     Ok(checks)
 }
 
+// ─────────────────────────────────────────
+// Actions
+// ─────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkflowRun {
+    pub id: u64,
+    pub name: String,
+    pub display_title: String,
+    pub status: String,
+    pub conclusion: Option<String>,
+    pub head_branch: String,
+    pub created_at: String,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkflowJob {
+    pub name: String,
+    pub status: String,
+    pub conclusion: Option<String>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkflowRunDetails {
+    #[serde(flatten)]
+    pub run: WorkflowRun,
+    pub jobs: Vec<WorkflowJob>,
+}
+
+const WORKFLOW_RUN_FIELDS: &str = "databaseId,name,displayTitle,status,conclusion,headBranch,createdAt,url";
+
+fn optional_str(v: &serde_json::Value) -> Option<String> {
+    v.as_str().filter(|s| !s.is_empty()).map(|s| s.to_string())
+}
+
+fn parse_workflow_run(v: &serde_json::Value) -> WorkflowRun {
+    WorkflowRun {
+        id: v["databaseId"].as_u64().unwrap_or(0),
+        name: v["name"].as_str().unwrap_or("").to_string(),
+        display_title: v["displayTitle"].as_str().unwrap_or("").to_string(),
+        status: v["status"].as_str().unwrap_or("").to_string(),
+        conclusion: optional_str(&v["conclusion"]),
+        head_branch: v["headBranch"].as_str().unwrap_or("").to_string(),
+        created_at: v["createdAt"].as_str().unwrap_or("").to_string(),
+        url: v["url"].as_str().unwrap_or("").to_string(),
+    }
+}
+
+/// gh errors that just mean "this repo has no Actions runs to show". A bare
+/// HTTP 404 isn't one of them: a missing repo or token scope looks the same.
+fn is_no_workflows_error(err: &AppError) -> bool {
+    const MARKERS: [&str; 3] = ["could not find any workflows", "no workflows found", "no runs found"];
+    let details = err.details.as_deref().unwrap_or("").to_lowercase();
+    MARKERS.iter().any(|marker| details.contains(marker))
+}
+
+/// Recent Actions runs, newest first, optionally for one branch
+pub fn list_workflow_runs(repo_path: &Path, branch: Option<&str>, limit: u32) -> AppResult<Vec<WorkflowRun>> {
+    let limit_str = limit.to_string();
+    let mut args = vec!["run", "list", "--limit", &limit_str, "--json", WORKFLOW_RUN_FIELDS];
+    if let Some(b) = branch {
+        args.extend(["--branch", b]);
+    }
+
+    let output = match run_gh_cached(repo_path, &args) {
+        Ok(output) => output,
+        Err(e) if is_no_workflows_error(&e) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let data: serde_json::Value = serde_json::from_str(&output)
+        .map_err(|e| AppError::with_details("PARSE_ERROR", "Erro ao parsear workflow runs", &e.to_string()))?;
+
+    Ok(data
+        .as_array()
+        .map(|arr| arr.iter().map(parse_workflow_run).collect())
+        .unwrap_or_default())
+}
+
+/// One Actions run with its jobs
+pub fn get_workflow_run(repo_path: &Path, id: u64) -> AppResult<WorkflowRunDetails> {
+    let id_str = id.to_string();
+    let fields = format!("{},jobs", WORKFLOW_RUN_FIELDS);
+    let output = run_gh_cached(repo_path, &["run", "view", &id_str, "--json", &fields])?;
+
+    let data: serde_json::Value = serde_json::from_str(&output)
+        .map_err(|e| AppError::with_details("PARSE_ERROR", "Erro ao parsear workflow run", &e.to_string()))?;
+
+    let jobs = data["jobs"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|j| WorkflowJob {
+                    name: j["name"].as_str().unwrap_or("").to_string(),
+                    status: j["status"].as_str().unwrap_or("").to_string(),
+                    conclusion: optional_str(&j["conclusion"]),
+                    started_at: optional_str(&j["startedAt"]),
+                    completed_at: optional_str(&j["completedAt"]),
+                    url: j["url"].as_str().unwrap_or("").to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(WorkflowRunDetails {
+        run: parse_workflow_run(&data),
+        jobs,
+    })
+}
+
 // ─────────────────────────────────────────
 // Issues
 // ─────────────────────────────────────────
@@ -724,7 +1285,7 @@ pub fn list_issues(
         args.push(&milestone_owned);
     }
 
-    let output = run_gh_command(repo_path, &args)?;
+    let output = run_gh_cached(repo_path, &args)?;
 
     if output.trim().is_empty() || output.trim() == "[]" {
         return Ok(Vec::new());
@@ -738,7 +1299,7 @@ pub fn list_issues(
 
 pub fn get_issue(repo_path: &Path, number: u64) -> AppResult<Issue> {
     let number_str = number.to_string();
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &["issue", "view", &number_str,
           "--json", "number,title,body,state,author,labels,assignees,milestone,url,createdAt,updatedAt,comments"],
@@ -812,7 +1373,7 @@ pub fn create_issue(
 }
 
 pub fn list_labels(repo_path: &Path) -> AppResult<Vec<IssueLabel>> {
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &["label", "list", "--json", "name,color,description", "--limit", "100"],
     )?;
@@ -858,7 +1419,7 @@ pub fn create_label(
 }
 
 pub fn list_milestones(repo_path: &Path) -> AppResult<Vec<IssueMilestone>> {
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &["api", "repos/{owner}/{repo}/milestones", "--method", "GET",
           "-f", "state=all", "-f", "per_page=100"],
@@ -888,7 +1449,7 @@ pub fn list_milestones(repo_path: &Path) -> AppResult<Vec<IssueMilestone>> {
 }
 
 pub fn list_collaborators(repo_path: &Path) -> AppResult<Vec<Collaborator>> {
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &["api", "repos/{owner}/{repo}/collaborators", "--method", "GET",
           "-f", "per_page=100"],
@@ -987,7 +1548,7 @@ pub fn reopen_issue(repo_path: &Path, number: u64) -> AppResult<()> {
 
 pub fn list_issue_comments(repo_path: &Path, number: u64) -> AppResult<Vec<IssueComment>> {
     let number_str = number.to_string();
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &["issue", "view", &number_str, "--json", "comments"],
     )?;
@@ -1017,7 +1578,7 @@ pub fn add_issue_comment(repo_path: &Path, number: u64, body: &str) -> AppResult
 }
 
 pub fn list_github_projects(repo_path: &Path) -> AppResult<Vec<GitHubProject>> {
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &["project", "list", "--format", "json", "--limit", "30"],
     );
@@ -1243,7 +1804,7 @@ pub fn get_issue_timeline(repo_path: &Path, number: u64) -> AppResult<Vec<serde_
     let number_str = number.to_string();
     let endpoint = format!("repos/{{owner}}/{{repo}}/issues/{}/timeline", number_str);
 
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &["api", &endpoint, "--method", "GET", "-f", "per_page=100"],
     );
@@ -1269,7 +1830,7 @@ pub fn list_issue_reactions(repo_path: &Path, number: u64) -> AppResult<Vec<serd
     let number_str = number.to_string();
     let endpoint = format!("repos/{{owner}}/{{repo}}/issues/{}/reactions", number_str);
 
-    let output = run_gh_command(repo_path, &["api", &endpoint, "--method", "GET"]);
+    let output = run_gh_cached(repo_path, &["api", &endpoint, "--method", "GET"]);
 
     let output = match output {
         Ok(o) => o,
@@ -1294,7 +1855,7 @@ pub fn list_comment_reactions(repo_path: &Path, comment_id: u64) -> AppResult<Ve
     let id_str = comment_id.to_string();
     let endpoint = format!("repos/{{owner}}/{{repo}}/issues/comments/{}/reactions", id_str);
 
-    let output = run_gh_command(repo_path, &["api", &endpoint, "--method", "GET"]);
+    let output = run_gh_cached(repo_path, &["api", &endpoint, "--method", "GET"]);
 
     let output = match output {
         Ok(o) => o,
@@ -1326,7 +1887,7 @@ pub struct IssueTemplate {
 }
 
 pub fn list_issue_templates(repo_path: &Path) -> AppResult<Vec<IssueTemplate>> {
-    let output = run_gh_command(
+    let output = run_gh_cached(
         repo_path,
         &["api", "repos/{owner}/{repo}/contents/.github/ISSUE_TEMPLATE", "--method", "GET"],
     );
@@ -1372,7 +1933,7 @@ pub fn list_issue_templates(repo_path: &Path) -> AppResult<Vec<IssueTemplate>> {
 
 fn fetch_issue_template_content(repo_path: &Path, path: &str) -> AppResult<String> {
     let endpoint = format!("repos/{{owner}}/{{repo}}/contents/{}", path);
-    let output = run_gh_command(repo_path, &["api", &endpoint, "--method", "GET"])?;
+    let output = run_gh_cached(repo_path, &["api", &endpoint, "--method", "GET"])?;
     let item: serde_json::Value = serde_json::from_str(&output).map_err(|e| {
         AppError::with_details("PARSE_ERROR", "Erro ao parsear template de issue", &e.to_string())
     })?;
@@ -1456,3 +2017,250 @@ fn parse_issue_template(filename: &str, content: &str) -> IssueTemplate {
 
     IssueTemplate { name, about, title, body }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PR_DIFF: &str = "diff --git a/src/main.rs b/src/main.rs
+index 1111111..2222222 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,2 +1,2 @@
+ fn main() {
+-    old();
++    new();
+diff --git a/novo.txt b/novo.txt
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/novo.txt
+@@ -0,0 +1 @@
++ola
+diff --git a/velho.txt b/velho.txt
+deleted file mode 100644
+index 4444444..0000000
+--- a/velho.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-tchau
+diff --git a/antes.rs b/depois.rs
+similarity index 100%
+rename from antes.rs
+rename to depois.rs
+diff --git a/logo.png b/logo.png
+index 5555555..6666666 100644
+Binary files a/logo.png and b/logo.png differ
+";
+
+    fn slug(owner: &str, repo: &str, host: &str) -> Option<RepoSlug> {
+        Some(RepoSlug {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            host: host.to_string(),
+        })
+    }
+
+    #[test]
+    fn parse_remote_url_aceita_variantes_comuns() {
+        let expected = slug("octo", "app", "github.com");
+        assert_eq!(parse_remote_url("git@github.com:octo/app.git"), expected);
+        assert_eq!(parse_remote_url("https://github.com/octo/app"), expected);
+        assert_eq!(parse_remote_url("https://github.com/octo/app.git"), expected);
+        assert_eq!(parse_remote_url("https://token@github.com/octo/app/"), expected);
+        assert_eq!(parse_remote_url("ssh://git@github.com:22/octo/app.git"), expected);
+    }
+
+    #[test]
+    fn parse_remote_url_mantem_host_enterprise() {
+        assert_eq!(
+            parse_remote_url("git@ghe.empresa.com:time/servico.git"),
+            slug("time", "servico", "ghe.empresa.com")
+        );
+    }
+
+    #[test]
+    fn parse_remote_url_rejeita_caminhos_sem_owner_repo() {
+        assert_eq!(parse_remote_url("/srv/git/app.git"), None);
+        assert_eq!(parse_remote_url("https://gitlab.com/grupo/sub/app.git"), None);
+    }
+
+    #[test]
+    fn file_web_url_codifica_ref_e_ancora_linha() {
+        let slug = slug("octo", "app", "github.com").unwrap();
+        assert_eq!(
+            file_web_url(&slug, "feature/nova tela", "src/main.rs", Some(42)),
+            "https://github.com/octo/app/blob/feature/nova%20tela/src/main.rs#L42"
+        );
+        assert_eq!(
+            file_web_url(&slug, "main", "README.md", None),
+            "https://github.com/octo/app/blob/main/README.md"
+        );
+    }
+
+    #[test]
+    fn default_branch_from_refs_segue_origin_head_e_depois_nomes_comuns() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Teste", "teste@test.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let oid = repo.commit(None, &sig, &sig, "inicial", &tree, &[]).unwrap();
+
+        assert_eq!(default_branch_from_refs(&repo), None);
+
+        repo.reference("refs/remotes/origin/master", oid, false, "teste").unwrap();
+        assert_eq!(default_branch_from_refs(&repo).as_deref(), Some("master"));
+
+        repo.reference("refs/remotes/origin/develop", oid, false, "teste").unwrap();
+        repo.reference_symbolic("refs/remotes/origin/HEAD", "refs/remotes/origin/develop", false, "teste")
+            .unwrap();
+        assert_eq!(default_branch_from_refs(&repo).as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn parse_review_threads_agrupa_comentarios_por_thread() {
+        let v = serde_json::json!({
+            "data": { "repository": { "pullRequest": { "reviewThreads": { "nodes": [
+                {
+                    "id": "PRRT_1",
+                    "isResolved": true,
+                    "isOutdated": false,
+                    "path": "src/main.rs",
+                    "line": 42,
+                    "originalLine": 40,
+                    "resolvedBy": { "login": "octocat" },
+                    "comments": { "nodes": [
+                        { "id": "C_1", "author": { "login": "ana" }, "body": "Renomear?",
+                          "createdAt": "2026-01-02T03:04:05Z", "url": "https://github.com/o/r/pull/1#c1" },
+                        { "id": "C_2", "author": null, "body": "Feito",
+                          "createdAt": "2026-01-02T04:04:05Z", "url": "https://github.com/o/r/pull/1#c2" }
+                    ] }
+                },
+                {
+                    "id": "PRRT_2",
+                    "isResolved": false,
+                    "isOutdated": true,
+                    "path": "README.md",
+                    "line": null,
+                    "originalLine": 3,
+                    "resolvedBy": null,
+                    "comments": { "nodes": [] }
+                }
+            ] } } } }
+        });
+
+        let threads = parse_review_threads(&v);
+        assert_eq!(threads.len(), 2);
+        assert!(threads[0].is_resolved);
+        assert_eq!(threads[0].line, Some(42));
+        assert_eq!(threads[0].resolved_by.as_deref(), Some("octocat"));
+        assert_eq!(threads[0].comments.len(), 2);
+        assert_eq!(threads[0].comments[1].author, "ghost");
+        assert!(threads[1].is_outdated);
+        assert_eq!((threads[1].line, threads[1].original_line), (None, Some(3)));
+        assert_eq!(threads[1].resolved_by, None);
+    }
+
+    #[test]
+    fn parse_review_threads_sem_threads_retorna_vazio() {
+        let empty = serde_json::json!({
+            "data": { "repository": { "pullRequest": { "reviewThreads": { "nodes": [] } } } }
+        });
+        assert!(parse_review_threads(&empty).is_empty());
+
+        let no_pr = serde_json::json!({ "data": { "repository": { "pullRequest": null } } });
+        assert!(parse_review_threads(&no_pr).is_empty());
+    }
+
+    #[test]
+    fn parse_workflow_run_trata_conclusao_vazia_como_none() {
+        let v = serde_json::json!({
+            "databaseId": 987,
+            "name": "CI",
+            "displayTitle": "feat: x",
+            "status": "in_progress",
+            "conclusion": "",
+            "headBranch": "main",
+            "createdAt": "2026-01-02T03:04:05Z",
+            "url": "https://github.com/octo/app/actions/runs/987"
+        });
+        let run = parse_workflow_run(&v);
+        assert_eq!(run.id, 987);
+        assert_eq!(run.display_title, "feat: x");
+        assert_eq!(run.conclusion, None);
+    }
+
+    #[test]
+    fn erro_sem_workflows_vira_lista_vazia() {
+        let err = AppError::with_details("GH_COMMAND_FAILED", "x", "could not find any workflows named ci");
+        assert!(is_no_workflows_error(&err));
+        let err = AppError::with_details("GH_COMMAND_FAILED", "x", "no runs found");
+        assert!(is_no_workflows_error(&err));
+        let err = AppError::with_details("GH_COMMAND_FAILED", "x", "HTTP 404: Not Found (https://api.github.com/...)");
+        assert!(!is_no_workflows_error(&err));
+        let err = AppError::with_details("GH_COMMAND_FAILED", "x", "HTTP 401: Bad credentials");
+        assert!(!is_no_workflows_error(&err));
+    }
+
+    #[test]
+    fn cache_do_gh_expira_e_e_limpo_por_repo() {
+        let repo_a = PathBuf::from("/tmp/cache-gh-a");
+        let repo_b = PathBuf::from("/tmp/cache-gh-b");
+        let key_a = gh_cache_key(&repo_a, &["pr", "list"]);
+        let key_b = gh_cache_key(&repo_b, &["pr", "list"]);
+        let now = Instant::now();
+        {
+            let mut cache = GH_CACHE.lock().unwrap();
+            cache.insert(key_a.clone(), (now, "[a]".to_string()));
+            cache.insert(key_b.clone(), (now, "[b]".to_string()));
+        }
+
+        assert_eq!(cached_gh_output(&key_a, now).as_deref(), Some("[a]"));
+        assert!(cached_gh_output(&key_a, now + GH_CACHE_TTL).is_none());
+
+        clear_gh_cache(&repo_a);
+        assert!(cached_gh_output(&key_a, now).is_none());
+        assert_eq!(cached_gh_output(&key_b, now).as_deref(), Some("[b]"));
+        clear_gh_cache(&repo_b);
+    }
+
+    #[test]
+    fn split_unified_diff_separa_arquivos_com_status_real() {
+        let files = split_unified_diff(PR_DIFF);
+        let summary: Vec<(&str, &str)> = files.iter().map(|f| (f.path.as_str(), f.status)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("src/main.rs", "modified"),
+                ("novo.txt", "added"),
+                ("velho.txt", "removed"),
+                ("depois.rs", "renamed"),
+                ("logo.png", "modified"),
+            ]
+        );
+        assert_eq!(files[3].old_path.as_deref(), Some("antes.rs"));
+        assert!(files[4].is_binary);
+    }
+
+    #[test]
+    fn diff_estruturado_reaproveita_formato_local() {
+        let diffs: Vec<DiffInfo> = split_unified_diff(PR_DIFF)
+            .into_iter()
+            .map(|f| diff_info_from_patch(f.path, f.old_path, f.status, f.is_binary, &f.patch))
+            .collect();
+
+        assert_eq!((diffs[0].additions, diffs[0].deletions), (1, 1));
+        assert_eq!(diffs[0].hunks[0].lines.len(), 3);
+        assert_eq!(diffs[3].old_path.as_deref(), Some("antes.rs"));
+        assert!(diffs[3].hunks.is_empty());
+        assert!(diffs[4].is_binary);
+    }
+
+    #[test]
+    fn split_unified_diff_patch_contem_apenas_hunks() {
+        let files = split_unified_diff(PR_DIFF);
+        assert_eq!(files[0].patch, "@@ -1,2 +1,2 @@\n fn main() {\n-    old();\n+    new();");
+        assert_eq!(files[2].patch, "@@ -1 +0,0 @@\n-tchau");
+        assert!(files[3].patch.is_empty());
+    }
+}
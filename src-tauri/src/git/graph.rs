@@ -0,0 +1,185 @@
+use crate::error::{AppError, AppResult};
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+
+use super::{commit_to_info, CommitInfo};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GraphEdge {
+    pub parent_hash: String,
+    pub from_lane: usize,
+    pub to_lane: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GraphCommit {
+    pub commit: CommitInfo,
+    pub lane: usize,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// Returns commits with lane assignments and parent edges for drawing the
+/// history graph. Lanes are always computed from the tip, so a page fetched
+/// with `skip` lines up with the pages before it.
+pub fn get_commit_graph(
+    repo: &Repository,
+    branch: Option<&str>,
+    limit: usize,
+    skip: usize,
+) -> AppResult<Vec<GraphCommit>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TIME | git2::Sort::TOPOLOGICAL)?;
+
+    if let Some(branch_name) = branch {
+        let oid = repo
+            .refname_to_id(&format!("refs/heads/{}", branch_name))
+            .or_else(|_| repo.refname_to_id(&format!("refs/remotes/origin/{}", branch_name)))
+            .map_err(|_| AppError::branch_not_found(branch_name))?;
+        revwalk.push(oid)?;
+    } else {
+        revwalk.push_head()?;
+    }
+
+    // Each slot holds the commit the lane is waiting for
+    let mut lanes: Vec<Option<Oid>> = Vec::new();
+    let mut result = Vec::new();
+
+    for (position, oid) in revwalk.enumerate() {
+        if position >= skip + limit {
+            break;
+        }
+
+        let oid = oid?;
+        let commit = repo.find_commit(oid)?;
+
+        let lane = match lanes.iter().position(|l| *l == Some(oid)) {
+            Some(lane) => lane,
+            None => allocate_lane(&mut lanes),
+        };
+        lanes[lane] = None;
+
+        let mut edges = Vec::new();
+        for (i, parent_id) in commit.parent_ids().enumerate() {
+            let to_lane = match lanes.iter().position(|l| *l == Some(parent_id)) {
+                // Another lane already leads to this parent: merge into it
+                Some(existing) => existing,
+                None if i == 0 => lane,
+                None => allocate_lane(&mut lanes),
+            };
+            lanes[to_lane] = Some(parent_id);
+
+            edges.push(GraphEdge {
+                parent_hash: parent_id.to_string(),
+                from_lane: lane,
+                to_lane,
+            });
+        }
+
+        while lanes.last() == Some(&None) {
+            lanes.pop();
+        }
+
+        if position >= skip {
+            result.push(GraphCommit {
+                commit: commit_to_info(&commit),
+                lane,
+                edges,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+fn allocate_lane(lanes: &mut Vec<Option<Oid>>) -> usize {
+    match lanes.iter().position(|l| l.is_none()) {
+        Some(free) => free,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn setup_repo() -> (TempDir, Repository) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    fn commit_on(repo: &Repository, dir: &Path, file: &str, msg: &str, parents: &[Oid]) -> Oid {
+        std::fs::write(dir.join(file), msg).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let parents: Vec<git2::Commit> = parents.iter().map(|p| repo.find_commit(*p).unwrap()).collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(None, &sig, &sig, msg, &tree, &parent_refs).unwrap()
+    }
+
+    #[test]
+    fn get_commit_graph_historico_linear_usa_uma_lane() {
+        let (dir, repo) = setup_repo();
+        let a = commit_on(&repo, dir.path(), "a.txt", "A", &[]);
+        let b = commit_on(&repo, dir.path(), "b.txt", "B", &[a]);
+        repo.reference("refs/heads/master", b, true, "teste").unwrap();
+        repo.set_head("refs/heads/master").unwrap();
+
+        let graph = get_commit_graph(&repo, None, 10, 0).unwrap();
+        assert_eq!(graph.len(), 2);
+        assert!(graph.iter().all(|c| c.lane == 0));
+        assert_eq!(graph[0].edges.len(), 1);
+        assert!(graph[1].edges.is_empty());
+    }
+
+    #[test]
+    fn get_commit_graph_merge_gera_arestas_em_lanes_distintas() {
+        let (dir, repo) = setup_repo();
+        let base = commit_on(&repo, dir.path(), "base.txt", "base", &[]);
+        let main = commit_on(&repo, dir.path(), "main.txt", "main", &[base]);
+        let feature = commit_on(&repo, dir.path(), "feature.txt", "feature", &[base]);
+        let merge = commit_on(&repo, dir.path(), "merge.txt", "merge", &[main, feature]);
+        repo.reference("refs/heads/master", merge, true, "teste").unwrap();
+        repo.set_head("refs/heads/master").unwrap();
+
+        let graph = get_commit_graph(&repo, None, 10, 0).unwrap();
+        let merge_node = &graph[0];
+        assert_eq!(merge_node.edges.len(), 2);
+        assert_ne!(merge_node.edges[0].to_lane, merge_node.edges[1].to_lane);
+
+        let feature_node = graph.iter().find(|c| c.commit.hash == feature.to_string()).unwrap();
+        assert_eq!(feature_node.lane, merge_node.edges[1].to_lane);
+
+        // Both branches converge back on the base commit
+        let base_node = graph.iter().find(|c| c.commit.hash == base.to_string()).unwrap();
+        assert_eq!(base_node.lane, 0);
+    }
+
+    #[test]
+    fn get_commit_graph_paginas_mantem_lanes() {
+        let (dir, repo) = setup_repo();
+        let base = commit_on(&repo, dir.path(), "base.txt", "base", &[]);
+        let main = commit_on(&repo, dir.path(), "main.txt", "main", &[base]);
+        let feature = commit_on(&repo, dir.path(), "feature.txt", "feature", &[base]);
+        let merge = commit_on(&repo, dir.path(), "merge.txt", "merge", &[main, feature]);
+        repo.reference("refs/heads/master", merge, true, "teste").unwrap();
+        repo.set_head("refs/heads/master").unwrap();
+
+        let full = get_commit_graph(&repo, None, 10, 0).unwrap();
+        let page = get_commit_graph(&repo, None, 2, 2).unwrap();
+        assert_eq!(page.len(), 2);
+        for (a, b) in full[2..].iter().zip(page.iter()) {
+            assert_eq!(a.commit.hash, b.commit.hash);
+            assert_eq!(a.lane, b.lane);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::error::{AppError, AppResult};
+use git2::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Hooks directory, honoring `core.hooksPath` (relative paths resolve against the workdir)
+fn hooks_dir(repo: &Repository) -> PathBuf {
+    let configured = repo
+        .config()
+        .ok()
+        .and_then(|config| config.get_path("core.hooksPath").ok());
+
+    match configured {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repo.workdir().unwrap_or_else(|| repo.path()).join(path),
+        None => repo.path().join("hooks"),
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(unix)]
+fn hook_command(hook: &Path) -> Command {
+    Command::new(hook)
+}
+
+/// Hooks are shell scripts Windows can't run by itself; use the `sh` that
+/// ships with Git for Windows, like git does
+#[cfg(not(unix))]
+fn hook_command(hook: &Path) -> Command {
+    let mut cmd = Command::new(windows_sh());
+    cmd.arg(hook);
+    cmd
+}
+
+/// `<git root>/bin/sh.exe`, found from `git --exec-path`
+/// (`<git root>/mingw64/libexec/git-core`); falls back to `sh` on PATH
+#[cfg(not(unix))]
+fn windows_sh() -> PathBuf {
+    let mut cmd = Command::new("git");
+    cmd.arg("--exec-path");
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    cmd.output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            let exec_path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+            exec_path.ancestors().nth(3).map(|root| root.join("bin").join("sh.exe"))
+        })
+        .filter(|sh| sh.is_file())
+        .unwrap_or_else(|| PathBuf::from("sh"))
+}
+
+/// Runs a hook from the repo's hooks directory; a missing or non-executable
+/// hook is skipped. Non-zero exit fails with HOOK_FAILED and the hook output.
+pub fn run_hook(repo: &Repository, name: &str, args: &[&str]) -> AppResult<()> {
+    let hook = hooks_dir(repo).join(name);
+    if !is_executable(&hook) {
+        return Ok(());
+    }
+
+    let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+    let mut cmd = hook_command(&hook);
+    cmd.args(args)
+        .current_dir(workdir)
+        .env("GIT_DIR", repo.path())
+        .env("GIT_INDEX_FILE", repo.path().join("index"));
+
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = cmd.output().map_err(|e| {
+        AppError::with_details(
+            "HOOK_FAILED",
+            &format!("Falha ao executar o hook {}", name),
+            &e.to_string(),
+        )
+    })?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let details = [stdout.trim(), stderr.trim()]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Err(AppError::with_details(
+        "HOOK_FAILED",
+        &format!("Hook {} bloqueou o commit", name),
+        &details,
+    ))
+}
+
+/// Runs `pre-commit` and `commit-msg` and returns the (possibly rewritten) message
+pub fn run_commit_hooks(repo: &Repository, message: &str) -> AppResult<String> {
+    run_hook(repo, "pre-commit", &[])?;
+
+    let message_file = repo.path().join("COMMIT_EDITMSG");
+    fs::write(&message_file, message)?;
+    let message_arg = message_file.to_string_lossy().to_string();
+    run_hook(repo, "commit-msg", &[&message_arg])?;
+
+    Ok(fs::read_to_string(&message_file)?)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn setup_repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    fn write_hook(repo: &Repository, name: &str, script: &str) {
+        let path = repo.path().join("hooks").join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn run_commit_hooks_sem_hooks_mantem_mensagem() {
+        let (_dir, repo) = setup_repo();
+        assert_eq!(run_commit_hooks(&repo, "feat: x").unwrap(), "feat: x");
+    }
+
+    #[test]
+    fn pre_commit_com_falha_retorna_saida_do_hook() {
+        let (_dir, repo) = setup_repo();
+        write_hook(&repo, "pre-commit", "#!/bin/sh\necho 'lint falhou' >&2\nexit 1\n");
+
+        let err = run_commit_hooks(&repo, "feat: x").unwrap_err();
+        assert_eq!(err.code, "HOOK_FAILED");
+        assert_eq!(err.details.as_deref(), Some("lint falhou"));
+    }
+
+    #[test]
+    fn commit_msg_pode_reescrever_mensagem() {
+        let (_dir, repo) = setup_repo();
+        write_hook(&repo, "commit-msg", "#!/bin/sh\necho 'Refs: #1' >> \"$1\"\n");
+
+        let message = run_commit_hooks(&repo, "feat: x\n").unwrap();
+        assert_eq!(message, "feat: x\nRefs: #1\n");
+    }
+}
@@ -0,0 +1,127 @@
+use crate::error::{AppError, AppResult};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+const LFS_SPEC_PREFIX: &str = "version https://git-lfs.github.com/spec/";
+/// Pointer files are tiny; anything bigger is real content
+const MAX_POINTER_SIZE: usize = 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LfsPointer {
+    pub oid: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LfsFile {
+    pub path: String,
+    pub oid: String,
+    /// False when only the pointer is checked out
+    pub downloaded: bool,
+}
+
+/// Parses the text of a Git LFS pointer file
+pub fn parse_lfs_pointer(content: &str) -> Option<LfsPointer> {
+    if content.len() > MAX_POINTER_SIZE || !content.starts_with(LFS_SPEC_PREFIX) {
+        return None;
+    }
+
+    let mut oid = None;
+    let mut size = None;
+    for line in content.lines().skip(1) {
+        if let Some(value) = line.strip_prefix("oid ") {
+            oid = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("size ") {
+            size = value.trim().parse::<u64>().ok();
+        }
+    }
+
+    Some(LfsPointer {
+        oid: oid?,
+        size: size?,
+    })
+}
+
+/// Whether `.gitattributes` routes the path through the LFS filter
+pub fn is_lfs_tracked(repo: &Repository, path: &str) -> bool {
+    repo.get_attr(Path::new(path), "filter", git2::AttrCheckFlags::default())
+        .ok()
+        .flatten()
+        == Some("lfs")
+}
+
+/// Lists LFS-tracked files via `git lfs ls-files`; nothing is downloaded
+pub fn list_lfs_files(repo_path: &Path) -> AppResult<Vec<LfsFile>> {
+    let mut cmd = Command::new("git");
+    cmd.args(["lfs", "ls-files", "--long"]).current_dir(repo_path);
+
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = cmd
+        .output()
+        .map_err(|e| AppError::with_details("GIT_COMMAND_FAILED", "Falha ao executar git", &e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::with_details(
+            "LFS_NOT_AVAILABLE",
+            "Git LFS não está disponível",
+            stderr.trim(),
+        ));
+    }
+
+    Ok(parse_ls_files(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parses `<oid> <*|-> <path>` lines from `git lfs ls-files --long`
+fn parse_ls_files(output: &str) -> Vec<LfsFile> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (oid, rest) = line.split_once(' ')?;
+            let (marker, path) = rest.split_once(' ')?;
+            Some(LfsFile {
+                path: path.to_string(),
+                oid: oid.to_string(),
+                downloaded: marker == "*",
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lfs_pointer_extrai_oid_e_tamanho() {
+        let pointer = "version https://git-lfs.github.com/spec/v1\noid sha256:abc123\nsize 2048\n";
+        assert_eq!(
+            parse_lfs_pointer(pointer),
+            Some(LfsPointer {
+                oid: "sha256:abc123".to_string(),
+                size: 2048,
+            })
+        );
+        assert_eq!(parse_lfs_pointer("fn main() {}\n"), None);
+        assert_eq!(parse_lfs_pointer("version https://git-lfs.github.com/spec/v1\n"), None);
+    }
+
+    #[test]
+    fn parse_ls_files_le_marcador_de_download() {
+        let files = parse_ls_files("aaa * assets/logo.png\nbbb - video com espaco.mp4\n");
+        assert_eq!(files.len(), 2);
+        assert!(files[0].downloaded);
+        assert_eq!(files[1].path, "video com espaco.mp4");
+        assert!(!files[1].downloaded);
+    }
+}
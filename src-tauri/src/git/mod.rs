@@ -1,27 +1,45 @@
 pub mod repository;
 pub mod branch;
+pub mod cancel;
 pub mod commit;
+pub mod commit_message;
 pub mod compare;
 pub mod conflict;
+pub mod credentials;
 pub mod diff;
+pub mod graph;
+pub mod hooks;
+pub mod lfs;
 pub mod rebase;
+pub mod reflog;
 pub mod remote;
 pub mod stash;
+pub mod stats;
 pub mod status;
+pub mod tree;
 pub mod github;
 pub mod worktree;
 pub mod submodule;
 
 pub use repository::*;
 pub use branch::*;
+pub use cancel::*;
 pub use commit::*;
+pub use commit_message::*;
 pub use compare::*;
 pub use conflict::*;
+pub use credentials::*;
 pub use diff::*;
+pub use graph::*;
+pub use hooks::*;
+pub use lfs::*;
 pub use rebase::*;
+pub use reflog::*;
 pub use remote::*;
 pub use stash::*;
+pub use stats::*;
 pub use status::*;
+pub use tree::*;
 pub use github::*;
 pub use worktree::*;
 pub use submodule::*;
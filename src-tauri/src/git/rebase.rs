@@ -1,8 +1,24 @@
-use git2::{Oid, Repository};
+use git2::{Oid, Repository, RepositoryState};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
-use crate::error::{AppError, AppResult};
+use crate::error::{AppError, AppResult, ErrorKind};
 use crate::git::commit::{commit_to_info, CommitInfo};
+use crate::git::conflicted_paths;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+const REBASE_STATES: [RepositoryState; 4] = [
+    RepositoryState::Rebase,
+    RepositoryState::RebaseInteractive,
+    RepositoryState::RebaseMerge,
+    RepositoryState::ApplyMailboxOrRebase,
+];
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -149,6 +165,335 @@ pub fn perform_interactive_rebase(
     Ok(())
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RewrittenCommit {
+    pub old_hash: String,
+    pub new_hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DropCommitResult {
+    pub new_head: String,
+    /// Old -> new hash for every commit replayed on top of the dropped one.
+    pub rewritten: Vec<RewrittenCommit>,
+}
+
+/// Removes a single commit from the current branch by replaying its
+/// descendants onto its parent in-memory. On conflict the replay is redone
+/// as an on-disk rebase that stops at the conflicting step, so it can be
+/// resolved and driven with `rebase_continue`/`rebase_abort`.
+pub fn drop_commit(repo: &Repository, commit_hash: &str) -> AppResult<DropCommitResult> {
+    if repo.state() != git2::RepositoryState::Clean {
+        return Err(AppError::internal(
+            "O repositório tem operações pendentes (merge, rebase, cherry-pick, etc.)",
+        ));
+    }
+    if !crate::git::is_working_tree_clean(repo)?.clean {
+        return Err(AppError::new(
+            "UNCOMMITTED_CHANGES",
+            "Existem alterações não commitadas. Faça commit ou stash antes de remover o commit.",
+        )
+        .with_kind(ErrorKind::Validation));
+    }
+
+    let head = repo.head()?;
+    let branch_ref = head
+        .name()
+        .ok_or_else(|| AppError::internal("HEAD está em modo detached"))?
+        .to_string();
+    let head_oid = head.peel_to_commit()?.id();
+
+    let oid = Oid::from_str(commit_hash).map_err(|_| AppError::commit_not_found(commit_hash))?;
+    let target = repo
+        .find_commit(oid)
+        .map_err(|_| AppError::commit_not_found(commit_hash))?;
+
+    if target.parent_count() > 1 {
+        return Err(AppError::with_details(
+            "DROP_MERGE_COMMIT",
+            "Nao e possivel remover um merge commit",
+            commit_hash,
+        ));
+    }
+    if target.parent_count() == 0 {
+        return Err(AppError::with_details(
+            "DROP_ROOT_COMMIT",
+            "Nao e possivel remover o commit inicial",
+            commit_hash,
+        ));
+    }
+    if head_oid != target.id() && !repo.graph_descendant_of(head_oid, target.id())? {
+        return Err(AppError::with_details(
+            "COMMIT_NOT_ANCESTOR",
+            "Commit nao e ancestral do HEAD",
+            commit_hash,
+        ));
+    }
+
+    let sig = repo
+        .signature()
+        .map_err(|_| AppError::git_user_not_configured())?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+    revwalk.push(head_oid)?;
+    revwalk.hide(target.id())?;
+    let descendants: Vec<Oid> = revwalk.collect::<Result<_, _>>()?;
+
+    let mut current = target.parent(0)?;
+    let mut rewritten = Vec::new();
+
+    for oid in descendants {
+        let commit = repo.find_commit(oid)?;
+        if commit.parent_count() > 1 {
+            return Err(AppError::with_details(
+                "DROP_MERGE_COMMIT",
+                "Existe um merge commit apos o commit a ser removido",
+                &oid.to_string(),
+            ));
+        }
+
+        let mut index = repo.cherrypick_commit(&commit, &current, 0, None)?;
+        if index.has_conflicts() {
+            return start_drop_rebase(repo, &branch_ref, &target, &sig);
+        }
+
+        let tree_oid = index.write_tree_to(repo)?;
+        let tree = repo.find_tree(tree_oid)?;
+        let new_oid = repo.commit(
+            None,
+            &commit.author(),
+            &sig,
+            commit.message().unwrap_or(""),
+            &tree,
+            &[&current],
+        )?;
+
+        rewritten.push(RewrittenCommit {
+            old_hash: oid.to_string(),
+            new_hash: new_oid.to_string(),
+        });
+        current = repo.find_commit(new_oid)?;
+    }
+
+    // Safe checkout first, so the branch only moves once the worktree does
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.safe();
+    repo.checkout_tree(current.as_object(), Some(&mut checkout))?;
+    repo.find_reference(&branch_ref)?
+        .set_target(current.id(), &format!("drop commit {}", commit_hash))?;
+
+    Ok(DropCommitResult {
+        new_head: current.id().to_string(),
+        rewritten,
+    })
+}
+
+/// Rebases the descendants of `target` onto its parent on disk, leaving the
+/// rebase in progress at the first conflicting step
+fn start_drop_rebase(
+    repo: &Repository,
+    branch_ref: &str,
+    target: &git2::Commit,
+    sig: &git2::Signature,
+) -> AppResult<DropCommitResult> {
+    let branch = repo.reference_to_annotated_commit(&repo.find_reference(branch_ref)?)?;
+    let upstream = repo.find_annotated_commit(target.id())?;
+    let onto = repo.find_annotated_commit(target.parent_id(0)?)?;
+    let mut rebase = repo.rebase(Some(&branch), Some(&upstream), Some(&onto), None)?;
+
+    let mut rewritten = Vec::new();
+    while let Some(operation) = rebase.next() {
+        let old_hash = operation?.id().to_string();
+        let mut index = repo.index()?;
+        index.read(false)?;
+        if index.has_conflicts() {
+            return Err(AppError::with_details(
+                "REBASE_CONFLICT",
+                "Conflito ao reaplicar commit. Resolva os conflitos e continue o rebase.",
+                &old_hash,
+            )
+            .with_kind(ErrorKind::Conflict));
+        }
+        match rebase.commit(None, sig, None) {
+            Ok(new_oid) => rewritten.push(RewrittenCommit {
+                old_hash,
+                new_hash: new_oid.to_string(),
+            }),
+            Err(e) if e.code() == git2::ErrorCode::Applied => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    rebase.finish(Some(sig))?;
+
+    Ok(DropCommitResult {
+        new_head: repo.head()?.peel_to_commit()?.id().to_string(),
+        rewritten,
+    })
+}
+
+/// A rebase in progress, whether started here or in a terminal
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RebaseStatus {
+    pub in_progress: bool,
+    /// 1-based step being applied; 0 when idle
+    pub current_step: usize,
+    pub total_steps: usize,
+    /// Branch being rebased, without `refs/heads/`
+    pub head_name: Option<String>,
+    pub onto: Option<String>,
+    pub conflicted_files: Vec<String>,
+}
+
+fn not_rebasing() -> AppError {
+    AppError::new("NOT_REBASING", "Não há rebase em andamento").with_kind(ErrorKind::Validation)
+}
+
+/// The state dir: `rebase-merge` (libgit2, `git rebase`) or `rebase-apply` (old apply backend)
+fn rebase_dir(repo: &Repository) -> Option<PathBuf> {
+    ["rebase-merge", "rebase-apply"]
+        .iter()
+        .map(|name| repo.path().join(name))
+        .find(|dir| dir.is_dir())
+}
+
+fn read_rebase_file(dir: &Path, name: &str) -> Option<String> {
+    std::fs::read_to_string(dir.join(name))
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+/// Reads the on-disk rebase state, so rebases started by the git CLI are seen too
+pub fn get_rebase_status(repo: &Repository) -> AppResult<RebaseStatus> {
+    if !REBASE_STATES.contains(&repo.state()) {
+        return Ok(RebaseStatus::default());
+    }
+    let Some(dir) = rebase_dir(repo) else {
+        return Ok(RebaseStatus::default());
+    };
+
+    // rebase-merge counts with msgnum/end, rebase-apply with next/last
+    let step = |merge_name: &str, apply_name: &str| {
+        read_rebase_file(&dir, merge_name)
+            .or_else(|| read_rebase_file(&dir, apply_name))
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(0)
+    };
+
+    // The git CLI may have rewritten the index behind libgit2's cached copy
+    let mut index = repo.index()?;
+    index.read(false)?;
+
+    Ok(RebaseStatus {
+        in_progress: true,
+        current_step: step("msgnum", "next"),
+        total_steps: step("end", "last"),
+        head_name: read_rebase_file(&dir, "head-name")
+            .map(|name| name.trim_start_matches("refs/heads/").to_string()),
+        onto: read_rebase_file(&dir, "onto"),
+        conflicted_files: conflicted_paths(&index)?,
+    })
+}
+
+/// Runs `git rebase <flag>`; the CLI's sequencer state can't be opened by libgit2
+fn run_git_rebase(repo: &Repository, flag: &str) -> AppResult<std::process::Output> {
+    let workdir = repo.workdir().ok_or_else(|| AppError::internal("Bare repository"))?;
+    let mut cmd = Command::new("git");
+    cmd.args(["rebase", flag])
+        .current_dir(workdir)
+        // Keep the recorded message instead of waiting on an editor
+        .env("GIT_EDITOR", "true");
+
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    cmd.output()
+        .map_err(|e| AppError::with_details("REBASE_ERROR", "Falha ao executar git rebase", &e.to_string()))
+}
+
+/// Commits the current step; a step whose changes are already upstream is skipped
+fn commit_rebase_step(rebase: &mut git2::Rebase, sig: &git2::Signature) -> AppResult<()> {
+    match rebase.commit(None, sig, None) {
+        Err(e) if e.code() != git2::ErrorCode::Applied => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Commits the resolved step and applies the remaining ones, stopping at
+/// the next conflict. Returns the status afterwards (idle once finished).
+pub fn rebase_continue(repo: &Repository) -> AppResult<RebaseStatus> {
+    if !get_rebase_status(repo)?.in_progress {
+        return Err(not_rebasing());
+    }
+
+    let mut index = repo.index()?;
+    index.read(false)?;
+    if index.has_conflicts() {
+        return Err(AppError::with_details(
+            "UNRESOLVED_CONFLICTS",
+            "Ainda há conflitos não resolvidos",
+            &conflicted_paths(&index)?.join("\n"),
+        ));
+    }
+
+    match repo.open_rebase(None) {
+        Ok(mut rebase) => {
+            let sig = repo
+                .signature()
+                .map_err(|_| AppError::git_user_not_configured())?;
+            commit_rebase_step(&mut rebase, &sig)?;
+            while let Some(operation) = rebase.next() {
+                operation?;
+                let mut index = repo.index()?;
+                index.read(false)?;
+                if index.has_conflicts() {
+                    return get_rebase_status(repo);
+                }
+                commit_rebase_step(&mut rebase, &sig)?;
+            }
+            rebase.finish(Some(&sig))?;
+        }
+        Err(_) => {
+            let output = run_git_rebase(repo, "--continue")?;
+            let status = get_rebase_status(repo)?;
+            // Stopping at the next conflict is a normal outcome, not a failure
+            if !output.status.success() && status.conflicted_files.is_empty() {
+                return Err(AppError::with_details(
+                    "REBASE_ERROR",
+                    "Falha ao continuar o rebase",
+                    String::from_utf8_lossy(&output.stderr).trim(),
+                ));
+            }
+            return Ok(status);
+        }
+    }
+
+    get_rebase_status(repo)
+}
+
+/// Abandons the rebase and restores the branch as it was before it started
+pub fn rebase_abort(repo: &Repository) -> AppResult<()> {
+    if !get_rebase_status(repo)?.in_progress {
+        return Err(not_rebasing());
+    }
+
+    match repo.open_rebase(None) {
+        Ok(mut rebase) => rebase.abort()?,
+        Err(_) => {
+            let output = run_git_rebase(repo, "--abort")?;
+            if !output.status.success() {
+                return Err(AppError::with_details(
+                    "REBASE_ERROR",
+                    "Falha ao abortar o rebase",
+                    String::from_utf8_lossy(&output.stderr).trim(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,4 +655,185 @@ mod tests {
         let result = perform_interactive_rebase(&repo, &hash_base, &entries);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn drop_commit_remove_commit_do_meio() {
+        let (dir, repo) = setup_repo();
+        let hash_a = make_commit(&repo, dir.path(), "a.txt", "a", "commit A");
+        let hash_b = make_commit(&repo, dir.path(), "b.txt", "b", "commit B");
+        let hash_c = make_commit(&repo, dir.path(), "c.txt", "c", "commit C");
+
+        let result = drop_commit(&repo, &hash_b).unwrap();
+
+        assert_eq!(result.rewritten.len(), 1);
+        assert_eq!(result.rewritten[0].old_hash, hash_c);
+        assert_eq!(result.rewritten[0].new_hash, result.new_head);
+
+        let commits = crate::git::list_commits(&repo, None, 10, 0).unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].summary, "commit C");
+        assert_eq!(commits[1].hash, hash_a);
+
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        assert!(tree.get_path(Path::new("b.txt")).is_err());
+        assert!(tree.get_path(Path::new("c.txt")).is_ok());
+        assert!(!dir.path().join("b.txt").exists());
+    }
+
+    #[test]
+    fn drop_commit_com_conflito_deixa_rebase_em_andamento() {
+        let (dir, repo) = setup_repo();
+        let hash_a = make_commit(&repo, dir.path(), "a.txt", "1", "commit A");
+        let hash_b = make_commit(&repo, dir.path(), "a.txt", "2", "commit B");
+        let hash_c = make_commit(&repo, dir.path(), "a.txt", "3", "commit C");
+
+        let err = drop_commit(&repo, &hash_b).unwrap_err();
+        assert_eq!(err.code, "REBASE_CONFLICT");
+
+        let status = get_rebase_status(&repo).unwrap();
+        assert!(status.in_progress);
+        assert_eq!(status.onto.as_deref(), Some(hash_a.as_str()));
+        assert_eq!(status.conflicted_files, vec!["a.txt"]);
+
+        std::fs::write(dir.path().join("a.txt"), "3").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        assert!(!rebase_continue(&repo).unwrap().in_progress);
+
+        let head = repo.head().unwrap();
+        assert!(head.name().unwrap().starts_with("refs/heads/"));
+        let head = head.peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("commit C"));
+        assert_eq!(head.parent_id(0).unwrap().to_string(), hash_a);
+        assert_ne!(head.id().to_string(), hash_c);
+    }
+
+    #[test]
+    fn drop_commit_com_conflito_pode_ser_abortado() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "1", "commit A");
+        let hash_b = make_commit(&repo, dir.path(), "a.txt", "2", "commit B");
+        let hash_c = make_commit(&repo, dir.path(), "a.txt", "3", "commit C");
+
+        drop_commit(&repo, &hash_b).unwrap_err();
+        rebase_abort(&repo).unwrap();
+
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert_eq!(repo.head().unwrap().target().unwrap().to_string(), hash_c);
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "3");
+    }
+
+    #[test]
+    fn drop_commit_recusa_alteracoes_nao_commitadas() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "a", "commit A");
+        let hash_b = make_commit(&repo, dir.path(), "b.txt", "b", "commit B");
+        let hash_c = make_commit(&repo, dir.path(), "c.txt", "c", "commit C");
+        std::fs::write(dir.path().join("c.txt"), "trabalho local").unwrap();
+
+        let err = drop_commit(&repo, &hash_b).unwrap_err();
+        assert_eq!(err.code, "UNCOMMITTED_CHANGES");
+        assert_eq!(repo.head().unwrap().target().unwrap().to_string(), hash_c);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("c.txt")).unwrap(),
+            "trabalho local"
+        );
+    }
+
+    /// Leaves `feature` mid-rebase onto the default branch, conflicted on a.txt
+    fn start_conflicting_rebase(repo: &Repository, dir: &Path) -> (String, String) {
+        make_commit(repo, dir, "a.txt", "base", "base");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        let main_ref = repo.head().unwrap().name().unwrap().to_string();
+        repo.branch("feature", &base, false).unwrap();
+        let main_tip = make_commit(repo, dir, "a.txt", "main", "main");
+
+        repo.set_head("refs/heads/feature").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        let feature_tip = make_commit(repo, dir, "a.txt", "feature", "feature");
+
+        let upstream = repo.reference_to_annotated_commit(&repo.find_reference(&main_ref).unwrap()).unwrap();
+        let mut rebase = repo.rebase(None, Some(&upstream), None, None).unwrap();
+        rebase.next().unwrap().unwrap();
+        assert!(repo.index().unwrap().has_conflicts());
+        (main_tip, feature_tip)
+    }
+
+    #[test]
+    fn get_rebase_status_sem_rebase_retorna_ocioso() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "a", "A");
+        let status = get_rebase_status(&repo).unwrap();
+        assert!(!status.in_progress);
+        assert_eq!(status.total_steps, 0);
+        assert_eq!(rebase_abort(&repo).unwrap_err().code, "NOT_REBASING");
+    }
+
+    #[test]
+    fn rebase_continue_conclui_rebase_iniciado_fora_do_app() {
+        let (dir, repo) = setup_repo();
+        let (main_tip, _) = start_conflicting_rebase(&repo, dir.path());
+
+        let status = get_rebase_status(&repo).unwrap();
+        assert!(status.in_progress);
+        assert_eq!((status.current_step, status.total_steps), (1, 1));
+        assert_eq!(status.head_name.as_deref(), Some("feature"));
+        assert_eq!(status.onto.as_deref(), Some(main_tip.as_str()));
+        assert_eq!(status.conflicted_files, vec!["a.txt"]);
+
+        assert_eq!(rebase_continue(&repo).unwrap_err().code, "UNRESOLVED_CONFLICTS");
+
+        std::fs::write(dir.path().join("a.txt"), "resolvido").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+
+        let status = rebase_continue(&repo).unwrap();
+        assert!(!status.in_progress);
+        let head = repo.head().unwrap();
+        assert_eq!(head.name(), Some("refs/heads/feature"));
+        let head = head.peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("feature"));
+        assert_eq!(head.parent_id(0).unwrap().to_string(), main_tip);
+    }
+
+    #[test]
+    fn rebase_abort_funciona_com_rebase_do_git_cli() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "base", "base");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        let main_branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.branch("feature", &base, false).unwrap();
+        make_commit(&repo, dir.path(), "a.txt", "main", "main");
+        repo.set_head("refs/heads/feature").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        let feature_tip = make_commit(&repo, dir.path(), "a.txt", "feature", "feature");
+
+        let output = Command::new("git")
+            .args(["rebase", &main_branch])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+
+        let status = get_rebase_status(&repo).unwrap();
+        assert!(status.in_progress);
+        assert_eq!(status.conflicted_files, vec!["a.txt"]);
+
+        rebase_abort(&repo).unwrap();
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert_eq!(repo.head().unwrap().target().unwrap().to_string(), feature_tip);
+    }
+
+    #[test]
+    fn rebase_abort_restaura_branch_original() {
+        let (dir, repo) = setup_repo();
+        let (_, feature_tip) = start_conflicting_rebase(&repo, dir.path());
+
+        rebase_abort(&repo).unwrap();
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert_eq!(repo.head().unwrap().target().unwrap().to_string(), feature_tip);
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "feature");
+    }
 }
@@ -0,0 +1,124 @@
+use crate::error::AppResult;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReflogEntry {
+    pub index: usize,
+    pub old_hash: String,
+    pub new_hash: String,
+    pub short_hash: String,
+    pub committer_name: String,
+    pub committer_email: String,
+    pub time: i64,
+    pub message: String,
+}
+
+/// Reads the reflog of `reference` (HEAD by default), newest entry first.
+/// Short names resolve like git does (`main`, `origin/main`, `stash`, tags);
+/// a ref without a reflog yields an empty list.
+pub fn get_reflog(repo: &Repository, reference: Option<&str>, limit: usize) -> AppResult<Vec<ReflogEntry>> {
+    let name = match reference {
+        None | Some("") => "HEAD".to_string(),
+        Some(name) => repo
+            .resolve_reference_from_short_name(name)
+            .ok()
+            .and_then(|r| r.name().map(String::from))
+            .unwrap_or_else(|| format!("refs/heads/{}", name)),
+    };
+
+    let reflog = repo.reflog(&name)?;
+
+    let entries = reflog
+        .iter()
+        .take(limit)
+        .enumerate()
+        .map(|(index, entry)| {
+            let committer = entry.committer();
+            let new_hash = entry.id_new().to_string();
+            ReflogEntry {
+                index,
+                old_hash: entry.id_old().to_string(),
+                short_hash: new_hash[..7.min(new_hash.len())].to_string(),
+                new_hash,
+                committer_name: committer.name().unwrap_or("").to_string(),
+                committer_email: committer.email().unwrap_or("").to_string(),
+                time: committer.when().seconds(),
+                message: entry.message().unwrap_or("").to_string(),
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn setup_repo() -> (TempDir, Repository) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    fn make_commit(repo: &Repository, dir: &Path, filename: &str, msg: &str) -> git2::Oid {
+        std::fs::write(dir.join(filename), msg).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(filename)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents).unwrap()
+    }
+
+    #[test]
+    fn get_reflog_lista_entradas_mais_recentes_primeiro() {
+        let (dir, repo) = setup_repo();
+        let first = make_commit(&repo, dir.path(), "a.txt", "primeiro");
+        let second = make_commit(&repo, dir.path(), "b.txt", "segundo");
+
+        let entries = get_reflog(&repo, None, 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].new_hash, second.to_string());
+        assert_eq!(entries[0].old_hash, first.to_string());
+        assert_eq!(entries[0].committer_name, "Teste");
+        assert!(entries[0].message.contains("segundo"));
+
+        let limited = get_reflog(&repo, None, 1).unwrap();
+        assert_eq!(limited.len(), 1);
+    }
+
+    #[test]
+    fn get_reflog_resolve_nome_curto_de_remote_e_stash() {
+        let (dir, mut repo) = setup_repo();
+        let head = make_commit(&repo, dir.path(), "a.txt", "primeiro");
+        repo.reference("refs/remotes/origin/main", head, true, "fetch: teste").unwrap();
+
+        let remote = get_reflog(&repo, Some("origin/main"), 10).unwrap();
+        assert_eq!(remote.len(), 1);
+        assert_eq!(remote[0].message, "fetch: teste");
+
+        std::fs::write(dir.path().join("a.txt"), "alterado").unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let stash = repo.stash_save(&sig, "guardado", None).unwrap();
+
+        let stashes = get_reflog(&repo, Some("stash"), 10).unwrap();
+        assert_eq!(stashes.len(), 1);
+        assert_eq!(stashes[0].new_hash, stash.to_string());
+    }
+
+    #[test]
+    fn get_reflog_ref_sem_reflog_retorna_vazio() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "a.txt", "primeiro");
+
+        let entries = get_reflog(&repo, Some("nao-existe"), 10).unwrap();
+        assert!(entries.is_empty());
+    }
+}
@@ -1,8 +1,12 @@
-use crate::error::{AppError, AppResult};
+use crate::error::{AppError, AppResult, ErrorKind};
+use crate::git::{apply_ssh_key, commit_to_info, is_ssh_auth_failure, ssh_auth_failed, CancelToken, CommitInfo, SshKey};
 use git2::Repository;
 use serde::{Deserialize, Serialize};
+use std::io::Read;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -18,21 +22,64 @@ pub struct RemoteInfo {
 }
 
 /// Run a git command in the repository directory (hidden window on Windows)
-fn run_git_command(repo_path: &Path, args: &[&str]) -> AppResult<String> {
+fn run_git_command(repo_path: &Path, args: &[&str], ssh_key: Option<&SshKey>) -> AppResult<String> {
+    run_git_command_cancellable(repo_path, args, ssh_key, &CancelToken::default())
+}
+
+/// Like `run_git_command`, but kills the child process once `cancel` fires
+fn run_git_command_cancellable(
+    repo_path: &Path,
+    args: &[&str],
+    ssh_key: Option<&SshKey>,
+    cancel: &CancelToken,
+) -> AppResult<String> {
     let mut cmd = Command::new("git");
-    cmd.args(args).current_dir(repo_path);
+    cmd.args(args)
+        .current_dir(repo_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Held until the child exits; dropping it deletes the askpass script
+    let _askpass = apply_ssh_key(&mut cmd, ssh_key)?;
 
     #[cfg(target_os = "windows")]
     cmd.creation_flags(CREATE_NO_WINDOW);
 
-    let output = cmd
-        .output()
+    let mut child = cmd
+        .spawn()
         .map_err(|e| AppError::with_details("GIT_COMMAND_FAILED", "Falha ao executar git", &e.to_string()))?;
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    // Drain the pipes on their own threads so a chatty command can't block on a full buffer
+    let stdout = drain_pipe(child.stdout.take());
+    let stderr = drain_pipe(child.stderr.take());
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if cancel.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(AppError::cancelled());
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if status.success() {
+        Ok(stdout)
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_ssh_auth_failure(&stderr) {
+            return Err(ssh_auth_failed(stderr.trim()));
+        }
+        if is_auth_failure(&stderr) {
+            return Err(AppError::with_details(
+                "AUTH_FAILED",
+                "Falha de autenticação no remote",
+                stderr.trim(),
+            ));
+        }
         Err(AppError::with_details(
             "GIT_COMMAND_FAILED",
             "Comando git falhou",
@@ -41,6 +88,27 @@ fn run_git_command(repo_path: &Path, args: &[&str]) -> AppResult<String> {
     }
 }
 
+fn drain_pipe(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        String::from_utf8_lossy(&buf).to_string()
+    })
+}
+
+/// Recognizes the credential failures the git CLI reports for HTTPS and SSH remotes
+fn is_auth_failure(stderr: &str) -> bool {
+    const MARKERS: [&str; 4] = [
+        "Authentication failed",
+        "could not read Username",
+        "Permission denied (publickey",
+        "terminal prompts disabled",
+    ];
+    MARKERS.iter().any(|marker| stderr.contains(marker))
+}
+
 pub fn list_remotes(repo: &Repository) -> AppResult<Vec<RemoteInfo>> {
     let remotes = repo.remotes()?;
 
@@ -74,22 +142,88 @@ pub fn rename_remote(repo: &Repository, old_name: &str, new_name: &str) -> AppRe
     Ok(())
 }
 
-pub fn fetch(repo: &Repository, remote_name: Option<&str>) -> AppResult<()> {
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FetchResult {
+    pub remote: String,
+    pub ok: bool,
+    pub error: Option<String>,
+    /// Tags that did not exist locally before this fetch
+    pub new_tags: usize,
+}
+
+fn tag_count(repo: &Repository) -> usize {
+    repo.tag_names(None).map(|tags| tags.len()).unwrap_or(0)
+}
+
+fn fetch_one(
+    repo: &Repository,
+    repo_path: &Path,
+    name: &str,
+    fetch_tags: bool,
+    ssh_key: Option<&SshKey>,
+    cancel: &CancelToken,
+) -> AppResult<usize> {
+    let tags_before = tag_count(repo);
+
+    let mut args = vec!["fetch", name];
+    if fetch_tags {
+        args.push("--tags");
+    }
+    run_git_command_cancellable(repo_path, &args, ssh_key, cancel)?;
+
+    Ok(tag_count(repo).saturating_sub(tags_before))
+}
+
+/// Fetches one remote (failing hard) or, with `None`, every remote while
+/// collecting per-remote errors so one bad remote doesn't block the rest
+pub fn fetch(
+    repo: &Repository,
+    remote_name: Option<&str>,
+    fetch_tags: bool,
+    ssh_key: Option<&SshKey>,
+    cancel: &CancelToken,
+) -> AppResult<Vec<FetchResult>> {
     let repo_path = repo.workdir().ok_or_else(|| AppError::internal("Repositório sem workdir (bare?)"))?;
 
     if let Some(name) = remote_name {
-        run_git_command(repo_path, &["fetch", name])?;
-    } else {
-        run_git_command(repo_path, &["fetch", "--all"])?;
+        let new_tags = fetch_one(repo, repo_path, name, fetch_tags, ssh_key, cancel)?;
+        return Ok(vec![FetchResult {
+            remote: name.to_string(),
+            ok: true,
+            error: None,
+            new_tags,
+        }]);
     }
 
-    Ok(())
+    let remotes = repo.remotes()?;
+    let results = remotes
+        .iter()
+        .flatten()
+        .map(|name| match fetch_one(repo, repo_path, name, fetch_tags, ssh_key, cancel) {
+            Ok(new_tags) => FetchResult {
+                remote: name.to_string(),
+                ok: true,
+                error: None,
+                new_tags,
+            },
+            Err(e) => FetchResult {
+                remote: name.to_string(),
+                ok: false,
+                error: Some(e.to_string()),
+                new_tags: 0,
+            },
+        })
+        .collect();
+
+    // A cancelled run stops the whole fetch instead of showing up as a per-remote failure
+    cancel.check()?;
+    Ok(results)
 }
 
-pub fn pull(repo: &Repository, remote_name: &str, branch: &str) -> AppResult<String> {
+pub fn pull(repo: &Repository, remote_name: &str, branch: &str, ssh_key: Option<&SshKey>) -> AppResult<String> {
     let repo_path = repo.workdir().ok_or_else(|| AppError::internal("Repositório sem workdir (bare?)"))?;
 
-    let output = run_git_command(repo_path, &["pull", remote_name, branch])?;
+    let output = run_git_command(repo_path, &["pull", remote_name, branch], ssh_key)?;
 
     // Parse output to determine result type
     let output_lower = output.to_lowercase();
@@ -102,21 +236,428 @@ pub fn pull(repo: &Repository, remote_name: &str, branch: &str) -> AppResult<Str
     }
 }
 
-pub fn push(repo: &Repository, remote_name: &str, branch: &str, force: bool) -> AppResult<()> {
+/// `force_with_lease` takes precedence over `force`: the lease is where the
+/// remote-tracking ref pointed before this call, i.e. what the user last saw
+/// (and reviewed with `push_dry_run`). The remote is fetched first and the
+/// push refused when it has moved since; the lease covers the rest.
+pub fn push(
+    repo: &Repository,
+    remote_name: &str,
+    branch: &str,
+    force: bool,
+    force_with_lease: bool,
+    set_upstream: bool,
+    ssh_key: Option<&SshKey>,
+) -> AppResult<()> {
+    let expected = if force_with_lease {
+        let seen = remote_tracking_oid(repo, remote_name, branch);
+        let repo_path = repo.workdir().ok_or_else(|| AppError::internal("Repositório sem workdir (bare?)"))?;
+        run_git_command(repo_path, &["fetch", remote_name], ssh_key)?;
+        if remote_tracking_oid(repo, remote_name, branch) != seen {
+            return Err(lease_failed(remote_name, branch));
+        }
+        Some(seen)
+    } else {
+        None
+    };
+    push_with_lease(repo, remote_name, branch, force, expected.as_deref(), set_upstream, ssh_key)
+}
+
+/// `push` once the lease is known: with `expected`, the remote branch is only
+/// overwritten while it still points there
+fn push_with_lease(
+    repo: &Repository,
+    remote_name: &str,
+    branch: &str,
+    force: bool,
+    expected: Option<&str>,
+    set_upstream: bool,
+    ssh_key: Option<&SshKey>,
+) -> AppResult<()> {
     let repo_path = repo.workdir().ok_or_else(|| AppError::internal("Repositório sem workdir (bare?)"))?;
 
+    let lease;
     let mut args = vec!["push", remote_name, branch];
-    if force {
+    if let Some(expected) = expected {
+        lease = format!("--force-with-lease={}:{}", branch, expected);
+        args.push(&lease);
+    } else if force {
         args.push("--force");
     }
+    if set_upstream {
+        args.push("--set-upstream");
+    }
 
-    run_git_command(repo_path, &args)?;
+    run_git_command(repo_path, &args, ssh_key).map_err(|e| {
+        if e.details.as_deref().is_some_and(|d| d.contains("stale info")) {
+            lease_failed(remote_name, branch)
+        } else {
+            e
+        }
+    })?;
     Ok(())
 }
 
+fn lease_failed(remote_name: &str, branch: &str) -> AppError {
+    AppError::with_details(
+        "PUSH_LEASE_FAILED",
+        "O remote avançou desde a verificação; faça fetch e revise antes de forçar o push",
+        &format!("{}/{}", remote_name, branch),
+    )
+    .with_kind(ErrorKind::Conflict)
+}
+
+/// Where the remote-tracking ref of `branch` points; empty when there is none
+/// (a lease then requires the branch stay absent on the remote)
+fn remote_tracking_oid(repo: &Repository, remote_name: &str, branch: &str) -> String {
+    repo.refname_to_id(&format!("refs/remotes/{}/{}", remote_name, branch))
+        .map(|oid| oid.to_string())
+        .unwrap_or_default()
+}
+
+const MAX_PUSH_PREVIEW_COMMITS: usize = 200;
+
+/// What a push of `branch` would change on the remote
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PushPreview {
+    pub remote: String,
+    pub branch: String,
+    /// Remote-tracking ref compared against; `None` when the branch is new there
+    pub remote_ref: Option<String>,
+    /// Commits that would be sent, newest first (capped)
+    pub commits: Vec<CommitInfo>,
+    pub ahead: usize,
+    pub behind: usize,
+    /// The remote has commits the local branch lacks; only a force push would succeed
+    pub non_fast_forward: bool,
+}
+
+/// Previews a push without sending anything. libgit2 can't dry-run over the
+/// wire, so this fetches the remote and compares against its tracking ref.
+pub fn push_dry_run(
+    repo: &Repository,
+    remote_name: &str,
+    branch: &str,
+    ssh_key: Option<&SshKey>,
+    cancel: &CancelToken,
+) -> AppResult<PushPreview> {
+    let repo_path = repo.workdir().ok_or_else(|| AppError::internal("Repositório sem workdir (bare?)"))?;
+    let local_oid = repo
+        .find_branch(branch, git2::BranchType::Local)
+        .map_err(|_| AppError::branch_not_found(branch))?
+        .get()
+        .target()
+        .ok_or_else(|| AppError::branch_not_found(branch))?;
+    repo.find_remote(remote_name)
+        .map_err(|_| AppError::remote_not_found(remote_name))?;
+
+    run_git_command_cancellable(repo_path, &["fetch", remote_name], ssh_key, cancel)?;
+
+    let remote_ref = format!("refs/remotes/{}/{}", remote_name, branch);
+    let remote_oid = repo.refname_to_id(&remote_ref).ok();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(local_oid)?;
+    match remote_oid {
+        Some(oid) => revwalk.hide(oid)?,
+        // A new branch only sends what no branch of that remote has yet
+        None => revwalk.hide_glob(&format!("refs/remotes/{}/*", remote_name))?,
+    }
+
+    let mut ahead = 0;
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let oid = oid?;
+        ahead += 1;
+        if commits.len() < MAX_PUSH_PREVIEW_COMMITS {
+            commits.push(commit_to_info(&repo.find_commit(oid)?));
+        }
+    }
+
+    let behind = match remote_oid {
+        Some(oid) => repo.graph_ahead_behind(local_oid, oid)?.1,
+        None => 0,
+    };
+
+    Ok(PushPreview {
+        remote: remote_name.to_string(),
+        branch: branch.to_string(),
+        remote_ref: remote_oid.map(|_| format!("{}/{}", remote_name, branch)),
+        commits,
+        ahead,
+        behind,
+        non_fast_forward: behind > 0,
+    })
+}
+
 pub fn set_upstream(repo: &Repository, branch: &str, remote: &str, remote_branch: &str) -> AppResult<()> {
     let mut local_branch = repo.find_branch(branch, git2::BranchType::Local)?;
     let upstream_name = format!("{}/{}", remote, remote_branch);
     local_branch.set_upstream(Some(&upstream_name))?;
     Ok(())
 }
+
+/// Clears `branch.<name>.remote`/`merge`; a branch without upstream is left as is
+pub fn unset_upstream(repo: &Repository, branch: &str) -> AppResult<()> {
+    repo.find_branch(branch, git2::BranchType::Local)
+        .map_err(|_| AppError::branch_not_found(branch))?;
+
+    // `Branch::set_upstream(None)` fails when either key is already missing
+    let mut config = repo.config()?;
+    for key in ["remote", "merge"] {
+        let name = format!("branch.{}.{}", branch, key);
+        if config.get_entry(&name).is_ok() {
+            config.remove(&name)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn fetch_todos_os_remotes_continua_apos_falha() {
+        let dir = TempDir::new().unwrap();
+        let upstream = TempDir::new().unwrap();
+        Repository::init_bare(upstream.path()).unwrap();
+
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.remote("quebrado", &dir.path().join("nao-existe").to_string_lossy())
+            .unwrap();
+        repo.remote("origin", &upstream.path().to_string_lossy()).unwrap();
+
+        let results = fetch(&repo, None, false, None, &CancelToken::default()).unwrap();
+        assert_eq!(results.len(), 2);
+        let origin = results.iter().find(|r| r.remote == "origin").unwrap();
+        assert!(origin.ok);
+        let broken = results.iter().find(|r| r.remote == "quebrado").unwrap();
+        assert!(!broken.ok);
+        assert!(broken.error.is_some());
+
+        assert!(fetch(&repo, Some("quebrado"), false, None, &CancelToken::default()).is_err());
+    }
+
+    #[test]
+    fn fetch_com_tags_conta_tags_novas() {
+        let upstream_dir = TempDir::new().unwrap();
+        let upstream = Repository::init(upstream_dir.path()).unwrap();
+        let sig = git2::Signature::now("Teste", "teste@test.com").unwrap();
+        let tree = upstream
+            .find_tree(upstream.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let oid = upstream.commit(Some("HEAD"), &sig, &sig, "inicial", &tree, &[]).unwrap();
+        let target = upstream.find_object(oid, None).unwrap();
+        upstream.tag("v1.0", &target, &sig, "release", false).unwrap();
+        upstream.tag_lightweight("v1.1", &target, false).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.remote("origin", &upstream_dir.path().to_string_lossy()).unwrap();
+
+        let results = fetch(&repo, Some("origin"), true, None, &CancelToken::default()).unwrap();
+        assert_eq!(results[0].new_tags, 2);
+
+        let results = fetch(&repo, Some("origin"), true, None, &CancelToken::default()).unwrap();
+        assert_eq!(results[0].new_tags, 0);
+    }
+
+    #[test]
+    fn fetch_cancelado_interrompe_processo_git() {
+        let dir = TempDir::new().unwrap();
+        let upstream = TempDir::new().unwrap();
+        Repository::init_bare(upstream.path()).unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.remote("origin", &upstream.path().to_string_lossy()).unwrap();
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let err = fetch(&repo, None, false, None, &cancel).unwrap_err();
+        assert_eq!(err.code, "CANCELLED");
+    }
+
+    #[test]
+    fn push_com_set_upstream_configura_tracking() {
+        let upstream = TempDir::new().unwrap();
+        Repository::init_bare(upstream.path()).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Teste", "teste@test.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "inicial", &tree, &[]).unwrap();
+        repo.remote("origin", &upstream.path().to_string_lossy()).unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        push(&repo, "origin", &branch, false, false, true, None).unwrap();
+
+        let local = repo.find_branch(&branch, git2::BranchType::Local).unwrap();
+        assert_eq!(
+            local.upstream().unwrap().name().unwrap(),
+            Some(format!("origin/{}", branch).as_str())
+        );
+    }
+
+    #[test]
+    fn push_dry_run_lista_commits_e_detecta_divergencia() {
+        let upstream = TempDir::new().unwrap();
+        Repository::init_bare(upstream.path()).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Teste", "teste@test.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let base = repo.commit(Some("HEAD"), &sig, &sig, "inicial", &tree, &[]).unwrap();
+        repo.remote("origin", &upstream.path().to_string_lossy()).unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        let novo = push_dry_run(&repo, "origin", &branch, None, &CancelToken::default()).unwrap();
+        assert_eq!(novo.remote_ref, None);
+        assert_eq!(novo.ahead, 1);
+
+        push(&repo, "origin", &branch, false, false, true, None).unwrap();
+        let base_commit = repo.find_commit(base).unwrap();
+        let local = repo.commit(Some("HEAD"), &sig, &sig, "local", &tree, &[&base_commit]).unwrap();
+
+        let preview = push_dry_run(&repo, "origin", &branch, None, &CancelToken::default()).unwrap();
+        assert_eq!(preview.commits.len(), 1);
+        assert_eq!(preview.commits[0].hash, local.to_string());
+        assert!(!preview.non_fast_forward);
+
+        // Rewrite the tip so the remote has a commit the local branch lost
+        let mut reference = repo.find_reference(&format!("refs/heads/{}", branch)).unwrap();
+        let rewritten = repo.commit(None, &sig, &sig, "reescrito", &tree, &[]).unwrap();
+        reference.set_target(rewritten, "teste").unwrap();
+
+        let forced = push_dry_run(&repo, "origin", &branch, None, &CancelToken::default()).unwrap();
+        assert_eq!((forced.ahead, forced.behind), (1, 1));
+        assert!(forced.non_fast_forward);
+    }
+
+    #[test]
+    fn push_force_with_lease_sobrescreve_remote_conferido() {
+        let upstream = TempDir::new().unwrap();
+        Repository::init_bare(upstream.path()).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Teste", "teste@test.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "inicial", &tree, &[]).unwrap();
+        repo.remote("origin", &upstream.path().to_string_lossy()).unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        push(&repo, "origin", &branch, false, false, true, None).unwrap();
+
+        let mut reference = repo.find_reference(&format!("refs/heads/{}", branch)).unwrap();
+        let rewritten = repo.commit(None, &sig, &sig, "reescrito", &tree, &[]).unwrap();
+        reference.set_target(rewritten, "teste").unwrap();
+
+        assert!(push(&repo, "origin", &branch, false, false, false, None).is_err());
+        push(&repo, "origin", &branch, false, true, false, None).unwrap();
+
+        let remote = Repository::open_bare(upstream.path()).unwrap();
+        let remote_tip = remote.refname_to_id(&format!("refs/heads/{}", branch)).unwrap();
+        assert_eq!(remote_tip, rewritten);
+    }
+
+    #[test]
+    fn push_force_with_lease_recusa_remote_que_avancou_apos_fetch() {
+        let upstream = TempDir::new().unwrap();
+        let remote = Repository::init_bare(upstream.path()).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Teste", "teste@test.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let base = repo.commit(Some("HEAD"), &sig, &sig, "inicial", &tree, &[]).unwrap();
+        repo.remote("origin", &upstream.path().to_string_lossy()).unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        push(&repo, "origin", &branch, false, false, true, None).unwrap();
+
+        let mut reference = repo.find_reference(&format!("refs/heads/{}", branch)).unwrap();
+        let rewritten = repo.commit(None, &sig, &sig, "reescrito", &tree, &[]).unwrap();
+        reference.set_target(rewritten, "teste").unwrap();
+
+        let expected = remote_tracking_oid(&repo, "origin", &branch);
+        assert_eq!(expected, base.to_string());
+
+        // Someone else pushes between our fetch and our push
+        let remote_ref = format!("refs/heads/{}", branch);
+        let remote_tree = remote.find_tree(remote.treebuilder(None).unwrap().write().unwrap()).unwrap();
+        let remote_base = remote.find_commit(base).unwrap();
+        let outro = remote
+            .commit(Some(&remote_ref), &sig, &sig, "de outra pessoa", &remote_tree, &[&remote_base])
+            .unwrap();
+
+        let err = push_with_lease(&repo, "origin", &branch, false, Some(&expected), false, None).unwrap_err();
+        assert_eq!(err.code, "PUSH_LEASE_FAILED");
+        assert_eq!(err.kind, ErrorKind::Conflict);
+        assert_eq!(remote.refname_to_id(&remote_ref).unwrap(), outro);
+    }
+
+    #[test]
+    fn push_force_with_lease_recusa_commit_que_ja_estava_no_remote() {
+        let upstream = TempDir::new().unwrap();
+        let remote = Repository::init_bare(upstream.path()).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Teste", "teste@test.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let base = repo.commit(Some("HEAD"), &sig, &sig, "inicial", &tree, &[]).unwrap();
+        repo.remote("origin", &upstream.path().to_string_lossy()).unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        push(&repo, "origin", &branch, false, false, true, None).unwrap();
+
+        // A teammate pushes before we even start the forced push
+        let remote_ref = format!("refs/heads/{}", branch);
+        let remote_tree = remote.find_tree(remote.treebuilder(None).unwrap().write().unwrap()).unwrap();
+        let remote_base = remote.find_commit(base).unwrap();
+        let outro = remote
+            .commit(Some(&remote_ref), &sig, &sig, "de outra pessoa", &remote_tree, &[&remote_base])
+            .unwrap();
+
+        let mut reference = repo.find_reference(&remote_ref).unwrap();
+        let rewritten = repo.commit(None, &sig, &sig, "reescrito", &tree, &[]).unwrap();
+        reference.set_target(rewritten, "teste").unwrap();
+
+        let err = push(&repo, "origin", &branch, false, true, false, None).unwrap_err();
+        assert_eq!(err.code, "PUSH_LEASE_FAILED");
+        assert_eq!(remote.refname_to_id(&remote_ref).unwrap(), outro);
+
+        // The fetch showed the new commit; once seen, forcing over it is allowed
+        assert_eq!(remote_tracking_oid(&repo, "origin", &branch), outro.to_string());
+        push(&repo, "origin", &branch, false, true, false, None).unwrap();
+        assert_eq!(remote.refname_to_id(&remote_ref).unwrap(), rewritten);
+    }
+
+    #[test]
+    fn unset_upstream_limpa_tracking_e_e_idempotente() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Teste", "teste@test.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let head = repo.commit(Some("HEAD"), &sig, &sig, "inicial", &tree, &[]).unwrap();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        repo.reference("refs/remotes/origin/main", head, true, "teste").unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        set_upstream(&repo, &branch, "origin", "main").unwrap();
+
+        unset_upstream(&repo, &branch).unwrap();
+        let local = repo.find_branch(&branch, git2::BranchType::Local).unwrap();
+        assert!(local.upstream().is_err());
+        assert!(repo.config().unwrap().get_string(&format!("branch.{}.merge", branch)).is_err());
+
+        unset_upstream(&repo, &branch).unwrap();
+        assert_eq!(unset_upstream(&repo, "nao-existe").unwrap_err().code, "BRANCH_NOT_FOUND");
+    }
+
+    #[test]
+    fn is_auth_failure_reconhece_erros_de_credencial() {
+        assert!(is_auth_failure("fatal: Authentication failed for 'https://x/y.git/'"));
+        assert!(is_auth_failure("git@github.com: Permission denied (publickey)."));
+        assert!(!is_auth_failure("fatal: couldn't find remote ref main"));
+    }
+}
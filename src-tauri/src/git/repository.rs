@@ -1,7 +1,16 @@
 use crate::error::{AppError, AppResult};
+use crate::git::{credential_callbacks, ssh_auth_failed, CancelToken, SshKey, TransferCredentials};
 use git2::Repository;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RepoInfo {
@@ -12,6 +21,9 @@ pub struct RepoInfo {
     pub current_branch: Option<String>,
     pub has_remote: bool,
     pub is_empty: bool,
+    /// Branch the user was last on, remembered across sessions (set by `open_repo`)
+    #[serde(default)]
+    pub last_branch: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -22,23 +34,45 @@ pub fn open_repository(path: &Path) -> AppResult<Repository> {
     Repository::open(path).map_err(|_| AppError::invalid_repo(&path.to_string_lossy()))
 }
 
-pub fn get_repo_info(path: &Path) -> AppResult<RepoInfo> {
-    let name = path
-        .file_name()
+/// The one form a repo path is stored and shown in: symlinks resolved, no
+/// trailing separator, and on Windows no `\\?\` prefix and the on-disk case
+pub fn canonical_repo_path(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| path.components().collect())
+}
+
+/// Identity of a repo path: the canonical path, case-folded where the
+/// filesystem usually ignores case, so `C:\Repo` and `c:\repo\` match
+pub fn repo_path_key(path: &Path) -> String {
+    let canonical = canonical_repo_path(path).to_string_lossy().to_string();
+    if cfg!(any(windows, target_os = "macos")) {
+        canonical.to_lowercase()
+    } else {
+        canonical
+    }
+}
+
+/// Name shown for a repo: its folder name, or the whole path for a root
+fn repo_name(path: &Path) -> String {
+    path.file_name()
         .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| path.to_string_lossy().to_string());
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
 
-    let repo = match Repository::open(path) {
+/// `path` may be any folder inside the repo; `RepoInfo.path` is always the
+/// discovered root (the working tree, or the git dir for a bare repo)
+pub fn get_repo_info(path: &Path) -> AppResult<RepoInfo> {
+    let repo = match Repository::discover(path) {
         Ok(r) => r,
         Err(_) => {
             return Ok(RepoInfo {
                 path: path.to_string_lossy().to_string(),
-                name,
+                name: repo_name(path),
                 is_repo: false,
                 is_bare: false,
                 current_branch: None,
                 has_remote: false,
                 is_empty: true,
+                last_branch: None,
             });
         }
     };
@@ -49,40 +83,293 @@ pub fn get_repo_info(path: &Path) -> AppResult<RepoInfo> {
 
     let is_empty = repo.is_empty().unwrap_or(true);
 
+    // libgit2 paths end in a separator (and use '/' on Windows)
+    let root = canonical_repo_path(repo.workdir().unwrap_or_else(|| repo.path()));
+
     Ok(RepoInfo {
-        path: path.to_string_lossy().to_string(),
-        name,
+        path: root.to_string_lossy().to_string(),
+        name: repo_name(&root),
         is_repo: true,
         is_bare: repo.is_bare(),
         current_branch,
         has_remote,
         is_empty,
+        last_branch: None,
     })
 }
 
-pub fn init_repository(path: &Path, bare: bool) -> AppResult<Repository> {
-    if bare {
-        Repository::init_bare(path).map_err(AppError::from)
+/// Initializes a repository, naming the first branch `initial_branch` when
+/// given instead of whatever git's `init.defaultBranch` dictates.
+pub fn init_repository(path: &Path, bare: bool, initial_branch: Option<&str>) -> AppResult<Repository> {
+    let mut opts = git2::RepositoryInitOptions::new();
+    opts.bare(bare);
+
+    // Bare repositories get the same symbolic HEAD, there is just no working tree
+    if let Some(branch) = initial_branch.map(str::trim).filter(|b| !b.is_empty()) {
+        opts.initial_head(branch);
+    }
+
+    Repository::init_opts(path, &opts).map_err(AppError::from)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CloneResult {
+    #[serde(flatten)]
+    pub info: RepoInfo,
+    /// Branch checked out after the clone; `None` for an empty remote
+    pub default_branch: Option<String>,
+    pub remote: String,
+}
+
+/// libgit2 only reports "exists and is not an empty directory"; check up front
+fn check_clone_destination(path: &Path) -> AppResult<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let is_empty_dir = path.is_dir()
+        && path
+            .read_dir()
+            .map(|mut entries| entries.next().is_none())
+            .unwrap_or(false);
+
+    if is_empty_dir {
+        Ok(())
     } else {
-        Repository::init(path).map_err(AppError::from)
+        Err(AppError::with_details(
+            "DESTINATION_NOT_EMPTY",
+            "O destino já existe e não está vazio",
+            &path.to_string_lossy(),
+        ))
     }
 }
 
-pub fn clone_repository(url: &str, path: &Path) -> AppResult<Repository> {
-    Repository::clone(url, path).map_err(AppError::from)
+pub fn clone_repository(
+    url: &str,
+    path: &Path,
+    ssh_key: Option<&SshKey>,
+    cancel: &CancelToken,
+) -> AppResult<CloneResult> {
+    check_clone_destination(path)?;
+
+    let credentials = TransferCredentials::default();
+    let mut callbacks = credential_callbacks(ssh_key, &credentials);
+    // Returning false from transfer_progress makes libgit2 abort the transfer
+    callbacks.transfer_progress(|_| !cancel.is_cancelled());
+    let mut fetch_options = git2::FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+
+    let repo = git2::build::RepoBuilder::new()
+        .fetch_options(fetch_options)
+        .clone(url, path)
+        .map_err(|e| {
+            if cancel.is_cancelled() {
+                AppError::cancelled()
+            } else if credentials.ssh_exhausted() {
+                ssh_auth_failed(e.message())
+            } else {
+                AppError::from(e)
+            }
+        })?;
+    credentials.approve();
+
+    let default_branch = repo.head().ok().and_then(|h| h.shorthand().map(String::from));
+    let remote = repo
+        .remotes()?
+        .iter()
+        .flatten()
+        .next()
+        .unwrap_or("origin")
+        .to_string();
+
+    Ok(CloneResult {
+        info: get_repo_info(path)?,
+        default_branch,
+        remote,
+    })
 }
 
-pub fn get_git_config(repo: &Repository, key: &str) -> Option<String> {
-    repo.config()
-        .ok()
-        .and_then(|c| c.get_string(key).ok())
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GcResult {
+    /// Size of the git directory before and after, in bytes
+    pub size_before: u64,
+    pub size_after: u64,
+    pub output: String,
 }
 
-pub fn set_git_config(repo: &Repository, key: &str, value: &str) -> AppResult<()> {
-    let mut config = repo.config().map_err(AppError::from)?;
+/// Runs `git gc`; libgit2 has no equivalent for repacking and pruning
+pub fn run_gc(repo_path: &Path, aggressive: bool) -> AppResult<GcResult> {
+    let git_dir = Repository::open(repo_path)
+        .map_err(|_| AppError::invalid_repo(&repo_path.to_string_lossy()))?
+        .path()
+        .to_path_buf();
+    let size_before = super::stats::dir_size(&git_dir);
+
+    let mut cmd = Command::new("git");
+    cmd.arg("gc").arg("--quiet").current_dir(repo_path);
+    if aggressive {
+        cmd.arg("--aggressive");
+    }
+
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = cmd.output().map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            AppError::with_details("GIT_NOT_FOUND", "Git não encontrado no PATH", &e.to_string())
+        } else {
+            AppError::with_details("GIT_COMMAND_FAILED", "Falha ao executar git", &e.to_string())
+        }
+    })?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(AppError::with_details("GC_FAILED", "Falha ao otimizar repositório", stderr.trim()));
+    }
+
+    Ok(GcResult {
+        size_before,
+        size_after: super::stats::dir_size(&git_dir),
+        output: format!("{}{}", stdout, stderr).trim().to_string(),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigScope {
+    Local,
+    Global,
+    System,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigValue {
+    pub value: String,
+    pub scope: ConfigScope,
+}
+
+fn open_config_scope(repo: Option<&Repository>, scope: ConfigScope) -> AppResult<git2::Config> {
+    match scope {
+        ConfigScope::Local => {
+            let repo = repo.ok_or_else(AppError::no_repo)?;
+            Ok(repo.config()?.open_level(git2::ConfigLevel::Local)?)
+        }
+        ConfigScope::Global => {
+            // ~/.gitconfig may not exist yet; opening it by path creates it on first write
+            let path = git2::Config::find_global().or_else(|_| {
+                dirs::home_dir()
+                    .map(|home| home.join(".gitconfig"))
+                    .ok_or_else(|| AppError::internal("Diretorio home nao encontrado"))
+            })?;
+            Ok(git2::Config::open(&path)?)
+        }
+        ConfigScope::System => Ok(git2::Config::open(&git2::Config::find_system()?)?),
+    }
+}
+
+/// Reads `key` from the given scope, or falls back local -> global -> system
+/// like git does when no scope is given.
+pub fn get_git_config(
+    repo: Option<&Repository>,
+    key: &str,
+    scope: Option<ConfigScope>,
+) -> AppResult<Option<ConfigValue>> {
+    let explicit = scope.is_some();
+    let scopes = match scope {
+        Some(scope) => vec![scope],
+        None => vec![ConfigScope::Local, ConfigScope::Global, ConfigScope::System],
+    };
+
+    for scope in scopes {
+        let config = match open_config_scope(repo, scope) {
+            Ok(config) => config,
+            // An explicit scope that can't be opened is an error; fallbacks just skip it
+            Err(e) if explicit => return Err(e),
+            Err(_) => continue,
+        };
+
+        if let Ok(value) = config.get_string(key) {
+            return Ok(Some(ConfigValue { value, scope }));
+        }
+    }
+
+    Ok(None)
+}
+
+pub fn set_git_config(
+    repo: Option<&Repository>,
+    key: &str,
+    value: &str,
+    scope: ConfigScope,
+) -> AppResult<()> {
+    let mut config = open_config_scope(repo, scope)?;
     config.set_str(key, value).map_err(AppError::from)
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigEntry {
+    pub name: String,
+    pub value: String,
+    pub level: ConfigScope,
+}
+
+fn scope_of_level(level: git2::ConfigLevel) -> ConfigScope {
+    match level {
+        git2::ConfigLevel::ProgramData | git2::ConfigLevel::System => ConfigScope::System,
+        git2::ConfigLevel::XDG | git2::ConfigLevel::Global => ConfigScope::Global,
+        _ => ConfigScope::Local,
+    }
+}
+
+fn level_precedence(level: git2::ConfigLevel) -> u8 {
+    match level {
+        git2::ConfigLevel::ProgramData => 1,
+        git2::ConfigLevel::System => 2,
+        git2::ConfigLevel::XDG => 3,
+        git2::ConfigLevel::Global => 4,
+        git2::ConfigLevel::Local => 5,
+        git2::ConfigLevel::App => 6,
+        git2::ConfigLevel::Highest => 7,
+    }
+}
+
+/// Lists config entries for a scope, or the effective entries across all
+/// scopes. A key keeps only the entries from the highest level defining it,
+/// so multivars such as `remote.*.fetch` still appear once per value.
+pub fn list_git_config(repo: Option<&Repository>, scope: Option<ConfigScope>) -> AppResult<Vec<ConfigEntry>> {
+    let config = match (scope, repo) {
+        (Some(scope), _) => open_config_scope(repo, scope)?,
+        (None, Some(repo)) => repo.config()?,
+        (None, None) => git2::Config::open_default()?,
+    };
+
+    let mut raw: Vec<(String, String, git2::ConfigLevel)> = Vec::new();
+    config.entries(None)?.for_each(|entry| {
+        if let Some(name) = entry.name() {
+            raw.push((name.to_string(), entry.value().unwrap_or("").to_string(), entry.level()));
+        }
+    })?;
+
+    let mut winning: HashMap<&str, u8> = HashMap::new();
+    for (name, _, level) in &raw {
+        let precedence = winning.entry(name.as_str()).or_insert(0);
+        *precedence = (*precedence).max(level_precedence(*level));
+    }
+
+    let entries = raw
+        .iter()
+        .filter(|(name, _, level)| winning.get(name.as_str()) == Some(&level_precedence(*level)))
+        .map(|(name, value, level)| ConfigEntry {
+            name: name.clone(),
+            value: value.clone(),
+            level: scope_of_level(*level),
+        })
+        .collect();
+
+    Ok(entries)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,10 +379,53 @@ mod tests {
         tempfile::tempdir().expect("falha ao criar diretório temporário")
     }
 
+    #[test]
+    fn get_repo_info_de_subdiretorio_retorna_raiz_do_repo() {
+        let dir = make_temp_dir();
+        init_repository(dir.path(), false, None).unwrap();
+        let nested = dir.path().join("src/modulo");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let info = get_repo_info(&nested).unwrap();
+        assert!(info.is_repo);
+        assert_eq!(Path::new(&info.path), canonical_repo_path(dir.path()));
+        assert!(!info.path.ends_with('/'));
+        assert_eq!(info.name, repo_name(dir.path()));
+
+        let outside = make_temp_dir();
+        assert!(!get_repo_info(outside.path()).unwrap().is_repo);
+    }
+
+    #[test]
+    fn repo_path_key_ignora_barra_final_e_symlinks() {
+        let dir = make_temp_dir();
+        let repo = dir.path().join("Repo");
+        std::fs::create_dir(&repo).unwrap();
+
+        let key = repo_path_key(&repo);
+        let with_slash = format!("{}{}", repo.to_string_lossy(), std::path::MAIN_SEPARATOR);
+        assert_eq!(repo_path_key(Path::new(&with_slash)), key);
+        assert_eq!(repo_path_key(&repo.join(".")), key);
+        assert!(!canonical_repo_path(&repo).to_string_lossy().starts_with(r"\\?\"));
+
+        #[cfg(unix)]
+        {
+            let link = dir.path().join("atalho");
+            std::os::unix::fs::symlink(&repo, &link).unwrap();
+            assert_eq!(repo_path_key(&link), key);
+        }
+
+        // Only meaningful where the filesystem is case-insensitive
+        let other_case = dir.path().join("REPO");
+        if other_case.exists() {
+            assert_eq!(repo_path_key(&other_case), key);
+        }
+    }
+
     #[test]
     fn init_repository_cria_repo_normal() {
         let dir = make_temp_dir();
-        let result = init_repository(dir.path(), false);
+        let result = init_repository(dir.path(), false, None);
         assert!(result.is_ok());
         assert!(!result.unwrap().is_bare());
     }
@@ -103,11 +433,36 @@ mod tests {
     #[test]
     fn init_repository_cria_repo_bare() {
         let dir = make_temp_dir();
-        let result = init_repository(dir.path(), true);
+        let result = init_repository(dir.path(), true, None);
         assert!(result.is_ok());
         assert!(result.unwrap().is_bare());
     }
 
+    #[test]
+    fn init_repository_usa_branch_padrao_configurada() {
+        let dir = make_temp_dir();
+        let repo = init_repository(dir.path(), false, Some("main")).unwrap();
+        let head = repo.find_reference("HEAD").unwrap();
+        assert_eq!(head.symbolic_target(), Some("refs/heads/main"));
+    }
+
+    #[test]
+    fn init_repository_bare_usa_branch_padrao_configurada() {
+        let dir = make_temp_dir();
+        let repo = init_repository(dir.path(), true, Some("trunk")).unwrap();
+        assert!(repo.is_bare());
+        let head = repo.find_reference("HEAD").unwrap();
+        assert_eq!(head.symbolic_target(), Some("refs/heads/trunk"));
+    }
+
+    #[test]
+    fn init_repository_branch_vazia_mantem_padrao_do_git() {
+        let dir = make_temp_dir();
+        let repo = init_repository(dir.path(), false, Some("  ")).unwrap();
+        let head = repo.find_reference("HEAD").unwrap();
+        assert!(head.symbolic_target().unwrap().starts_with("refs/heads/"));
+    }
+
     #[test]
     fn get_repo_info_em_diretorio_sem_git_retorna_is_repo_false() {
         let dir = make_temp_dir();
@@ -120,7 +475,7 @@ mod tests {
     #[test]
     fn get_repo_info_em_repo_valido_retorna_is_repo_true() {
         let dir = make_temp_dir();
-        init_repository(dir.path(), false).unwrap();
+        init_repository(dir.path(), false, None).unwrap();
         let info = get_repo_info(dir.path()).unwrap();
         assert!(info.is_repo);
         assert!(!info.is_bare);
@@ -129,7 +484,7 @@ mod tests {
     #[test]
     fn get_repo_info_retorna_nome_do_diretorio() {
         let dir = make_temp_dir();
-        init_repository(dir.path(), false).unwrap();
+        init_repository(dir.path(), false, None).unwrap();
         let info = get_repo_info(dir.path()).unwrap();
         let expected_name = dir.path().file_name().unwrap().to_string_lossy().to_string();
         assert_eq!(info.name, expected_name);
@@ -138,7 +493,7 @@ mod tests {
     #[test]
     fn get_repo_info_repo_vazio_sem_branch_atual() {
         let dir = make_temp_dir();
-        init_repository(dir.path(), false).unwrap();
+        init_repository(dir.path(), false, None).unwrap();
         let info = get_repo_info(dir.path()).unwrap();
         // Repo novo sem commits não tem branch atual definida
         assert!(info.is_empty);
@@ -147,7 +502,7 @@ mod tests {
     #[test]
     fn get_repo_info_repo_sem_remote_tem_has_remote_false() {
         let dir = make_temp_dir();
-        init_repository(dir.path(), false).unwrap();
+        init_repository(dir.path(), false, None).unwrap();
         let info = get_repo_info(dir.path()).unwrap();
         assert!(!info.has_remote);
     }
@@ -173,7 +528,7 @@ mod tests {
     #[test]
     fn open_repository_sucesso_em_repo_valido() {
         let dir = make_temp_dir();
-        init_repository(dir.path(), false).unwrap();
+        init_repository(dir.path(), false, None).unwrap();
         let result = open_repository(dir.path());
         assert!(result.is_ok());
     }
@@ -181,17 +536,100 @@ mod tests {
     #[test]
     fn get_e_set_git_config_funcionam() {
         let dir = make_temp_dir();
-        let repo = init_repository(dir.path(), false).unwrap();
-        set_git_config(&repo, "user.name", "Teste").unwrap();
-        let value = get_git_config(&repo, "user.name");
-        assert_eq!(value.as_deref(), Some("Teste"));
+        let repo = init_repository(dir.path(), false, None).unwrap();
+        set_git_config(Some(&repo), "user.name", "Teste", ConfigScope::Local).unwrap();
+        let value = get_git_config(Some(&repo), "user.name", Some(ConfigScope::Local)).unwrap();
+        assert_eq!(value.map(|v| v.value).as_deref(), Some("Teste"));
     }
 
     #[test]
     fn get_git_config_retorna_none_para_chave_inexistente() {
         let dir = make_temp_dir();
-        let repo = init_repository(dir.path(), false).unwrap();
-        let value = get_git_config(&repo, "chave.que.nao.existe.xyz");
+        let repo = init_repository(dir.path(), false, None).unwrap();
+        let value = get_git_config(Some(&repo), "chave.que.nao.existe.xyz", None).unwrap();
         assert!(value.is_none());
     }
+
+    #[test]
+    fn get_git_config_sem_escopo_prefere_local() {
+        let dir = make_temp_dir();
+        let repo = init_repository(dir.path(), false, None).unwrap();
+        set_git_config(Some(&repo), "gitgui.teste", "local", ConfigScope::Local).unwrap();
+
+        let value = get_git_config(Some(&repo), "gitgui.teste", None).unwrap().unwrap();
+        assert_eq!(value.value, "local");
+        assert_eq!(value.scope, ConfigScope::Local);
+    }
+
+    #[test]
+    fn get_git_config_local_sem_repo_falha() {
+        let err = get_git_config(None, "user.name", Some(ConfigScope::Local)).unwrap_err();
+        assert_eq!(err.code, "NO_REPO");
+    }
+
+    #[test]
+    fn list_git_config_mantem_multivar_e_valor_efetivo() {
+        let dir = make_temp_dir();
+        let repo = init_repository(dir.path(), false, None).unwrap();
+        {
+            let mut config = repo.config().unwrap().open_level(git2::ConfigLevel::Local).unwrap();
+            config.set_str("user.name", "Local").unwrap();
+            config.set_multivar("remote.origin.fetch", "^$", "+refs/heads/*:refs/remotes/origin/*").unwrap();
+            config.set_multivar("remote.origin.fetch", "^$", "+refs/tags/*:refs/tags/*").unwrap();
+        }
+
+        let entries = list_git_config(Some(&repo), None).unwrap();
+
+        let names: Vec<&ConfigEntry> = entries.iter().filter(|e| e.name == "user.name").collect();
+        assert_eq!(names.len(), 1);
+        assert_eq!(names[0].value, "Local");
+        assert_eq!(names[0].level, ConfigScope::Local);
+
+        let fetch = entries.iter().filter(|e| e.name == "remote.origin.fetch").count();
+        assert_eq!(fetch, 2);
+    }
+
+    #[test]
+    fn list_git_config_com_escopo_local_retorna_apenas_local() {
+        let dir = make_temp_dir();
+        let repo = init_repository(dir.path(), false, None).unwrap();
+        set_git_config(Some(&repo), "gitgui.teste", "1", ConfigScope::Local).unwrap();
+
+        let entries = list_git_config(Some(&repo), Some(ConfigScope::Local)).unwrap();
+        assert!(entries.iter().all(|e| e.level == ConfigScope::Local));
+        assert!(entries.iter().any(|e| e.name == "gitgui.teste" && e.value == "1"));
+    }
+
+    #[test]
+    fn run_gc_em_diretorio_sem_repo_retorna_erro() {
+        let dir = make_temp_dir();
+        let err = run_gc(dir.path(), false).unwrap_err();
+        assert_eq!(err.code, "INVALID_REPO");
+    }
+
+    #[test]
+    fn clone_repository_retorna_branch_padrao_e_remote() {
+        let origin_dir = make_temp_dir();
+        let origin = init_repository(origin_dir.path(), false, Some("trunk")).unwrap();
+        let sig = git2::Signature::now("Teste", "teste@test.com").unwrap();
+        let tree = origin.find_tree(origin.index().unwrap().write_tree().unwrap()).unwrap();
+        origin.commit(Some("HEAD"), &sig, &sig, "inicial", &tree, &[]).unwrap();
+
+        let dest = make_temp_dir();
+        let target = dest.path().join("clone");
+        let result = clone_repository(&origin_dir.path().to_string_lossy(), &target, None, &CancelToken::default()).unwrap();
+
+        assert!(result.info.is_repo);
+        assert_eq!(result.default_branch.as_deref(), Some("trunk"));
+        assert_eq!(result.remote, "origin");
+    }
+
+    #[test]
+    fn clone_repository_em_diretorio_nao_vazio_retorna_erro_claro() {
+        let dest = make_temp_dir();
+        std::fs::write(dest.path().join("arquivo.txt"), "x").unwrap();
+
+        let err = clone_repository("https://example.com/repo.git", dest.path(), None, &CancelToken::default()).unwrap_err();
+        assert_eq!(err.code, "DESTINATION_NOT_EMPTY");
+    }
 }
@@ -1,4 +1,5 @@
 use crate::error::{AppError, AppResult};
+use crate::git::conflicted_paths;
 use git2::Repository;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -19,6 +20,23 @@ pub struct StashInfo {
     pub date: i64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StashApplyResult {
+    /// Files left with conflict markers for the conflict UI to resolve
+    pub conflicts: Vec<String>,
+    /// Whether a pop actually removed the stash; kept when it conflicted
+    pub dropped: bool,
+}
+
+/// Branch a stash was created on, from its "On <branch>:" / "WIP on <branch>:" prefix
+fn stash_branch(message: &str) -> Option<String> {
+    message
+        .strip_prefix("On ")
+        .or_else(|| message.strip_prefix("WIP on "))
+        .and_then(|s| s.split(':').next())
+        .map(String::from)
+}
+
 pub fn list_stashes(repo: &mut Repository) -> AppResult<Vec<StashInfo>> {
     // First collect basic stash info
     let mut stash_data: Vec<(usize, String, git2::Oid)> = Vec::new();
@@ -31,19 +49,7 @@ pub fn list_stashes(repo: &mut Repository) -> AppResult<Vec<StashInfo>> {
     // Then process each stash
     let mut stashes = Vec::new();
     for (index, message, oid) in stash_data {
-        let branch = if message.starts_with("On ") {
-            message
-                .strip_prefix("On ")
-                .and_then(|s| s.split(':').next())
-                .map(String::from)
-        } else if message.starts_with("WIP on ") {
-            message
-                .strip_prefix("WIP on ")
-                .and_then(|s| s.split(':').next())
-                .map(String::from)
-        } else {
-            None
-        };
+        let branch = stash_branch(&message);
 
         let date = repo
             .find_commit(oid)
@@ -83,11 +89,35 @@ pub fn create_stash(
     Ok(oid.to_string()[..7].to_string())
 }
 
-/// Create stash with specific files using git command
+/// Rejects flag combinations git would refuse or silently ignore.
+/// `staged_only` stashes just the index and leaves the worktree as is, so it
+/// can't be combined with `keep_index` (which stashes everything but keeps
+/// the index) nor with `include_untracked` (untracked files are never staged).
+pub fn validate_stash_options(include_untracked: bool, keep_index: bool, staged_only: bool) -> AppResult<()> {
+    if staged_only && keep_index {
+        return Err(AppError::with_details(
+            "INVALID_STASH_OPTIONS",
+            "Opções de stash incompatíveis",
+            "staged_only não pode ser combinado com keep_index",
+        ));
+    }
+    if staged_only && include_untracked {
+        return Err(AppError::with_details(
+            "INVALID_STASH_OPTIONS",
+            "Opções de stash incompatíveis",
+            "staged_only não pode ser combinado com include_untracked",
+        ));
+    }
+    Ok(())
+}
+
+/// Create stash with specific files (or only the staged changes) using git command,
+/// since libgit2's stash flags can't express either
 pub fn create_stash_with_files(
     repo_path: &Path,
     message: Option<&str>,
     include_untracked: bool,
+    staged_only: bool,
     files: &[String],
 ) -> AppResult<String> {
     let mut args = vec!["stash", "push"];
@@ -96,15 +126,21 @@ pub fn create_stash_with_files(
         args.push("--include-untracked");
     }
 
+    if staged_only {
+        args.push("--staged");
+    }
+
     if let Some(msg) = message {
         args.push("-m");
         args.push(msg);
     }
 
     // Add separator and files
-    args.push("--");
-    for file in files {
-        args.push(file);
+    if !files.is_empty() {
+        args.push("--");
+        for file in files {
+            args.push(file);
+        }
     }
 
     let mut cmd = Command::new("git");
@@ -130,15 +166,74 @@ pub fn create_stash_with_files(
     }
 }
 
-pub fn apply_stash(repo: &mut Repository, index: usize, drop_after: bool) -> AppResult<()> {
+/// Applies a stash over the current (possibly dirty) working tree. Conflicts
+/// with HEAD are left as markers and reported; like `git stash pop`, a pop
+/// that conflicts keeps the stash so nothing is lost.
+pub fn apply_stash(repo: &mut Repository, index: usize, drop_after: bool) -> AppResult<StashApplyResult> {
     let mut opts = git2::StashApplyOptions::new();
 
-    if drop_after {
-        repo.stash_pop(index, Some(&mut opts))?;
-    } else {
-        repo.stash_apply(index, Some(&mut opts))?;
+    // ECONFLICT here means uncommitted edits would be overwritten; nothing was touched
+    repo.stash_apply(index, Some(&mut opts)).map_err(|e| {
+        if e.code() == git2::ErrorCode::Conflict {
+            AppError::with_details(
+                "STASH_CONFLICT",
+                "Alterações locais seriam sobrescritas pelo stash",
+                e.message(),
+            )
+        } else {
+            AppError::from(e)
+        }
+    })?;
+
+    let conflicts = conflicted_paths(&repo.index()?)?;
+    let dropped = drop_after && conflicts.is_empty();
+    if dropped {
+        repo.stash_drop(index)?;
+    }
+
+    Ok(StashApplyResult { conflicts, dropped })
+}
+
+/// Changes a stash's message. libgit2 can't edit one stash in place, so the
+/// `refs/stash` reflog is rewritten with the same commits, committers and
+/// order; only the target entry's message changes, keeping its "On <branch>:" prefix.
+pub fn rename_stash(repo: &Repository, index: usize, new_message: &str) -> AppResult<()> {
+    let new_message = new_message.trim();
+    if new_message.is_empty() {
+        return Err(AppError::new("INVALID_STASH_MESSAGE", "Mensagem do stash não pode ser vazia"));
     }
 
+    let mut reflog = repo.reflog("refs/stash")?;
+    if index >= reflog.len() {
+        return Err(AppError::stash_not_found(index));
+    }
+
+    let mut entries: Vec<(git2::Oid, git2::Signature<'static>, String)> = reflog
+        .iter()
+        .map(|entry| {
+            (
+                entry.id_new(),
+                entry.committer().to_owned(),
+                entry.message().unwrap_or("").to_string(),
+            )
+        })
+        .collect();
+
+    let message = &mut entries[index].2;
+    *message = match stash_branch(message) {
+        Some(branch) => format!("On {}: {}", branch, new_message),
+        None => new_message.to_string(),
+    };
+
+    while !reflog.is_empty() {
+        reflog.remove(0, false)?;
+    }
+    // Entry 0 is the newest, so re-append from the oldest
+    for (oid, committer, message) in entries.iter().rev() {
+        reflog.append(*oid, committer, Some(message))?;
+    }
+    reflog.write()?;
+
     Ok(())
 }
 
@@ -316,4 +411,110 @@ mod tests {
         let stashes = list_stashes(&mut repo).unwrap();
         assert!(stashes.is_empty());
     }
+
+    fn commit_readme(dir: &TempDir, repo: &Repository, content: &str) {
+        std::fs::write(dir.path().join("README.md"), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, content, &tree, &[&head])
+            .unwrap();
+    }
+
+    #[test]
+    fn pop_com_conflito_lista_arquivos_e_mantem_stash() {
+        let (dir, mut repo) = setup_repo_with_commit();
+        std::fs::write(dir.path().join("README.md"), "do stash").unwrap();
+        create_stash(&mut repo, Some("conflita"), false, false).unwrap();
+        commit_readme(&dir, &repo, "local");
+
+        let result = apply_stash(&mut repo, 0, true).unwrap();
+        assert_eq!(result.conflicts, vec!["README.md".to_string()]);
+        assert!(!result.dropped);
+        assert_eq!(list_stashes(&mut repo).unwrap().len(), 1);
+
+        let content = std::fs::read_to_string(dir.path().join("README.md")).unwrap();
+        assert!(content.contains("<<<<<<<"));
+    }
+
+    #[test]
+    fn apply_sobre_edicao_local_do_mesmo_arquivo_retorna_stash_conflict() {
+        let (dir, mut repo) = setup_repo_with_commit();
+        std::fs::write(dir.path().join("README.md"), "do stash").unwrap();
+        create_stash(&mut repo, Some("conflita"), false, false).unwrap();
+        std::fs::write(dir.path().join("README.md"), "edição local").unwrap();
+
+        let err = apply_stash(&mut repo, 0, true).unwrap_err();
+        assert_eq!(err.code, "STASH_CONFLICT");
+        assert_eq!(list_stashes(&mut repo).unwrap().len(), 1);
+        let content = std::fs::read_to_string(dir.path().join("README.md")).unwrap();
+        assert_eq!(content, "edição local");
+    }
+
+    #[test]
+    fn validate_stash_options_rejeita_staged_only_com_keep_index() {
+        let err = validate_stash_options(false, true, true).unwrap_err();
+        assert_eq!(err.code, "INVALID_STASH_OPTIONS");
+        assert!(validate_stash_options(true, false, true).is_err());
+        assert!(validate_stash_options(true, true, false).is_ok());
+    }
+
+    #[test]
+    fn stash_staged_only_mantem_alteracoes_nao_staged() {
+        let (dir, mut repo) = setup_repo_with_commit();
+        add_modified_file(&dir, &repo);
+        std::fs::write(dir.path().join("outro.txt"), "novo").unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("outro.txt")).unwrap();
+            index.write().unwrap();
+        }
+        std::fs::write(dir.path().join("solto.txt"), "x").unwrap();
+
+        create_stash_with_files(dir.path(), Some("só staged"), false, true, &[]).unwrap();
+
+        assert_eq!(list_stashes(&mut repo).unwrap().len(), 1);
+        let readme = std::fs::read_to_string(dir.path().join("README.md")).unwrap();
+        assert_eq!(readme, "base");
+        assert!(!dir.path().join("outro.txt").exists());
+        assert!(dir.path().join("solto.txt").exists());
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        assert!(index.get_path(std::path::Path::new("outro.txt"), 0).is_none());
+    }
+
+    #[test]
+    fn rename_stash_preserva_branch_e_ordem() {
+        let (dir, mut repo) = setup_repo_with_commit();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        add_modified_file(&dir, &repo);
+        create_stash(&mut repo, Some("antigo"), false, false).unwrap();
+        std::fs::write(dir.path().join("README.md"), "segundo").unwrap();
+        create_stash(&mut repo, None, false, false).unwrap();
+        let before = list_stashes(&mut repo).unwrap();
+
+        rename_stash(&repo, 1, "novo nome").unwrap();
+
+        let after = list_stashes(&mut repo).unwrap();
+        assert_eq!(after.len(), 2);
+        assert_eq!(after[1].message, format!("On {}: novo nome", branch));
+        assert_eq!(after[1].branch.as_deref(), Some(branch.as_str()));
+        assert_eq!(after[1].commit_hash, before[1].commit_hash);
+        assert_eq!(after[0].message, before[0].message);
+
+        apply_stash(&mut repo, 1, true).unwrap();
+        let content = std::fs::read_to_string(dir.path().join("README.md")).unwrap();
+        assert_eq!(content, "modificado");
+    }
+
+    #[test]
+    fn rename_stash_indice_invalido_retorna_stash_not_found() {
+        let (_dir, repo) = setup_repo_with_commit();
+        let err = rename_stash(&repo, 0, "x").unwrap_err();
+        assert_eq!(err.code, "STASH_NOT_FOUND");
+    }
 }
@@ -0,0 +1,162 @@
+use crate::error::AppResult;
+use git2::{BranchType, Oid, Repository};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+const MAX_STATS_CACHE_ENTRIES: usize = 64;
+
+/// History stats keyed by HEAD OID; the history behind a commit never changes
+static HISTORY_STATS_CACHE: Lazy<Mutex<HashMap<Oid, HistoryStats>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy)]
+struct HistoryStats {
+    commit_count: usize,
+    contributor_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RepoStats {
+    pub local_branch_count: usize,
+    pub remote_branch_count: usize,
+    pub tag_count: usize,
+    /// Size of the git directory on disk, in bytes
+    pub size_on_disk: u64,
+    /// Only computed with `deep`, since it walks the whole history
+    pub commit_count: Option<usize>,
+    /// Unique author emails reachable from HEAD (only with `deep`)
+    pub contributor_count: Option<usize>,
+}
+
+pub fn get_repo_stats(repo: &Repository, deep: bool) -> AppResult<RepoStats> {
+    let local_branch_count = repo.branches(Some(BranchType::Local))?.count();
+    let remote_branch_count = repo.branches(Some(BranchType::Remote))?.count();
+    let tag_count = repo.tag_names(None)?.len();
+    let size_on_disk = dir_size(repo.path());
+
+    let history = if deep {
+        Some(history_stats(repo)?)
+    } else {
+        None
+    };
+
+    Ok(RepoStats {
+        local_branch_count,
+        remote_branch_count,
+        tag_count,
+        size_on_disk,
+        commit_count: history.map(|h| h.commit_count),
+        contributor_count: history.map(|h| h.contributor_count),
+    })
+}
+
+fn history_stats(repo: &Repository) -> AppResult<HistoryStats> {
+    // Unborn HEAD has no history yet
+    let Some(head) = repo.head().ok().and_then(|h| h.target()) else {
+        return Ok(HistoryStats {
+            commit_count: 0,
+            contributor_count: 0,
+        });
+    };
+
+    if let Some(stats) = HISTORY_STATS_CACHE.lock().unwrap().get(&head) {
+        return Ok(*stats);
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head)?;
+
+    let mut commit_count = 0;
+    let mut authors = HashSet::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        commit_count += 1;
+        authors.insert(commit.author().email().unwrap_or("").to_lowercase());
+    }
+
+    let stats = HistoryStats {
+        commit_count,
+        contributor_count: authors.len(),
+    };
+
+    let mut cache = HISTORY_STATS_CACHE.lock().unwrap();
+    if cache.len() >= MAX_STATS_CACHE_ENTRIES {
+        cache.clear();
+    }
+    cache.insert(head, stats);
+
+    Ok(stats)
+}
+
+/// Total size of regular files under `path`, without following symlinks
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn setup_repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    fn make_commit(repo: &Repository, dir: &Path, content: &str, email: &str) -> Oid {
+        fs::write(dir.join("a.txt"), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", email).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, content, &tree, &parents).unwrap()
+    }
+
+    #[test]
+    fn get_repo_stats_conta_commits_e_autores_com_deep() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "um", "ana@test.com");
+        make_commit(&repo, dir.path(), "dois", "ANA@test.com");
+        let head = make_commit(&repo, dir.path(), "tres", "bia@test.com");
+        repo.tag_lightweight("v1", &repo.find_object(head, None).unwrap(), false)
+            .unwrap();
+
+        let stats = get_repo_stats(&repo, true).unwrap();
+        assert_eq!(stats.commit_count, Some(3));
+        assert_eq!(stats.contributor_count, Some(2));
+        assert_eq!(stats.local_branch_count, 1);
+        assert_eq!(stats.tag_count, 1);
+        assert!(stats.size_on_disk > 0);
+    }
+
+    #[test]
+    fn get_repo_stats_sem_deep_omite_historico() {
+        let (dir, repo) = setup_repo();
+        make_commit(&repo, dir.path(), "um", "ana@test.com");
+
+        let stats = get_repo_stats(&repo, false).unwrap();
+        assert_eq!(stats.commit_count, None);
+        assert_eq!(stats.contributor_count, None);
+        assert_eq!(stats.remote_branch_count, 0);
+    }
+}
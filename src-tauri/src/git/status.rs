@@ -1,4 +1,5 @@
 use crate::error::{AppError, AppResult};
+use crate::git::is_lfs_tracked;
 use git2::{BranchType, Repository, StatusOptions};
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +8,8 @@ pub struct FileStatus {
     pub path: String,
     pub status: FileStatusType,
     pub is_binary: bool,
+    /// Tracked by Git LFS according to `.gitattributes`
+    pub is_lfs: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -22,6 +25,37 @@ pub enum FileStatusType {
     Conflicted,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatusEntry {
+    pub path: String,
+    pub index_status: Option<FileStatusType>,
+    pub worktree_status: Option<FileStatusType>,
+}
+
+/// An untracked directory reported as one entry, like git's `?? newdir/`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UntrackedDir {
+    /// With a trailing `/`, as it appears in `untracked_files`
+    pub path: String,
+    /// Files inside, ignored ones excluded; stops at `UNTRACKED_COUNT_LIMIT`
+    pub file_count: usize,
+    /// The walk stopped at the limit, so there may be more files
+    pub file_count_capped: bool,
+}
+
+/// Walking a huge untracked dir (a stray `node_modules`) would stall the status
+pub const UNTRACKED_COUNT_LIMIT: usize = 1000;
+
+/// Configured upstream of the checked-out branch
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UpstreamInfo {
+    pub remote: String,
+    /// Branch name on the remote, e.g. `main`
+    pub branch: String,
+    /// Local tracking ref, e.g. `refs/remotes/origin/main`
+    pub ref_name: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RepoStatus {
     pub current_branch: String,
@@ -29,15 +63,29 @@ pub struct RepoStatus {
     pub staged_files: Vec<FileStatus>,
     pub unstaged_files: Vec<FileStatus>,
     pub untracked_files: Vec<String>,
+    /// Only filled with `collapse_untracked`; their paths are in `untracked_files` too
+    #[serde(default)]
+    pub untracked_dirs: Vec<UntrackedDir>,
     pub conflicted_files: Vec<String>,
+    pub ignored_files: Vec<String>,
+    /// One entry per changed path with both columns, so a file that is staged
+    /// and then modified again shows up once as "MM" instead of twice.
+    pub entries: Vec<StatusEntry>,
     pub ahead: usize,
     pub behind: usize,
+    /// `None` when HEAD's branch tracks nothing (or HEAD is detached)
+    pub upstream: Option<UpstreamInfo>,
     pub is_rebasing: bool,
     pub is_merging: bool,
     pub is_cherry_picking: bool,
+    pub is_detached: bool,
+    /// Full hash HEAD points at while detached
+    pub detached_commit: Option<String>,
 }
 
-pub fn get_status(repo: &Repository) -> AppResult<RepoStatus> {
+/// With `collapse_untracked`, a new directory is one entry instead of every
+/// file under it, so adding a huge folder doesn't flood the list
+pub fn get_status(repo: &Repository, include_ignored: bool, collapse_untracked: bool) -> AppResult<RepoStatus> {
     let head = repo.head().ok();
 
     let current_branch = head
@@ -50,18 +98,30 @@ pub fn get_status(repo: &Repository) -> AppResult<RepoStatus> {
         .and_then(|h| h.target())
         .map(|oid| oid.to_string()[..7].to_string());
 
+    let is_detached = repo.head_detached().unwrap_or(false);
+    let detached_commit = if is_detached {
+        head.as_ref().and_then(|h| h.target()).map(|oid| oid.to_string())
+    } else {
+        None
+    };
+
     let mut status_opts = StatusOptions::new();
     status_opts
         .include_untracked(true)
-        .include_ignored(false)
-        .recurse_untracked_dirs(true);
+        .include_ignored(include_ignored)
+        // Ignored directories are reported once (e.g. "target/") instead of every nested file
+        .recurse_ignored_dirs(false)
+        .recurse_untracked_dirs(!collapse_untracked);
 
     let statuses = repo.statuses(Some(&mut status_opts))?;
 
     let mut staged_files = Vec::new();
     let mut unstaged_files = Vec::new();
     let mut untracked_files = Vec::new();
+    let mut untracked_dirs = Vec::new();
     let mut conflicted_files = Vec::new();
+    let mut ignored_files = Vec::new();
+    let mut entries = Vec::new();
 
     for entry in statuses.iter() {
         let path = entry.path().unwrap_or("").to_string();
@@ -70,50 +130,59 @@ pub fn get_status(repo: &Repository) -> AppResult<RepoStatus> {
         // Conflicted
         if status.is_conflicted() {
             conflicted_files.push(path.clone());
+            entries.push(StatusEntry {
+                path,
+                index_status: Some(FileStatusType::Conflicted),
+                worktree_status: Some(FileStatusType::Conflicted),
+            });
             continue;
         }
 
+        if status.is_ignored() {
+            ignored_files.push(path);
+            continue;
+        }
+
+        let index_status = index_status_of(status);
+        let worktree_status = worktree_status_of(status);
+
         // Staged changes
-        if status.is_index_new() {
+        if let Some(ref s) = index_status {
             staged_files.push(FileStatus {
                 path: path.clone(),
-                status: FileStatusType::Added,
-                is_binary: false,
-            });
-        } else if status.is_index_modified() {
-            staged_files.push(FileStatus {
-                path: path.clone(),
-                status: FileStatusType::Modified,
-                is_binary: false,
-            });
-        } else if status.is_index_deleted() {
-            staged_files.push(FileStatus {
-                path: path.clone(),
-                status: FileStatusType::Deleted,
-                is_binary: false,
-            });
-        } else if status.is_index_renamed() {
-            staged_files.push(FileStatus {
-                path: path.clone(),
-                status: FileStatusType::Renamed,
+                status: s.clone(),
                 is_binary: false,
+                is_lfs: is_lfs_tracked(repo, &path),
             });
         }
 
         // Unstaged changes
-        if status.is_wt_new() {
-            untracked_files.push(path);
-        } else if status.is_wt_modified() {
-            unstaged_files.push(FileStatus {
+        match worktree_status {
+            Some(FileStatusType::Untracked) => {
+                if path.ends_with('/') {
+                    let file_count = count_untracked_files(repo, &path);
+                    untracked_dirs.push(UntrackedDir {
+                        path: path.clone(),
+                        file_count,
+                        file_count_capped: file_count >= UNTRACKED_COUNT_LIMIT,
+                    });
+                }
+                untracked_files.push(path.clone());
+            }
+            Some(ref s) => unstaged_files.push(FileStatus {
                 path: path.clone(),
-                status: FileStatusType::Modified,
+                status: s.clone(),
                 is_binary: false,
-            });
-        } else if status.is_wt_deleted() {
-            unstaged_files.push(FileStatus {
+                is_lfs: is_lfs_tracked(repo, &path),
+            }),
+            None => {}
+        }
+
+        if index_status.is_some() || worktree_status.is_some() {
+            entries.push(StatusEntry {
                 path,
-                status: FileStatusType::Deleted,
-                is_binary: false,
+                index_status,
+                worktree_status,
             });
         }
     }
@@ -138,12 +207,181 @@ pub fn get_status(repo: &Repository) -> AppResult<RepoStatus> {
         staged_files,
         unstaged_files,
         untracked_files,
+        untracked_dirs,
         conflicted_files,
+        ignored_files,
+        entries,
         ahead,
         behind,
+        upstream: head.as_ref().and_then(|h| head_upstream(repo, h)),
         is_rebasing,
         is_merging,
         is_cherry_picking,
+        is_detached,
+        detached_commit,
+    })
+}
+
+/// Files under an untracked directory, up to `UNTRACKED_COUNT_LIMIT`.
+/// Ignored paths are skipped and a nested repo counts as one entry, the
+/// way `git status` lists it.
+fn count_untracked_files(repo: &Repository, dir: &str) -> usize {
+    let Some(workdir) = repo.workdir() else {
+        return 0;
+    };
+    let mut count = 0;
+    let mut pending = vec![dir.trim_end_matches('/').to_string()];
+
+    while let Some(rel) = pending.pop() {
+        let Ok(read_dir) = std::fs::read_dir(workdir.join(&rel)) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ".git" {
+                continue;
+            }
+            let child = format!("{}/{}", rel, name);
+            if repo.is_path_ignored(&child).unwrap_or(false) {
+                continue;
+            }
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() && !entry.path().join(".git").exists() => pending.push(child),
+                Ok(_) => count += 1,
+                Err(_) => {}
+            }
+            if count >= UNTRACKED_COUNT_LIMIT {
+                return count;
+            }
+        }
+    }
+    count
+}
+
+/// Counts for a repo tab badge, without the per-file lists of `get_status`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RepoStatusSummary {
+    pub branch: String,
+    /// Changed, untracked or conflicted paths (untracked dirs count once)
+    pub dirty_count: usize,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+pub fn get_status_summary(repo: &Repository) -> AppResult<RepoStatusSummary> {
+    let branch = repo
+        .head()
+        .ok()
+        .and_then(|h| h.shorthand().map(String::from))
+        .unwrap_or_else(|| "HEAD".to_string());
+
+    let mut status_opts = StatusOptions::new();
+    status_opts
+        .include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .exclude_submodules(true);
+    let dirty_count = repo.statuses(Some(&mut status_opts))?.len();
+
+    let (ahead, behind) = get_ahead_behind(repo).unwrap_or((0, 0));
+
+    Ok(RepoStatusSummary {
+        branch,
+        dirty_count,
+        ahead,
+        behind,
+    })
+}
+
+/// Dirty-or-not answer for preflight checks before checkout, pull or a hard reset
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct WorkingTreeState {
+    pub clean: bool,
+    pub staged: usize,
+    pub unstaged: usize,
+    /// Untracked dirs count once
+    pub untracked: usize,
+    pub conflicted: usize,
+}
+
+/// Much cheaper than `get_status`: no per-file lists, no rename detection,
+/// untracked dirs aren't walked and submodules are skipped.
+pub fn is_working_tree_clean(repo: &Repository) -> AppResult<WorkingTreeState> {
+    let mut status_opts = StatusOptions::new();
+    status_opts
+        .include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .exclude_submodules(true)
+        .renames_head_to_index(false)
+        .renames_index_to_workdir(false);
+
+    let mut state = WorkingTreeState::default();
+    for entry in repo.statuses(Some(&mut status_opts))?.iter() {
+        let status = entry.status();
+        if status.is_conflicted() {
+            state.conflicted += 1;
+            continue;
+        }
+        if index_status_of(status).is_some() {
+            state.staged += 1;
+        }
+        if status.is_wt_new() {
+            state.untracked += 1;
+        } else if worktree_status_of(status).is_some() {
+            state.unstaged += 1;
+        }
+    }
+    state.clean = state.staged + state.unstaged + state.untracked + state.conflicted == 0;
+    Ok(state)
+}
+
+fn index_status_of(status: git2::Status) -> Option<FileStatusType> {
+    if status.is_index_new() {
+        Some(FileStatusType::Added)
+    } else if status.is_index_modified() || status.is_index_typechange() {
+        Some(FileStatusType::Modified)
+    } else if status.is_index_deleted() {
+        Some(FileStatusType::Deleted)
+    } else if status.is_index_renamed() {
+        Some(FileStatusType::Renamed)
+    } else {
+        None
+    }
+}
+
+fn worktree_status_of(status: git2::Status) -> Option<FileStatusType> {
+    if status.is_wt_new() {
+        Some(FileStatusType::Untracked)
+    } else if status.is_wt_modified() || status.is_wt_typechange() {
+        Some(FileStatusType::Modified)
+    } else if status.is_wt_deleted() {
+        Some(FileStatusType::Deleted)
+    } else if status.is_wt_renamed() {
+        Some(FileStatusType::Renamed)
+    } else {
+        None
+    }
+}
+
+/// Reads the tracking config only, so it stays cheap for the frequent status
+/// polls; the remote ref itself may be gone
+fn head_upstream(repo: &Repository, head: &git2::Reference) -> Option<UpstreamInfo> {
+    if !head.is_branch() {
+        return None;
+    }
+    let refname = head.name()?;
+    let remote = repo.branch_upstream_remote(refname).ok()?.as_str()?.to_string();
+    let ref_name = repo.branch_upstream_name(refname).ok()?.as_str()?.to_string();
+    let merge = repo
+        .config()
+        .ok()?
+        .get_string(&format!("branch.{}.merge", head.shorthand()?))
+        .ok()?;
+    let branch = merge.strip_prefix("refs/heads/").unwrap_or(&merge).to_string();
+
+    Some(UpstreamInfo {
+        remote,
+        branch,
+        ref_name,
     })
 }
 
@@ -170,3 +408,222 @@ fn get_ahead_behind(repo: &Repository) -> AppResult<(usize, usize)> {
     repo.graph_ahead_behind(head_oid, upstream_oid)
         .map_err(AppError::from)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn setup_repo_with_commit() -> (TempDir, Repository) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        std::fs::write(dir.path().join(".gitignore"), "target/\n*.log\n").unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new(".gitignore")).unwrap();
+            index.write().unwrap();
+            let tree_id = index.write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let sig = Signature::now("Teste", "teste@test.com").unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "inicial", &tree, &[])
+                .unwrap();
+        }
+
+        (dir, repo)
+    }
+
+    #[test]
+    fn is_working_tree_clean_conta_alteracoes_por_tipo() {
+        let (dir, repo) = setup_repo_with_commit();
+        assert!(is_working_tree_clean(&repo).unwrap().clean);
+
+        std::fs::write(dir.path().join("debug.log"), "ignorado").unwrap();
+        assert!(is_working_tree_clean(&repo).unwrap().clean);
+
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir(dir.path().join("novo")).unwrap();
+        std::fs::write(dir.path().join("novo/a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("novo/b.txt"), "b").unwrap();
+        std::fs::write(dir.path().join("staged.txt"), "s").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+
+        let state = is_working_tree_clean(&repo).unwrap();
+        assert!(!state.clean);
+        assert_eq!((state.staged, state.unstaged, state.untracked), (1, 1, 2));
+    }
+
+    #[test]
+    fn get_status_com_collapse_untracked_agrupa_diretorio_novo() {
+        let (dir, repo) = setup_repo_with_commit();
+        std::fs::write(dir.path().join(".gitignore"), "*.log\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("novo/sub")).unwrap();
+        std::fs::write(dir.path().join("novo/a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("novo/sub/b.txt"), "b").unwrap();
+        std::fs::write(dir.path().join("novo/debug.log"), "log").unwrap();
+
+        let status = get_status(&repo, false, true).unwrap();
+        assert_eq!(status.untracked_files, vec!["novo/"]);
+        assert_eq!(
+            status.untracked_dirs,
+            vec![UntrackedDir { path: "novo/".to_string(), file_count: 2, file_count_capped: false }]
+        );
+
+        let expanded = get_status(&repo, false, false).unwrap();
+        assert_eq!(expanded.untracked_files, vec!["novo/a.txt", "novo/sub/b.txt"]);
+        assert!(expanded.untracked_dirs.is_empty());
+    }
+
+    #[test]
+    fn count_untracked_files_para_no_limite_e_conta_repo_aninhado_uma_vez() {
+        let (dir, repo) = setup_repo_with_commit();
+        std::fs::create_dir_all(dir.path().join("grande")).unwrap();
+        for i in 0..UNTRACKED_COUNT_LIMIT + 5 {
+            std::fs::write(dir.path().join(format!("grande/{}.txt", i)), "x").unwrap();
+        }
+        Repository::init(dir.path().join("novo/vendor")).unwrap();
+        std::fs::write(dir.path().join("novo/vendor/a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("novo/b.txt"), "b").unwrap();
+
+        let status = get_status(&repo, false, true).unwrap();
+        assert_eq!(
+            status.untracked_dirs,
+            vec![
+                UntrackedDir { path: "grande/".to_string(), file_count: UNTRACKED_COUNT_LIMIT, file_count_capped: true },
+                UntrackedDir { path: "novo/".to_string(), file_count: 2, file_count_capped: false },
+            ]
+        );
+    }
+
+    #[test]
+    fn get_status_sem_include_ignored_nao_lista_ignorados() {
+        let (dir, repo) = setup_repo_with_commit();
+        std::fs::write(dir.path().join("debug.log"), "log").unwrap();
+
+        let status = get_status(&repo, false, false).unwrap();
+        assert!(status.ignored_files.is_empty());
+        assert!(status.untracked_files.is_empty());
+    }
+
+    #[test]
+    fn get_status_com_include_ignored_agrupa_diretorios_ignorados() {
+        let (dir, repo) = setup_repo_with_commit();
+        std::fs::write(dir.path().join("debug.log"), "log").unwrap();
+        std::fs::create_dir_all(dir.path().join("target/debug/deps")).unwrap();
+        std::fs::write(dir.path().join("target/debug/deps/a.o"), "a").unwrap();
+        std::fs::write(dir.path().join("target/debug/b.o"), "b").unwrap();
+
+        let status = get_status(&repo, true, false).unwrap();
+        assert!(status.ignored_files.contains(&"debug.log".to_string()));
+        assert!(status.ignored_files.contains(&"target/".to_string()));
+        assert_eq!(status.ignored_files.len(), 2);
+    }
+
+    #[test]
+    fn get_status_arquivo_estagiado_e_modificado_aparece_como_mm() {
+        let (dir, repo) = setup_repo_with_commit();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new(".gitignore")).unwrap();
+            index.write().unwrap();
+        }
+        std::fs::write(dir.path().join(".gitignore"), "target/\n*.tmp\n").unwrap();
+
+        let status = get_status(&repo, false, false).unwrap();
+        let entries: Vec<_> = status.entries.iter().filter(|e| e.path == ".gitignore").collect();
+        assert_eq!(entries.len(), 1, "o arquivo deve aparecer uma unica vez");
+        assert_eq!(entries[0].index_status, Some(FileStatusType::Modified));
+        assert_eq!(entries[0].worktree_status, Some(FileStatusType::Modified));
+        assert!(status.staged_files.iter().any(|f| f.path == ".gitignore"));
+        assert!(status.unstaged_files.iter().any(|f| f.path == ".gitignore"));
+    }
+
+    #[test]
+    fn get_status_arquivo_novo_tem_apenas_worktree_status() {
+        let (dir, repo) = setup_repo_with_commit();
+        std::fs::write(dir.path().join("novo.txt"), "novo").unwrap();
+
+        let status = get_status(&repo, false, false).unwrap();
+        let entry = status.entries.iter().find(|e| e.path == "novo.txt").unwrap();
+        assert_eq!(entry.index_status, None);
+        assert_eq!(entry.worktree_status, Some(FileStatusType::Untracked));
+        assert_eq!(status.untracked_files, vec!["novo.txt".to_string()]);
+    }
+
+    #[test]
+    fn get_status_indica_head_destacado() {
+        let (_dir, repo) = setup_repo_with_commit();
+        let status = get_status(&repo, false, false).unwrap();
+        assert!(!status.is_detached);
+        assert!(status.detached_commit.is_none());
+
+        let oid = repo.head().unwrap().target().unwrap();
+        repo.set_head_detached(oid).unwrap();
+
+        let status = get_status(&repo, false, false).unwrap();
+        assert!(status.is_detached);
+        assert_eq!(status.detached_commit, Some(oid.to_string()));
+        assert_eq!(status.current_branch, "HEAD");
+    }
+
+    #[test]
+    fn get_status_informa_upstream_da_branch_atual() {
+        let (_dir, repo) = setup_repo_with_commit();
+        assert_eq!(get_status(&repo, false, false).unwrap().upstream, None);
+
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        let head = repo.head().unwrap();
+        let branch = head.shorthand().unwrap().to_string();
+        repo.reference(&format!("refs/remotes/origin/{}", branch), head.target().unwrap(), false, "teste")
+            .unwrap();
+        repo.find_branch(&branch, BranchType::Local)
+            .unwrap()
+            .set_upstream(Some(&format!("origin/{}", branch)))
+            .unwrap();
+
+        let upstream = get_status(&repo, false, false).unwrap().upstream.unwrap();
+        assert_eq!(upstream.remote, "origin");
+        assert_eq!(upstream.branch, branch);
+        assert_eq!(upstream.ref_name, format!("refs/remotes/origin/{}", branch));
+    }
+
+    #[test]
+    fn get_status_summary_conta_caminhos_sujos() {
+        let (dir, repo) = setup_repo_with_commit();
+        assert_eq!(get_status_summary(&repo).unwrap().dirty_count, 0);
+
+        std::fs::write(dir.path().join(".gitignore"), "mudou\n").unwrap();
+        std::fs::create_dir(dir.path().join("novo")).unwrap();
+        std::fs::write(dir.path().join("novo/a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("novo/b.txt"), "b").unwrap();
+
+        let summary = get_status_summary(&repo).unwrap();
+        assert_eq!(summary.dirty_count, 2);
+        assert_eq!((summary.ahead, summary.behind), (0, 0));
+        assert_eq!(summary.branch, repo.head().unwrap().shorthand().unwrap());
+    }
+
+    #[test]
+    fn get_status_marca_arquivos_lfs_pelo_gitattributes() {
+        let (dir, repo) = setup_repo_with_commit();
+        std::fs::write(dir.path().join(".gitattributes"), "*.bin filter=lfs diff=lfs merge=lfs -text\n").unwrap();
+        std::fs::write(dir.path().join("dados.bin"), "ponteiro").unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "mudou\n").unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("dados.bin")).unwrap();
+            index.write().unwrap();
+        }
+
+        let status = get_status(&repo, false, false).unwrap();
+        let lfs = status.staged_files.iter().find(|f| f.path == "dados.bin").unwrap();
+        assert!(lfs.is_lfs);
+        let plain = status.unstaged_files.iter().find(|f| f.path == ".gitignore").unwrap();
+        assert!(!plain.is_lfs);
+    }
+}
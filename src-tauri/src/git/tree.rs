@@ -0,0 +1,144 @@
+use crate::error::{AppError, AppResult};
+use git2::{ObjectType, Repository};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TreeEntryKind {
+    File,
+    Dir,
+    Submodule,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TreeEntryInfo {
+    pub name: String,
+    pub path: String,
+    pub kind: TreeEntryKind,
+    pub oid: String,
+    /// Blob size in bytes; `None` for directories and submodules
+    pub size: Option<usize>,
+}
+
+/// Lists one level of the tree at `commit_ish` (HEAD by default) under `path`.
+/// Directories come first; an unborn HEAD yields an empty list.
+pub fn list_tree(
+    repo: &Repository,
+    commit_ish: Option<&str>,
+    path: Option<&str>,
+) -> AppResult<Vec<TreeEntryInfo>> {
+    let root = match commit_ish {
+        Some(spec) => repo
+            .revparse_single(spec)
+            .and_then(|obj| obj.peel_to_tree())
+            .map_err(|_| AppError::with_details("REF_NOT_FOUND", "Referencia nao encontrada", spec))?,
+        None => match repo.head().and_then(|h| h.peel_to_tree()) {
+            Ok(tree) => tree,
+            Err(_) => return Ok(Vec::new()),
+        },
+    };
+
+    let prefix = path.map(|p| p.trim_matches('/')).filter(|p| !p.is_empty());
+    let tree = match prefix {
+        Some(prefix) => {
+            let entry = root.get_path(Path::new(prefix)).map_err(|_| {
+                AppError::with_details("PATH_NOT_FOUND", "Caminho nao encontrado", prefix)
+            })?;
+            if entry.kind() != Some(ObjectType::Tree) {
+                return Err(AppError::with_details("NOT_A_DIRECTORY", "Caminho nao e um diretorio", prefix));
+            }
+            repo.find_tree(entry.id())?
+        }
+        None => root,
+    };
+
+    let odb = repo.odb()?;
+    let mut entries = Vec::new();
+    for entry in tree.iter() {
+        let name = entry.name().unwrap_or("").to_string();
+        let kind = match entry.kind() {
+            Some(ObjectType::Tree) => TreeEntryKind::Dir,
+            Some(ObjectType::Commit) => TreeEntryKind::Submodule,
+            _ => TreeEntryKind::File,
+        };
+        // Reading only the header avoids inflating every blob
+        let size = if kind == TreeEntryKind::File {
+            odb.read_header(entry.id()).ok().map(|(size, _)| size)
+        } else {
+            None
+        };
+
+        entries.push(TreeEntryInfo {
+            path: match prefix {
+                Some(prefix) => format!("{}/{}", prefix, name),
+                None => name.clone(),
+            },
+            name,
+            kind,
+            oid: entry.id().to_string(),
+            size,
+        });
+    }
+
+    entries.sort_by(|a, b| {
+        (b.kind == TreeEntryKind::Dir)
+            .cmp(&(a.kind == TreeEntryKind::Dir))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn setup_repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    fn commit_files(repo: &Repository, dir: &Path, files: &[(&str, &str)]) {
+        let mut index = repo.index().unwrap();
+        for (name, content) in files {
+            let full = dir.join(name);
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::write(&full, content).unwrap();
+            index.add_path(Path::new(name)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Teste", "teste@test.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "arquivos", &tree, &[]).unwrap();
+    }
+
+    #[test]
+    fn list_tree_lista_um_nivel_com_diretorios_primeiro() {
+        let (dir, repo) = setup_repo();
+        commit_files(&repo, dir.path(), &[("b.txt", "bb"), ("src/main.rs", "fn main() {}"), ("a.txt", "a")]);
+
+        let entries = list_tree(&repo, None, None).unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["src", "a.txt", "b.txt"]);
+        assert_eq!(entries[0].kind, TreeEntryKind::Dir);
+        assert_eq!(entries[0].size, None);
+        assert_eq!(entries[2].size, Some(2));
+
+        let nested = list_tree(&repo, Some("HEAD"), Some("src/")).unwrap();
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].path, "src/main.rs");
+
+        let err = list_tree(&repo, None, Some("a.txt")).unwrap_err();
+        assert_eq!(err.code, "NOT_A_DIRECTORY");
+    }
+
+    #[test]
+    fn list_tree_sem_head_retorna_vazio() {
+        let (_dir, repo) = setup_repo();
+        assert!(list_tree(&repo, None, None).unwrap().is_empty());
+    }
+}
@@ -118,6 +118,20 @@ pub fn add_worktree(
     branch: &str,
     create_branch: bool,
 ) -> AppResult<WorktreeInfo> {
+    if !create_branch {
+        // git refuses this too, but its message is hard to surface; report where the branch lives
+        if let Some(existing) = list_worktrees(repo_path)?
+            .into_iter()
+            .find(|w| w.branch.as_deref() == Some(branch))
+        {
+            return Err(AppError::with_details(
+                "WORKTREE_BRANCH_IN_USE",
+                "Branch ja esta em uso em outro worktree",
+                &format!("{} ({})", branch, existing.path),
+            ));
+        }
+    }
+
     let mut args = vec!["worktree", "add"];
     if create_branch {
         args.push("-b");
@@ -220,3 +234,63 @@ pub fn unlock_worktree(repo_path: &PathBuf, path: &str) -> AppResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Repository, Signature};
+    use tempfile::TempDir;
+
+    fn setup_repo_with_commit() -> (TempDir, Repository) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        std::fs::write(dir.path().join("README.md"), "# Teste").unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("README.md")).unwrap();
+            index.write().unwrap();
+            let tree_id = index.write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let sig = Signature::now("Teste", "teste@test.com").unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "commit inicial", &tree, &[])
+                .unwrap();
+        }
+
+        (dir, repo)
+    }
+
+    #[test]
+    fn parse_worktree_porcelain_le_branch_e_lock() {
+        let main = PathBuf::from("/repo");
+        let text = "worktree /repo\nHEAD 1234567890abcdef\nbranch refs/heads/main\n\n\
+                    worktree /wt/feature\nHEAD abcdef1234567890\nbranch refs/heads/feature\nlocked em uso\n";
+        let worktrees = parse_worktree_porcelain(text, &main).unwrap();
+
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[0].branch.as_deref(), Some("main"));
+        assert_eq!(worktrees[1].name, "feature");
+        assert_eq!(worktrees[1].head, "abcdef1");
+        assert!(worktrees[1].is_locked);
+        assert_eq!(worktrees[1].lock_reason.as_deref(), Some("em uso"));
+    }
+
+    #[test]
+    fn add_worktree_com_branch_ja_em_uso_retorna_erro() {
+        let (dir, repo) = setup_repo_with_commit();
+        let current = repo.head().unwrap().shorthand().unwrap().to_string();
+        let target = tempfile::tempdir().unwrap();
+        let wt_path = target.path().join("wt");
+
+        let result = add_worktree(
+            &dir.path().to_path_buf(),
+            &wt_path.to_string_lossy(),
+            &current,
+            false,
+        );
+
+        assert_eq!(result.unwrap_err().code, "WORKTREE_BRANCH_IN_USE");
+        assert!(!wt_path.exists());
+    }
+}
@@ -24,59 +24,109 @@ fn main() {
             commands::close_repo,
             commands::close_repo_by_id,
             commands::get_open_repos,
+            commands::get_all_repo_statuses,
             commands::set_active_repo,
             commands::get_repo_info,
             commands::get_repo_status,
+            commands::is_working_tree_clean,
+            commands::get_repo_stats,
+            commands::run_gc,
             commands::init_repo,
             commands::clone_repo,
+            commands::cancel_operation,
             commands::get_recent_repos,
             commands::remove_recent_repo,
             commands::clear_recent_repos,
+            commands::get_settings,
+            commands::update_settings,
             commands::get_git_config_value,
             commands::set_git_config_value,
+            commands::list_git_config,
             commands::read_file,
             commands::write_file,
             commands::open_in_vscode,
+            commands::open_in_editor,
             commands::open_in_explorer,
+            commands::open_in_file_manager,
             commands::open_in_terminal,
             // Branches
             commands::get_branches,
+            commands::get_branch_details,
+            commands::get_branch_description,
+            commands::set_branch_description,
             commands::get_current_branch,
             commands::create_branch,
             commands::checkout_branch,
+            commands::checkout_commit,
             commands::delete_branch,
             commands::rename_branch,
+            commands::copy_branch,
             commands::merge_branch,
             // Commits
             commands::get_commits,
+            commands::get_commit_count,
+            commands::search_commits,
+            commands::get_commit_graph,
             commands::get_commit,
             commands::get_file_history,
             commands::create_commit,
+            commands::add_to_last_commit,
+            commands::can_commit,
+            commands::get_commit_template,
+            commands::validate_commit_message,
+            commands::squash_commits,
             commands::stage_files,
+            commands::stage_path,
             commands::stage_partial_changes,
             commands::unstage_files,
             commands::unstage_partial_changes,
             commands::stage_all,
             commands::unstage_all,
+            commands::preview_discard,
             commands::discard_changes,
+            commands::reset_working_tree,
+            commands::clean_working_tree,
+            commands::restore_files,
             commands::cherry_pick,
             commands::revert_commit,
+            commands::continue_cherry_pick,
+            commands::continue_revert,
             commands::reset_to_commit,
+            commands::reset_paths_to_commit,
+            commands::get_reflog,
             commands::get_rebase_range,
             commands::execute_interactive_rebase,
+            commands::get_rebase_status,
+            commands::rebase_continue,
+            commands::rebase_abort,
+            commands::drop_commit,
             commands::compare_refs,
+            commands::get_merge_base,
+            commands::get_ahead_behind_between,
             // Diff
             commands::get_working_diff,
             commands::get_staged_diff,
             commands::get_commit_diff,
+            commands::get_diff_stats,
+            commands::get_commit_diff_summary,
             commands::get_file_diff,
+            commands::get_file_diff_paged,
             commands::get_file_blame,
             commands::get_file_diff_at_commit,
+            commands::get_file_at_commit,
+            commands::list_tree,
+            commands::lfs_status,
             // Conflict
             commands::get_conflict_info,
+            commands::get_all_conflicts,
             commands::get_conflicted_file,
             commands::resolve_conflict,
+            commands::resolve_conflict_side,
+            commands::resolve_conflict_section,
             commands::abort_merge,
+            commands::commit_merge,
+            commands::abort_cherry_pick,
+            commands::abort_revert,
             // Remote
             commands::get_remotes,
             commands::add_remote,
@@ -85,21 +135,38 @@ fn main() {
             commands::fetch_remote,
             commands::pull_remote,
             commands::push_remote,
+            commands::push_dry_run,
             commands::set_upstream,
+            commands::unset_upstream,
+            commands::get_repo_ssh_key,
+            commands::set_repo_ssh_key,
+            commands::set_ssh_passphrase,
+            commands::has_ssh_passphrase,
             // Stash
             commands::get_stash_list,
             commands::create_stash,
             commands::apply_stash,
             commands::pop_stash,
+            commands::rename_stash,
             commands::drop_stash,
             commands::clear_stashes,
             // GitHub / Pull Requests
             commands::check_github_cli,
+            commands::get_repo_slug,
+            commands::get_default_branch,
+            commands::open_on_github_commit,
+            commands::open_on_github_branch,
+            commands::open_on_github_file,
             commands::list_pull_requests,
+            commands::refresh_pull_requests,
             commands::get_pull_request,
             commands::create_pull_request,
+            commands::publish_branch_and_pr,
             commands::get_pull_request_reviews,
             commands::get_pull_request_comments,
+            commands::get_pr_review_threads,
+            commands::resolve_review_thread,
+            commands::unresolve_review_thread,
             commands::get_pull_request_files,
             commands::review_pull_request,
             commands::comment_pull_request,
@@ -108,8 +175,12 @@ fn main() {
             commands::reopen_pull_request,
             commands::ready_pull_request,
             commands::get_pull_request_diff,
+            commands::get_pull_request_diff_structured,
             commands::get_pr_checks,
             commands::checkout_pull_request,
+            // GitHub Actions
+            commands::list_workflow_runs,
+            commands::get_workflow_run,
             // Issues
             commands::list_issues,
             commands::get_issue,
@@ -1,14 +1,20 @@
 use crate::error::{AppError, AppResult};
+use crate::git::{self, CancelToken};
 use git2::Repository;
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 pub struct AppState {
     /// Map of repo ID to repo path
     repos: Mutex<HashMap<String, PathBuf>>,
     /// Currently active repo ID
     active_repo: Mutex<Option<String>>,
+    /// Per-repo write locks; mutating commands serialize on these. Async, so
+    /// a command waiting its turn doesn't block a runtime thread.
+    write_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// Cancel flags of in-flight long operations, keyed by operation id
+    operations: Mutex<HashMap<String, CancelToken>>,
 }
 
 impl AppState {
@@ -16,13 +22,15 @@ impl AppState {
         Self {
             repos: Mutex::new(HashMap::new()),
             active_repo: Mutex::new(None),
+            write_locks: Mutex::new(HashMap::new()),
+            operations: Mutex::new(HashMap::new()),
         }
     }
 
-    /// Generate a unique ID for a repo based on its path
-    fn path_to_id(path: &PathBuf) -> String {
-        // Use the path string as ID (normalized)
-        path.to_string_lossy().to_string()
+    /// Generate a unique ID for a repo based on its path, so different
+    /// spellings of the same folder end up in the same tab
+    fn path_to_id(path: &Path) -> String {
+        git::repo_path_key(path)
     }
 
     /// Get the currently active repo path
@@ -39,6 +47,7 @@ impl AppState {
 
     /// Add a repo and set it as active
     pub fn set_repo_path(&self, path: PathBuf) {
+        let path = git::canonical_repo_path(&path);
         let id = Self::path_to_id(&path);
         {
             let mut repos = self.repos.lock().unwrap();
@@ -108,6 +117,51 @@ impl AppState {
         let path = self.require_repo_path()?;
         Repository::open(&path).map_err(|e| AppError::git_error(e))
     }
+
+    /// Runs a mutating operation (stage, commit, merge, reset, checkout,
+    /// rebase...) on the active repo while holding that repo's write lock.
+    /// Read-only commands keep using `open_repo` and never wait.
+    pub async fn with_repo_lock<T>(&self, f: impl FnOnce(&Repository) -> AppResult<T>) -> AppResult<T> {
+        self.with_repo_lock_mut(|repo| f(repo)).await
+    }
+
+    /// `with_repo_lock` for operations that need `&mut Repository` (stashes)
+    pub async fn with_repo_lock_mut<T>(&self, f: impl FnOnce(&mut Repository) -> AppResult<T>) -> AppResult<T> {
+        let path = self.require_repo_path()?;
+        let lock = {
+            let mut locks = self.write_locks.lock().unwrap();
+            locks.entry(Self::path_to_id(&path)).or_default().clone()
+        };
+        let _guard = lock.lock().await;
+
+        let mut repo = Repository::open(&path).map_err(AppError::git_error)?;
+        f(&mut repo)
+    }
+
+    /// Runs a long operation with a cancel token registered under `id` so
+    /// `cancel_operation` can reach it. Without an id the token is private.
+    pub fn with_operation<T>(&self, id: Option<&str>, f: impl FnOnce(&CancelToken) -> AppResult<T>) -> AppResult<T> {
+        let token = CancelToken::new();
+        let Some(id) = id else {
+            return f(&token);
+        };
+
+        self.operations.lock().unwrap().insert(id.to_string(), token.clone());
+        let result = f(&token);
+        self.operations.lock().unwrap().remove(id);
+        result
+    }
+
+    /// Flags a running operation for cancellation; false if it already finished
+    pub fn cancel_operation(&self, id: &str) -> bool {
+        match self.operations.lock().unwrap().get(id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl Default for AppState {
@@ -115,3 +169,95 @@ impl Default for AppState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn set_repo_path_reaproveita_aba_para_o_mesmo_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("Repo");
+        std::fs::create_dir(&repo).unwrap();
+
+        let state = AppState::new();
+        state.set_repo_path(repo.clone());
+        let with_slash = format!("{}{}", repo.to_string_lossy(), std::path::MAIN_SEPARATOR);
+        state.set_repo_path(PathBuf::from(with_slash));
+        state.set_repo_path(repo.join("."));
+
+        // Only meaningful where the filesystem is case-insensitive
+        let other_case = dir.path().join("REPO");
+        if other_case.exists() {
+            state.set_repo_path(other_case);
+        }
+
+        let open = state.get_open_repos();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].1, git::canonical_repo_path(&repo));
+        assert_eq!(state.get_repo_path(), Some(git::canonical_repo_path(&repo)));
+    }
+
+    #[test]
+    fn with_repo_lock_serializa_stage_e_commit_concorrentes() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Teste").unwrap();
+            config.set_str("user.email", "teste@test.com").unwrap();
+        }
+
+        let state = Arc::new(AppState::new());
+        state.set_repo_path(dir.path().to_path_buf());
+
+        let workers: Vec<_> = (0..4)
+            .map(|i| {
+                let state = Arc::clone(&state);
+                let workdir = dir.path().to_path_buf();
+                thread::spawn(move || {
+                    let name = format!("arquivo{}.txt", i);
+                    std::fs::write(workdir.join(&name), format!("{}", i)).unwrap();
+                    tauri::async_runtime::block_on(state.with_repo_lock(|repo| {
+                        git::stage_files(repo, std::slice::from_ref(&name), &workdir)?;
+                        git::create_commit(repo, &name, false, &git::AuthorOverride::default(), false, None)
+                    }))
+                    .unwrap();
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        let repo = state.open_repo().unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let mut revwalk = repo.revwalk().unwrap();
+        revwalk.push(head.id()).unwrap();
+        assert_eq!(revwalk.count(), 4);
+
+        // Every commit landed and the index matches HEAD
+        let tree = head.tree().unwrap();
+        assert_eq!(tree.len(), 4);
+        let mut index = repo.index().unwrap();
+        assert_eq!(index.write_tree().unwrap(), tree.id());
+    }
+
+    #[test]
+    fn cancel_operation_alcanca_token_registrado() {
+        let state = AppState::new();
+        assert!(!state.cancel_operation("fetch-1"));
+
+        let err = state
+            .with_operation(Some("fetch-1"), |token| {
+                assert!(state.cancel_operation("fetch-1"));
+                token.check()
+            })
+            .unwrap_err();
+        assert_eq!(err.code, "CANCELLED");
+
+        // Finished operations are unregistered
+        assert!(!state.cancel_operation("fetch-1"));
+    }
+}
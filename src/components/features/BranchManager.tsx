@@ -10,6 +10,7 @@ import {
 } from '@/hooks/useGit';
 import { git } from '@/services/git';
 import { getErrorMessage } from '@/lib/error';
+import { confirmIfDirty } from '@/lib/workingTree';
 import type { BranchInfo } from '@/types';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
@@ -40,6 +41,7 @@ import {
   User,
   Users,
   Link2,
+  Unlink,
 } from 'lucide-react';
 
 type OwnerFilter = 'all' | 'mine' | 'others' | 'old';
@@ -150,7 +152,8 @@ export default function BranchManager({ onOpenCompare }: BranchManagerProps) {
     );
   };
 
-  const handleCheckout = (name: string) => {
+  const handleCheckout = async (name: string) => {
+    if (!(await confirmIfDirty(`Fazer checkout de "${name}"`))) return;
     checkoutBranch.mutate(name, {
       onSuccess: () => {
         toast({ title: 'Branch ativada', description: `Checkout para "${name}"` });
@@ -185,11 +188,27 @@ export default function BranchManager({ onOpenCompare }: BranchManagerProps) {
     );
   };
 
-  const handleMerge = (name: string) => {
-    if (!confirm(`Fazer merge de "${name}" na branch atual?`)) return;
+  const handleMerge = (name: string, noFf = false, noCommit = false) => {
+    let message: string | undefined;
+    if (noCommit) {
+      if (!confirm(`Aplicar o merge de "${name}" sem commitar, para revisar antes?`)) return;
+    } else if (noFf) {
+      const input = window.prompt(`Mensagem do commit de merge de "${name}":`, `Merge branch '${name}'`);
+      if (input === null) return;
+      message = input;
+    } else if (!confirm(`Fazer merge de "${name}" na branch atual?`)) {
+      return;
+    }
 
-    mergeBranch.mutate(name, {
+    mergeBranch.mutate({ name, noFf, message, noCommit }, {
       onSuccess: (result) => {
+        if (result === 'pending') {
+          toast({
+            title: 'Merge aplicado',
+            description: 'Revise as alterações e conclua ou aborte o merge',
+          });
+          return;
+        }
         toast({
           title: 'Merge concluído',
           description: result === 'fast-forward' ? 'Fast-forward merge' : 'Merge commit criado',
@@ -228,6 +247,23 @@ export default function BranchManager({ onOpenCompare }: BranchManagerProps) {
     }
   };
 
+  const handleUnsetUpstream = async (name: string) => {
+    try {
+      await git.remote.unsetUpstream(name);
+      await refetchBranches?.();
+      toast({
+        title: 'Upstream removido',
+        description: `${name} não acompanha mais nenhuma branch remota`,
+      });
+    } catch (err) {
+      toast({
+        title: 'Erro ao remover upstream',
+        description: getErrorMessage(err),
+        variant: 'destructive',
+      });
+    }
+  };
+
   if (isLoading) {
     return (
       <div className="flex items-center justify-center h-full">
@@ -237,7 +273,8 @@ export default function BranchManager({ onOpenCompare }: BranchManagerProps) {
   }
 
   const BranchItem = ({ branch }: { branch: BranchInfo }) => {
-    const trackingRef = normalizeTrackingRef(branch.upstream);
+    const upstreamGone = branch.upstream_status === 'gone';
+    const trackingRef = upstreamGone ? null : normalizeTrackingRef(branch.upstream);
 
     return (
       <div
@@ -297,6 +334,15 @@ export default function BranchManager({ onOpenCompare }: BranchManagerProps) {
                 sem upstream
               </span>
             )}
+            {upstreamGone && (
+              <span
+                className="flex items-center gap-1 text-red-600"
+                title={`${branch.upstream} foi removida do remoto`}
+              >
+                <Link2 className="w-3 h-3" />
+                upstream removido
+              </span>
+            )}
           </div>
         </div>
 
@@ -338,9 +384,19 @@ export default function BranchManager({ onOpenCompare }: BranchManagerProps) {
                     onSelect: () => handleMerge(branch.name),
                     separatorBefore: currentBranchName != null && branch.name !== currentBranchName,
                   },
+                  {
+                    label: 'Fazer merge sem fast-forward',
+                    icon: GitMerge,
+                    onSelect: () => handleMerge(branch.name, true),
+                  },
+                  {
+                    label: 'Fazer merge para revisar',
+                    icon: GitMerge,
+                    onSelect: () => handleMerge(branch.name, true, true),
+                  },
                 ]
               : []),
-            ...(!branch.is_remote && !branch.upstream
+            ...(!branch.is_remote && (!branch.upstream || upstreamGone)
               ? [
                   {
                     label: 'Configurar upstream',
@@ -350,6 +406,16 @@ export default function BranchManager({ onOpenCompare }: BranchManagerProps) {
                   },
                 ]
               : []),
+            ...(!branch.is_remote && branch.upstream
+              ? [
+                  {
+                    label: 'Parar de rastrear upstream',
+                    icon: Unlink,
+                    onSelect: () => handleUnsetUpstream(branch.name),
+                    separatorBefore: !upstreamGone && !branch.is_current,
+                  },
+                ]
+              : []),
             ...(!branch.is_remote && !branch.is_current
               ? [
                   {
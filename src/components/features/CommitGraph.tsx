@@ -31,21 +31,6 @@ import { useDiffViewerStore } from '@/stores/diffViewerStore';
 import { Prism as SyntaxHighlighter } from 'react-syntax-highlighter';
 import { vscDarkPlus } from 'react-syntax-highlighter/dist/esm/styles/prism';
 
-// Map file extensions to language names
-const extensionToLanguage: Record<string, string> = {
-  js: 'javascript', jsx: 'jsx', ts: 'typescript', tsx: 'tsx',
-  py: 'python', rb: 'ruby', java: 'java', go: 'go', rs: 'rust',
-  c: 'c', cpp: 'cpp', h: 'c', hpp: 'cpp', cs: 'csharp', php: 'php',
-  swift: 'swift', scala: 'scala', sql: 'sql', html: 'html', xml: 'xml',
-  css: 'css', scss: 'scss', json: 'json', yaml: 'yaml', yml: 'yaml',
-  md: 'markdown', sh: 'bash', bash: 'bash', ps1: 'powershell',
-};
-
-function getLanguageFromPath(path: string): string {
-  const ext = path.split('.').pop()?.toLowerCase() || '';
-  return extensionToLanguage[ext] || 'text';
-}
-
 const customSyntaxStyle = {
   ...vscDarkPlus,
   'pre[class*="language-"]': { ...vscDarkPlus['pre[class*="language-"]'], background: 'transparent', margin: 0, padding: 0 },
@@ -397,7 +382,7 @@ function FileDiffViewer({ diff, isExpanded, onToggle, commit, allDiffs }: {
   allDiffs: DiffInfo[];
 }) {
   const { openDiff } = useDiffViewerStore();
-  const language = useMemo(() => getLanguageFromPath(diff.path), [diff.path]);
+  const language = diff.language ?? 'text';
 
   const handleOpenFullscreen = (e: React.MouseEvent) => {
     e.stopPropagation();
@@ -7,6 +7,7 @@ import {
 } from '@/hooks/useGit';
 import { git } from '@/services/git';
 import { getErrorMessage } from '@/lib/error';
+import { confirmIfDirty } from '@/lib/workingTree';
 import { useRepoStore } from '@/stores/repoStore';
 import { useDiffViewerStore } from '@/stores/diffViewerStore';
 import type { CommitInfo, DiffInfo } from '@/types';
@@ -31,6 +32,7 @@ import {
   History,
   Columns2,
   Workflow,
+  ExternalLink,
 } from 'lucide-react';
 import { useToast } from '@/components/ui/use-toast';
 import DiffViewer from './DiffViewer';
@@ -40,7 +42,7 @@ interface CommitHistoryProps {
 }
 
 export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
-  const { data: commits, isLoading } = useCommits();
+  const { data: commits, isLoading } = useCommits(undefined, 100, true, true, true);
   const cherryPickCommit = useCherryPickCommit();
   const revertCommit = useRevertCommit();
   const resetCommit = useResetCommit();
@@ -52,6 +54,8 @@ export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
   const [selectedCommit, setSelectedCommit] = useState<CommitInfo | null>(null);
   const [commitDiff, setCommitDiff] = useState<DiffInfo[] | null>(null);
   const [diffLoading, setDiffLoading] = useState(false);
+  // Which parent a merge commit is diffed against
+  const [parentIndex, setParentIndex] = useState(0);
   const [fileHistoryOpen, setFileHistoryOpen] = useState(false);
   const [fileHistoryPath, setFileHistoryPath] = useState<string | null>(null);
   const [rebaseOpen, setRebaseOpen] = useState(false);
@@ -94,6 +98,7 @@ export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
     const nextCommit =
       commits?.find((commit) => commit.hash === selectedCommitHash) || null;
     setSelectedCommit(nextCommit);
+    setParentIndex(0);
   }, [commits, selectedCommitHash]);
 
   useEffect(() => {
@@ -106,7 +111,7 @@ export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
     setDiffLoading(true);
 
     git.diff
-      .getCommit(selectedCommit.hash)
+      .getCommit(selectedCommit.hash, false, undefined, parentIndex)
       .then((diff) => {
         if (!cancelled) {
           setCommitDiff(diff);
@@ -127,7 +132,7 @@ export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
     return () => {
       cancelled = true;
     };
-  }, [selectedCommit]);
+  }, [selectedCommit, parentIndex]);
 
   const copyHash = (hash: string) => {
     navigator.clipboard.writeText(hash);
@@ -137,6 +142,16 @@ export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
     });
   };
 
+  const openOnGithub = (hash: string) => {
+    git.repo.openOnGithubCommit(hash).catch((err: unknown) => {
+      toast({
+        title: 'Erro',
+        description: getErrorMessage(err),
+        variant: 'destructive',
+      });
+    });
+  };
+
   const handleCherryPick = (commit: CommitInfo) => {
     if (!confirm(`Aplicar cherry-pick do commit ${commit.short_hash}?`)) {
       return;
@@ -181,10 +196,14 @@ export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
     });
   };
 
-  const handleReset = (commit: CommitInfo, mode: 'soft' | 'mixed' | 'hard') => {
+  const handleReset = async (commit: CommitInfo, mode: 'soft' | 'mixed' | 'hard') => {
     if (!confirm(`Fazer reset ${mode} para ${commit.short_hash}?`)) {
       return;
     }
+    // A hard reset throws local changes away for good
+    if (mode === 'hard' && !(await confirmIfDirty('Descartar tudo com reset hard'))) {
+      return;
+    }
 
     resetCommit.mutate(
       { commitHash: commit.hash, mode },
@@ -245,7 +264,9 @@ export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
                 >
                   <div className="flex items-center gap-2">
                     {commit.is_merge ? (
-                      <GitMerge className="h-4 w-4 shrink-0 text-purple-500" />
+                      <span title={commit.parent_summaries.map((s) => `← ${s}`).join('\n')}>
+                        <GitMerge className="h-4 w-4 shrink-0 text-purple-500" />
+                      </span>
                     ) : (
                       <GitCommit className="h-4 w-4 shrink-0 text-muted-foreground" />
                     )}
@@ -262,6 +283,13 @@ export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
                         locale: ptBR,
                       })}
                     </span>
+                    {commit.stats && (
+                      <span className="ml-auto font-mono">
+                        <span className="text-green-600">+{commit.stats.insertions}</span>{' '}
+                        <span className="text-red-600">−{commit.stats.deletions}</span>,{' '}
+                        {commit.stats.files_changed} arq.
+                      </span>
+                    )}
                   </div>
                 </div>
               ))}
@@ -364,6 +392,11 @@ export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
                         onSelect: () => copyHash(selectedCommit.hash),
                         separatorBefore: true,
                       },
+                      {
+                        label: 'Abrir no GitHub',
+                        icon: ExternalLink,
+                        onSelect: () => openOnGithub(selectedCommit.hash),
+                      },
                     ]}
                   />
                 </div>
@@ -397,6 +430,27 @@ export default function CommitHistory({ onOpenCompare }: CommitHistoryProps) {
                 </div>
               </div>
 
+              {selectedCommit.parents.length > 1 && (
+                <div className="flex items-center gap-1 border-b border-border px-4 py-2 text-xs">
+                  <span className="mr-1 text-muted-foreground">Alterações em relação a</span>
+                  {selectedCommit.parents.map((parent, index) => (
+                    <button
+                      key={parent}
+                      className={cn(
+                        'rounded px-2 py-0.5 font-mono transition-colors',
+                        index === parentIndex
+                          ? 'bg-primary text-primary-foreground'
+                          : 'bg-muted text-muted-foreground hover:text-foreground'
+                      )}
+                      title={selectedCommit.parent_summaries[index]}
+                      onClick={() => setParentIndex(index)}
+                    >
+                      pai {index + 1} · {parent.slice(0, 7)}
+                    </button>
+                  ))}
+                </div>
+              )}
+
               <ScrollArea className="flex-1">
                 {diffLoading ? (
                   <div className="flex h-32 items-center justify-center">
@@ -5,77 +5,6 @@ import { Loader2, Minus, Plus, Columns2, AlignLeft } from 'lucide-react';
 import { Prism as SyntaxHighlighter } from 'react-syntax-highlighter';
 import { vscDarkPlus } from 'react-syntax-highlighter/dist/esm/styles/prism';
 
-// Map file extensions to language names for syntax highlighting
-const extensionToLanguage: Record<string, string> = {
-  js: 'javascript',
-  jsx: 'jsx',
-  ts: 'typescript',
-  tsx: 'tsx',
-  py: 'python',
-  rb: 'ruby',
-  java: 'java',
-  kt: 'kotlin',
-  go: 'go',
-  rs: 'rust',
-  c: 'c',
-  cpp: 'cpp',
-  h: 'c',
-  hpp: 'cpp',
-  cs: 'csharp',
-  php: 'php',
-  swift: 'swift',
-  m: 'objectivec',
-  mm: 'objectivec',
-  scala: 'scala',
-  r: 'r',
-  sql: 'sql',
-  html: 'html',
-  htm: 'html',
-  xml: 'xml',
-  svg: 'xml',
-  css: 'css',
-  scss: 'scss',
-  sass: 'sass',
-  less: 'less',
-  json: 'json',
-  yaml: 'yaml',
-  yml: 'yaml',
-  toml: 'toml',
-  md: 'markdown',
-  sh: 'bash',
-  bash: 'bash',
-  zsh: 'bash',
-  fish: 'bash',
-  ps1: 'powershell',
-  dockerfile: 'docker',
-  makefile: 'makefile',
-  cmake: 'cmake',
-  gradle: 'groovy',
-  groovy: 'groovy',
-  lua: 'lua',
-  perl: 'perl',
-  pl: 'perl',
-  ex: 'elixir',
-  exs: 'elixir',
-  erl: 'erlang',
-  hs: 'haskell',
-  clj: 'clojure',
-  vim: 'vim',
-  vue: 'vue',
-  svelte: 'svelte',
-};
-
-function getLanguageFromPath(path: string): string {
-  const ext = path.split('.').pop()?.toLowerCase() || '';
-  const filename = path.split('/').pop()?.toLowerCase() || '';
-
-  if (filename === 'dockerfile') return 'docker';
-  if (filename === 'makefile') return 'makefile';
-  if (filename === 'cmakelists.txt') return 'cmake';
-
-  return extensionToLanguage[ext] || 'text';
-}
-
 // Custom style based on vscDarkPlus but with transparent background
 const customStyle = {
   ...vscDarkPlus,
@@ -185,7 +114,7 @@ export default function DiffViewer({
   onActionHunk,
   onActionLine,
 }: DiffViewerProps) {
-  const language = useMemo(() => getLanguageFromPath(diff.path), [diff.path]);
+  const language = diff.language ?? 'text';
   const actionIcon = hunkActionLabel?.toLowerCase().includes('unstage') ? Minus : Plus;
   const ActionIcon = actionIcon;
   const [sideBySide, setSideBySide] = useState(false);
@@ -199,6 +128,14 @@ export default function DiffViewer({
     );
   }
 
+  if (diff.is_lfs && diff.lfs_pointer) {
+    return (
+      <div className="p-4 text-center text-muted-foreground">
+        Objeto LFS ({(diff.lfs_pointer.size / (1024 * 1024)).toFixed(1)} MB)
+      </div>
+    );
+  }
+
   if (diff.hunks.length === 0) {
     return (
       <div className="p-4 text-center text-muted-foreground">
@@ -237,6 +174,17 @@ export default function DiffViewer({
     typeof onActionLine === 'function' &&
     (line.line_type === 'addition' || line.line_type === 'deletion');
 
+  const normalizationBadges = (
+    <>
+      {diff.line_ending_only && (
+        <span className="text-yellow-500" title="Só os finais de linha mudaram">
+          apenas CRLF/LF
+        </span>
+      )}
+      {diff.encoding && <span className="text-zinc-500">encoding: {diff.encoding}</span>}
+    </>
+  );
+
   if (sideBySide) {
     return (
       <div className="font-mono text-xs flex flex-col h-full">
@@ -245,6 +193,7 @@ export default function DiffViewer({
           <span className="text-green-400">+{diff.additions}</span>
           <span className="text-red-400">-{diff.deletions}</span>
           <span className="text-zinc-500">{diff.status}</span>
+          {normalizationBadges}
           <button type="button" onClick={() => setSideBySide(false)} className="ml-auto flex items-center gap-1 text-xs text-zinc-400 hover:text-zinc-200 border border-zinc-700 rounded px-2 py-0.5" title="Vista unificada">
             <AlignLeft className="h-3 w-3" />
             Unificado
@@ -312,6 +261,7 @@ export default function DiffViewer({
         <span className="text-green-400">+{diff.additions}</span>
         <span className="text-red-400">-{diff.deletions}</span>
         <span className="text-zinc-500">{diff.status}</span>
+        {normalizationBadges}
         <button type="button" onClick={() => setSideBySide(true)} className="ml-auto flex items-center gap-1 text-xs text-zinc-400 hover:text-zinc-200 border border-zinc-700 rounded px-2 py-0.5" title="Vista lado a lado">
           <Columns2 className="h-3 w-3" />
           Lado a lado
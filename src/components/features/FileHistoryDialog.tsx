@@ -5,7 +5,7 @@ import { Loader2, GitCommit } from 'lucide-react';
 import { git } from '@/services/git';
 import { getErrorMessage } from '@/lib/error';
 import { useToast } from '@/components/ui/use-toast';
-import type { CommitInfo, DiffInfo } from '@/types';
+import type { DiffInfo, FileHistoryEntry } from '@/types';
 import { format } from 'date-fns';
 import { ptBR } from 'date-fns/locale';
 import { Panel, PanelGroup, PanelResizeHandle } from 'react-resizable-panels';
@@ -25,8 +25,8 @@ export default function FileHistoryDialog({
   onNavigateToCommit,
 }: FileHistoryDialogProps) {
   const [loading, setLoading] = useState(false);
-  const [commits, setCommits] = useState<CommitInfo[]>([]);
-  const [selectedCommit, setSelectedCommit] = useState<CommitInfo | null>(null);
+  const [commits, setCommits] = useState<FileHistoryEntry[]>([]);
+  const [selectedCommit, setSelectedCommit] = useState<FileHistoryEntry | null>(null);
   const [diffLoading, setDiffLoading] = useState(false);
   const [diff, setDiff] = useState<DiffInfo | null>(null);
   const { toast } = useToast();
@@ -39,7 +39,7 @@ export default function FileHistoryDialog({
     setDiff(null);
 
     git.fileHistory
-      .get(path)
+      .get(path, undefined, true)
       .then((result) => {
         setCommits(result);
         if (result.length > 0) setSelectedCommit(result[0]);
@@ -65,7 +65,7 @@ export default function FileHistoryDialog({
     setDiffLoading(true);
 
     git.diff
-      .getFileAtCommit(selectedCommit.hash, path)
+      .getFileAtCommit(selectedCommit.hash, selectedCommit.path)
       .then((result) => {
         if (!cancelled) setDiff(result);
       })
@@ -1,12 +1,15 @@
-import React, { useState, useMemo } from 'react';
+import { useState, useMemo } from 'react';
 import {
   useGitHubCliStatus,
   usePullRequests,
   usePullRequest,
   usePRReviews,
   usePRComments,
+  usePRReviewThreads,
+  useResolveReviewThread,
   usePRFiles,
   usePRDiff,
+  useRefreshPullRequests,
   usePRChecks,
   useCreatePR,
   useReviewPR,
@@ -18,9 +21,10 @@ import {
   useCheckoutPR,
   useBranches,
   useRepoStatus,
+  useDefaultBranch,
 } from '@/hooks/useGit';
 import { getErrorMessage } from '@/lib/error';
-import type { PullRequest, CheckRun } from '@/types';
+import type { PullRequest, CheckRun, DiffInfo } from '@/types';
 import ActionMenu from '@/components/ui/action-menu';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
@@ -63,6 +67,7 @@ import {
   CircleDot,
   Ban,
   Minus,
+  RefreshCw,
 } from 'lucide-react';
 import { Prism as SyntaxHighlighter } from 'react-syntax-highlighter';
 import { vscDarkPlus } from 'react-syntax-highlighter/dist/esm/styles/prism';
@@ -307,12 +312,14 @@ function PRListItem({
 function CreatePRDialog({ onSuccess }: { onSuccess: () => void }) {
   const { data: branches } = useBranches();
   const { data: status } = useRepoStatus();
+  const { data: defaultBranch } = useDefaultBranch();
   const createPR = useCreatePR();
   const { toast } = useToast();
 
   const [title, setTitle] = useState('');
   const [body, setBody] = useState('');
-  const [base, setBase] = useState('main');
+  const [selectedBase, setBase] = useState<string | null>(null);
+  const base = selectedBase ?? defaultBranch ?? 'main';
   const [draft, setDraft] = useState(false);
   const [open, setOpen] = useState(false);
 
@@ -433,62 +440,39 @@ interface DiffLine {
   newLineNum: number | null;
 }
 
-function FileDiffView({ diff, filename }: { diff: string; filename: string }) {
+function FileDiffView({ diff, filename }: { diff: DiffInfo[]; filename: string }) {
   const language = useMemo(() => getLanguageFromPath(filename), [filename]);
+  const file = useMemo(() => diff.find((d) => d.path === filename), [diff, filename]);
 
-  // Parse the diff to extract only the relevant file's diff with line numbers
-  const parsedDiff = React.useMemo(() => {
-    const lines = diff.split('\n');
-    let capturing = false;
+  // Flatten the file's hunks into rows, keeping hunk headers as separators
+  const parsedDiff = useMemo(() => {
     const result: DiffLine[] = [];
-    let oldLine = 0;
-    let newLine = 0;
-
-    for (let i = 0; i < lines.length; i++) {
-      const line = lines[i];
-
-      // Check if this is the start of a new file diff
-      if (line.startsWith('diff --git')) {
-        if (line.includes(filename)) {
-          capturing = true;
-          result.length = 0;
-        } else if (capturing) {
-          break;
-        }
-        continue;
-      }
-
-      if (!capturing) continue;
-
-      // Skip metadata lines
-      if (line.startsWith('index ') || line.startsWith('---') || line.startsWith('+++') ||
-          line.startsWith('new file') || line.startsWith('deleted file')) {
-        continue;
-      }
-
-      // Parse hunk header to get line numbers
-      if (line.startsWith('@@')) {
-        const match = line.match(/@@ -(\d+)(?:,\d+)? \+(\d+)(?:,\d+)? @@/);
-        if (match) {
-          oldLine = parseInt(match[1], 10);
-          newLine = parseInt(match[2], 10);
-        }
-        result.push({ type: 'header', content: line, oldLineNum: null, newLineNum: null });
-        continue;
-      }
-
-      // Parse content lines
-      if (line.startsWith('+')) {
-        result.push({ type: 'add', content: line.slice(1), oldLineNum: null, newLineNum: newLine++ });
-      } else if (line.startsWith('-')) {
-        result.push({ type: 'remove', content: line.slice(1), oldLineNum: oldLine++, newLineNum: null });
-      } else {
-        result.push({ type: 'context', content: line.slice(1) || line, oldLineNum: oldLine++, newLineNum: newLine++ });
+    for (const hunk of file?.hunks ?? []) {
+      result.push({ type: 'header', content: hunk.header, oldLineNum: null, newLineNum: null });
+      for (const line of hunk.lines) {
+        result.push({
+          type:
+            line.line_type === 'addition'
+              ? 'add'
+              : line.line_type === 'deletion'
+                ? 'remove'
+                : 'context',
+          content: line.content,
+          oldLineNum: line.old_line,
+          newLineNum: line.new_line,
+        });
       }
     }
-
     return result;
-  }, [diff, filename]);
+  }, [file]);
+
+  if (file?.is_binary) {
+    return (
+      <div className="text-sm text-muted-foreground text-center py-8">
+        Arquivo binário
+      </div>
+    );
+  }
 
   if (parsedDiff.length === 0) {
     return (
@@ -552,6 +536,8 @@ function PRDetails({ number }: { number: number }) {
   const { data: pr, isLoading } = usePullRequest(number);
   const { data: reviews } = usePRReviews(number);
   const { data: comments } = usePRComments(number);
+  const { data: reviewThreads } = usePRReviewThreads(number);
+  const resolveThread = useResolveReviewThread();
   const { data: files } = usePRFiles(number);
   const { data: diff, isLoading: diffLoading } = usePRDiff(number);
   const { data: checks, isLoading: checksLoading } = usePRChecks(number);
@@ -564,6 +550,21 @@ function PRDetails({ number }: { number: number }) {
   const checkoutPR = useCheckoutPR();
   const { toast } = useToast();
 
+  const handleToggleThread = (threadId: string, resolved: boolean) => {
+    resolveThread.mutate(
+      { number, threadId, resolved },
+      {
+        onError: (err) => {
+          toast({
+            title: resolved ? 'Erro ao resolver thread' : 'Erro ao reabrir thread',
+            description: getErrorMessage(err),
+            variant: 'destructive',
+          });
+        },
+      }
+    );
+  };
+
   const [reviewBody, setReviewBody] = useState('');
   const [commentBody, setCommentBody] = useState('');
   const [mergeMethod, setMergeMethod] = useState<'merge' | 'squash' | 'rebase'>('squash');
@@ -873,6 +874,47 @@ function PRDetails({ number }: { number: number }) {
                 )}
               </div>
 
+              {/* Review threads */}
+              {reviewThreads && reviewThreads.length > 0 && (
+                <div>
+                  <h4 className="text-sm font-medium mb-2">
+                    Threads de review ({reviewThreads.filter((t) => !t.is_resolved).length} abertas)
+                  </h4>
+                  <div className="space-y-2">
+                    {reviewThreads.map((thread) => (
+                      <div
+                        key={thread.id}
+                        className={cn('p-2 bg-muted/50 rounded', thread.is_resolved && 'opacity-60')}
+                      >
+                        <div className="flex items-center justify-between gap-2 text-xs">
+                          <span className="font-mono truncate">
+                            {thread.path}
+                            {(thread.line ?? thread.original_line) != null &&
+                              `:${thread.line ?? thread.original_line}`}
+                            {thread.is_outdated && ' (desatualizada)'}
+                          </span>
+                          <Button
+                            size="sm"
+                            variant="ghost"
+                            className="h-6 text-xs"
+                            onClick={() => handleToggleThread(thread.id, !thread.is_resolved)}
+                            disabled={resolveThread.isPending}
+                          >
+                            {thread.is_resolved ? 'Reabrir' : 'Resolver'}
+                          </Button>
+                        </div>
+                        {thread.comments.map((comment) => (
+                          <div key={comment.id} className="text-sm mt-1">
+                            <span className="font-medium">{comment.author}</span>{' '}
+                            <span className="whitespace-pre-wrap">{comment.body}</span>
+                          </div>
+                        ))}
+                      </div>
+                    ))}
+                  </div>
+                </div>
+              )}
+
               {/* Comments */}
               <div>
                 <h4 className="text-sm font-medium mb-2">Comentários ({comments?.length || 0})</h4>
@@ -1119,6 +1161,7 @@ export default function PullRequestManager() {
   const [selectedPR, setSelectedPR] = useState<number | null>(null);
 
   const { data: pullRequests, isLoading, refetch } = usePullRequests(filter);
+  const refreshPullRequests = useRefreshPullRequests();
   const { data: selectedPRChecks } = usePRChecks(selectedPR || 0);
 
   if (checkingCli) {
@@ -1160,7 +1203,19 @@ export default function PullRequestManager() {
         <div className="p-4 border-b border-border">
           <div className="flex items-center justify-between mb-3">
             <h2 className="font-semibold">Pull Requests</h2>
-            <CreatePRDialog onSuccess={() => refetch()} />
+            <div className="flex items-center gap-1">
+              <Button
+                size="icon"
+                variant="ghost"
+                className="h-8 w-8"
+                title="Atualizar do GitHub"
+                onClick={() => refreshPullRequests.mutate(filter)}
+                disabled={refreshPullRequests.isPending}
+              >
+                <RefreshCw className={cn('w-4 h-4', refreshPullRequests.isPending && 'animate-spin')} />
+              </Button>
+              <CreatePRDialog onSuccess={() => refetch()} />
+            </div>
           </div>
           <div className="flex gap-1">
             {(['open', 'closed', 'all'] as const).map((f) => (
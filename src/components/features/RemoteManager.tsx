@@ -2,6 +2,7 @@ import { useState } from 'react';
 import { useRemotes, useFetch, usePull, usePush, useRepoStatus, useBranches } from '@/hooks/useGit';
 import { git } from '@/services/git';
 import { getErrorMessage } from '@/lib/error';
+import { confirmIfDirty } from '@/lib/workingTree';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { ScrollArea } from '@/components/ui/scroll-area';
@@ -19,6 +20,7 @@ import {
 } from '@/components/ui/popover';
 import { useToast } from '@/components/ui/use-toast';
 import { cn } from '@/lib/utils';
+import type { PushPreview } from '@/types';
 import {
   Cloud,
   Plus,
@@ -32,6 +34,8 @@ import {
   ArrowDown,
   ChevronUp,
   GitBranch,
+  Eye,
+  AlertTriangle,
 } from 'lucide-react';
 
 export default function RemoteManager() {
@@ -42,6 +46,8 @@ export default function RemoteManager() {
   const pullRemote = usePull();
   const pushRemote = usePush();
   const { toast } = useToast();
+  const [pushPreview, setPushPreview] = useState<PushPreview | null>(null);
+  const [previewingRemote, setPreviewingRemote] = useState<string | null>(null);
 
   const [showAddDialog, setShowAddDialog] = useState(false);
   const [newRemoteName, setNewRemoteName] = useState('');
@@ -104,8 +110,22 @@ export default function RemoteManager() {
 
   const handleFetch = (remote?: string) => {
     fetchRemote.mutate(remote, {
-      onSuccess: () => {
-        toast({ title: 'Fetch concluído', description: 'Referências atualizadas' });
+      onSuccess: (results) => {
+        const failed = results.filter((r) => !r.ok);
+        if (failed.length > 0) {
+          toast({
+            title: 'Fetch concluído com erros',
+            description: failed.map((r) => `${r.remote}: ${r.error ?? ''}`).join('\n'),
+            variant: 'destructive',
+          });
+          return;
+        }
+        const newTags = results.reduce((sum, r) => sum + r.new_tags, 0);
+        toast({
+          title: 'Fetch concluído',
+          description:
+            newTags > 0 ? `Referências atualizadas, ${newTags} nova(s) tag(s)` : 'Referências atualizadas',
+        });
       },
       onError: (err) => {
         console.error('Fetch error:', err);
@@ -118,8 +138,9 @@ export default function RemoteManager() {
     });
   };
 
-  const handlePull = (remote: string, branch?: string) => {
+  const handlePull = async (remote: string, branch?: string) => {
     const targetBranch = branch || status?.current_branch || 'main';
+    if (!(await confirmIfDirty(`Fazer pull de ${remote}/${targetBranch}`))) return;
     pullRemote.mutate(
       { remote, branch: targetBranch },
       {
@@ -145,10 +166,10 @@ export default function RemoteManager() {
     );
   };
 
-  const handlePush = (remote: string, force = false) => {
+  const handlePush = (remote: string, forceWithLease = false) => {
     const branch = status?.current_branch || 'main';
     pushRemote.mutate(
-      { remote, branch, force },
+      { remote, branch, forceWithLease },
       {
         onSuccess: () => {
           toast({ title: 'Push concluído', description: 'Alterações enviadas' });
@@ -165,6 +186,28 @@ export default function RemoteManager() {
     );
   };
 
+  const handlePreviewPush = async (remote: string) => {
+    const branch = status?.current_branch || 'main';
+    setPreviewingRemote(remote);
+    try {
+      setPushPreview(await git.remote.pushDryRun(remote, branch));
+    } catch (err) {
+      toast({
+        title: 'Erro ao prever push',
+        description: getErrorMessage(err),
+        variant: 'destructive',
+      });
+    } finally {
+      setPreviewingRemote(null);
+    }
+  };
+
+  const handleConfirmPush = () => {
+    if (!pushPreview) return;
+    handlePush(pushPreview.remote, pushPreview.non_fast_forward);
+    setPushPreview(null);
+  };
+
   if (isLoading) {
     return (
       <div className="flex items-center justify-center h-full">
@@ -234,8 +277,14 @@ export default function RemoteManager() {
         </div>
 
         {/* Sync Status */}
-        {status && (status.ahead > 0 || status.behind > 0) && (
+        {status && !status.is_detached && (
           <div className="flex items-center gap-4 mt-2 text-sm">
+            <span className="text-muted-foreground flex items-center gap-1">
+              <GitBranch className="w-4 h-4" />
+              {status.upstream
+                ? `${status.current_branch} → ${status.upstream.remote}/${status.upstream.branch}`
+                : `${status.current_branch} sem upstream (ainda não publicada)`}
+            </span>
             {status.ahead > 0 && (
               <span className="text-green-600 flex items-center gap-1">
                 <ArrowUp className="w-4 h-4" />
@@ -378,6 +427,19 @@ export default function RemoteManager() {
                     <Upload className="w-3.5 h-3.5 mr-1" />
                     Push
                   </Button>
+                  <Button
+                    size="sm"
+                    variant="ghost"
+                    onClick={() => handlePreviewPush(remote.name)}
+                    disabled={previewingRemote !== null}
+                    title="Ver o que seria enviado"
+                  >
+                    {previewingRemote === remote.name ? (
+                      <Loader2 className="w-3.5 h-3.5 animate-spin" />
+                    ) : (
+                      <Eye className="w-3.5 h-3.5" />
+                    )}
+                  </Button>
                 </div>
               </div>
             ))
@@ -392,6 +454,55 @@ export default function RemoteManager() {
           )}
         </div>
       </ScrollArea>
+
+      <Dialog open={pushPreview !== null} onOpenChange={(open) => !open && setPushPreview(null)}>
+        <DialogContent className="max-w-lg">
+          <DialogHeader>
+            <DialogTitle>
+              Push de {pushPreview?.branch} para {pushPreview?.remote}
+            </DialogTitle>
+          </DialogHeader>
+          {pushPreview && (
+            <div className="space-y-3">
+              <p className="text-sm text-muted-foreground">
+                {pushPreview.remote_ref
+                  ? `${pushPreview.ahead} commit(s) à frente de ${pushPreview.remote_ref}`
+                  : `Branch nova no remote: ${pushPreview.ahead} commit(s)`}
+              </p>
+              {pushPreview.non_fast_forward && (
+                <div className="flex items-start gap-2 rounded border border-red-500/40 bg-red-500/10 p-2 text-sm text-red-600">
+                  <AlertTriangle className="w-4 h-4 mt-0.5 shrink-0" />
+                  O remote tem {pushPreview.behind} commit(s) que não existem localmente. Só um force
+                  push funcionaria, e ele apagaria esses commits do remote. O push usa
+                  --force-with-lease e falha se o remote mudar de novo.
+                </div>
+              )}
+              <ScrollArea className="max-h-64">
+                <div className="space-y-1">
+                  {pushPreview.commits.map((commit) => (
+                    <div key={commit.hash} className="flex gap-2 text-sm">
+                      <span className="font-mono text-xs text-muted-foreground">{commit.short_hash}</span>
+                      <span className="truncate">{commit.summary}</span>
+                    </div>
+                  ))}
+                </div>
+              </ScrollArea>
+              <div className="flex justify-end gap-2">
+                <Button variant="outline" onClick={() => setPushPreview(null)}>
+                  Cancelar
+                </Button>
+                <Button
+                  variant={pushPreview.non_fast_forward ? 'destructive' : 'default'}
+                  onClick={handleConfirmPush}
+                  disabled={pushPreview.ahead === 0 && !pushPreview.non_fast_forward}
+                >
+                  {pushPreview.non_fast_forward ? 'Force push (with lease)' : 'Push'}
+                </Button>
+              </div>
+            </div>
+          )}
+        </DialogContent>
+      </Dialog>
     </div>
   );
 }
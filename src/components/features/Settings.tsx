@@ -42,6 +42,8 @@ import {
 import { useUpdateChecker } from '@/hooks/useUpdateChecker';
 import { getVersion } from '@tauri-apps/api/app';
 import { cn } from '@/lib/utils';
+import type { AppSettings, ConfigValue } from '@/types';
+import { getErrorMessage } from '@/lib/error';
 
 interface SettingsProps {
   open: boolean;
@@ -120,6 +122,10 @@ export default function Settings({ open, onOpenChange, onOpenUpdateDialog }: Set
   // Local state for Git settings (need to be saved to git config)
   const [gitName, setGitName] = useState(settings.gitUserName);
   const [gitEmail, setGitEmail] = useState(settings.gitUserEmail);
+  const [sshKeyPath, setSshKeyPath] = useState('');
+  const [externalEditor, setExternalEditor] = useState('');
+  const [sshPassphrase, setSshPassphrase] = useState('');
+  const [hasSshPassphrase, setHasSshPassphrase] = useState(false);
 
   useEffect(() => {
     getVersion().then(setAppVersion).catch(() => setAppVersion('?.?.?'));
@@ -129,26 +135,89 @@ export default function Settings({ open, onOpenChange, onOpenUpdateDialog }: Set
   // Load git config on open
   useEffect(() => {
     if (open) {
-      invoke<string | null>('get_git_config_value', { key: 'user.name' })
+      invoke<ConfigValue | null>('get_git_config_value', { key: 'user.name' })
         .then((name) => {
           if (name) {
-            setGitName(name);
-            settings.setGitUserName(name);
+            setGitName(name.value);
+            settings.setGitUserName(name.value);
           }
         })
         .catch(() => {});
 
-      invoke<string | null>('get_git_config_value', { key: 'user.email' })
+      invoke<ConfigValue | null>('get_git_config_value', { key: 'user.email' })
         .then((email) => {
           if (email) {
-            setGitEmail(email);
-            settings.setGitUserEmail(email);
+            setGitEmail(email.value);
+            settings.setGitUserEmail(email.value);
           }
         })
         .catch(() => {});
+
+      invoke<AppSettings>('get_settings')
+        .then(async (appSettings) => {
+          const keyPath = appSettings.ssh_key_path ?? '';
+          setExternalEditor(appSettings.external_editor ?? '');
+          setSshKeyPath(keyPath);
+          setSshPassphrase('');
+          setHasSshPassphrase(
+            keyPath ? await invoke<boolean>('has_ssh_passphrase', { keyPath }) : false
+          );
+        })
+        .catch(() => {});
     }
   }, [open]);
 
+  const handleSaveSshKey = async () => {
+    try {
+      const appSettings = await invoke<AppSettings>('get_settings');
+      const keyPath = sshKeyPath.trim();
+      await invoke('update_settings', {
+        settings: { ...appSettings, ssh_key_path: keyPath || null },
+      });
+      if (keyPath && sshPassphrase) {
+        await invoke('set_ssh_passphrase', { keyPath, passphrase: sshPassphrase });
+        setHasSshPassphrase(true);
+        setSshPassphrase('');
+      }
+      toast({ title: 'Chave SSH salva' });
+    } catch (err) {
+      toast({
+        title: 'Erro ao salvar chave SSH',
+        description: getErrorMessage(err),
+        variant: 'destructive',
+      });
+    }
+  };
+
+  const handleSaveExternalEditor = async () => {
+    try {
+      const appSettings = await invoke<AppSettings>('get_settings');
+      await invoke('update_settings', {
+        settings: { ...appSettings, external_editor: externalEditor.trim() },
+      });
+      toast({ title: 'Editor externo salvo' });
+    } catch (err) {
+      toast({
+        title: 'Erro ao salvar editor',
+        description: getErrorMessage(err),
+        variant: 'destructive',
+      });
+    }
+  };
+
+  const handleForgetSshPassphrase = async () => {
+    try {
+      await invoke('set_ssh_passphrase', { keyPath: sshKeyPath.trim(), passphrase: null });
+      setHasSshPassphrase(false);
+    } catch (err) {
+      toast({
+        title: 'Erro ao remover senha',
+        description: getErrorMessage(err),
+        variant: 'destructive',
+      });
+    }
+  };
+
   const handleSaveGitConfig = async () => {
     try {
       if (gitName) {
@@ -361,6 +430,26 @@ export default function Settings({ open, onOpenChange, onOpenUpdateDialog }: Set
                           onCheckedChange={settings.setEditorWordWrap}
                         />
                       </div>
+
+                      <Separator />
+
+                      <div className="space-y-2">
+                        <Label htmlFor="external-editor">Editor Externo</Label>
+                        <Input
+                          id="external-editor"
+                          value={externalEditor}
+                          onChange={(e) => setExternalEditor(e.target.value)}
+                          placeholder="code"
+                        />
+                        <p className="text-xs text-muted-foreground">
+                          Comando de um editor grafico (code, subl, idea, gvim...). Vazio usa o VS Code
+                        </p>
+                      </div>
+
+                      <Button onClick={handleSaveExternalEditor}>
+                        <Save className="w-4 h-4 mr-2" />
+                        Salvar Editor
+                      </Button>
                     </div>
                   </div>
                 </>
@@ -475,6 +564,49 @@ export default function Settings({ open, onOpenChange, onOpenUpdateDialog }: Set
 
                       <Separator />
 
+                      <div className="space-y-2">
+                        <Label htmlFor="ssh-key-path">Chave SSH</Label>
+                        <Input
+                          id="ssh-key-path"
+                          value={sshKeyPath}
+                          onChange={(e) => setSshKeyPath(e.target.value)}
+                          placeholder="~/.ssh/id_ed25519"
+                        />
+                        <p className="text-xs text-muted-foreground">
+                          Usada quando o ssh-agent não tem uma chave aceita pelo remote
+                        </p>
+                      </div>
+
+                      <div className="space-y-2">
+                        <Label htmlFor="ssh-passphrase">Senha da Chave</Label>
+                        <Input
+                          id="ssh-passphrase"
+                          type="password"
+                          value={sshPassphrase}
+                          onChange={(e) => setSshPassphrase(e.target.value)}
+                          placeholder={hasSshPassphrase ? 'Senha salva' : 'Sem senha'}
+                          disabled={!sshKeyPath.trim()}
+                        />
+                        <p className="text-xs text-muted-foreground">
+                          Guardada no cofre de senhas do sistema
+                          {hasSshPassphrase && (
+                            <>
+                              {' · '}
+                              <button className="underline" onClick={handleForgetSshPassphrase}>
+                                esquecer senha
+                              </button>
+                            </>
+                          )}
+                        </p>
+                      </div>
+
+                      <Button onClick={handleSaveSshKey}>
+                        <Save className="w-4 h-4 mr-2" />
+                        Salvar Chave SSH
+                      </Button>
+
+                      <Separator />
+
                       <div className="space-y-2">
                         <Label>Auto Fetch: {settings.autoFetchInterval === 0 ? 'Desativado' : `${settings.autoFetchInterval} min`}</Label>
                         <Slider
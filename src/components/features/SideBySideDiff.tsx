@@ -16,78 +16,6 @@ import { Prism as SyntaxHighlighter } from 'react-syntax-highlighter';
 import { vscDarkPlus } from 'react-syntax-highlighter/dist/esm/styles/prism';
 import type { DiffInfo, HunkInfo, LineInfo } from '@/types';
 
-// Map file extensions to language names for syntax highlighting
-const extensionToLanguage: Record<string, string> = {
-  js: 'javascript',
-  jsx: 'jsx',
-  ts: 'typescript',
-  tsx: 'tsx',
-  py: 'python',
-  rb: 'ruby',
-  java: 'java',
-  kt: 'kotlin',
-  go: 'go',
-  rs: 'rust',
-  c: 'c',
-  cpp: 'cpp',
-  h: 'c',
-  hpp: 'cpp',
-  cs: 'csharp',
-  php: 'php',
-  swift: 'swift',
-  m: 'objectivec',
-  mm: 'objectivec',
-  scala: 'scala',
-  r: 'r',
-  sql: 'sql',
-  html: 'html',
-  htm: 'html',
-  xml: 'xml',
-  svg: 'xml',
-  css: 'css',
-  scss: 'scss',
-  sass: 'sass',
-  less: 'less',
-  json: 'json',
-  yaml: 'yaml',
-  yml: 'yaml',
-  toml: 'toml',
-  md: 'markdown',
-  sh: 'bash',
-  bash: 'bash',
-  zsh: 'bash',
-  fish: 'bash',
-  ps1: 'powershell',
-  dockerfile: 'docker',
-  makefile: 'makefile',
-  cmake: 'cmake',
-  gradle: 'groovy',
-  groovy: 'groovy',
-  lua: 'lua',
-  perl: 'perl',
-  pl: 'perl',
-  ex: 'elixir',
-  exs: 'elixir',
-  erl: 'erlang',
-  hs: 'haskell',
-  clj: 'clojure',
-  vim: 'vim',
-  vue: 'vue',
-  svelte: 'svelte',
-};
-
-function getLanguageFromPath(path: string): string {
-  const ext = path.split('.').pop()?.toLowerCase() || '';
-  const filename = path.split('/').pop()?.toLowerCase() || '';
-
-  // Check for special filenames
-  if (filename === 'dockerfile') return 'docker';
-  if (filename === 'makefile') return 'makefile';
-  if (filename === 'cmakelists.txt') return 'cmake';
-
-  return extensionToLanguage[ext] || 'text';
-}
-
 // Build side-by-side line pairs from hunks
 interface SideBySideLine {
   leftLineNo: number | null;
@@ -255,7 +183,7 @@ function DiffContent({ diff }: { diff: DiffInfo }) {
     [diff.hunks]
   );
 
-  const language = useMemo(() => getLanguageFromPath(diff.path), [diff.path]);
+  const language = diff.language ?? 'text';
 
   if (diff.is_binary) {
     return (
@@ -5,6 +5,7 @@ import {
   useApplyStash,
   usePopStash,
   useDropStash,
+  useRenameStash,
   useRepoStatus,
 } from '@/hooks/useGit';
 import { getErrorMessage } from '@/lib/error';
@@ -38,6 +39,7 @@ import {
   FilePlus,
   FileEdit,
   FileMinus,
+  Pencil,
 } from 'lucide-react';
 import { cn } from '@/lib/utils';
 
@@ -54,12 +56,14 @@ export default function StashPanel() {
   const applyStash = useApplyStash();
   const popStash = usePopStash();
   const dropStash = useDropStash();
+  const renameStash = useRenameStash();
   const { toast } = useToast();
 
   const [showCreateDialog, setShowCreateDialog] = useState(false);
   const [stashMessage, setStashMessage] = useState('');
   const [includeUntracked, setIncludeUntracked] = useState(true);
   const [keepIndex, setKeepIndex] = useState(false);
+  const [stagedOnly, setStagedOnly] = useState(false);
   const [selectedFiles, setSelectedFiles] = useState<Set<string>>(new Set());
   const [selectSpecificFiles, setSelectSpecificFiles] = useState(false);
 
@@ -152,8 +156,9 @@ export default function StashPanel() {
     createStash.mutate(
       {
         message: stashMessage || undefined,
-        includeUntracked: filesToStash ? true : includeUntracked,
-        keepIndex: filesToStash ? false : keepIndex,
+        includeUntracked: filesToStash ? true : includeUntracked && !stagedOnly,
+        keepIndex: filesToStash ? false : keepIndex && !stagedOnly,
+        stagedOnly: filesToStash ? false : stagedOnly,
         files: filesToStash,
       },
       {
@@ -162,6 +167,7 @@ export default function StashPanel() {
           setStashMessage('');
           setIncludeUntracked(true);
           setKeepIndex(false);
+          setStagedOnly(false);
           setSelectSpecificFiles(false);
           setSelectedFiles(new Set());
           setShowCreateDialog(false);
@@ -179,7 +185,15 @@ export default function StashPanel() {
 
   const handleApply = (index: number) => {
     applyStash.mutate(index, {
-      onSuccess: () => {
+      onSuccess: (result) => {
+        if (result.conflicts.length > 0) {
+          toast({
+            title: 'Stash aplicado com conflitos',
+            description: `Resolva os conflitos em: ${result.conflicts.join(', ')}`,
+            variant: 'destructive',
+          });
+          return;
+        }
         toast({ title: 'Stash aplicado', description: 'Alterações restauradas' });
       },
       onError: (err) => {
@@ -194,7 +208,15 @@ export default function StashPanel() {
 
   const handlePop = (index: number) => {
     popStash.mutate(index, {
-      onSuccess: () => {
+      onSuccess: (result) => {
+        if (!result.dropped) {
+          toast({
+            title: 'Stash aplicado com conflitos',
+            description: `O stash foi mantido. Resolva os conflitos em: ${result.conflicts.join(', ')}`,
+            variant: 'destructive',
+          });
+          return;
+        }
         toast({ title: 'Stash aplicado e removido', description: 'Alterações restauradas' });
       },
       onError: (err) => {
@@ -224,6 +246,28 @@ export default function StashPanel() {
     });
   };
 
+  const handleRename = (index: number, currentMessage: string) => {
+    const label = currentMessage.replace(/^(WIP on|On) [^:]+:\s*/, '');
+    const message = window.prompt('Nova mensagem do stash:', label);
+    if (!message?.trim()) return;
+
+    renameStash.mutate(
+      { index, message },
+      {
+        onSuccess: () => {
+          toast({ title: 'Stash renomeado' });
+        },
+        onError: (err) => {
+          toast({
+            title: 'Erro',
+            description: getErrorMessage(err),
+            variant: 'destructive',
+          });
+        },
+      }
+    );
+  };
+
   if (isLoading) {
     return (
       <div className="flex items-center justify-center h-full">
@@ -376,7 +420,8 @@ export default function StashPanel() {
                       <div className="flex items-center gap-2">
                         <Checkbox
                           id="includeUntracked"
-                          checked={includeUntracked}
+                          checked={includeUntracked && !stagedOnly}
+                          disabled={stagedOnly}
                           onCheckedChange={(c) => setIncludeUntracked(c === true)}
                         />
                         <label htmlFor="includeUntracked" className="text-sm">
@@ -387,13 +432,25 @@ export default function StashPanel() {
                       <div className="flex items-center gap-2">
                         <Checkbox
                           id="keepIndex"
-                          checked={keepIndex}
+                          checked={keepIndex && !stagedOnly}
+                          disabled={stagedOnly}
                           onCheckedChange={(c) => setKeepIndex(c === true)}
                         />
                         <label htmlFor="keepIndex" className="text-sm">
                           Manter arquivos staged
                         </label>
                       </div>
+
+                      <div className="flex items-center gap-2">
+                        <Checkbox
+                          id="stagedOnly"
+                          checked={stagedOnly}
+                          onCheckedChange={(c) => setStagedOnly(c === true)}
+                        />
+                        <label htmlFor="stagedOnly" className="text-sm">
+                          Guardar apenas arquivos staged
+                        </label>
+                      </div>
                     </div>
                   )}
 
@@ -472,6 +529,12 @@ export default function StashPanel() {
                               onSelect: () => handleApply(stash.index),
                               disabled: applyStash.isPending,
                             },
+                            {
+                              label: 'Renomear stash',
+                              icon: Pencil,
+                              onSelect: () => handleRename(stash.index, stash.message),
+                              disabled: renameStash.isPending,
+                            },
                             {
                               label: 'Remover stash',
                               icon: Trash2,
@@ -1,20 +1,31 @@
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
 import {
   useRepoStatus,
   useStageFiles,
+  useStagePath,
   useStagePartial,
   useUnstageFiles,
   useUnstagePartial,
   useUnstageAll,
   useCreateCommit,
+  useAddToLastCommit,
   useDiscardChanges,
+  useResetWorkingTree,
+  useCleanWorkingTree,
   useFileDiff,
   useCreateStash,
+  useCommitTemplate,
+  useCommitMessageViolations,
+  useRebaseStatus,
+  useRebaseContinue,
+  useRebaseAbort,
+  useCommitMerge,
 } from '@/hooks/useGit';
-import type { BlameInfo, FileStatus, PartialHunkSelection } from '@/types';
+import type { BlameInfo, DiscardAction, FileStatus, PartialHunkSelection } from '@/types';
 import { git } from '@/services/git';
 import { getErrorMessage } from '@/lib/error';
 import { useRepoStore } from '@/stores/repoStore';
+import { useSettingsStore } from '@/stores/settingsStore';
 import FileHistoryDialog from './FileHistoryDialog';
 import { useDiffViewerStore } from '@/stores/diffViewerStore';
 
@@ -55,23 +66,42 @@ import {
   UserRoundSearch,
   History,
   Columns2,
+  FilePlus2,
+  FolderOpen,
+  Code,
 } from 'lucide-react';
 import DiffViewer from './DiffViewer';
 import ConflictResolver from './ConflictResolver';
 import CodeEditor from './CodeEditor';
 import BlameViewer from './BlameViewer';
 
+const DISCARD_ACTION_LABELS: Record<DiscardAction, string> = {
+  revert: 'volta ao HEAD',
+  delete: 'será apagado',
+  restore: 'será restaurado',
+};
+
 export default function WorkingArea() {
   const { data: status } = useRepoStatus();
+  const { data: rebaseStatus } = useRebaseStatus(!!status?.is_rebasing);
+  const rebaseContinue = useRebaseContinue();
+  const rebaseAbort = useRebaseAbort();
+  const commitMerge = useCommitMerge();
   const stageFiles = useStageFiles();
+  const stagePath = useStagePath();
   const stagePartial = useStagePartial();
   const unstageFiles = useUnstageFiles();
   const unstagePartial = useUnstagePartial();
   const unstageAll = useUnstageAll();
   const createCommit = useCreateCommit();
+  const addToLastCommit = useAddToLastCommit();
   const discardChanges = useDiscardChanges();
+  const resetWorkingTree = useResetWorkingTree();
+  const cleanWorkingTree = useCleanWorkingTree();
   const createStash = useCreateStash();
+  const { data: commitTemplate } = useCommitTemplate();
   const { toast } = useToast();
+  const { terminalEmulator } = useSettingsStore();
 
   const [message, setMessage] = useState('');
   const [amend, setAmend] = useState(false);
@@ -99,6 +129,16 @@ export default function WorkingArea() {
     unstaged: true,
     untracked: true,
   });
+
+  const { data: messageViolations } = useCommitMessageViolations(message);
+
+  // Pre-fill an empty commit box with the repo's commit template
+  useEffect(() => {
+    if (commitTemplate) {
+      setMessage((current) => current || commitTemplate);
+    }
+  }, [commitTemplate]);
+
   const selectedFile = selectedFilePath
     ? { path: selectedFilePath, staged: selectedFileStaged }
     : null;
@@ -173,6 +213,56 @@ export default function WorkingArea() {
     }
   };
 
+  const handleCommitMerge = () => {
+    commitMerge.mutate(undefined, {
+      onSuccess: (hash) => {
+        toast({ title: 'Merge concluído', description: `Commit ${hash} criado` });
+      },
+      onError: (err) => {
+        toast({
+          title: 'Erro ao concluir merge',
+          description: getErrorMessage(err),
+          variant: 'destructive',
+        });
+      },
+    });
+  };
+
+  const handleRebaseContinue = () => {
+    rebaseContinue.mutate(undefined, {
+      onSuccess: (result) => {
+        toast({
+          title: result.in_progress ? 'Rebase parado em novo conflito' : 'Rebase concluído',
+          description: result.in_progress
+            ? `Passo ${result.current_step} de ${result.total_steps}`
+            : undefined,
+        });
+      },
+      onError: (err) => {
+        toast({
+          title: 'Erro ao continuar rebase',
+          description: getErrorMessage(err),
+          variant: 'destructive',
+        });
+      },
+    });
+  };
+
+  const handleRebaseAbort = () => {
+    if (!confirm('Deseja abortar o rebase? A branch voltará ao estado anterior.')) return;
+
+    rebaseAbort.mutate(undefined, {
+      onSuccess: () => toast({ title: 'Rebase abortado' }),
+      onError: (err) => {
+        toast({
+          title: 'Erro ao abortar rebase',
+          description: getErrorMessage(err),
+          variant: 'destructive',
+        });
+      },
+    });
+  };
+
   const handleStage = (files: string[]) => {
     stageFiles.mutate(files);
   };
@@ -209,8 +299,26 @@ export default function WorkingArea() {
     );
   };
 
-  const handleDiscard = (files: string[]) => {
-    if (confirm(`Descartar alterações em ${files.length} arquivo(s)?`)) {
+  const handleDiscard = async (files: string[]) => {
+    let details = '';
+    try {
+      const preview = await git.staging.previewDiscard(files);
+      if (preview.length === 0) {
+        toast({ title: 'Nada para descartar' });
+        return;
+      }
+      details = preview
+        .map((item) => {
+          const lines = item.is_binary ? 'binário' : `−${item.additions} +${item.deletions} linhas`;
+          return `• ${item.path}: ${DISCARD_ACTION_LABELS[item.action]} (${lines})`;
+        })
+        .join('\n');
+    } catch {
+      // Fall back to the generic confirmation
+    }
+
+    const question = `Descartar alterações em ${files.length} arquivo(s)?`;
+    if (confirm(details ? `${question}\n\n${details}` : question)) {
       discardChanges.mutate(files);
       if (selectedFile && files.includes(selectedFile.path)) {
         setSelectedFilePath(null);
@@ -218,6 +326,57 @@ export default function WorkingArea() {
     }
   };
 
+  const handleResetWorkingTree = () => {
+    if (!confirm('Descartar TODAS as alterações, staged e não staged? Isso não pode ser desfeito.')) {
+      return;
+    }
+    const untrackedCount = status?.untracked_files.length || 0;
+    const includeUntracked =
+      untrackedCount > 0 && confirm(`Apagar também ${untrackedCount} item(ns) não rastreado(s)?`);
+
+    resetWorkingTree.mutate(
+      { includeUntracked, includeIgnored: false },
+      {
+        onSuccess: (result) => {
+          setSelectedFilePath(null);
+          toast({
+            title: 'Working tree limpo',
+            description: `${result.reset} arquivo(s) restaurado(s), ${result.removed} removido(s)`,
+          });
+        },
+        onError: (err) => {
+          toast({
+            title: 'Erro ao limpar working tree',
+            description: getErrorMessage(err),
+            variant: 'destructive',
+          });
+        },
+      }
+    );
+  };
+
+  const handleCleanUntracked = async () => {
+    try {
+      const paths = await git.staging.clean(true, false, true);
+      if (paths.length === 0) {
+        toast({ title: 'Nada para limpar' });
+        return;
+      }
+      const shown = paths.slice(0, 20).map((path) => `• ${path}`).join('\n');
+      const more = paths.length > 20 ? `\n… e mais ${paths.length - 20}` : '';
+      if (!confirm(`Apagar ${paths.length} item(ns) não rastreado(s)?\n\n${shown}${more}`)) return;
+
+      const removed = await cleanWorkingTree.mutateAsync({ directories: true, ignored: false });
+      toast({ title: 'Limpeza concluída', description: `${removed.length} item(ns) apagado(s)` });
+    } catch (err) {
+      toast({
+        title: 'Erro ao limpar não rastreados',
+        description: getErrorMessage(err),
+        variant: 'destructive',
+      });
+    }
+  };
+
   const handleCommit = () => {
     if (!message.trim()) {
       toast({
@@ -231,9 +390,14 @@ export default function WorkingArea() {
     createCommit.mutate(
       { message: message.trim(), amend },
       {
-        onSuccess: () => {
-          toast({ title: 'Sucesso', description: 'Commit criado com sucesso' });
-          setMessage('');
+        onSuccess: (result) => {
+          toast({
+            title: 'Sucesso',
+            description: result.detached
+              ? `Commit ${result.short_hash} criado com HEAD destacado (fora de qualquer branch)`
+              : `Commit ${result.short_hash} criado em ${result.branch}`,
+          });
+          setMessage(commitTemplate ?? '');
           setAmend(false);
         },
         onError: (err: unknown) => {
@@ -267,6 +431,32 @@ export default function WorkingArea() {
     );
   };
 
+  const handleAddToLastCommit = (force = false) => {
+    addToLastCommit.mutate(force, {
+      onSuccess: (result) => {
+        toast({
+          title: 'Sucesso',
+          description: `Alterações adicionadas ao commit ${result.short_hash}`,
+        });
+      },
+      onError: (err: unknown) => {
+        const code = (err as { code?: string } | null)?.code;
+        if (
+          code === 'COMMIT_ALREADY_PUSHED' &&
+          confirm('O último commit já foi enviado ao remoto. Emendar mesmo assim? Será preciso force push.')
+        ) {
+          handleAddToLastCommit(true);
+          return;
+        }
+        toast({
+          title: 'Erro',
+          description: getErrorMessage(err),
+          variant: 'destructive',
+        });
+      },
+    });
+  };
+
   const handleOpenBlame = async () => {
     if (!selectedFile) return;
     await handleOpenBlameForPath(selectedFile.path, selectedFile.staged);
@@ -336,6 +526,9 @@ export default function WorkingArea() {
     const path = typeof file === 'string' ? file : file.path;
     const fileStatus = typeof file === 'string' ? 'untracked' : file.status;
     const isSelected = selectedFile?.path === path && selectedFile?.staged === staged;
+    const untrackedDir = isUntracked
+      ? status?.untracked_dirs?.find((dir) => dir.path === path)
+      : undefined;
 
     return (
       <div
@@ -346,8 +539,18 @@ export default function WorkingArea() {
         onClick={() => setSelectedFilePath(path, staged)}
       >
         {getStatusIcon(fileStatus)}
-        <File className="w-4 h-4 text-muted-foreground shrink-0" />
+        {untrackedDir ? (
+          <FolderOpen className="w-4 h-4 text-muted-foreground shrink-0" />
+        ) : (
+          <File className="w-4 h-4 text-muted-foreground shrink-0" />
+        )}
         <span className="flex-1 text-sm truncate">{path}</span>
+        {untrackedDir && (
+          <span className="text-xs text-muted-foreground shrink-0">
+            {untrackedDir.file_count}
+            {untrackedDir.file_count_capped ? '+' : ''} arquivo(s)
+          </span>
+        )}
 
         <ActionMenu
           title={`Acoes do arquivo ${path}`}
@@ -368,6 +571,30 @@ export default function WorkingArea() {
               icon: UserRoundSearch,
               onSelect: () => handleOpenBlameForPath(path, staged),
             },
+            {
+              label: 'Abrir no editor',
+              icon: Code,
+              onSelect: () =>
+                git.repo.openInEditor(path, undefined, terminalEmulator).catch((err: unknown) =>
+                  toast({
+                    title: 'Erro ao abrir editor',
+                    description: getErrorMessage(err),
+                    variant: 'destructive',
+                  })
+                ),
+            },
+            {
+              label: 'Mostrar na pasta',
+              icon: FolderOpen,
+              onSelect: () =>
+                git.repo.openInFileManager(path).catch((err: unknown) =>
+                  toast({
+                    title: 'Erro ao abrir pasta',
+                    description: getErrorMessage(err),
+                    variant: 'destructive',
+                  })
+                ),
+            },
             ...(staged
               ? [
                   {
@@ -381,7 +608,8 @@ export default function WorkingArea() {
                   {
                     label: 'Stage',
                     icon: Plus,
-                    onSelect: () => handleStage([path]),
+                    onSelect: () =>
+                      untrackedDir ? stagePath.mutate(path) : handleStage([path]),
                     separatorBefore: true,
                   },
                   ...(!isUntracked
@@ -472,6 +700,81 @@ export default function WorkingArea() {
         <div className="h-full flex flex-col border-r border-border">
           <ScrollArea className="flex-1">
             <div className="p-2">
+              {/* Rebase in progress, possibly started outside the app */}
+              {rebaseStatus?.in_progress && (
+                <div className="mb-2 rounded border border-yellow-500/40 bg-yellow-500/10 p-2 text-xs">
+                  <div className="font-medium">
+                    Rebase em andamento
+                    {rebaseStatus.head_name && ` de ${rebaseStatus.head_name}`}
+                  </div>
+                  <div className="text-muted-foreground">
+                    Passo {rebaseStatus.current_step} de {rebaseStatus.total_steps}
+                    {rebaseStatus.conflicted_files.length > 0 &&
+                      ` · ${rebaseStatus.conflicted_files.length} conflito(s)`}
+                  </div>
+                  <div className="mt-2 flex gap-1">
+                    <Button
+                      size="sm"
+                      className="h-6 text-xs"
+                      onClick={handleRebaseContinue}
+                      disabled={
+                        rebaseContinue.isPending || rebaseStatus.conflicted_files.length > 0
+                      }
+                    >
+                      {rebaseContinue.isPending && <Loader2 className="w-3 h-3 mr-1 animate-spin" />}
+                      Continuar
+                    </Button>
+                    <Button
+                      size="sm"
+                      variant="ghost"
+                      className="h-6 text-xs text-red-500 hover:text-red-600"
+                      onClick={handleRebaseAbort}
+                      disabled={rebaseAbort.isPending}
+                    >
+                      <XCircle className="w-3 h-3 mr-1" />
+                      Abortar Rebase
+                    </Button>
+                  </div>
+                </div>
+              )}
+
+              {/* Merge waiting for review or conflict resolution */}
+              {status?.is_merging && (
+                <div className="mb-2 rounded border border-yellow-500/40 bg-yellow-500/10 p-2 text-xs">
+                  <div className="font-medium">Merge em andamento</div>
+                  <div className="text-muted-foreground">
+                    {status.conflicted_files.length > 0
+                      ? `${status.conflicted_files.length} conflito(s) para resolver`
+                      : 'Revise as alterações antes de concluir'}
+                  </div>
+                  <div className="mt-2 flex gap-1">
+                    <Button
+                      size="sm"
+                      className="h-6 text-xs"
+                      onClick={handleCommitMerge}
+                      disabled={commitMerge.isPending || status.conflicted_files.length > 0}
+                    >
+                      {commitMerge.isPending && <Loader2 className="w-3 h-3 mr-1 animate-spin" />}
+                      Concluir merge
+                    </Button>
+                    <Button
+                      size="sm"
+                      variant="ghost"
+                      className="h-6 text-xs text-red-500 hover:text-red-600"
+                      onClick={handleAbortMerge}
+                      disabled={abortingMerge}
+                    >
+                      {abortingMerge ? (
+                        <Loader2 className="w-3 h-3 mr-1 animate-spin" />
+                      ) : (
+                        <XCircle className="w-3 h-3 mr-1" />
+                      )}
+                      Abortar Merge
+                    </Button>
+                  </div>
+                </div>
+              )}
+
               {/* Conflicted Files */}
               {status && status.conflicted_files.length > 0 && (
                 <div className="mb-2">
@@ -480,27 +783,6 @@ export default function WorkingArea() {
                     count={status.conflicted_files.length}
                     section="conflicts"
                     variant="danger"
-                    actions={
-                      status.is_merging && (
-                        <Button
-                          size="sm"
-                          variant="ghost"
-                          className="h-6 text-xs text-red-500 hover:text-red-600"
-                          onClick={(e) => {
-                            e.stopPropagation();
-                            handleAbortMerge();
-                          }}
-                          disabled={abortingMerge}
-                        >
-                          {abortingMerge ? (
-                            <Loader2 className="w-3 h-3 mr-1 animate-spin" />
-                          ) : (
-                            <XCircle className="w-3 h-3 mr-1" />
-                          )}
-                          Abortar Merge
-                        </Button>
-                      )
-                    }
                   />
                   {expandedSections.conflicts && (
                     <div className="ml-2 space-y-1">
@@ -583,17 +865,30 @@ export default function WorkingArea() {
                     count={status.untracked_files.length}
                     section="untracked"
                     actions={
-                      <Button
-                        size="sm"
-                        variant="ghost"
-                        className="h-6 text-xs"
-                        onClick={(e) => {
-                          e.stopPropagation();
-                          handleStage(status.untracked_files);
-                        }}
-                      >
-                        Stage All
-                      </Button>
+                      <>
+                        <Button
+                          size="sm"
+                          variant="ghost"
+                          className="h-6 text-xs text-red-500 hover:text-red-600"
+                          onClick={(e) => {
+                            e.stopPropagation();
+                            handleCleanUntracked();
+                          }}
+                        >
+                          Limpar
+                        </Button>
+                        <Button
+                          size="sm"
+                          variant="ghost"
+                          className="h-6 text-xs"
+                          onClick={(e) => {
+                            e.stopPropagation();
+                            handleStage(status.untracked_files);
+                          }}
+                        >
+                          Stage All
+                        </Button>
+                      </>
                     }
                   />
                   {expandedSections.untracked && (
@@ -628,6 +923,23 @@ export default function WorkingArea() {
               className="min-h-20 resize-none text-sm"
             />
 
+            {message.trim() && messageViolations && messageViolations.length > 0 && (
+              <ul className="space-y-0.5">
+                {messageViolations.map((violation) => (
+                  <li
+                    key={violation.rule}
+                    className={cn(
+                      'flex items-start gap-1 text-xs',
+                      violation.severity === 'error' ? 'text-red-500' : 'text-yellow-500'
+                    )}
+                  >
+                    <AlertTriangle className="w-3 h-3 mt-0.5 shrink-0" />
+                    {violation.message}
+                  </li>
+                ))}
+              </ul>
+            )}
+
             <div className="flex items-center gap-2">
               <Checkbox
                 id="amend"
@@ -653,6 +965,19 @@ export default function WorkingArea() {
                 Commit ({status?.staged_files.length || 0})
               </Button>
 
+              <Button
+                variant="outline"
+                onClick={() => handleAddToLastCommit()}
+                disabled={addToLastCommit.isPending || !status?.staged_files.length || !status?.head_commit}
+                title="Adicionar ao último commit (mantém mensagem e autor)"
+              >
+                {addToLastCommit.isPending ? (
+                  <Loader2 className="w-4 h-4 animate-spin" />
+                ) : (
+                  <FilePlus2 className="w-4 h-4" />
+                )}
+              </Button>
+
               {/* Stash Button with Popover */}
               {totalChanges > 0 && (
                 <Popover open={stashPopoverOpen} onOpenChange={setStashPopoverOpen}>
@@ -722,6 +1047,22 @@ export default function WorkingArea() {
                   </PopoverContent>
                 </Popover>
               )}
+
+              {totalChanges > 0 && (
+                <Button
+                  variant="outline"
+                  className="text-red-500 hover:text-red-600"
+                  onClick={handleResetWorkingTree}
+                  disabled={resetWorkingTree.isPending}
+                  title="Descartar todas as alterações"
+                >
+                  {resetWorkingTree.isPending ? (
+                    <Loader2 className="w-4 h-4 animate-spin" />
+                  ) : (
+                    <Trash2 className="w-4 h-4" />
+                  )}
+                </Button>
+              )}
             </div>
           </div>
         </div>
@@ -3,6 +3,8 @@ import { open } from '@tauri-apps/plugin-dialog';
 import { Panel, PanelGroup, PanelResizeHandle } from 'react-resizable-panels';
 import { AlertCircle, Loader2 } from 'lucide-react';
 import {
+  useActiveRepoEvents,
+  useCheckoutBranch,
   useCloneRepo,
   useInitRepo,
   useOpenRepo,
@@ -16,12 +18,14 @@ import {
 import { useUpdateChecker } from '@/hooks/useUpdateChecker';
 import { useKeyboardShortcuts } from '@/hooks/useKeyboardShortcuts';
 import { useToast } from '@/components/ui/use-toast';
+import { ToastAction } from '@/components/ui/toast';
 import { useDiffViewerStore } from '@/stores/diffViewerStore';
 import { useRepoStore } from '@/stores/repoStore';
 import { useTerminalStore } from '@/stores/terminalStore';
 import { useLayoutStore } from '@/stores/layoutStore';
 import { type AppView } from '@/lib/navigation';
 import { getErrorMessage } from '@/lib/error';
+import type { RepoInfo } from '@/types';
 import UpdateDialog from '../features/UpdateDialog';
 import WelcomeScreen from '../features/WelcomeScreen';
 import WorkingArea from '../features/WorkingArea';
@@ -98,6 +102,7 @@ export default function MainLayout() {
 
   const { data: repoInfo } = useRepoInfo();
   const { data: openRepos } = useOpenRepos();
+  useActiveRepoEvents();
   const hasOpenRepos = (openRepos?.length || 0) > 0;
   const isRepoOpen = repoInfo?.is_repo === true;
   const { data: status, isLoading: statusLoading, error } = useRepoStatus(isRepoOpen);
@@ -107,6 +112,7 @@ export default function MainLayout() {
   const recentRepos = useRecentRepos();
   const removeRecent = useRemoveRecentRepo();
   const refreshAll = useRefreshAll();
+  const checkoutBranch = useCheckoutBranch();
 
   useEffect(() => {
     if (repoInfo?.path) {
@@ -133,6 +139,22 @@ export default function MainLayout() {
     { key: 'ArrowLeft', action: () => isDiffOpen && prevDiff() },
   ]);
 
+  // HEAD may have moved outside the app; offer to go back to the branch last used here
+  const offerLastBranch = (info: RepoInfo) => {
+    const lastBranch = info.last_branch;
+    if (!lastBranch || lastBranch === info.current_branch) return;
+
+    toast({
+      title: 'Branch diferente da última sessão',
+      description: `Você estava em ${lastBranch}; agora o HEAD está em ${info.current_branch ?? 'HEAD'}`,
+      action: (
+        <ToastAction altText={`Voltar para ${lastBranch}`} onClick={() => checkoutBranch.mutate(lastBranch)}>
+          Voltar
+        </ToastAction>
+      ),
+    });
+  };
+
   const handleOpenRepo = async () => {
     try {
       const selected = await open({
@@ -149,6 +171,8 @@ export default function MainLayout() {
             description: 'O diretorio selecionado nao e um repositorio Git valido',
             variant: 'destructive',
           });
+        } else {
+          offerLastBranch(info);
         }
       }
     } catch (err) {
@@ -172,6 +196,8 @@ export default function MainLayout() {
           variant: 'destructive',
         });
         removeRecent.mutate(path);
+      } else {
+        offerLastBranch(info);
       }
     } catch (err) {
       toast({
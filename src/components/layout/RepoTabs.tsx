@@ -1,14 +1,43 @@
-import { useOpenRepos, useSetActiveRepo, useCloseRepoById } from '@/hooks/useGit';
+import { useOpenRepos, useOpenRepoStatuses, useSetActiveRepo, useCloseRepoById } from '@/hooks/useGit';
 import { cn } from '@/lib/utils';
-import { X, FolderGit2, Plus } from 'lucide-react';
+import { X, FolderGit2, Plus, AlertTriangle } from 'lucide-react';
+import type { RepoStatusBadge } from '@/services/git';
 import { Button } from '@/components/ui/button';
 
+function RepoTabBadge({ badge }: { badge?: RepoStatusBadge }) {
+  if (!badge) return null;
+
+  if (badge.error) {
+    return (
+      <span title={badge.error}>
+        <AlertTriangle className="w-3 h-3 shrink-0 text-red-500" />
+      </span>
+    );
+  }
+
+  const { dirty_count = 0, ahead = 0, behind = 0 } = badge;
+  if (!dirty_count && !ahead && !behind) return null;
+
+  return (
+    <span className="flex items-center gap-1 text-[10px] text-muted-foreground shrink-0">
+      {dirty_count > 0 && (
+        <span className="text-yellow-500" title={`${dirty_count} alteração(ões) pendente(s)`}>
+          ●{dirty_count}
+        </span>
+      )}
+      {ahead > 0 && <span title={`${ahead} commit(s) à frente`}>↑{ahead}</span>}
+      {behind > 0 && <span title={`${behind} commit(s) atrás`}>↓{behind}</span>}
+    </span>
+  );
+}
+
 interface RepoTabsProps {
   onAddRepo: () => void;
 }
 
 export default function RepoTabs({ onAddRepo }: RepoTabsProps) {
   const { data: openRepos } = useOpenRepos();
+  const { data: statuses } = useOpenRepoStatuses();
   const setActiveRepo = useSetActiveRepo();
   const closeRepo = useCloseRepoById();
 
@@ -50,6 +79,7 @@ export default function RepoTabs({ onAddRepo }: RepoTabsProps) {
             )}>
               {repo.name}
             </span>
+            <RepoTabBadge badge={statuses?.find((s) => s.id === repo.id)} />
             <button
               className="ml-auto p-0.5 rounded hover:bg-muted opacity-0 group-hover:opacity-100 transition-opacity"
               onClick={(e) => handleCloseTab(e, repo.id)}
@@ -11,6 +11,7 @@ import {
   useCheckoutBranch,
   useCloseRepoById,
   useOpenRepos,
+  useDiffStats,
 } from '@/hooks/useGit';
 import { git } from '@/services/git';
 import { Button } from '@/components/ui/button';
@@ -173,6 +174,7 @@ export default function Sidebar({
     (status?.staged_files.length || 0) +
     (status?.unstaged_files.length || 0) +
     (status?.untracked_files.length || 0);
+  const { data: diffStats } = useDiffStats(false, changesCount > 0);
 
   return (
     <div className="h-full min-w-0 border-r border-border bg-card flex flex-col">
@@ -302,7 +304,14 @@ export default function Sidebar({
                   <Icon className="mr-2 h-4 w-4 shrink-0" />
                   <span className="flex-1 truncate text-left">{item.label}</span>
                   {showBadge && (
-                    <span className="ml-1 shrink-0 rounded-full bg-primary px-1.5 py-0.5 text-xs text-primary-foreground">
+                    <span
+                      title={
+                        diffStats
+                          ? `+${diffStats.insertions} −${diffStats.deletions} em ${diffStats.files_changed} arquivo(s)`
+                          : undefined
+                      }
+                      className="ml-1 shrink-0 rounded-full bg-primary px-1.5 py-0.5 text-xs text-primary-foreground"
+                    >
                       {changesCount}
                     </span>
                   )}
@@ -1,11 +1,14 @@
-import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
-import { git } from '@/services/git';
+import { useEffect } from 'react';
+import { useQuery, useInfiniteQuery, useMutation, useQueryClient } from '@tanstack/react-query';
+import { git, type OpenRepoInfo } from '@/services/git';
+import type { AuthorOverride } from '@/types';
 
 // Query Keys
 export const queryKeys = {
   repoInfo: ['repo', 'info'] as const,
   repoStatus: ['repo', 'status'] as const,
   openRepos: ['open', 'repos'] as const,
+  openRepoStatuses: ['open', 'repos', 'statuses'] as const,
   recentRepos: ['recent', 'repos'] as const,
   commits: (branch?: string) => ['commits', branch] as const,
   branches: ['branches'] as const,
@@ -13,16 +16,25 @@ export const queryKeys = {
   stashes: ['stashes'] as const,
   workingDiff: ['diff', 'working'] as const,
   stagedDiff: ['diff', 'staged'] as const,
+  // Nested under the diff keys so their invalidations refresh it too
+  diffStats: (staged: boolean) => ['diff', staged ? 'staged' : 'working', 'stats'] as const,
   commitDiff: (hash: string) => ['diff', 'commit', hash] as const,
   fileDiff: (path: string, staged: boolean) => ['diff', 'file', path, staged] as const,
+  fileDiffPaged: (path: string, staged: boolean, maxLines?: number) =>
+    ['diff', 'file', path, staged, 'paged', maxLines] as const,
   compareRefs: (baseRef: string, headRef: string) => ['compare', baseRef, headRef] as const,
   pullRequests: (state?: string) => ['pullRequests', state] as const,
   pullRequest: (number: number) => ['pullRequest', number] as const,
   prReviews: (number: number) => ['prReviews', number] as const,
   prComments: (number: number) => ['prComments', number] as const,
+  prReviewThreads: (number: number) => ['prReviewThreads', number] as const,
   prFiles: (number: number) => ['prFiles', number] as const,
   ghCliStatus: ['ghCliStatus'] as const,
+  defaultBranch: ['defaultBranch'] as const,
   gitConfig: (key: string) => ['gitConfig', key] as const,
+  commitTemplate: (clean: boolean) => ['commitTemplate', clean] as const,
+  rebaseStatus: ['rebase', 'status'] as const,
+  commitMessageViolations: (message: string) => ['commitMessageViolations', message] as const,
   issues: (state?: string, label?: string, assignee?: string, milestone?: string) => ['issues', state, label, assignee, milestone] as const,
   issue: (number: number) => ['issue', number] as const,
   issueComments: (number: number) => ['issueComments', number] as const,
@@ -36,7 +48,7 @@ export const queryKeys = {
 export function useGitConfig(key: string) {
   return useQuery({
     queryKey: queryKeys.gitConfig(key),
-    queryFn: () => git.repo.getConfig(key),
+    queryFn: async () => (await git.repo.getConfig(key))?.value ?? null,
     staleTime: Infinity,
   });
 }
@@ -54,7 +66,8 @@ export function useRepoInfo(enabled = true) {
 export function useRepoStatus(enabled = true) {
   return useQuery({
     queryKey: queryKeys.repoStatus,
-    queryFn: git.repo.getStatus,
+    // New folders come back as one "dir/" entry instead of every file inside
+    queryFn: () => git.repo.getStatus(false, true),
     refetchInterval: 5000,
     enabled,
   });
@@ -76,6 +89,14 @@ export function useOpenRepos() {
   });
 }
 
+export function useOpenRepoStatuses() {
+  return useQuery({
+    queryKey: queryKeys.openRepoStatuses,
+    queryFn: git.repo.getAllStatuses,
+    refetchInterval: 15000,
+  });
+}
+
 export function useOpenRepo() {
   const queryClient = useQueryClient();
 
@@ -202,6 +223,41 @@ export function useSetActiveRepo() {
   });
 }
 
+// Keeps this window in sync when any window opens, closes or switches repos
+export function useActiveRepoEvents() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = git.repo.onActiveChanged(({ id, open_repos }) => {
+      const previous = queryClient.getQueryData<OpenRepoInfo[]>(queryKeys.openRepos);
+      const previousId = previous?.find((repo) => repo.is_active)?.id ?? null;
+      queryClient.setQueryData(queryKeys.openRepos, open_repos);
+      if (previousId === id) {
+        return;
+      }
+
+      if (id === null) {
+        queryClient.setQueryData(queryKeys.repoInfo, { is_repo: false });
+        return;
+      }
+      queryClient.invalidateQueries({ queryKey: queryKeys.repoInfo });
+      queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
+      queryClient.invalidateQueries({ queryKey: ['commits'] });
+      queryClient.invalidateQueries({ queryKey: queryKeys.branches });
+      queryClient.invalidateQueries({ queryKey: queryKeys.remotes });
+      queryClient.invalidateQueries({ queryKey: queryKeys.stashes });
+      queryClient.invalidateQueries({ queryKey: ['pullRequests'] });
+      queryClient.invalidateQueries({ queryKey: ['compare'] });
+      queryClient.invalidateQueries({ queryKey: queryKeys.defaultBranch });
+      queryClient.invalidateQueries({ queryKey: ['commitTemplate'] });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+}
+
 export function useRemoveRecentRepo() {
   const queryClient = useQueryClient();
 
@@ -214,20 +270,58 @@ export function useRemoveRecentRepo() {
 }
 
 // Commit Hooks
-export function useCommits(branch?: string, limit = 100, enabled = true) {
+export function useCommits(
+  branch?: string,
+  limit = 100,
+  enabled = true,
+  withParents = false,
+  withStats = false
+) {
   return useQuery({
-    queryKey: queryKeys.commits(branch),
-    queryFn: () => git.commit.list(branch, limit),
+    queryKey: [
+      ...queryKeys.commits(branch),
+      ...(withParents ? ['parents'] : []),
+      ...(withStats ? ['stats'] : []),
+    ],
+    queryFn: () => git.commit.list(branch, limit, 0, withParents, withStats),
     enabled,
   });
 }
 
+export function useCommitTemplate(clean = true) {
+  return useQuery({
+    queryKey: queryKeys.commitTemplate(clean),
+    queryFn: () => git.commit.getTemplate(clean),
+    staleTime: 60000,
+  });
+}
+
+export function useCommitMessageViolations(message: string) {
+  return useQuery({
+    queryKey: queryKeys.commitMessageViolations(message),
+    queryFn: () => git.commit.validateMessage(message),
+    enabled: message.trim().length > 0,
+    placeholderData: (previous) => previous,
+  });
+}
+
 export function useCreateCommit() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: ({ message, amend }: { message: string; amend?: boolean }) =>
-      git.commit.create(message, amend),
+    mutationFn: ({
+      message,
+      amend,
+      author,
+      runHooks,
+      initialBranch,
+    }: {
+      message: string;
+      amend?: boolean;
+      author?: AuthorOverride;
+      runHooks?: boolean;
+      initialBranch?: string;
+    }) => git.commit.create(message, amend, author, runHooks, initialBranch),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
       queryClient.invalidateQueries({ queryKey: ['commits'] });
@@ -237,6 +331,56 @@ export function useCreateCommit() {
   });
 }
 
+export function useAddToLastCommit() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (force: boolean = false) => git.commit.addToLast(force),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
+      queryClient.invalidateQueries({ queryKey: ['commits'] });
+      queryClient.invalidateQueries({ queryKey: queryKeys.branches });
+    },
+  });
+}
+
+export function useRebaseStatus(enabled = true) {
+  return useQuery({
+    queryKey: queryKeys.rebaseStatus,
+    queryFn: () => git.rebase.getStatus(),
+    refetchInterval: 5000,
+    enabled,
+  });
+}
+
+export function useRebaseContinue() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => git.rebase.continue(),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.rebaseStatus });
+      queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
+      queryClient.invalidateQueries({ queryKey: ['commits'] });
+      queryClient.invalidateQueries({ queryKey: queryKeys.branches });
+    },
+  });
+}
+
+export function useRebaseAbort() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => git.rebase.abort(),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.rebaseStatus });
+      queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
+      queryClient.invalidateQueries({ queryKey: ['commits'] });
+      queryClient.invalidateQueries({ queryKey: queryKeys.branches });
+    },
+  });
+}
+
 export function useCherryPickCommit() {
   const queryClient = useQueryClient();
 
@@ -296,6 +440,19 @@ export function useStageFiles() {
   });
 }
 
+export function useStagePath() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: git.staging.stagePath,
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
+      queryClient.invalidateQueries({ queryKey: queryKeys.workingDiff });
+      queryClient.invalidateQueries({ queryKey: queryKeys.stagedDiff });
+    },
+  });
+}
+
 export function useStagePartial() {
   const queryClient = useQueryClient();
 
@@ -358,11 +515,38 @@ export function useDiscardChanges() {
   });
 }
 
+export function useCleanWorkingTree() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ directories, ignored }: { directories: boolean; ignored: boolean }) =>
+      git.staging.clean(directories, ignored, false),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
+      queryClient.invalidateQueries({ queryKey: queryKeys.workingDiff });
+    },
+  });
+}
+
+export function useResetWorkingTree() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ includeUntracked, includeIgnored }: { includeUntracked: boolean; includeIgnored: boolean }) =>
+      git.staging.resetWorkingTree(includeUntracked, includeIgnored),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
+      queryClient.invalidateQueries({ queryKey: queryKeys.workingDiff });
+      queryClient.invalidateQueries({ queryKey: queryKeys.stagedDiff });
+    },
+  });
+}
+
 // Branch Hooks
 export function useBranches(enabled = true) {
   return useQuery({
     queryKey: queryKeys.branches,
-    queryFn: git.branch.list,
+    queryFn: () => git.branch.list(),
     enabled,
   });
 }
@@ -400,7 +584,7 @@ export function useCheckoutBranch() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: git.branch.checkout,
+    mutationFn: (name: string) => git.branch.checkout(name),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: queryKeys.branches });
       queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
@@ -427,7 +611,31 @@ export function useMergeBranch() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: git.branch.merge,
+    mutationFn: ({
+      name,
+      noFf,
+      message,
+      noCommit,
+    }: {
+      name: string;
+      noFf?: boolean;
+      message?: string;
+      noCommit?: boolean;
+    }) => git.branch.merge(name, noFf, message, noCommit),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
+      queryClient.invalidateQueries({ queryKey: ['commits'] });
+      queryClient.invalidateQueries({ queryKey: queryKeys.branches });
+      queryClient.invalidateQueries({ queryKey: ['compare'] });
+    },
+  });
+}
+
+export function useCommitMerge() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (message?: string) => git.conflict.commitMerge(message),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
       queryClient.invalidateQueries({ queryKey: ['commits'] });
@@ -441,14 +649,23 @@ export function useMergeBranch() {
 export function useWorkingDiff() {
   return useQuery({
     queryKey: queryKeys.workingDiff,
-    queryFn: git.diff.getWorking,
+    queryFn: () => git.diff.getWorking(),
   });
 }
 
 export function useStagedDiff() {
   return useQuery({
     queryKey: queryKeys.stagedDiff,
-    queryFn: git.diff.getStaged,
+    queryFn: () => git.diff.getStaged(),
+  });
+}
+
+export function useDiffStats(staged = false, enabled = true) {
+  return useQuery({
+    queryKey: queryKeys.diffStats(staged),
+    queryFn: () => git.diff.getStats(staged),
+    refetchInterval: 5000,
+    enabled,
   });
 }
 
@@ -460,6 +677,20 @@ export function useFileDiff(path: string, staged: boolean) {
   });
 }
 
+// Loads a file's hunks a page at a time; `fetchNextPage` brings the next window
+export function useFileDiffPaged(path: string, staged: boolean, hunkLimit = 50, maxLines?: number) {
+  return useInfiniteQuery({
+    queryKey: queryKeys.fileDiffPaged(path, staged, maxLines),
+    queryFn: ({ pageParam }) => git.diff.getFilePaged(path, staged, pageParam, hunkLimit, maxLines),
+    initialPageParam: 0,
+    getNextPageParam: (last) => {
+      const next = last.hunk_offset + last.hunks.length;
+      return !last.truncated && next < last.total_hunks ? next : undefined;
+    },
+    enabled: !!path,
+  });
+}
+
 export function useCompareRefs(baseRef: string, headRef: string, enabled = true) {
   return useQuery({
     queryKey: queryKeys.compareRefs(baseRef, headRef),
@@ -508,8 +739,17 @@ export function usePush() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: ({ remote, branch, force }: { remote: string; branch: string; force?: boolean }) =>
-      git.remote.push(remote, branch, force),
+    mutationFn: ({
+      remote,
+      branch,
+      force,
+      forceWithLease,
+    }: {
+      remote: string;
+      branch: string;
+      force?: boolean;
+      forceWithLease?: boolean;
+    }) => git.remote.push(remote, branch, force, false, forceWithLease),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
       queryClient.invalidateQueries({ queryKey: queryKeys.branches });
@@ -529,12 +769,13 @@ export function useCreateStash() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: ({ message, includeUntracked, keepIndex, files }: {
+    mutationFn: ({ message, includeUntracked, keepIndex, stagedOnly, files }: {
       message?: string;
       includeUntracked?: boolean;
       keepIndex?: boolean;
+      stagedOnly?: boolean;
       files?: string[];
-    }) => git.stash.create(message, includeUntracked, keepIndex, files),
+    }) => git.stash.create(message, includeUntracked, keepIndex, files, stagedOnly),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: queryKeys.stashes });
       queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
@@ -565,6 +806,18 @@ export function usePopStash() {
   });
 }
 
+export function useRenameStash() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ index, message }: { index: number; message: string }) =>
+      git.stash.rename(index, message),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.stashes });
+    },
+  });
+}
+
 export function useDropStash() {
   const queryClient = useQueryClient();
 
@@ -612,6 +865,31 @@ export function usePullRequests(state?: string) {
   });
 }
 
+export function useDefaultBranch() {
+  return useQuery({
+    queryKey: queryKeys.defaultBranch,
+    queryFn: () => git.pr.getDefaultBranch(),
+    staleTime: Infinity,
+    retry: false,
+  });
+}
+
+export function useRefreshPullRequests() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (state?: string) => git.pr.refresh(state),
+    onSuccess: (data, state) => {
+      // Backend cache is gone; drop ours too so open PR details refetch
+      queryClient.invalidateQueries({ queryKey: ['pullRequest'] });
+      queryClient.invalidateQueries({ queryKey: ['prFiles'] });
+      queryClient.invalidateQueries({ queryKey: ['prDiff'] });
+      queryClient.invalidateQueries({ queryKey: ['prChecks'] });
+      queryClient.setQueryData(queryKeys.pullRequests(state), data);
+    },
+  });
+}
+
 export function usePullRequest(number: number) {
   return useQuery({
     queryKey: queryKeys.pullRequest(number),
@@ -636,6 +914,26 @@ export function usePRComments(number: number) {
   });
 }
 
+export function usePRReviewThreads(number: number) {
+  return useQuery({
+    queryKey: queryKeys.prReviewThreads(number),
+    queryFn: () => git.pr.getReviewThreads(number),
+    enabled: number > 0,
+  });
+}
+
+export function useResolveReviewThread() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ threadId, resolved }: { number: number; threadId: string; resolved: boolean }) =>
+      resolved ? git.pr.resolveThread(threadId) : git.pr.unresolveThread(threadId),
+    onSuccess: (_, variables) => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.prReviewThreads(variables.number) });
+    },
+  });
+}
+
 export function usePRFiles(number: number) {
   return useQuery({
     queryKey: queryKeys.prFiles(number),
@@ -647,7 +945,7 @@ export function usePRFiles(number: number) {
 export function usePRDiff(number: number) {
   return useQuery({
     queryKey: ['prDiff', number] as const,
-    queryFn: () => git.pr.getDiff(number),
+    queryFn: () => git.pr.getDiffStructured(number),
     enabled: number > 0,
   });
 }
@@ -678,6 +976,25 @@ export function useCreatePR() {
   });
 }
 
+export function usePublishBranchAndPR() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ title, body, base, remote, draft }: {
+      title: string;
+      body: string | null;
+      base: string;
+      remote?: string;
+      draft?: boolean;
+    }) => git.pr.publish(title, body, base, remote, draft),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['pullRequests'] });
+      queryClient.invalidateQueries({ queryKey: queryKeys.branches });
+      queryClient.invalidateQueries({ queryKey: queryKeys.repoStatus });
+    },
+  });
+}
+
 export function useReviewPR() {
   const queryClient = useQueryClient();
 
@@ -0,0 +1,24 @@
+import { git } from '@/services/git';
+
+/**
+ * Pergunta antes de uma ação que pode perder ou conflitar com alterações locais.
+ * Retorna true se a árvore está limpa ou o usuário confirmou.
+ */
+export async function confirmIfDirty(action: string): Promise<boolean> {
+  let state;
+  try {
+    state = await git.repo.isWorkingTreeClean();
+  } catch {
+    // The action itself will report a broken repo
+    return true;
+  }
+  if (state.clean) return true;
+
+  const parts = [
+    state.staged && `${state.staged} staged`,
+    state.unstaged && `${state.unstaged} modificado(s)`,
+    state.untracked && `${state.untracked} não rastreado(s)`,
+    state.conflicted && `${state.conflicted} em conflito`,
+  ].filter(Boolean);
+  return confirm(`Existem alterações locais (${parts.join(', ')}). ${action} mesmo assim?`);
+}
@@ -1,22 +1,58 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type {
+  AheadBehind,
+  AuthorOverride,
+  AppSettings,
   RepoInfo,
+  CloneResult,
+  RepoStats,
+  GcResult,
   RepoStatus,
+  DiscardPreview,
+  DiffPage,
+  ResetWorkingTreeResult,
+  RepoStatusSummary,
+  WorkingTreeState,
   RecentRepo,
   CommitInfo,
+  FileHistoryEntry,
+  CommitQuery,
+  CommitReadiness,
+  CreateCommitResult,
+  CommitMessageViolation,
+  CommitRules,
+  ConfigEntry,
+  ConfigScope,
+  ConfigValue,
+  GraphCommit,
+  ReflogEntry,
+  ResetPathsResult,
   CompareResult,
   BranchInfo,
+  DiffFileSummary,
   DiffInfo,
+  DiffStats,
+  LfsFile,
+  FileAtCommit,
+  TreeEntryInfo,
   BlameInfo,
   PartialHunkSelection,
   RemoteInfo,
+  FetchResult,
   StashInfo,
+  StashApplyResult,
   WorktreeInfo,
   SubmoduleInfo,
   PullRequest,
+  PushPreview,
+  RepoSlug,
   PullRequestReview,
   PullRequestComment,
+  ReviewThread,
   PullRequestFile,
+  ConflictChoice,
+  ConflictEntry,
   ConflictInfo,
   Issue,
   IssueComment,
@@ -26,7 +62,11 @@ import type {
   GitHubProject,
   IssueTemplate,
   CheckRun,
+  WorkflowRun,
+  WorkflowRunDetails,
   RebaseEntry,
+  DropCommitResult,
+  RebaseStatus,
 } from '@/types';
 
 // Open repo info type
@@ -37,24 +77,55 @@ export interface OpenRepoInfo {
   is_active: boolean;
 }
 
+// Payload of `repo://active-changed`, sent whenever tabs or the active repo change
+export interface ActiveRepoChanged {
+  id: string | null;
+  path: string | null;
+  open_repos: OpenRepoInfo[];
+}
+
+// Tab badge for an open repo; counts are missing when `error` is set
+export type RepoStatusBadge = { id: string; error: string | null } & Partial<RepoStatusSummary>;
+
 // Repository
 export const repoService = {
   open: (path: string) => invoke<RepoInfo>('open_repo', { path }),
   close: () => invoke<void>('close_repo'),
   closeById: (id: string) => invoke<void>('close_repo_by_id', { id }),
   getOpenRepos: () => invoke<OpenRepoInfo[]>('get_open_repos'),
+  getAllStatuses: () => invoke<RepoStatusBadge[]>('get_all_repo_statuses'),
   setActiveRepo: (id: string) => invoke<boolean>('set_active_repo', { id }),
+  onActiveChanged: (handler: (payload: ActiveRepoChanged) => void): Promise<UnlistenFn> =>
+    listen<ActiveRepoChanged>('repo://active-changed', (event) => handler(event.payload)),
   getInfo: () => invoke<RepoInfo>('get_repo_info'),
-  getStatus: () => invoke<RepoStatus>('get_repo_status'),
+  getStatus: (includeIgnored = false, collapseUntracked = false) =>
+    invoke<RepoStatus>('get_repo_status', { includeIgnored, collapseUntracked }),
+  isWorkingTreeClean: () => invoke<WorkingTreeState>('is_working_tree_clean'),
+  getStats: (deep = false) => invoke<RepoStats>('get_repo_stats', { deep }),
+  runGc: (aggressive = false) => invoke<GcResult>('run_gc', { aggressive }),
   init: (path: string, bare = false) => invoke<RepoInfo>('init_repo', { path, bare }),
-  clone: (url: string, path: string) => invoke<RepoInfo>('clone_repo', { url, path }),
+  clone: (url: string, path: string, operationId?: string) =>
+    invoke<CloneResult>('clone_repo', { url, path, operationId }),
+  cancelOperation: (operationId: string) => invoke<boolean>('cancel_operation', { operationId }),
   getRecent: () => invoke<RecentRepo[]>('get_recent_repos'),
   removeRecent: (path: string) => invoke<void>('remove_recent_repo', { path }),
   clearRecent: () => invoke<void>('clear_recent_repos'),
-  getConfig: (key: string) => invoke<string | null>('get_git_config_value', { key }),
-  setConfig: (key: string, value: string) => invoke<void>('set_git_config_value', { key, value }),
+  getSettings: () => invoke<AppSettings>('get_settings'),
+  updateSettings: (settings: AppSettings) => invoke<AppSettings>('update_settings', { settings }),
+  getConfig: (key: string, scope?: ConfigScope) =>
+    invoke<ConfigValue | null>('get_git_config_value', { key, scope }),
+  setConfig: (key: string, value: string, scope?: ConfigScope) =>
+    invoke<void>('set_git_config_value', { key, value, scope }),
+  listConfig: (scope?: ConfigScope) => invoke<ConfigEntry[]>('list_git_config', { scope }),
   openInVscode: () => invoke<void>('open_in_vscode'),
+  openInEditor: (path?: string, line?: number, emulator?: string) =>
+    invoke<void>('open_in_editor', { path, line, emulator }),
   openInExplorer: () => invoke<void>('open_in_explorer'),
+  openInFileManager: (path?: string) => invoke<void>('open_in_file_manager', { path }),
+  openOnGithubCommit: (hash: string) => invoke<void>('open_on_github_commit', { hash }),
+  openOnGithubBranch: (name: string) => invoke<void>('open_on_github_branch', { name }),
+  openOnGithubFile: (path: string, line?: number) =>
+    invoke<void>('open_on_github_file', { path, line }),
   openInTerminal: (emulator: string) => invoke<void>('open_in_terminal', { emulator }),
   readFile: (path: string) => invoke<string>('read_file', { path }),
   writeFile: (path: string, content: string) => invoke<void>('write_file', { path, content }),
@@ -62,20 +133,54 @@ export const repoService = {
 
 // Commits
 export const commitService = {
-  list: (branch?: string, limit = 100, skip = 0) =>
-    invoke<CommitInfo[]>('get_commits', { branch, limit, skip }),
-  get: (hash: string) => invoke<CommitInfo>('get_commit', { hash }),
-  create: (message: string, amend = false) =>
-    invoke<string>('create_commit', { message, amend }),
+  list: (branch?: string, limit = 100, skip = 0, withParents = false, withStats = false) =>
+    invoke<CommitInfo[]>('get_commits', { branch, limit, skip, withParents, withStats }),
+  count: (branch?: string) => invoke<number>('get_commit_count', { branch }),
+  search: (query: CommitQuery, limit = 100, skip = 0) =>
+    invoke<CommitInfo[]>('search_commits', { query, limit, skip }),
+  graph: (branch?: string, limit = 100, skip = 0) =>
+    invoke<GraphCommit[]>('get_commit_graph', { branch, limit, skip }),
+  get: (hash: string, withParents = false) =>
+    invoke<CommitInfo>('get_commit', { hash, withParents }),
+  create: (
+    message: string,
+    amend = false,
+    author?: AuthorOverride,
+    runHooks?: boolean,
+    initialBranch?: string
+  ) =>
+    invoke<CreateCommitResult>('create_commit', {
+      message,
+      amend,
+      authorName: author?.name,
+      authorEmail: author?.email,
+      authorDate: author?.date,
+      runHooks,
+      initialBranch,
+    }),
+  addToLast: (force = false) => invoke<CreateCommitResult>('add_to_last_commit', { force }),
+  canCommit: () => invoke<CommitReadiness>('can_commit'),
+  getTemplate: (clean = false) => invoke<string | null>('get_commit_template', { clean }),
+  validateMessage: (message: string, rules?: CommitRules) =>
+    invoke<CommitMessageViolation[]>('validate_commit_message', { message, rules }),
+  squash: (fromHash: string, message: string) =>
+    invoke<string>('squash_commits', { fromHash, message }),
   cherryPick: (commitHash: string) => invoke<string>('cherry_pick', { commitHash }),
   revert: (commitHash: string) => invoke<string>('revert_commit', { commitHash }),
+  continueCherryPick: () => invoke<string>('continue_cherry_pick'),
+  continueRevert: () => invoke<string>('continue_revert'),
   reset: (commitHash: string, mode: 'soft' | 'mixed' | 'hard') =>
     invoke<void>('reset_to_commit', { commitHash, mode }),
+  resetPaths: (commitHash: string, paths: string[], updateWorktree = true) =>
+    invoke<ResetPathsResult>('reset_paths_to_commit', { commitHash, paths, updateWorktree }),
+  reflog: (reference?: string, limit = 100) =>
+    invoke<ReflogEntry[]>('get_reflog', { reference, limit }),
 };
 
 // Staging
 export const stagingService = {
   stageFiles: (files: string[]) => invoke<void>('stage_files', { files }),
+  stagePath: (path: string) => invoke<void>('stage_path', { path }),
   stagePartial: (path: string, selections: PartialHunkSelection[]) =>
     invoke<void>('stage_partial_changes', { path, selections }),
   unstageFiles: (files: string[]) => invoke<void>('unstage_files', { files }),
@@ -83,43 +188,87 @@ export const stagingService = {
     invoke<void>('unstage_partial_changes', { path, selections }),
   stageAll: () => invoke<void>('stage_all'),
   unstageAll: () => invoke<void>('unstage_all'),
+  previewDiscard: (files: string[]) => invoke<DiscardPreview[]>('preview_discard', { files }),
   discardChanges: (files: string[]) => invoke<void>('discard_changes', { files }),
+  // No defaults on purpose: deleting files must be asked for explicitly
+  resetWorkingTree: (includeUntracked: boolean, includeIgnored: boolean) =>
+    invoke<ResetWorkingTreeResult>('reset_working_tree', { includeUntracked, includeIgnored }),
+  // Returns what was (or, with dryRun, would be) deleted
+  clean: (directories: boolean, ignored: boolean, dryRun: boolean) =>
+    invoke<string[]>('clean_working_tree', { directories, ignored, dryRun }),
+  restoreFiles: (files: string[], staged = false) =>
+    invoke<string[]>('restore_files', { files, staged }),
 };
 
 // Branches
 export const branchService = {
-  list: () => invoke<BranchInfo[]>('get_branches'),
+  list: (filter?: string, includeLocal = true, includeRemote = true, withDetails = true) =>
+    invoke<BranchInfo[]>('get_branches', { filter, includeLocal, includeRemote, withDetails }),
+  getDetails: (name: string) => invoke<BranchInfo>('get_branch_details', { name }),
+  getDescription: (name: string) => invoke<string | null>('get_branch_description', { name }),
+  setDescription: (name: string, text: string) =>
+    invoke<void>('set_branch_description', { name, text }),
   getCurrent: () => invoke<string>('get_current_branch'),
   create: (name: string, checkout = false) =>
     invoke<void>('create_branch', { name, checkout }),
-  checkout: (name: string) => invoke<void>('checkout_branch', { name }),
+  checkout: (name: string, force = false) => invoke<void>('checkout_branch', { name, force }),
+  checkoutCommit: (commitHash: string, force = false) =>
+    invoke<void>('checkout_commit', { commitHash, force }),
   delete: (name: string, force = false) =>
     invoke<void>('delete_branch', { name, force }),
   rename: (oldName: string, newName: string) =>
     invoke<void>('rename_branch', { oldName, newName }),
-  merge: (name: string) => invoke<string>('merge_branch', { name }),
+  copy: (source: string, newName: string, checkout = false) =>
+    invoke<void>('copy_branch', { source, newName, checkout }),
+  merge: (name: string, noFf = false, message?: string, noCommit = false) =>
+    invoke<string>('merge_branch', { name, noFf, noCommit, message }),
 };
 
 // Diff
 export const diffService = {
-  getWorking: () => invoke<DiffInfo[]>('get_working_diff'),
-  getStaged: () => invoke<DiffInfo[]>('get_staged_diff'),
-  getCommit: (commitHash: string) => invoke<DiffInfo[]>('get_commit_diff', { commitHash }),
-  getFile: (path: string, staged: boolean) =>
-    invoke<DiffInfo>('get_file_diff', { path, staged }),
-  getBlame: (path: string) => invoke<BlameInfo[]>('get_file_blame', { path }),
-  getFileAtCommit: (commitHash: string, path: string) =>
-    invoke<DiffInfo>('get_file_diff_at_commit', { commitHash, path }),
+  getWorking: (wordDiff = false, operationId?: string) =>
+    invoke<DiffInfo[]>('get_working_diff', { wordDiff, operationId }),
+  getStaged: (wordDiff = false, operationId?: string) =>
+    invoke<DiffInfo[]>('get_staged_diff', { wordDiff, operationId }),
+  getCommit: (commitHash: string, wordDiff = false, operationId?: string, parentIndex?: number) =>
+    invoke<DiffInfo[]>('get_commit_diff', { commitHash, parentIndex, wordDiff, operationId }),
+  getStats: (staged = false) => invoke<DiffStats>('get_diff_stats', { staged }),
+  getCommitSummary: (commitHash: string) =>
+    invoke<DiffFileSummary[]>('get_commit_diff_summary', { commitHash }),
+  getFile: (path: string, staged: boolean, wordDiff = false) =>
+    invoke<DiffInfo>('get_file_diff', { path, staged, wordDiff }),
+  getFilePaged: (
+    path: string,
+    staged: boolean,
+    hunkOffset = 0,
+    hunkLimit = 50,
+    maxLines?: number,
+    wordDiff = false
+  ) =>
+    invoke<DiffPage>('get_file_diff_paged', { path, staged, hunkOffset, hunkLimit, maxLines, wordDiff }),
+  getBlame: (path: string, operationId?: string) =>
+    invoke<BlameInfo[]>('get_file_blame', { path, operationId }),
+  getFileAtCommit: (commitHash: string, path: string, wordDiff = false) =>
+    invoke<DiffInfo>('get_file_diff_at_commit', { commitHash, path, wordDiff }),
+  getFileContentAtCommit: (commitHash: string, path: string) =>
+    invoke<FileAtCommit>('get_file_at_commit', { commitHash, path }),
+  listTree: (commitIsh?: string, path?: string) =>
+    invoke<TreeEntryInfo[]>('list_tree', { commitIsh, path }),
+  lfsStatus: () => invoke<LfsFile[]>('lfs_status'),
 };
 
 export const fileHistoryService = {
-  get: (path: string, limit?: number) =>
-    invoke<CommitInfo[]>('get_file_history', { path, limit }),
+  get: (path: string, limit?: number, follow = false, skip = 0) =>
+    invoke<FileHistoryEntry[]>('get_file_history', { path, limit, skip, follow }),
 };
 
 export const compareService = {
   refs: (baseRef: string, headRef: string) =>
     invoke<CompareResult>('compare_refs', { baseRef, headRef }),
+  mergeBase: (a: string, b: string) =>
+    invoke<CommitInfo | null>('get_merge_base', { a, b }),
+  aheadBehind: (a: string, b: string) =>
+    invoke<AheadBehind>('get_ahead_behind_between', { a, b }),
 };
 
 export const worktreeService = {
@@ -140,22 +289,38 @@ export const remoteService = {
   remove: (name: string) => invoke<void>('remove_remote', { name }),
   rename: (oldName: string, newName: string) =>
     invoke<void>('rename_remote', { oldName, newName }),
-  fetch: (remote?: string) => invoke<void>('fetch_remote', { remote }),
+  fetch: (remote?: string, fetchTags = false, operationId?: string) =>
+    invoke<FetchResult[]>('fetch_remote', { remote, fetchTags, operationId }),
   pull: (remote: string, branch: string) =>
     invoke<string>('pull_remote', { remote, branch }),
-  push: (remote: string, branch: string, force = false) =>
-    invoke<void>('push_remote', { remote, branch, force }),
+  push: (remote: string, branch: string, force = false, setUpstream = false, forceWithLease = false) =>
+    invoke<void>('push_remote', { remote, branch, force, setUpstream, forceWithLease }),
+  pushDryRun: (remote: string, branch: string, operationId?: string) =>
+    invoke<PushPreview>('push_dry_run', { remote, branch, operationId }),
   setUpstream: (branch: string, remote: string, remoteBranch: string) =>
     invoke<void>('set_upstream', { branch, remote, remoteBranch }),
+  getRepoSshKey: () => invoke<string | null>('get_repo_ssh_key'),
+  setRepoSshKey: (keyPath: string | null) => invoke<void>('set_repo_ssh_key', { keyPath }),
+  setSshPassphrase: (keyPath: string, passphrase: string | null) =>
+    invoke<void>('set_ssh_passphrase', { keyPath, passphrase }),
+  hasSshPassphrase: (keyPath: string) => invoke<boolean>('has_ssh_passphrase', { keyPath }),
+  unsetUpstream: (branch: string) => invoke<void>('unset_upstream', { branch }),
 };
 
 // Stash
 export const stashService = {
   list: () => invoke<StashInfo[]>('get_stash_list'),
-  create: (message?: string, includeUntracked = false, keepIndex = false, files?: string[]) =>
-    invoke<string>('create_stash', { message, includeUntracked, keepIndex, files }),
-  apply: (index: number) => invoke<void>('apply_stash', { index }),
-  pop: (index: number) => invoke<void>('pop_stash', { index }),
+  create: (
+    message?: string,
+    includeUntracked = false,
+    keepIndex = false,
+    files?: string[],
+    stagedOnly = false
+  ) => invoke<string>('create_stash', { message, includeUntracked, keepIndex, stagedOnly, files }),
+  apply: (index: number) => invoke<StashApplyResult>('apply_stash', { index }),
+  pop: (index: number) => invoke<StashApplyResult>('pop_stash', { index }),
+  rename: (index: number, message: string) =>
+    invoke<void>('rename_stash', { index, message }),
   drop: (index: number) => invoke<void>('drop_stash', { index }),
   clear: () => invoke<void>('clear_stashes'),
 };
@@ -163,15 +328,25 @@ export const stashService = {
 // Pull Requests (GitHub)
 export const prService = {
   checkCli: () => invoke<boolean>('check_github_cli'),
+  getRepoSlug: () => invoke<RepoSlug>('get_repo_slug'),
+  getDefaultBranch: () => invoke<string>('get_default_branch'),
   list: (state?: string, limit?: number) =>
     invoke<PullRequest[]>('list_pull_requests', { prState: state, limit }),
+  refresh: (state?: string, limit?: number) =>
+    invoke<PullRequest[]>('refresh_pull_requests', { prState: state, limit }),
   get: (number: number) => invoke<PullRequest>('get_pull_request', { number }),
   create: (title: string, body: string | null, base: string, head?: string, draft = false) =>
     invoke<PullRequest>('create_pull_request', { title, body, base, head, draft }),
+  publish: (title: string, body: string | null, base: string, remote?: string, draft = false) =>
+    invoke<PullRequest>('publish_branch_and_pr', { title, body, base, remote, draft }),
   getReviews: (number: number) =>
     invoke<PullRequestReview[]>('get_pull_request_reviews', { number }),
   getComments: (number: number) =>
     invoke<PullRequestComment[]>('get_pull_request_comments', { number }),
+  getReviewThreads: (number: number) =>
+    invoke<ReviewThread[]>('get_pr_review_threads', { number }),
+  resolveThread: (threadId: string) => invoke<void>('resolve_review_thread', { threadId }),
+  unresolveThread: (threadId: string) => invoke<void>('unresolve_review_thread', { threadId }),
   getFiles: (number: number) =>
     invoke<PullRequestFile[]>('get_pull_request_files', { number }),
   review: (number: number, action: 'approve' | 'request-changes' | 'comment', body?: string) =>
@@ -184,10 +359,19 @@ export const prService = {
   reopen: (number: number) => invoke<void>('reopen_pull_request', { number }),
   ready: (number: number) => invoke<void>('ready_pull_request', { number }),
   getDiff: (number: number) => invoke<string>('get_pull_request_diff', { number }),
+  getDiffStructured: (number: number) =>
+    invoke<DiffInfo[]>('get_pull_request_diff_structured', { number }),
   checkout: (number: number) => invoke<void>('checkout_pull_request', { number }),
   getChecks: (number: number) => invoke<CheckRun[]>('get_pr_checks', { number }),
 };
 
+// GitHub Actions
+export const actionsService = {
+  listRuns: (branch?: string, limit?: number) =>
+    invoke<WorkflowRun[]>('list_workflow_runs', { branch, limit }),
+  getRun: (id: number) => invoke<WorkflowRunDetails>('get_workflow_run', { id }),
+};
+
 // Issues
 export const issueService = {
   list: (state?: string, limit?: number, label?: string, assignee?: string, milestone?: string) =>
@@ -261,15 +445,28 @@ export const rebaseService = {
     invoke<CommitInfo[]>('get_rebase_range', { baseHash }),
   execute: (baseHash: string, entries: RebaseEntry[]) =>
     invoke<void>('execute_interactive_rebase', { baseHash, entries }),
+  dropCommit: (commitHash: string) =>
+    invoke<DropCommitResult>('drop_commit', { commitHash }),
+  getStatus: () => invoke<RebaseStatus>('get_rebase_status'),
+  continue: () => invoke<RebaseStatus>('rebase_continue'),
+  abort: () => invoke<void>('rebase_abort'),
 };
 
 // Conflict Resolution
 export const conflictService = {
   getInfo: (path: string) => invoke<ConflictInfo>('get_conflict_info', { path }),
+  getAll: () => invoke<ConflictEntry[]>('get_all_conflicts'),
   getFile: (path: string) => invoke<string>('get_conflicted_file', { path }),
   resolve: (path: string, content: string, markResolved = true) =>
     invoke<void>('resolve_conflict', { path, content, markResolved }),
+  resolveSide: (path: string, choice: ConflictChoice) =>
+    invoke<void>('resolve_conflict_side', { path, choice }),
+  resolveSection: (path: string, sectionId: number, choice: ConflictChoice) =>
+    invoke<boolean>('resolve_conflict_section', { path, sectionId, choice }),
   abortMerge: () => invoke<void>('abort_merge'),
+  commitMerge: (message?: string) => invoke<string>('commit_merge', { message }),
+  abortCherryPick: () => invoke<void>('abort_cherry_pick'),
+  abortRevert: () => invoke<void>('abort_revert'),
 };
 
 export const submoduleService = {
@@ -290,6 +487,7 @@ export const git = {
   remote: remoteService,
   stash: stashService,
   pr: prService,
+  actions: actionsService,
   issue: issueService,
   conflict: conflictService,
   fileHistory: fileHistoryService,
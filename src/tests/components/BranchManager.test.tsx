@@ -15,9 +15,11 @@ const mockBranches: BranchInfo[] = [
     upstream: 'origin/main',
     ahead: 0,
     behind: 0,
+    upstream_status: 'synced',
     author_name: 'Pablo',
     author_email: 'pablo@test.com',
     commit_date: Math.floor(Date.now() / 1000) - 86400,
+    description: null,
   },
   {
     name: 'feature-login',
@@ -29,9 +31,11 @@ const mockBranches: BranchInfo[] = [
     upstream: null,
     ahead: null,
     behind: null,
+    upstream_status: 'none',
     author_name: 'Pablo',
     author_email: 'pablo@test.com',
     commit_date: Math.floor(Date.now() / 1000) - 86400,
+    description: null,
   },
   {
     name: 'origin/feature-login',
@@ -43,9 +47,11 @@ const mockBranches: BranchInfo[] = [
     upstream: null,
     ahead: null,
     behind: null,
+    upstream_status: 'none',
     author_name: 'Pablo',
     author_email: 'pablo@test.com',
     commit_date: Math.floor(Date.now() / 1000) - 86400,
+    description: null,
   },
   {
     name: 'fix-bug-123',
@@ -57,9 +63,11 @@ const mockBranches: BranchInfo[] = [
     upstream: null,
     ahead: null,
     behind: null,
+    upstream_status: 'none',
     author_name: 'Outro Dev',
     author_email: 'outro@test.com',
     commit_date: Math.floor(Date.now() / 1000) - 86400,
+    description: null,
   },
 ];
 
@@ -15,9 +15,11 @@ const mockBranches: BranchInfo[] = [
     upstream: 'origin/main',
     ahead: 0,
     behind: 0,
+    upstream_status: 'synced',
     author_name: 'Pablo',
     author_email: 'pablo@test.com',
     commit_date: Math.floor(Date.now() / 1000),
+    description: null,
   },
   {
     name: 'feature/palette',
@@ -29,9 +31,11 @@ const mockBranches: BranchInfo[] = [
     upstream: null,
     ahead: null,
     behind: null,
+    upstream_status: 'none',
     author_name: 'Pablo',
     author_email: 'pablo@test.com',
     commit_date: Math.floor(Date.now() / 1000),
+    description: null,
   },
 ];
 
@@ -41,6 +45,7 @@ const mockCommits: CommitInfo[] = [
     short_hash: '1111111',
     message: 'feat: add palette',
     summary: 'feat: add palette',
+    subject: 'feat: add palette',
     body: null,
     author_name: 'Pablo',
     author_email: 'pablo@test.com',
@@ -49,6 +54,7 @@ const mockCommits: CommitInfo[] = [
     committer_email: 'pablo@test.com',
     committer_date: Math.floor(Date.now() / 1000),
     parents: [],
+    parent_summaries: [],
     is_merge: false,
   },
 ];
@@ -95,20 +101,27 @@ describe('CommandPalette', () => {
     current_branch: 'main',
     has_remote: true,
     is_empty: false,
+    last_branch: null,
   };
 
   const status: RepoStatus = {
     current_branch: 'main',
     head_commit: '1111111',
-    staged_files: [{ path: 'src/App.tsx', status: 'modified', is_binary: false }],
+    staged_files: [{ path: 'src/App.tsx', status: 'modified', is_binary: false, is_lfs: false }],
     unstaged_files: [],
     untracked_files: [],
+    untracked_dirs: [],
     conflicted_files: [],
+    ignored_files: [],
+    entries: [],
     ahead: 0,
     behind: 0,
+    upstream: null,
     is_rebasing: false,
     is_merging: false,
     is_cherry_picking: false,
+    is_detached: false,
+    detached_commit: null,
   };
 
   beforeEach(() => {
@@ -17,9 +17,11 @@ const mockBranches: BranchInfo[] = [
     upstream: 'refs/remotes/origin/main',
     ahead: 0,
     behind: 0,
+    upstream_status: 'synced',
     author_name: 'Pablo',
     author_email: 'pablo@test.com',
     commit_date: now,
+    description: null,
   },
   {
     name: 'feature/login',
@@ -31,9 +33,11 @@ const mockBranches: BranchInfo[] = [
     upstream: null,
     ahead: null,
     behind: null,
+    upstream_status: 'none',
     author_name: 'Pablo',
     author_email: 'pablo@test.com',
     commit_date: now,
+    description: null,
   },
 ];
 
@@ -43,6 +47,7 @@ const mockCommits: CommitInfo[] = [
     short_hash: 'bbbbbbb',
     message: 'feat: login',
     summary: 'feat: login',
+    subject: 'feat: login',
     body: null,
     author_name: 'Pablo',
     author_email: 'pablo@test.com',
@@ -51,6 +56,7 @@ const mockCommits: CommitInfo[] = [
     committer_email: 'pablo@test.com',
     committer_date: now,
     parents: ['aaaaaaaaaaaaaaaa'],
+    parent_summaries: [],
     is_merge: false,
   },
   {
@@ -58,6 +64,7 @@ const mockCommits: CommitInfo[] = [
     short_hash: 'aaaaaaa',
     message: 'chore: base',
     summary: 'chore: base',
+    subject: 'chore: base',
     body: null,
     author_name: 'Pablo',
     author_email: 'pablo@test.com',
@@ -66,6 +73,7 @@ const mockCommits: CommitInfo[] = [
     committer_email: 'pablo@test.com',
     committer_date: now - 3600,
     parents: [],
+    parent_summaries: [],
     is_merge: false,
   },
 ];
@@ -76,12 +84,18 @@ const mockStatus: RepoStatus = {
   staged_files: [],
   unstaged_files: [],
   untracked_files: [],
+  untracked_dirs: [],
   conflicted_files: [],
+  ignored_files: [],
+  entries: [],
   ahead: 0,
   behind: 0,
+  upstream: null,
   is_rebasing: false,
   is_merging: false,
   is_cherry_picking: false,
+  is_detached: false,
+  detached_commit: null,
 };
 
 const mockCompareResult: CompareResult = {
@@ -118,6 +132,7 @@ const mockCompareResult: CompareResult = {
               content: 'const a = 1;',
               origin: '-',
               line_type: 'deletion',
+              segments: [],
             },
             {
               old_line: null,
@@ -125,10 +140,17 @@ const mockCompareResult: CompareResult = {
               content: 'const a = 2;',
               origin: '+',
               line_type: 'addition',
+              segments: [],
             },
           ],
         },
       ],
+      truncated: false,
+      is_lfs: false,
+      lfs_pointer: null,
+      line_ending_only: false,
+      encoding: null,
+      language: null,
     },
   ],
 };
@@ -6,9 +6,9 @@ import type { RecentRepo } from '@/types';
 const now = Math.floor(Date.now() / 1000);
 
 const recentRepos: RecentRepo[] = [
-  { path: '/repos/meu-projeto', name: 'meu-projeto', last_opened: now - 3600 },
-  { path: '/repos/backend', name: 'backend', last_opened: now - 86400 },
-  { path: '/repos/antigo', name: 'antigo', last_opened: now - 86400 * 10 },
+  { path: '/repos/meu-projeto', name: 'meu-projeto', last_opened: now - 3600, last_branch: null },
+  { path: '/repos/backend', name: 'backend', last_opened: now - 86400, last_branch: null },
+  { path: '/repos/antigo', name: 'antigo', last_opened: now - 86400 * 10, last_branch: null },
 ];
 
 describe('WelcomeScreen', () => {
@@ -123,7 +123,7 @@ describe('WelcomeScreen', () => {
   it('mostra "Hoje" para repo aberto hoje', () => {
     render(
       <WelcomeScreen
-        recentRepos={[{ path: '/x', name: 'x', last_opened: now - 100 }]}
+        recentRepos={[{ path: '/x', name: 'x', last_opened: now - 100, last_branch: null }]}
         isLoading={false}
         onOpenRepo={vi.fn()}
         onOpenRecent={vi.fn()}
@@ -136,7 +136,7 @@ describe('WelcomeScreen', () => {
   it('mostra "Ontem" para repo aberto ontem', () => {
     render(
       <WelcomeScreen
-        recentRepos={[{ path: '/x', name: 'x', last_opened: now - 86400 - 100 }]}
+        recentRepos={[{ path: '/x', name: 'x', last_opened: now - 86400 - 100, last_branch: null }]}
         isLoading={false}
         onOpenRepo={vi.fn()}
         onOpenRecent={vi.fn()}
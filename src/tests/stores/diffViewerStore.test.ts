@@ -10,6 +10,12 @@ const makeDiff = (path: string): DiffInfo => ({
   deletions: 0,
   is_binary: false,
   status: 'modified',
+  truncated: false,
+  is_lfs: false,
+  lfs_pointer: null,
+  line_ending_only: false,
+  encoding: null,
+  language: null,
 });
 
 const makeCommit = (): CommitInfo => ({
@@ -17,6 +23,7 @@ const makeCommit = (): CommitInfo => ({
   short_hash: 'abc1234',
   message: 'feat: teste',
   summary: 'feat: teste',
+  subject: 'feat: teste',
   body: null,
   author_name: 'Test',
   author_email: 'test@test.com',
@@ -25,6 +32,7 @@ const makeCommit = (): CommitInfo => ({
   committer_email: 'test@test.com',
   committer_date: 0,
   parents: [],
+  parent_summaries: [],
   is_merge: false,
 });
 
@@ -7,33 +7,107 @@ export interface RepoInfo {
   current_branch: string | null;
   has_remote: boolean;
   is_empty: boolean;
+  last_branch: string | null;
+}
+
+export interface CloneResult extends RepoInfo {
+  default_branch: string | null;
+  remote: string;
 }
 
 export interface RecentRepo {
   path: string;
   name: string;
   last_opened: number;
+  last_branch: string | null;
 }
 
 // Status
+export interface GcResult {
+  size_before: number;
+  size_after: number;
+  output: string;
+}
+
+export interface RepoStats {
+  local_branch_count: number;
+  remote_branch_count: number;
+  tag_count: number;
+  size_on_disk: number;
+  commit_count: number | null;
+  contributor_count: number | null;
+}
+
 export interface RepoStatus {
   current_branch: string;
   head_commit: string | null;
   staged_files: FileStatus[];
   unstaged_files: FileStatus[];
   untracked_files: string[];
+  // Only with collapseUntracked; their paths ("dir/") are in untracked_files too
+  untracked_dirs: UntrackedDir[];
   conflicted_files: string[];
+  ignored_files: string[];
+  entries: StatusEntry[];
   ahead: number;
   behind: number;
+  // null when the current branch tracks nothing (or HEAD is detached)
+  upstream: UpstreamInfo | null;
   is_rebasing: boolean;
   is_merging: boolean;
   is_cherry_picking: boolean;
+  is_detached: boolean;
+  detached_commit: string | null;
+}
+
+export interface UpstreamInfo {
+  remote: string;
+  branch: string;
+  // e.g. refs/remotes/origin/main
+  ref_name: string;
+}
+
+export interface RepoStatusSummary {
+  branch: string;
+  dirty_count: number;
+  ahead: number;
+  behind: number;
 }
 
 export interface FileStatus {
   path: string;
   status: FileStatusType;
   is_binary: boolean;
+  is_lfs: boolean;
+}
+
+export interface ResetWorkingTreeResult {
+  reset: number;
+  removed: number;
+}
+
+export type DiscardAction = 'revert' | 'delete' | 'restore';
+
+export interface DiscardPreview {
+  path: string;
+  action: DiscardAction;
+  // Lines that would be lost / come back
+  additions: number;
+  deletions: number;
+  is_binary: boolean;
+}
+
+export interface UntrackedDir {
+  path: string;
+  file_count: number;
+  // Counting stopped at the backend limit; show as "N+"
+  file_count_capped: boolean;
+}
+
+export interface StatusEntry {
+  path: string;
+  index_status: FileStatusType | null;
+  worktree_status: FileStatusType | null;
 }
 
 export type FileStatusType = 'added' | 'modified' | 'deleted' | 'renamed' | 'copied' | 'untracked' | 'ignored' | 'conflicted';
@@ -49,6 +123,7 @@ export interface CommitInfo {
   short_hash: string;
   message: string;
   summary: string;
+  subject: string;
   body: string | null;
   author_name: string;
   author_email: string;
@@ -57,9 +132,86 @@ export interface CommitInfo {
   committer_email: string;
   committer_date: number;
   parents: string[];
+  parent_summaries: string[];
+  // Only filled when listing with withStats
+  stats?: CommitStats | null;
   is_merge: boolean;
 }
 
+export interface DiffStats {
+  files_changed: number;
+  insertions: number;
+  deletions: number;
+}
+
+export interface CommitStats {
+  files_changed: number;
+  insertions: number;
+  deletions: number;
+}
+
+export interface FileHistoryEntry extends CommitInfo {
+  file_status: 'added' | 'modified' | 'deleted' | 'renamed';
+  path: string;
+  old_path: string | null;
+}
+
+export interface GraphEdge {
+  parent_hash: string;
+  from_lane: number;
+  to_lane: number;
+}
+
+export interface GraphCommit {
+  commit: CommitInfo;
+  lane: number;
+  edges: GraphEdge[];
+}
+
+export interface ReflogEntry {
+  index: number;
+  old_hash: string;
+  new_hash: string;
+  short_hash: string;
+  committer_name: string;
+  committer_email: string;
+  time: number;
+  message: string;
+}
+
+export interface CommitReadiness {
+  has_staged_changes: boolean;
+  has_conflicts: boolean;
+  head_exists: boolean;
+}
+
+export interface AuthorOverride {
+  name?: string;
+  email?: string;
+  /** Unix seconds or RFC3339 */
+  date?: string;
+}
+
+export interface ResetPathsResult {
+  restored: string[];
+  removed: string[];
+  missing: string[];
+}
+
+export interface CommitQuery {
+  branch?: string;
+  message?: string;
+  author?: string;
+  path?: string;
+  since?: number;
+  until?: number;
+}
+
+export interface AheadBehind {
+  ahead: number;
+  behind: number;
+}
+
 export interface CompareResult {
   base_ref: string;
   head_ref: string;
@@ -75,6 +227,51 @@ export interface CompareResult {
   diff: DiffInfo[];
 }
 
+// App settings
+export interface CommitRules {
+  types: string[];
+  subject_pattern: string | null;
+  max_subject_length: number;
+  require_blank_line: boolean;
+}
+
+export interface CreateCommitResult {
+  short_hash: string;
+  branch: string | null;
+  detached: boolean;
+}
+
+export interface CommitMessageViolation {
+  rule: string;
+  severity: 'error' | 'warning';
+  message: string;
+}
+
+export interface AppSettings {
+  theme: '' | 'light' | 'dark' | 'system';
+  default_branch: string;
+  run_hooks: boolean;
+  commit_rules: CommitRules;
+  // Passphrase lives in the OS keyring (set_ssh_passphrase)
+  ssh_key_path: string | null;
+  // Command for open_in_editor (code, subl, idea, gvim...); empty means VS Code
+  external_editor: string;
+}
+
+// Git config
+export type ConfigScope = 'local' | 'global' | 'system';
+
+export interface ConfigValue {
+  value: string;
+  scope: ConfigScope;
+}
+
+export interface ConfigEntry {
+  name: string;
+  value: string;
+  level: ConfigScope;
+}
+
 // Branches
 export interface BranchInfo {
   name: string;
@@ -86,11 +283,36 @@ export interface BranchInfo {
   upstream: string | null;
   ahead: number | null;
   behind: number | null;
+  upstream_status: UpstreamStatus | null;
   author_name: string | null;
   author_email: string | null;
   commit_date: number | null;
+  description: string | null;
+}
+
+// Cheap dirty check for preflight warnings (is_working_tree_clean)
+export interface WorkingTreeState {
+  clean: boolean;
+  staged: number;
+  unstaged: number;
+  untracked: number;
+  conflicted: number;
+}
+
+// Result of push_dry_run: what a push would send
+export interface PushPreview {
+  remote: string;
+  branch: string;
+  remote_ref: string | null;
+  commits: CommitInfo[];
+  ahead: number;
+  behind: number;
+  non_fast_forward: boolean;
 }
 
+// 'gone' = upstream configured but its ref no longer exists
+export type UpstreamStatus = 'synced' | 'ahead' | 'behind' | 'diverged' | 'gone' | 'none';
+
 // Diff
 export interface DiffInfo {
   path: string;
@@ -100,6 +322,54 @@ export interface DiffInfo {
   deletions: number;
   is_binary: boolean;
   hunks: HunkInfo[];
+  truncated: boolean;
+  is_lfs: boolean;
+  lfs_pointer: LfsPointer | null;
+  line_ending_only: boolean;
+  encoding: string | null;
+  // Prism language id for highlighting, null when unknown
+  language: string | null;
+}
+
+// One window of a file's hunks; additions/deletions cover the whole file
+export interface DiffPage extends DiffInfo {
+  total_hunks: number;
+  hunk_offset: number;
+}
+
+export interface FileAtCommit {
+  path: string;
+  content: string | null;
+  is_binary: boolean;
+  size: number;
+}
+
+export interface TreeEntryInfo {
+  name: string;
+  path: string;
+  kind: 'file' | 'dir' | 'submodule';
+  oid: string;
+  size: number | null;
+}
+
+export interface LfsPointer {
+  oid: string;
+  size: number;
+}
+
+export interface LfsFile {
+  path: string;
+  oid: string;
+  downloaded: boolean;
+}
+
+export interface DiffFileSummary {
+  path: string;
+  old_path: string | null;
+  status: string;
+  additions: number;
+  deletions: number;
+  is_binary: boolean;
 }
 
 export interface HunkInfo {
@@ -117,6 +387,12 @@ export interface LineInfo {
   content: string;
   origin: string;
   line_type: LineType;
+  segments: LineSegment[];
+}
+
+export interface LineSegment {
+  text: string;
+  changed: boolean;
 }
 
 export type LineType = 'context' | 'addition' | 'deletion' | 'header' | 'binary';
@@ -148,6 +424,13 @@ export interface RemoteInfo {
   push_url: string;
 }
 
+export interface FetchResult {
+  remote: string;
+  ok: boolean;
+  error: string | null;
+  new_tags: number;
+}
+
 // Stash
 export interface StashInfo {
   index: number;
@@ -157,14 +440,34 @@ export interface StashInfo {
   date: number;
 }
 
+export interface StashApplyResult {
+  conflicts: string[];
+  dropped: boolean;
+}
+
 // Error
+export type ErrorKind =
+  | 'not_found'
+  | 'conflict'
+  | 'auth'
+  | 'network'
+  | 'validation'
+  | 'internal';
+
 export interface AppError {
   code: string;
+  kind: ErrorKind;
   message: string;
   details: string | null;
 }
 
 // Pull Requests
+export interface RepoSlug {
+  owner: string;
+  repo: string;
+  host: string;
+}
+
 export interface PullRequest {
   number: number;
   title: string;
@@ -203,6 +506,26 @@ export interface PullRequestComment {
   created_at: string;
 }
 
+// Review conversation on a diff line; `line` is null once outdated
+export interface ReviewThread {
+  id: string;
+  is_resolved: boolean;
+  is_outdated: boolean;
+  path: string;
+  line: number | null;
+  original_line: number | null;
+  resolved_by: string | null;
+  comments: ReviewThreadComment[];
+}
+
+export interface ReviewThreadComment {
+  id: string;
+  author: string;
+  body: string;
+  created_at: string;
+  url: string;
+}
+
 export interface PullRequestFile {
   filename: string;
   status: string;
@@ -269,6 +592,30 @@ export interface CheckRun {
   bucket: string | null;
 }
 
+export interface WorkflowRun {
+  id: number;
+  name: string;
+  display_title: string;
+  status: string;
+  conclusion: string | null;
+  head_branch: string;
+  created_at: string;
+  url: string;
+}
+
+export interface WorkflowJob {
+  name: string;
+  status: string;
+  conclusion: string | null;
+  started_at: string | null;
+  completed_at: string | null;
+  url: string;
+}
+
+export interface WorkflowRunDetails extends WorkflowRun {
+  jobs: WorkflowJob[];
+}
+
 export interface IssueReaction {
   id: number;
   content: string;
@@ -311,6 +658,26 @@ export interface RebaseEntry {
   message?: string;
 }
 
+export interface RewrittenCommit {
+  old_hash: string;
+  new_hash: string;
+}
+
+export interface RebaseStatus {
+  in_progress: boolean;
+  // 1-based; 0 when idle
+  current_step: number;
+  total_steps: number;
+  head_name: string | null;
+  onto: string | null;
+  conflicted_files: string[];
+}
+
+export interface DropCommitResult {
+  new_head: string;
+  rewritten: RewrittenCommit[];
+}
+
 // Conflict Resolution
 export interface ConflictInfo {
   path: string;
@@ -320,6 +687,19 @@ export interface ConflictInfo {
   conflicts: ConflictSection[];
 }
 
+export interface ConflictEntry {
+  path: string;
+  ours_oid: string | null;
+  theirs_oid: string | null;
+  base_oid: string | null;
+  is_binary: boolean;
+  ours_content: string | null;
+  theirs_content: string | null;
+  base_content: string | null;
+}
+
+export type ConflictChoice = 'ours' | 'theirs' | 'base';
+
 export interface ConflictSection {
   id: number;
   ours: string;
@@ -327,6 +707,8 @@ export interface ConflictSection {
   base: string | null;
   start_line: number;
   end_line: number;
+  start_offset: number;
+  end_offset: number;
 }
 
 // Submodules